@@ -0,0 +1,25 @@
+fn main() {
+    // the C header is only needed along with the functions it declares
+    #[cfg(feature = "ffi")]
+    generate_header();
+}
+
+/// Write the C header declaring the functions of the `ffi` module to `wrapperize.h` in the output directory, which a
+/// test compares against the copy in `include/`.
+#[cfg(feature = "ffi")]
+fn generate_header() {
+    println!("cargo::rerun-if-changed=src/ffi.rs");
+    println!("cargo::rerun-if-changed=cbindgen.toml");
+
+    let dir = std::env::var("CARGO_MANIFEST_DIR").expect("cargo should set the manifest directory");
+    let out_dir = std::env::var("OUT_DIR").expect("cargo should set the output directory");
+    let config = cbindgen::Config::from_file(format!("{dir}/cbindgen.toml"))
+        .expect("cbindgen.toml should be valid");
+
+    cbindgen::Builder::new()
+        .with_config(config)
+        .with_src(format!("{dir}/src/ffi.rs"))
+        .generate()
+        .expect("the C header should be generated")
+        .write_to_file(format!("{out_dir}/wrapperize.h"));
+}
@@ -0,0 +1,57 @@
+# bash completion for wrapperize
+# Install to /usr/share/bash-completion/completions/wrapperize
+
+_wrapperize() {
+    local cur prev command
+    _init_completion || return
+
+    # the first word that isn't an option, or the value of `--escalate`, is the command
+    local i
+    for ((i = 1; i < cword; i++)); do
+        case "${words[i]}" in
+            --escalate) ((i++)) ;;
+            -*) ;;
+            *) command="${words[i]}"; break ;;
+        esac
+    done
+
+    if [[ -z "$command" ]]; then
+        case "$prev" in
+            --escalate) COMPREPLY=($(compgen -W "sudo doas" -- "$cur")); return ;;
+        esac
+
+        COMPREPLY=($(compgen -W "wrap run apply check watch-config edit validate schema unwrap mask verify list
+            which simulate bench diff export preflight purge --escalate --help" -- "$cur"))
+        return
+    fi
+
+    case "$prev" in
+        --tag)
+            mapfile -t COMPREPLY < <(wrapperize __complete tags "$cur" 2>/dev/null)
+            return
+            ;;
+        --preset)
+            mapfile -t COMPREPLY < <(wrapperize __complete presets "$cur" 2>/dev/null)
+            return
+            ;;
+    esac
+
+    if [[ "$cur" == -* ]]; then
+        COMPREPLY=($(compgen -W "$(wrapperize "$command" --help 2>/dev/null | grep -o -- '--[a-z-]*' | sort -u)" -- "$cur"))
+        return
+    fi
+
+    case "$command" in
+        unwrap | verify | which | simulate | bench | diff | edit)
+            # only the executable is completed from the wrappers, and the arguments after it as files
+            if ((i + 1 == cword)); then
+                mapfile -t COMPREPLY < <(wrapperize __complete paths "$cur" 2>/dev/null)
+            else
+                _filedir
+            fi
+            ;;
+        *) _filedir ;;
+    esac
+}
+
+complete -F _wrapperize wrapperize
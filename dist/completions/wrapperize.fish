@@ -0,0 +1,12 @@
+# fish completion for wrapperize
+# Install to /usr/share/fish/vendor_completions.d/wrapperize.fish
+
+set -l commands wrap run apply check watch-config edit validate schema unwrap mask verify list which simulate bench diff export preflight purge
+set -l path_commands unwrap verify which simulate bench diff edit
+
+complete -c wrapperize -n "not __fish_seen_subcommand_from $commands" -l escalate -xa "sudo doas"
+complete -c wrapperize -n "not __fish_seen_subcommand_from $commands" -xa "$commands"
+
+complete -c wrapperize -n "__fish_seen_subcommand_from $path_commands" -xa "(wrapperize __complete paths (commandline -ct) 2>/dev/null)"
+complete -c wrapperize -n "__fish_seen_subcommand_from wrap unwrap verify list" -l tag -xa "(wrapperize __complete tags (commandline -ct) 2>/dev/null)"
+complete -c wrapperize -n "__fish_seen_subcommand_from wrap run" -l preset -xa "(wrapperize __complete presets (commandline -ct) 2>/dev/null)"
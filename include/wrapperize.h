@@ -0,0 +1,50 @@
+/* Generated by cbindgen from src/ffi.rs when building with the `ffi` feature; don't edit. */
+
+#ifndef WRAPPERIZE_H
+#define WRAPPERIZE_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Wrap an executable as described by `entry`, a TOML table in the format of a `[[wrapper]]` entry of a manifest, such
+// as `path = "/usr/bin/vim"` followed by `args = ["-u", "NONE"]`. An existing wrapper is regenerated with it.
+//
+// Returns 0 if the wrapper was created or already up to date, and -1 otherwise. If `notice` isn't null, it's set to a
+// note for the user, such as why no `pacman` hooks were generated, or to null if there is none. The note has to be
+// released with `wrapperize_string_free`.
+//
+// # Safety
+//
+// `entry` has to point to a string terminated by a null byte, and `notice` has to be null or valid for writes.
+int wrapperize_wrap(const char *entry,
+                    char **notice);
+
+// The paths of every wrapped executable, each followed by a line break, or null if they couldn't be found.
+//
+// The string has to be released with `wrapperize_string_free`.
+char *wrapperize_list(void);
+
+// Description of the error of the last call on this thread that failed, or null if none has.
+//
+// The string is owned by this library and stays valid until the next call on this thread.
+const char *wrapperize_last_error(void);
+
+// Release a string returned by this library. Does nothing if `string` is null.
+//
+// # Safety
+//
+// `string` has to be null or returned by this library, and can't be used afterwards.
+void wrapperize_string_free(char *string);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* WRAPPERIZE_H */
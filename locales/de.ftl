@@ -0,0 +1,506 @@
+# Messages shown to users, in German.
+
+## General
+
+error = Fehler: { $error }
+error-exit = Fehler: { $error }
+problem = Problem: { $problem }
+prompt-default-yes = [J/n]
+prompt-default-no = [j/N]
+prompt-yes-answers = j, ja
+duplicate-env-var = Warnung: Umgebungsvariable `{ $name }` ist mehrfach definiert, der letzte Wert wird verwendet
+git-committed = Änderungen im Git-Repository unter `{ $repo }` committet
+snapshot-created = { $tool }-Snapshot „{ $description }“ erstellt
+escalate-confirm = Zugriff verweigert. Mit `{ $command }` erneut ausführen?
+escalate-hint = Hinweis: Zugriff verweigert; als root erneut ausführen, etwa mit `{ $command }`
+
+## Progress of commands handling many executables
+
+progress-wrapping = umhülle
+progress-unwrapping = entferne Hülle von
+progress-succeeded = erfolgreich
+progress-skipped = übersprungen
+progress-failed = fehlgeschlagen
+
+## Installing systemd units
+
+unit-enable-failed = Warnung: { $error }; führe `systemctl --user daemon-reload && systemctl --user enable --now '{ $unit }'` aus, damit die Hülle neu erstellt wird, wenn die ausführbare Datei ersetzt wird
+expiry-enable-failed = Warnung: { $error }; führe `{ $systemctl } daemon-reload && { $systemctl } enable --now '{ $timer }'` aus, damit die Hülle entfernt wird, sobald sie abläuft
+
+## Reasons pacman treats an executable differently
+
+pacman-unmanaged-prefix = `{ $prefix }` nicht von pacman verwaltet wird
+pacman-unowned = `{ $path }` zu keinem Paket gehört
+pacman-backup-array = sie im `backup`-Array von `{ $package }` steht
+pacman-no-upgrade = sie auf `NoUpgrade = { $pattern }` in der pacman-Konfiguration passt
+
+## wrap
+
+wrap-snap-path-order = Warnung: `{ $dir }` steht in `$PATH` nicht vor `/snap/bin`, daher wird der Shim womöglich nicht verwendet
+wrap-snap-shim = Hinweis: der Snap `{ $app }` wird mit einem Shim unter `{ $path }` umhüllt
+wrap-appimage = Hinweis: `{ $path }` ist ein AppImage und sieht daher den Pfad der Hülle als den Namen, mit dem es gestartet wurde
+wrap-config-protected = Warnung: pacman behandelt `{ $path }` als Konfiguration, da { $reason }, daher legt ein Upgrade die neue Version unter `{ $path }.pacnew` ab, statt die Hülle zu ersetzen; die pacman-Hooks verschieben sie als ursprüngliche ausführbare Datei an ihren Platz
+wrap-no-pacman-hooks = Hinweis: für `{ $path }` werden keine pacman-Hooks erzeugt, da { $reason }
+wrap-script-self-reference = Warnung: `{ $path }` ist ein Skript, das über { $references } auf seinen eigenen Pfad verweisen könnte, der nach dem Umhüllen der Pfad seines Originals ist; Dateien, die es neben sich erwartet, werden womöglich nicht gefunden, wenn das Original woanders liegt
+wrap-apparmor = Warnung: Das AppArmor-Profil `{ $profile }` ({ $mode }) beschränkt `{ $path }`, das nun die Hülle ist, und hindert die Hülle womöglich daran, bash oder das Original unter `{ $unwrapped }` auszuführen
+wrap-apparmor-snippet-hint = Hinweis: `--apparmor-snippet` gibt die Regeln aus, mit denen die Hülle das Original unter demselben Profil ausführen darf
+wrap-symlinks = Hinweis: die folgenden symbolischen Links auf die ausführbare Datei verwenden ebenfalls die Hülle:
+wrap-symlinks-exclude = verwende `--exclude-symlinks`, um sie stattdessen auf die nicht umhüllte ausführbare Datei zeigen zu lassen
+wrap-symlinks-applet = falls die ausführbare Datei anhand des Namens, mit dem sie gestartet wurde, entscheidet, was sie ausführt, wie BusyBox, entferne die Hülle und verwende `--applet NAME`, um stattdessen diese Namen zu umhüllen
+wrap-repointed = `{ $link }` zeigt jetzt auf `{ $target }`
+wrap-merge-wrapped = `{ $path }` ist bereits umhüllt; diese Parameter darin übernehmen?
+wrap-merge-belongs = `{ $path }` gehört zur Hülle für `{ $wrapper }`; diese Parameter darin übernehmen?
+wrap-replacing-env-var = Warnung: der bisherige Wert der Umgebungsvariable `{ $name }` wird ersetzt
+wrap-repeated-arg = Warnung: `{ $arg }` wird erneut hinzugefügt, obwohl die Hülle es bereits übergibt; verwende `edit`, um stattdessen ihre Argumente zu ändern
+wrap-unchanged = Hülle für `{ $path }` unverändert
+wrap-would-create = Hülle für `{ $path }` würde erstellt
+wrap-would-update = Hülle für `{ $path }` würde aktualisiert
+wrap-created = Hülle für `{ $path }` erfolgreich erstellt
+wrap-updated = Hülle für `{ $path }` erfolgreich aktualisiert
+wrap-apply-changes = diese Änderungen übernehmen?
+wrap-declined = Hülle für `{ $path }` unverändert gelassen
+preview-changes = das Installationsskript von `{ $path }` würde Folgendes ändern:
+preview-unchanged = das Installationsskript von `{ $path }` würde nichts ändern
+preview-created = `{ $path }` erstellen
+preview-modified = `{ $path }` ändern
+preview-removed = `{ $path }` entfernen
+preview-renamed = `{ $from }` in `{ $to }` umbenennen
+
+## unwrap
+
+unwrap-restored = ursprüngliche ausführbare Datei unter `{ $path }` wiederhergestellt
+unwrap-shim-removed = Shim unter `{ $path }` entfernt
+unwrap-missing = weder `{ $path }` noch sein Original unter `{ $original }` existieren noch; übrige Dateien der Hülle entfernt
+unwrap-hooks-kept = die Hooks wurden behalten, daher wird die Hülle beim nächsten Upgrade neu erstellt
+unwrap-tagged-confirm = die Hülle von allen entfernen?
+
+## edit
+
+edit-no-changes = keine Änderungen vorgenommen
+edit-invalid-params = Fehler: ungültige Parameter: { $error }
+edit-again = erneut bearbeiten?
+
+## list
+
+list-entry = { $path } ({ $status })
+list-none = keine Hüllen gefunden
+
+## which
+
+which-symlink = `{ $path }` ist ein symbolischer Link auf `{ $target }`
+which-dormant = `{ $path }` ist die ursprüngliche ausführbare Datei einer ruhenden Hülle, die das nächste Upgrade neu erstellt
+which-not-wrapper = `{ $path }` ist keine Hülle
+which-bypassed = `{ $path }` ist die ursprüngliche ausführbare Datei der Hülle unter `{ $wrapper }`, daher wird die Hülle umgangen
+which-copy = `{ $path }` ist eine Kopie der Hülle unter `{ $wrapper }`
+which-wrapper = `{ $path }` ist eine Hülle von `{ $original }`
+which-profile = Profil: { $name }
+which-command = Befehl: { $command }
+which-masked = maskiert: { $message }
+which-reason = Grund: { $reason }
+which-tags = Tags: { $tags }
+which-env = Umgebung: { $env }
+which-jvm-options = JVM-Optionen: { $options }
+which-ulimit = ulimit: { $limit }
+which-access = Zugriff: { $rules }
+which-allowed-hours = erlaubte Zeiten: { $hours }
+which-expires = läuft ab: { $expires }
+which-preset = Hinweis: die Voreinstellung `{ $preset }` kann den Befehl und die Umgebung ändern, wenn die Hülle läuft
+which-journal = Hinweis: die Ausgabe wird an das Journal gesendet, wenn sie nicht in einem Terminal angezeigt wird
+which-runtime-config = Hinweis: `{ $path }` kann den Befehl und die Umgebung ändern, wenn die Hülle läuft, falls die Datei existiert
+which-user-override = Hinweis: `{ $path }` des Benutzers, der die Hülle ausführt, kann den Befehl und die Umgebung ändern, wenn die Hülle läuft, falls die Datei existiert
+
+## simulate
+
+simulate-argv = argv[{ $index }]: { $arg }
+simulate-disabled = `{ $var }` ist gesetzt, daher führt die Hülle `{ $path }` mit den Argumenten aus, mit denen sie ausgeführt wurde
+
+## bench
+
+bench-cold = erster Lauf: { $wrapped } mit Hülle, { $unwrapped } ohne Hülle ({ $overhead })
+bench-warm = Median von { $runs } Läufen: { $wrapped } mit Hülle, { $unwrapped } ohne Hülle ({ $overhead })
+
+## verify
+
+verify-intact = Hülle für `{ $path }` ist intakt
+verify-problems = Hülle für `{ $path }` hat { $count } { $count ->
+    [one] Problem
+   *[other] Probleme
+    }
+verify-expected = erwartet: { $description }, da sie umhüllt ist
+
+## Manifests
+
+validate-valid = Manifest ist gültig
+check-valid = Manifest ist gültig, und { $count ->
+    [one] die { $count } Hülle darin kann
+   *[other] alle { $count } Hüllen darin können
+    } erzeugt werden
+apply-duplicate-env-var = Warnung: `{ $path }`: Umgebungsvariable `{ $name }` ist mehrfach definiert, der letzte Wert wird verwendet
+export-skipping = Warnung: `{ $path }` wird übersprungen: { $error }
+export-skipping-dormant = Warnung: `{ $path }` wird übersprungen: Hülle ruht
+watch-config-watching = `{ $path }` wird auf Änderungen überwacht
+watch-config-changed = `{ $path }` wurde geändert und wird angewendet
+
+## Other commands
+
+purge-list = die Hülle der folgenden ausführbaren Dateien wird entfernt:
+purge-confirm = alle Spuren von Hüllen entfernen?
+purge-removed = { $count } übrige { $count ->
+    [one] Datei
+   *[other] Dateien
+    } aus `{ $dir }` entfernt
+preflight-ready = `{ $path }` kann umhüllt werden
+diff-matches = Hülle für `{ $path }` entspricht ihrer neu erzeugten Ausgabe
+run-already-wrapper = Hinweis: `{ $path }` ist bereits eine Hülle, daher werden diese Parameter zusätzlich zu ihren eigenen angewendet
+run-session-created = Hülle für `{ $path }` unter `{ $wrapper }` erstellt
+daemon-serving = `{ $bus }` wird auf dem Systembus bereitgestellt
+
+## Errors of files
+
+io-check-exists = Prüfen, ob der angegebene Pfad existiert, fehlgeschlagen
+io-read-file = Lesen der Datei fehlgeschlagen
+file-create-temp-dir-failed = Erstellen des temporären Verzeichnisses fehlgeschlagen
+file-no-temp-name = kein unbenutzter Name für ein temporäres Verzeichnis gefunden
+file-mode-not-octal = `{ $mode }` ist kein oktaler Modus
+file-mode-special-bits = der Modus `{ $mode }` darf nur Berechtigungsbits enthalten
+file-create-failed = Erstellen der Datei fehlgeschlagen
+file-set-mode-failed = Setzen der Dateiberechtigungen fehlgeschlagen
+file-write-failed = Schreiben in die Datei fehlgeschlagen
+file-move-failed = Verschieben der Datei an ihren Ort fehlgeschlagen
+file-metadata-failed = Lesen der Metadaten der Datei fehlgeschlagen
+file-dir-not-writable = das Verzeichnis ist nicht beschreibbar
+file-remove-probe-failed = Entfernen der Testdatei fehlgeschlagen
+file-remove-failed = Entfernen der Datei fehlgeschlagen
+program-path-failed = Ermitteln des Pfads dieses Programms fehlgeschlagen
+file-no-temp-file-name = kein unbenutzter Name für eine temporäre Datei gefunden
+
+## Errors of wrappers
+
+wrapper-pattern-no-name = das Muster `{ $pattern }` für den Dateinamen des Originals muss `{ $placeholder }` enthalten
+wrapper-pattern-is-name = das Muster für den Dateinamen des Originals kann nicht der Name der Hülle selbst sein
+wrapper-pattern-slash = das Muster `{ $pattern }` für den Dateinamen des Originals darf kein `/` enthalten
+wrapper-invalid-tag = das Tag `{ $tag }` darf nur Buchstaben, Ziffern, `-`, `_` und `.` enthalten
+wrapper-invalid-path = ungültiger Pfad angegeben
+wrapper-check-wrapped = Prüfen, ob der umhüllte Pfad bereits existiert, fehlgeschlagen
+wrapper-keep-hooks-no-metadata = die Hooks einer Hülle können ohne ihre gespeicherten Parameter nicht behalten werden
+wrapper-no-hooks-to-keep = die Hülle hat keine pacman-Hooks und kein Backend, die behalten werden könnten
+wrapper-original-missing-reinstall = die ursprüngliche ausführbare Datei fehlt unter `{ $path }`; installiere das Paket neu, das sie bereitstellt
+wrapper-not-wrapped = die ausführbare Datei ist nicht umhüllt
+wrapper-executable-gone = die ausführbare Datei existiert nicht mehr
+wrapper-restore-failed = Wiederherstellen der ursprünglichen ausführbaren Datei fehlgeschlagen
+wrapper-generate-install-script-failed = Erzeugen des Installationsskripts der Hülle fehlgeschlagen
+wrapper-write-install-script-failed = Schreiben des Installationsskripts der Hülle für den pacman-Hook fehlgeschlagen
+wrapper-original-elsewhere = die ursprüngliche ausführbare Datei liegt unter `{ $path }`; entferne zuerst die Hülle, um sie stattdessen unter `{ $expected }` abzulegen
+wrapper-none = für diese Datei existiert keine Hülle
+wrapper-exists = für diese Datei existiert bereits eine Hülle unter `{ $path }`
+wrapper-exists-version = für diese Datei existiert bereits eine Hülle unter `{ $path }` (erstellt von { $program } { $version })
+wrapper-generate-failed = Erzeugen des Skripts der Hülle fehlgeschlagen
+wrapper-store-dir-relative = das Ablageverzeichnis muss absolut sein
+wrapper-empty-reason = der Grund für das Umhüllen darf nicht leer sein
+wrapper-empty-profile-name = Profilnamen dürfen nicht leer sein
+wrapper-container-and-host-container = ein Container-Image und ein { $tool }-Container können nicht zusammen verwendet werden
+wrapper-appimage-container = ein AppImage kann nicht in einem Container oder als Snap ausgeführt werden
+wrapper-interpreter-not-script = nur ein Skript kann mit einem Interpreter ausgeführt werden
+wrapper-interpreter-args = einem Interpreter kann nur ein einzelnes Argument übergeben werden
+wrapper-applet-container = ein Applet kann nicht in einem Container, als Snap oder mit einem Interpreter ausgeführt werden
+wrapper-applet-launcher = ein Applet kann nicht über ein anderes Programm ausgeführt werden, etwa mit `--via`, `--no-network`, `--cpus`, `--preset` oder `--journal`
+wrapper-mask-with-params = einer maskierten ausführbaren Datei können keine Parameter gegeben werden, die ändern, wie sie ausgeführt wird
+wrapper-snap-container = ein Snap kann nicht in einem Container ausgeführt werden
+wrapper-snap-hooks = der Shim eines Snap-Befehls kann keine pacman-Hooks haben
+wrapper-expired = der Ablauf der Hülle am { $expires } ist bereits vorbei
+wrapper-hooks-and-user-units = eine Hülle kann nicht sowohl von pacman-Hooks als auch von systemd-Benutzer-Units neu erstellt werden
+wrapper-snap-user-units = der Shim eines Snap-Befehls kann keine systemd-Benutzer-Units haben
+wrapper-backend-and-hooks = eine Hülle, die vom Backend `{ $backend }` neu erstellt wird, kann nicht auch von pacman-Hooks oder systemd-Benutzer-Units neu erstellt werden
+wrapper-snap-backend = der Shim eines Snap-Befehls kann nicht von einem Backend neu erstellt werden
+wrapper-create-backend-dir-failed = Erstellen des Verzeichnisses für die Installationsskripte der Backends fehlgeschlagen
+wrapper-snap-preview = der Shim eines Snap-Befehls hat kein Installationsskript, das vorab ausgeführt werden könnte
+wrapper-create-store-dir-failed = Erstellen des Verzeichnisses für die ursprüngliche ausführbare Datei fehlgeschlagen
+wrapper-move-original-failed = Verschieben der ursprünglichen ausführbaren Datei fehlgeschlagen
+wrapper-original-mode-failed = Lesen der Berechtigungen der ursprünglichen ausführbaren Datei fehlgeschlagen
+wrapper-write-failed = Schreiben der Hülle fehlgeschlagen
+wrapper-rollback-failed = Zurückverschieben der ursprünglichen ausführbaren Datei von `{ $path }` fehlgeschlagen: { $error }
+wrapper-shim-in-the-way = am Ort des Shims liegt bereits eine Datei, die keine Hülle ist
+wrapper-write-shim-failed = Schreiben des Shims fehlgeschlagen
+wrapper-remove-exposed-failed = Entfernen des symbolischen Links auf die ursprüngliche ausführbare Datei fehlgeschlagen
+
+## Problems of wrappers
+
+problem-recreate-file-missing = `{ $path }` fehlt, daher wird die Hülle nicht neu erstellt, wenn die ausführbare Datei ersetzt wird
+problem-backend-missing = das Backend `{ $backend }` ist nicht installiert, daher wird die Hülle nicht neu erstellt, wenn die ausführbare Datei ersetzt wird
+problem-expired = die Hülle ist am { $expires } abgelaufen, aber noch installiert; führe `{ $program } unwrap { $path }` aus, um sie zu entfernen
+problem-expiry-file-missing = `{ $path }` fehlt, daher wird die Hülle nicht entfernt, sobald sie abläuft
+problem-expiry-program-missing = `{ $service }` führt `{ $missing }` aus, das fehlt, daher wird die Hülle nicht entfernt, sobald sie abläuft; führe `{ $program } wrap { $path } --update` aus, damit es dieses Programm verwendet
+problem-wrapper-missing = `{ $path }` existiert nicht
+problem-original-missing = die ursprüngliche ausführbare Datei fehlt unter `{ $path }`
+problem-not-generated = `{ $path }` wurde nicht von { $program } erzeugt
+problem-params-differ = `{ $path }` wurde mit anderen Parametern erzeugt als den in `{ $metadata }` gespeicherten
+
+## Errors of wrap
+
+wrap-check-and-preview-exec = `--check` und `--preview-exec` können nicht zusammen verwendet werden
+wrap-strict-and-no-strict = `--strict` und `--no-strict` können nicht zusammen verwendet werden
+wrap-nohooks-and-hooks = `--nohooks` und `--hooks` können nicht zusammen verwendet werden
+wrap-shim-dir-not-snap = `--shim-dir` kann nur beim Umhüllen eines Befehls in `/snap/bin` verwendet werden
+wrap-args-and-profile = `--arg-before` und `--arg-after` können nicht mit `--profile` verwendet werden
+wrap-distrobox-and-toolbox = `--distrobox` und `--toolbox` können nicht zusammen verwendet werden
+wrap-container-options = Container-Optionen können nur mit `--container-image`, `--distrobox` oder `--toolbox` verwendet werden
+wrap-interpreter-no-shebang = `--interpreter` kann nur mit einem Skript verwendet werden, das mit einem Shebang beginnt
+wrap-resolve-applet-failed = Auflösen des Applets fehlgeschlagen
+wrap-applets-of-wrapped = `{ $path }` ist bereits umhüllt; entferne die Hülle, bevor du seine Applets umhüllst
+wrap-resolve-path-failed = Auflösen des Pfads fehlgeschlagen
+wrap-applet-not-found = kein symbolischer Link namens `{ $name }` zeigt auf `{ $path }`
+wrap-backend-no-plugins = kein Backend `{ $backend }` gefunden, da kein Plugin `wrapperize-backend-<name>` in `$PATH` liegt
+wrap-backend-not-found = kein Backend `{ $backend }` gefunden, erwartet wurde eines von: { $known }
+wrap-already-wrapped = `{ $path }` ist bereits umhüllt
+wrap-belongs-to-wrapper = `{ $path }` gehört zur Hülle für `{ $wrapper }`
+wrap-merge-hint = { $problem }; verwende `{ $program } wrap { $path } --update`, um diese Parameter hinzuzufügen, oder `{ $program } edit { $path }`, um ihre Parameter zu ändern
+wrap-update-no-metadata = eine Hülle kann ohne ihre gespeicherten Parameter nicht aktualisiert werden
+wrap-nothing-to-wrap = keine Argumente, Umgebungsvariablen oder Startoptionen zum Umhüllen angegeben
+wrap-snap-command-missing = der Snap-Befehl existiert nicht
+wrap-shim-path-relative = der Pfad des Shims muss absolut sein
+wrap-path-missing = der Pfad existiert nicht
+wrap-path-not-file = der Pfad zeigt nicht auf eine Datei
+wrap-path-relative = der Pfad muss absolut sein
+wrap-not-executable = die Datei ist { $kind } statt einer ausführbaren Datei; übergib `--allow-non-executable`, um sie trotzdem zu umhüllen
+
+## Errors of pacman hooks
+
+pacman-hook-create-dir-failed = Erstellen des Verzeichnisses für pacman-Hooks unter `{ $path }` fehlgeschlagen
+pacman-hook-generate-removal-failed = Erzeugen des Inhalts des pacman-Hooks zum Entfernen fehlgeschlagen
+pacman-hook-write-failed = Schreiben des pacman-Hooks für { $action } fehlgeschlagen
+pacman-hook-append-failed = Anhängen von `{ $path }` an den Befehl zum Entfernen fehlgeschlagen
+pacman-hook-unknown-operation = unbekannte pacman-Operation `{ $operation }`
+
+## Errors of plugins
+
+plugin-invalid-name = `{ $name }` ist kein Pluginname, der nur ASCII-Buchstaben, Ziffern, `-` und `_` enthalten darf
+plugin-not-found = kein Plugin `{ $name }` in `$PATH` gefunden
+plugin-serialize-failed = Serialisieren der Anfrage an das Plugin fehlgeschlagen
+plugin-run-failed = Ausführen des Plugins fehlgeschlagen
+plugin-send-failed = Senden der Anfrage an das Plugin fehlgeschlagen
+plugin-wait-failed = Warten auf das Plugin fehlgeschlagen
+plugin-exited = beendet mit { $status }
+plugin-failed = das Plugin `{ $path }` ist fehlgeschlagen
+plugin-invalid-response = ungültige Antwort
+
+## Errors of watch-config
+
+watch-invalid-manifest-path = ungültiger Pfad des Manifests
+watch-inotify-init-failed = Initialisieren von inotify fehlgeschlagen
+watch-dir-failed = Beobachten des Verzeichnisses fehlgeschlagen
+watch-read-events-failed = Lesen der inotify-Ereignisse fehlgeschlagen
+watch-dir-gone = `{ $path }` wurde verschoben oder entfernt; Beobachtung beendet
+watch-no-manifest = unter diesem Pfad existiert kein Manifest
+
+## Problems of preflight
+
+preflight-not-absolute = `{ $path }` ist nicht absolut
+preflight-not-file = `{ $path }` ist keine reguläre Datei
+preflight-not-executable = `{ $path }` ist nicht ausführbar
+preflight-hook-conflict = `{ $hook }` steht im Konflikt mit den pacman-Hooks für `{ $path }`
+preflight-failed = `{ $path }` kann wegen { $count } { $count ->
+    [one] Problem
+   *[other] Problemen
+    } nicht umhüllt werden
+
+## Errors of manifests
+
+manifest-schema-invalid = das Schema der Manifeste ist ungültig
+manifest-duplicate-path = `/wrapper/{ $index }/path`: `{ $path }` ist bereits unter `/wrapper/{ $other }` aufgeführt
+manifest-path-relative = der Pfad muss absolut sein
+manifest-path-not-normal = der Pfad muss ohne `.`- oder `..`-Bestandteile zu einer Datei führen
+manifest-parse-failed = Parsen des Manifests fehlgeschlagen
+manifest-parse-stdin-failed = Parsen des Manifests von der Standardeingabe fehlgeschlagen
+manifest-read-dir-failed = Lesen des Drop-in-Verzeichnisses fehlgeschlagen
+manifest-read-stdin-failed = Lesen des Manifests von der Standardeingabe fehlgeschlagen
+manifest-read-failed = Lesen des Manifests fehlgeschlagen
+
+## Errors of presets
+
+preset-unknown = unbekanntes Preset `{ $preset }`, erwartet wurde eines von: { $known }
+preset-plugin-failed = Anwenden des Presets `{ $preset }` fehlgeschlagen
+preset-gpu-device-without-preset = ein GPU-Gerät kann nur mit dem Preset `gpu-offload` ausgewählt werden
+preset-wayland-flags-without-preset = Wayland-Flags können nur mit dem Preset `wayland` gesetzt werden
+preset-proxy-without-preset = ein Proxy kann nur mit dem Preset `proxy` gesetzt werden
+preset-proxy-missing = das Preset `proxy` benötigt die URL eines Proxys
+preset-invalid-default-value = `{ $value }` darf nicht leer sein und keine Leerzeichen, Anführungszeichen, Backticks, `$`, `\` oder `{ "}" }` enthalten
+preset-invalid-gpu-device = `{ $device }` ist kein DRI-Gerät wie `1`, `pci-0000_01_00_0` oder `1002:6a2b`
+
+## Errors of expiry
+
+expiry-invalid = ungültiger Ablauf `{ $expiry }`, erwartet wurde ein Datum wie `2025-12-31`, eine Zeit wie `2025-12-31T18:00` oder eine Dauer wie `12h` oder `7d`
+expiry-create-unit-dir-failed = Erstellen des Verzeichnisses für systemd-Units fehlgeschlagen
+expiry-write-unit-failed = Schreiben der systemd-Unit fehlgeschlagen
+expiry-too-long = die Dauer `{ $duration }` ist zu lang
+
+## Errors of systemd units
+
+systemctl-run-failed = Ausführen von `systemctl` fehlgeschlagen
+systemctl-failed = `systemctl { $args }` fehlgeschlagen ({ $status })
+unit-no-config-home = weder `$XDG_CONFIG_HOME` noch `$HOME` ist gesetzt
+unit-write-failed = Schreiben der systemd-Benutzer-Unit fehlgeschlagen
+unit-create-dir-failed = Erstellen des Verzeichnisses für systemd-Benutzer-Units fehlgeschlagen
+
+## Errors of run
+
+run-unrecognized-session-file = unbekannte Datei im Sitzungsverzeichnis
+run-session-args = Argumente für die ausführbare Datei können nicht mit `--session` verwendet werden; verwende `-a`, um sie der Hülle hinzuzufügen
+run-invalid-executable-path = ungültiger Pfad der ausführbaren Datei
+run-shell-failed = Ausführen von bash fehlgeschlagen
+run-not-found = keine ausführbare Datei `{ $command }` gefunden
+
+## Errors of edit
+
+edit-no-metadata = eine Hülle kann ohne ihre gespeicherten Parameter nicht bearbeitet werden
+edit-read-stdin-failed = Lesen der Parameter von der Standardeingabe fehlgeschlagen
+edit-parse-stdin-failed = Parsen der Parameter von der Standardeingabe fehlgeschlagen
+edit-file-header = Parameter der Hülle von `{ $path }`. Speichere und beende, um sie anzuwenden.
+edit-serialize-failed = Serialisieren der Parameter der Hülle fehlgeschlagen
+edit-write-temp-failed = Schreiben der temporären Datei fehlgeschlagen
+edit-read-temp-failed = Lesen der temporären Datei fehlgeschlagen
+edit-launch-editor-failed = Starten des Editors `{ $editor }` fehlgeschlagen
+edit-editor-failed = der Editor `{ $editor }` wurde mit { $status } beendet
+
+## Errors of settings
+
+settings-invalid-config = ungültige Konfigurationsdatei
+settings-invalid-strict = `{ $var }` muss `true` oder `false` sein, nicht `{ $value }`
+settings-invalid-escalate = `{ $var }` muss `sudo` oder `doas` sein, nicht `{ $value }`
+settings-invalid-env = ungültige Umgebungsvariable
+settings-path-relative = `{ $name }` muss ein absoluter Pfad sein, nicht `{ $path }`
+
+## Errors of previews
+
+preview-create-dir-failed = Erstellen des Verzeichnisses für die Vorschau fehlgeschlagen
+preview-no-bwrap = die Vorschau des Installationsskripts erfordert, dass bubblewrap (`bwrap`) installiert ist
+preview-bwrap-failed = Ausführen von `bwrap` fehlgeschlagen
+preview-script-failed = das Installationsskript ist in der Sandbox der Vorschau fehlgeschlagen ({ $status })
+preview-read-changes-failed = Lesen der Änderungen der Vorschau fehlgeschlagen
+
+## Errors of escalation
+
+escalate-id-failed = Ausführen von `id` fehlgeschlagen
+escalate-run-failed = Ausführen von `{ $tool }` fehlgeschlagen
+
+## Errors of containers
+
+container-empty-image = das Container-Image darf nicht leer sein
+container-invalid-volume = das Volume `{ $volume }` muss das Format `/host/path:/container/path` haben
+container-engine-missing = `{ $engine }` ist nicht installiert, daher kann der Container nicht ausgeführt werden
+container-empty-name = der Name des { $tool }-Containers darf nicht leer sein
+container-tool-missing = `{ $tool }` ist nicht installiert, daher kann der Container nicht betreten werden
+
+## Errors of commands
+
+aborted = abgebrochen
+executable-and-tag = eine ausführbare Datei und `--tag` können nicht zusammen verwendet werden
+executable-or-tag = eine ausführbare Datei oder `--tag` ist erforderlich
+manifest-problems = das Manifest hat { $count } { $count ->
+    [one] Problem
+   *[other] Probleme
+    }
+verify-broken = { $broken } { $broken ->
+    [one] Hülle hat
+   *[other] Hüllen haben
+    } Probleme
+verify-unexpected = { $unexpected } { $unexpected ->
+    [one] Datei weicht
+   *[other] Dateien weichen
+    } aus anderen Gründen als dem Umhüllen von ihrem Paket ab
+verify-broken-and-unexpected = { $broken } { $broken ->
+    [one] Hülle hat
+   *[other] Hüllen haben
+    } Probleme, und { $unexpected } { $unexpected ->
+    [one] Datei weicht
+   *[other] Dateien weichen
+    } aus anderen Gründen als dem Umhüllen von ihrem Paket ab
+apply-check-failed = { $count } { $count ->
+    [one] Hülle konnte
+   *[other] Hüllen konnten
+    } nicht geprüft werden
+progress-items-failed = { $failed } von { $total } Elementen fehlgeschlagen
+prompt-flush-failed = Leeren der Standardausgabe fehlgeschlagen
+prompt-read-failed = Lesen der Antwort von der Standardeingabe fehlgeschlagen
+mask-already-wrapped = `{ $path }` ist bereits umhüllt; entferne die Hülle `{ $wrapper }`, bevor du es maskierst
+which-not-found-path = unter `{ $path }` existiert keine ausführbare Datei
+which-not-found = `{ $command }` wurde in `$PATH` nicht gefunden
+simulate-no-profile = `{ $path }` hat kein Profil namens `{ $name }`
+simulate-runtime-command = die Befehlszeile eines Containers oder Snaps wird erst zusammengesetzt, wenn die Hülle ausgeführt wird; verwende `{ $program } which { $path }`, um zu sehen, wie er gestartet wird
+daemon-no-interface = keine Schnittstelle zum Bereitstellen; verwende `--dbus`
+daemon-register-failed = Registrieren des Dienstes auf dem Systembus fehlgeschlagen
+daemon-no-sender = die Nachricht hat keinen Absender
+daemon-not-authorized = nicht berechtigt für `{ $action }`
+diff-run-failed = Ausführen von diff fehlgeschlagen
+diff-pass-failed = Übergeben der neu erzeugten Datei an diff fehlgeschlagen
+diff-wait-failed = Warten auf diff fehlgeschlagen
+diff-failed = diff ist für `{ $path }` fehlgeschlagen ({ $status })
+bench-no-runs = die Anzahl der Durchläufe muss mindestens 1 sein
+bench-status-differs = die Hülle wurde mit { $wrapped } beendet, aber `{ $path }` mit { $unwrapped }
+export-invalid-function-name = der Name der ausführbaren Datei kann nicht als Name einer Funktion verwendet werden
+export-function-params = nur Argumente und Umgebungsvariablen können von einer Shell-Funktion übergeben werden
+export-fish-env = Umgebungsvariablen, die auf andere Variablen verweisen oder Befehle ausführen, können nur mit bash verwendet werden
+export-unknown-format = unbekanntes Exportformat `{ $format }`
+bench-run-failed = Ausführen von `{ $path }` fehlgeschlagen
+
+## Errors of the system
+
+symlink-no-file-name = der Pfad des symbolischen Links hat keinen Dateinamen
+symlink-create-failed = Erstellen des symbolischen Links fehlgeschlagen
+symlink-replace-failed = Ersetzen des symbolischen Links fehlgeschlagen
+symlink-unsupported = symbolische Links werden nur unter Unix unterstützt
+metadata-read-failed = Lesen der Metadaten der Hülle fehlgeschlagen
+metadata-parse-failed = Parsen der Metadaten der Hülle fehlgeschlagen
+metadata-write-failed = Schreiben der Metadaten der Hülle fehlgeschlagen
+metadata-serialize-failed = Serialisieren der Metadaten der Hülle fehlgeschlagen
+session-create-dir-failed = Erstellen des Sitzungsverzeichnisses fehlgeschlagen
+git-not-repository = nicht innerhalb eines git-Repositorys
+git-run-failed = Ausführen von git fehlgeschlagen
+git-failed = `git { $args }` fehlgeschlagen: { $error }
+pacman-lock-check-failed = Prüfen auf die Sperrdatei von pacman fehlgeschlagen
+pacman-lock-timeout = Zeitüberschreitung beim Warten auf das Ende der pacman-Transaktion (die Sperrdatei `{ $path }` existiert noch)
+pacman-lock-locked = eine pacman-Transaktion läuft gerade (die Sperrdatei `{ $path }` existiert); warte, bis sie beendet ist, oder verwende `--wait-for-pacman`
+pacman-files-failed = Ausführen von `pacman -Qkk` fehlgeschlagen
+snapshot-run-failed = Ausführen von `{ $tool }` fehlgeschlagen
+snapshot-failed = `{ $tool }` konnte keinen Snapshot erstellen ({ $status })
+snapshot-no-tool = ein Snapshot wurde angefordert, aber weder `snapper` noch `timeshift` ist installiert
+mount-read-only = `{ $path }` liegt auf einem schreibgeschützten Dateisystem, das unter `{ $mount }` eingehängt ist, und kann daher nicht durch eine Hülle ersetzt werden; auf abbildbasierten Systemen (etwa auf rpm-ostree basierenden Distributionen) können nur ausführbare Dateien in beschreibbaren Verzeichnissen wie `/usr/local/bin` oder `/opt` umhüllt werden
+complete-usage = Verwendung: { $program } { $command } <paths|presets|tags> [prefix]
+complete-unknown = unbekannte Vervollständigung `{ $kind }`
+registry-no-tagged = keine Hülle hat das Tag { $tags }
+ffi-null-arg = `{ $name }` ist null
+ffi-invalid-utf8 = `{ $name }` ist kein gültiges UTF-8
+ffi-parse-failed = Parsen der Hülle fehlgeschlagen
+ffi-panicked = { $program } ist abgestürzt
+registry-tag-separator = { " oder " }
+
+## Errors of parameters
+
+cpu-not-number = `{ $cpu }` in der CPU-Liste `{ $list }` ist keine CPU-Nummer
+cpu-range-reversed = der Bereich `{ $range }` in der CPU-Liste `{ $list }` ist umgekehrt
+cpu-stride-without-range = eine Schrittweite in der CPU-Liste `{ $list }` kann nur auf einen Bereich folgen
+cpu-invalid-stride = die Schrittweite `{ $stride }` in der CPU-Liste `{ $list }` muss eine positive Zahl sein
+missing-separator = fehlendes Trennzeichen '='
+limit-unknown-resource = unbekannte Ressource `{ $resource }`, erwartet wurde eine von: { $known }
+limit-invalid-value = das Limit `{ $value }` muss eine Zahl oder `unlimited` sein
+hours-invalid-time = `{ $time }` ist keine Uhrzeit im Format `08:00`
+hours-invalid = die erlaubten Stunden `{ $hours }` müssen ein Anfang und ein Ende sein, etwa `08:00-18:00`
+hours-empty = die erlaubten Stunden `{ $hours }` beginnen und enden zur selben Zeit
+jvm-unknown-gc = unbekannter Garbage Collector `{ $gc }`, erwartet wurde einer von: { $known }
+jvm-invalid-heap-size = `{ $size }` ist keine Heap-Größe wie `512m` oder `4g`
+jvm-invalid-option = die JVM-Option `{ $option }` darf nicht leer sein oder Leerzeichen enthalten
+env-invalid-name = ungültiger Name für die Umgebungsvariable `{ $name }`
+via-unknown = unbekanntes Proxy-Programm `{ $program }`, erwartet wurde eines von: { $known }
+via-not-installed = `{ $program }` ist nicht installiert, daher kann nichts darüber geleitet werden
+status-file-relative = die Statusdatei `{ $path }` muss absolut sein oder mit einer Variable beginnen
+snap-invalid-app = `{ $app }` ist nicht der Name einer Snap-App wie `firefox` oder `lxd.lxc`
+access-invalid-name = `{ $name }` ist kein gültiger Benutzer- oder Gruppenname
+access-allow-user = Benutzer { $name } erlauben
+access-allow-group = Gruppe { $name } erlauben
+access-deny-user = Benutzer { $name } verweigern
+access-deny-group = Gruppe { $name } verweigern
+exec-type-elf = eine ausführbare ELF-Datei
+exec-type-shared-library = eine gemeinsam genutzte Bibliothek
+exec-type-elf-other = eine ELF-Datei, die nicht ausführbar ist
+exec-type-script = ein Skript
+exec-type-mach-o = eine Mach-O-Binärdatei für macOS
+exec-type-data = eine Datendatei
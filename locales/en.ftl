@@ -0,0 +1,509 @@
+# Messages shown to users, in English, which is the fallback for every other language.
+#
+# Every other catalog has to define exactly the same messages with the same arguments.
+
+## General
+
+error = error: { $error }
+error-exit = Error: { $error }
+problem = problem: { $problem }
+prompt-default-yes = [Y/n]
+prompt-default-no = [y/N]
+# comma-separated answers that count as agreeing to a question, besides `y` and `yes`
+prompt-yes-answers = y, yes
+duplicate-env-var = warning: environment variable `{ $name }` is defined multiple times, using the last value
+git-committed = committed changes to the git repository at `{ $repo }`
+snapshot-created = created { $tool } snapshot "{ $description }"
+escalate-confirm = Permission was denied. Run it again with `{ $command }`?
+escalate-hint = note: permission was denied; run it again as root, such as with `{ $command }`
+
+## Progress of commands handling many executables
+
+progress-wrapping = wrapping
+progress-unwrapping = unwrapping
+progress-succeeded = succeeded
+progress-skipped = skipped
+progress-failed = failed
+
+## Installing systemd units
+
+unit-enable-failed = warning: { $error }; run `systemctl --user daemon-reload && systemctl --user enable --now '{ $unit }'` to recreate the wrapper when the executable is replaced
+expiry-enable-failed = warning: { $error }; run `{ $systemctl } daemon-reload && { $systemctl } enable --now '{ $timer }'` to remove the wrapper once it expires
+
+## Reasons pacman treats an executable differently
+
+pacman-unmanaged-prefix = `{ $prefix }` is not managed by pacman
+pacman-unowned = `{ $path }` is not owned by any package
+pacman-backup-array = it's in the `backup` array of `{ $package }`
+pacman-no-upgrade = it matches `NoUpgrade = { $pattern }` in the pacman configuration
+
+## wrap
+
+wrap-snap-path-order = warning: `{ $dir }` doesn't come before `/snap/bin` in `$PATH`, so the shim may not be used
+wrap-snap-shim = note: wrapping the snap `{ $app }` with a shim at `{ $path }`
+wrap-appimage = note: `{ $path }` is an AppImage, so it will see the path of the wrapper as the name it was run with
+wrap-config-protected = warning: pacman treats `{ $path }` as configuration since { $reason }, so an upgrade leaves its new version at `{ $path }.pacnew` instead of replacing the wrapper; the pacman hooks move it into place as the original executable
+wrap-no-pacman-hooks = note: not generating pacman hooks for `{ $path }` since { $reason }
+wrap-script-self-reference = warning: `{ $path }` is a script that may refer to its own path through { $references }, which will be the path of its original once wrapped; files it expects next to itself may not be found if the original is kept elsewhere
+wrap-apparmor = warning: AppArmor profile `{ $profile }` ({ $mode }) confines `{ $path }`, which is now the wrapper, so it may stop the wrapper from running bash or the original executable at `{ $unwrapped }`
+wrap-apparmor-snippet-hint = note: use `--apparmor-snippet` to print the rules that let the wrapper run the original executable under the same profile
+wrap-symlinks = note: the following symlinks to the executable will also use the wrapper:
+wrap-symlinks-exclude = use `--exclude-symlinks` to point them at the unwrapped executable instead
+wrap-symlinks-applet = if the executable decides what to run by the name it was run with, like BusyBox, unwrap it and use `--applet NAME` to wrap those names instead
+wrap-repointed = repointed `{ $link }` to `{ $target }`
+wrap-merge-wrapped = `{ $path }` is already wrapped; merge these parameters into it?
+wrap-merge-belongs = `{ $path }` belongs to the wrapper for `{ $wrapper }`; merge these parameters into it?
+wrap-replacing-env-var = warning: replacing the existing value of environment variable `{ $name }`
+wrap-repeated-arg = warning: adding `{ $arg }` again, which the wrapper already passes; use `edit` to change its arguments instead
+wrap-unchanged = wrapper for `{ $path }` unchanged
+wrap-would-create = wrapper for `{ $path }` would be created
+wrap-would-update = wrapper for `{ $path }` would be updated
+wrap-created = wrapper successfully created for `{ $path }`
+wrap-updated = wrapper successfully updated for `{ $path }`
+wrap-apply-changes = apply these changes?
+wrap-declined = wrapper for `{ $path }` left unchanged
+preview-changes = the install script of `{ $path }` would make these changes:
+preview-unchanged = the install script of `{ $path }` would change nothing
+preview-created = create `{ $path }`
+preview-modified = modify `{ $path }`
+preview-removed = remove `{ $path }`
+preview-renamed = rename `{ $from }` to `{ $to }`
+
+## unwrap
+
+unwrap-restored = original executable restored to `{ $path }`
+unwrap-shim-removed = removed the shim at `{ $path }`
+unwrap-missing = neither `{ $path }` nor its original at `{ $original }` exist anymore; removed leftover wrapper files
+unwrap-hooks-kept = hooks were kept, so the wrapper will be recreated on the next upgrade
+unwrap-tagged-confirm = unwrap all of them?
+
+## edit
+
+edit-no-changes = no changes made
+edit-invalid-params = error: invalid parameters: { $error }
+edit-again = edit again?
+
+## list
+
+list-entry = { $path } ({ $status })
+list-none = no wrappers match
+
+## which
+
+which-symlink = `{ $path }` is a symlink to `{ $target }`
+which-dormant = `{ $path }` is the original executable of a dormant wrapper, which the next upgrade recreates
+which-not-wrapper = `{ $path }` is not a wrapper
+which-bypassed = `{ $path }` is the original executable of the wrapper at `{ $wrapper }`, so the wrapper is bypassed
+which-copy = `{ $path }` is a copy of the wrapper at `{ $wrapper }`
+which-wrapper = `{ $path }` is a wrapper of `{ $original }`
+which-profile = profile: { $name }
+which-command = command: { $command }
+which-masked = masked: { $message }
+which-reason = reason: { $reason }
+which-tags = tags: { $tags }
+which-env = env: { $env }
+which-jvm-options = jvm options: { $options }
+which-ulimit = ulimit: { $limit }
+which-access = access: { $rules }
+which-allowed-hours = allowed hours: { $hours }
+which-expires = expires: { $expires }
+which-preset = note: the `{ $preset }` preset can change the command and environment when the wrapper runs
+which-journal = note: output is sent to the journal when it isn't shown in a terminal
+which-runtime-config = note: `{ $path }` can change the command and environment when the wrapper runs, if it exists
+which-user-override = note: `{ $path }` of the user running the wrapper can change the command and environment when the wrapper runs, if it exists
+
+## simulate
+
+simulate-argv = argv[{ $index }]: { $arg }
+simulate-disabled = `{ $var }` is set, so the wrapper runs `{ $path }` with the arguments it was run with
+
+## bench
+
+bench-cold = first run: { $wrapped } wrapped, { $unwrapped } without the wrapper ({ $overhead })
+bench-warm = median of { $runs } runs: { $wrapped } wrapped, { $unwrapped } without the wrapper ({ $overhead })
+
+## verify
+
+verify-intact = wrapper for `{ $path }` is intact
+verify-problems = wrapper for `{ $path }` has { $count } { $count ->
+    [one] problem
+   *[other] problems
+    }
+verify-expected = expected: { $description }, since it's wrapped
+
+## Manifests
+
+validate-valid = manifest is valid
+check-valid = manifest is valid, and all { $count } { $count ->
+    [one] wrapper
+   *[other] wrappers
+    } in it can be generated
+apply-duplicate-env-var = warning: `{ $path }`: environment variable `{ $name }` is defined multiple times, using the last value
+export-skipping = warning: skipping `{ $path }`: { $error }
+export-skipping-dormant = warning: skipping `{ $path }`: wrapper is dormant
+watch-config-watching = watching `{ $path }` for changes
+watch-config-changed = `{ $path }` changed, applying it
+
+## Other commands
+
+purge-list = the following executables will be unwrapped:
+purge-confirm = remove all traces of wrappers?
+purge-removed = removed { $count } leftover { $count ->
+    [one] file
+   *[other] files
+    } from `{ $dir }`
+preflight-ready = `{ $path }` is ready to be wrapped
+diff-matches = wrapper for `{ $path }` matches its regenerated output
+run-already-wrapper = note: `{ $path }` is already a wrapper, so these parameters are applied on top of its own
+run-session-created = wrapper for `{ $path }` created at `{ $wrapper }`
+daemon-serving = serving `{ $bus }` on the system bus
+
+## Errors of files
+
+io-check-exists = failed to check if specified path exists
+io-read-file = failed to read file
+file-create-temp-dir-failed = failed to create temporary directory
+file-no-temp-name = failed to find an unused name for a temporary directory
+file-mode-not-octal = `{ $mode }` is not an octal mode
+file-mode-special-bits = mode `{ $mode }` can only contain permission bits
+file-create-failed = failed to create file
+file-set-mode-failed = failed to set file permissions
+file-write-failed = failed to write to file
+file-move-failed = failed to move file into place
+file-metadata-failed = failed to get metadata for file
+file-dir-not-writable = directory is not writable
+file-remove-probe-failed = failed to remove probe file
+file-remove-failed = failed to remove file
+program-path-failed = failed to find the path of this program
+file-no-temp-file-name = failed to find an unused name for a temporary file
+
+## Errors of wrappers
+
+wrapper-pattern-no-name = unwrapped filename pattern `{ $pattern }` must contain `{ $placeholder }`
+wrapper-pattern-is-name = unwrapped filename pattern can't be the name of the wrapper itself
+wrapper-pattern-slash = unwrapped filename pattern `{ $pattern }` can't contain `/`
+wrapper-invalid-tag = tag `{ $tag }` can only contain letters, digits, `-`, `_` and `.`
+wrapper-invalid-path = invalid path provided
+wrapper-check-wrapped = failed to check if wrapped path already exists
+wrapper-keep-hooks-no-metadata = cannot keep the hooks of a wrapper without its stored parameters
+wrapper-no-hooks-to-keep = wrapper has no pacman hooks or backend to keep
+wrapper-original-missing-reinstall = original executable is missing from `{ $path }`; reinstall the package that provides it
+wrapper-not-wrapped = executable is not wrapped
+wrapper-executable-gone = executable no longer exists
+wrapper-restore-failed = failed to restore original executable
+wrapper-generate-install-script-failed = failed to generate wrapper install script
+wrapper-write-install-script-failed = failed to write wrapper install script for pacman hook
+wrapper-original-elsewhere = the original executable is kept at `{ $path }`; unwrap it first to keep it at `{ $expected }` instead
+wrapper-none = no wrapper exists for this file
+wrapper-exists = wrapper already exists for this file at `{ $path }`
+wrapper-exists-version = wrapper already exists for this file at `{ $path }` (created by { $program } { $version })
+wrapper-generate-failed = failed to generate wrapper script
+wrapper-store-dir-relative = store directory must be absolute
+wrapper-empty-reason = the reason for wrapping can't be empty
+wrapper-empty-profile-name = profile names can't be empty
+wrapper-container-and-host-container = a container image and a { $tool } container can't be used together
+wrapper-appimage-container = an AppImage can't be run in a container or as a snap
+wrapper-interpreter-not-script = only a script can be run with an interpreter
+wrapper-interpreter-args = an interpreter can only be given a single argument
+wrapper-applet-container = an applet can't be run in a container, as a snap or with an interpreter
+wrapper-applet-launcher = an applet can't be run through another program, such as with `--via`, `--no-network`, `--cpus`, `--preset` or `--journal`
+wrapper-mask-with-params = a masked executable can't also be given parameters that change how it runs
+wrapper-snap-container = a snap can't be run in a container
+wrapper-snap-hooks = the shim of a snap command can't have pacman hooks
+wrapper-expired = the expiry of the wrapper at { $expires } has already passed
+wrapper-hooks-and-user-units = a wrapper can't be recreated by both pacman hooks and systemd user units
+wrapper-snap-user-units = the shim of a snap command can't have systemd user units
+wrapper-backend-and-hooks = a wrapper recreated by the `{ $backend }` backend can't also be recreated by pacman hooks or systemd user units
+wrapper-snap-backend = the shim of a snap command can't be recreated by a backend
+wrapper-create-backend-dir-failed = failed to create backend install script directory
+wrapper-snap-preview = the shim of a snap command has no install script to preview
+wrapper-create-store-dir-failed = failed to create directory for original executable
+wrapper-move-original-failed = failed to move original executable out of the way
+wrapper-original-mode-failed = failed to get original executable permissions
+wrapper-write-failed = failed to write wrapper
+wrapper-rollback-failed = failed to move original executable back from `{ $path }`: { $error }
+wrapper-shim-in-the-way = a file that isn't a wrapper is already in the way of the shim
+wrapper-write-shim-failed = failed to write shim
+wrapper-remove-exposed-failed = failed to remove symlink to original executable
+
+## Problems of wrappers
+
+problem-recreate-file-missing = `{ $path }` is missing, so the wrapper isn't recreated when the executable is replaced
+problem-backend-missing = the `{ $backend }` backend is not installed, so the wrapper isn't recreated when the executable is replaced
+problem-expired = the wrapper expired at { $expires } but is still installed; run `{ $program } unwrap { $path }` to remove it
+problem-expiry-file-missing = `{ $path }` is missing, so the wrapper isn't removed once it expires
+problem-expiry-program-missing = `{ $service }` runs `{ $missing }`, which is missing, so the wrapper isn't removed once it expires; run `{ $program } wrap { $path } --update` to point it at this program
+problem-wrapper-missing = `{ $path }` does not exist
+problem-original-missing = original executable is missing from `{ $path }`
+problem-not-generated = `{ $path }` was not generated by { $program }
+problem-params-differ = `{ $path }` was generated with different parameters than the ones stored in `{ $metadata }`
+
+## Errors of wrap
+
+wrap-check-and-preview-exec = `--check` and `--preview-exec` can't be used together
+wrap-strict-and-no-strict = `--strict` and `--no-strict` can't be used together
+wrap-nohooks-and-hooks = `--nohooks` and `--hooks` can't be used together
+wrap-shim-dir-not-snap = `--shim-dir` can only be used when wrapping a command in `/snap/bin`
+wrap-args-and-profile = `--arg-before` and `--arg-after` can't be used with `--profile`
+wrap-distrobox-and-toolbox = `--distrobox` and `--toolbox` can't be used together
+wrap-container-options = container options can only be used with `--container-image`, `--distrobox` or `--toolbox`
+wrap-interpreter-no-shebang = `--interpreter` can only be used with a script that starts with a shebang
+wrap-resolve-applet-failed = failed to resolve applet
+wrap-applets-of-wrapped = `{ $path }` is already wrapped; unwrap it before wrapping its applets
+wrap-resolve-path-failed = failed to resolve path
+wrap-applet-not-found = no symlink named `{ $name }` points to `{ $path }`
+wrap-backend-no-plugins = no backend `{ $backend }` found, since no `wrapperize-backend-<name>` plugin is in `$PATH`
+wrap-backend-not-found = no backend `{ $backend }` found, expected one of: { $known }
+wrap-already-wrapped = `{ $path }` is already wrapped
+wrap-belongs-to-wrapper = `{ $path }` belongs to the wrapper for `{ $wrapper }`
+wrap-merge-hint = { $problem }; use `{ $program } wrap { $path } --update` to merge these parameters into it, or `{ $program } edit { $path }` to change its parameters
+wrap-update-no-metadata = cannot update a wrapper without its stored parameters
+wrap-nothing-to-wrap = no arguments, environment variables or launch options provided to wrap
+wrap-snap-command-missing = snap command does not exist
+wrap-shim-path-relative = shim path must be absolute
+wrap-path-missing = path does not exist
+wrap-path-not-file = path does not point to a file
+wrap-path-relative = path must be absolute
+wrap-not-executable = file is { $kind } rather than an executable; pass `--allow-non-executable` to wrap it anyway
+
+## Errors of pacman hooks
+
+pacman-hook-create-dir-failed = failed to create pacman user hook directory at `{ $path }`
+pacman-hook-generate-removal-failed = failed to generate content for pacman removal hook
+pacman-hook-write-failed = failed to write pacman { $action } hook
+pacman-hook-append-failed = failed to append `{ $path }` to the removal command
+pacman-hook-unknown-operation = unknown pacman operation `{ $operation }`
+
+## Errors of plugins
+
+plugin-invalid-name = `{ $name }` is not a plugin name, which can only contain ASCII letters, digits, `-` and `_`
+plugin-not-found = no plugin `{ $name }` found in `$PATH`
+plugin-serialize-failed = failed to serialize plugin request
+plugin-run-failed = failed to run plugin
+plugin-send-failed = failed to send request to plugin
+plugin-wait-failed = failed to wait for plugin
+plugin-exited = exited with { $status }
+plugin-failed = plugin `{ $path }` failed
+plugin-invalid-response = invalid response
+
+## Errors of watch-config
+
+watch-invalid-manifest-path = invalid manifest path
+watch-inotify-init-failed = failed to initialize inotify
+watch-dir-failed = failed to watch directory
+watch-read-events-failed = failed to read inotify events
+watch-dir-gone = `{ $path }` was moved or removed; stopped watching
+watch-no-manifest = no manifest exists at this path
+
+## Problems of preflight
+
+preflight-not-absolute = `{ $path }` is not absolute
+preflight-not-file = `{ $path }` is not a regular file
+preflight-not-executable = `{ $path }` is not executable
+preflight-hook-conflict = `{ $hook }` conflicts with the pacman hooks for `{ $path }`
+preflight-failed = `{ $path }` can't be wrapped due to { $count } { $count ->
+    [one] problem
+   *[other] problems
+    }
+
+## Errors of manifests
+
+manifest-schema-invalid = manifest schema is invalid
+manifest-duplicate-path = `/wrapper/{ $index }/path`: `{ $path }` is already listed at `/wrapper/{ $other }`
+manifest-path-relative = path must be absolute
+manifest-path-not-normal = path must lead to a file without `.` or `..` components
+manifest-parse-failed = failed to parse manifest
+manifest-parse-stdin-failed = failed to parse manifest from stdin
+manifest-read-dir-failed = failed to read drop-in directory
+manifest-read-stdin-failed = failed to read manifest from stdin
+manifest-read-failed = failed to read manifest
+
+## Errors of presets
+
+preset-unknown = unknown preset `{ $preset }`, expected one of: { $known }
+preset-plugin-failed = failed to apply the `{ $preset }` preset
+preset-gpu-device-without-preset = a GPU device can only be selected with the `gpu-offload` preset
+preset-wayland-flags-without-preset = Wayland flags can only be set with the `wayland` preset
+preset-proxy-without-preset = a proxy can only be set with the `proxy` preset
+preset-proxy-missing = the `proxy` preset needs the URL of a proxy
+preset-invalid-default-value = `{ $value }` can't be empty or contain whitespace, quotes, backticks, `$`, `\` or `{ "}" }`
+preset-invalid-gpu-device = `{ $device }` is not a DRI device, such as `1`, `pci-0000_01_00_0`, or `1002:6a2b`
+
+## Errors of expiry
+
+expiry-invalid = invalid expiry `{ $expiry }`, expected a date such as `2025-12-31`, a time such as `2025-12-31T18:00`, or a duration such as `12h` or `7d`
+expiry-create-unit-dir-failed = failed to create systemd unit directory
+expiry-write-unit-failed = failed to write systemd unit
+expiry-too-long = duration `{ $duration }` is too long
+
+## Errors of systemd units
+
+systemctl-run-failed = failed to run `systemctl`
+systemctl-failed = `systemctl { $args }` failed ({ $status })
+unit-no-config-home = neither `$XDG_CONFIG_HOME` nor `$HOME` is set
+unit-write-failed = failed to write systemd user unit
+unit-create-dir-failed = failed to create systemd user unit directory
+
+## Errors of run
+
+run-unrecognized-session-file = unrecognized file in the session directory
+run-session-args = arguments to run the executable with can't be used with `--session`; use `-a` to add them to the wrapper
+run-invalid-executable-path = invalid executable path
+run-shell-failed = failed to run bash
+run-not-found = no executable `{ $command }` found
+
+## Errors of edit
+
+edit-no-metadata = cannot edit a wrapper without its stored parameters
+edit-read-stdin-failed = failed to read parameters from stdin
+edit-parse-stdin-failed = failed to parse parameters from stdin
+edit-file-header = Parameters for the wrapper of `{ $path }`. Save and exit to apply them.
+edit-serialize-failed = failed to serialize wrapper parameters
+edit-write-temp-failed = failed to write temporary file
+edit-read-temp-failed = failed to read temporary file
+edit-launch-editor-failed = failed to launch editor `{ $editor }`
+edit-editor-failed = editor `{ $editor }` exited with { $status }
+
+## Errors of settings
+
+settings-invalid-config = invalid config file
+settings-invalid-strict = `{ $var }` has to be `true` or `false`, not `{ $value }`
+settings-invalid-escalate = `{ $var }` has to be `sudo` or `doas`, not `{ $value }`
+settings-invalid-env = invalid environment variable
+settings-path-relative = `{ $name }` has to be an absolute path, not `{ $path }`
+
+## Errors of previews
+
+preview-create-dir-failed = failed to create preview directory
+preview-no-bwrap = previewing the install script needs bubblewrap (`bwrap`) to be installed
+preview-bwrap-failed = failed to run `bwrap`
+preview-script-failed = the install script failed in the preview sandbox ({ $status })
+preview-read-changes-failed = failed to read preview changes
+
+## Errors of escalation
+
+escalate-id-failed = failed to run `id`
+escalate-run-failed = failed to run `{ $tool }`
+
+## Errors of containers
+
+container-empty-image = container image can't be empty
+container-invalid-volume = volume `{ $volume }` must be in the format of `/host/path:/container/path`
+container-engine-missing = `{ $engine }` is not installed, so the container can't be run
+container-empty-name = { $tool } container name can't be empty
+container-tool-missing = `{ $tool }` is not installed, so the container can't be entered
+
+## Errors of commands
+
+aborted = aborted
+executable-and-tag = an executable and `--tag` can't be used together
+executable-or-tag = an executable or `--tag` is required
+manifest-problems = manifest has { $count } { $count ->
+    [one] problem
+   *[other] problems
+    }
+verify-broken = { $broken } { $broken ->
+    [one] wrapper has
+   *[other] wrappers have
+    } problems
+verify-unexpected = { $unexpected } { $unexpected ->
+    [one] file differs
+   *[other] files differ
+    } from their package for reasons other than wrapping
+verify-broken-and-unexpected = { $broken } { $broken ->
+    [one] wrapper has
+   *[other] wrappers have
+    } problems, and { $unexpected } { $unexpected ->
+    [one] file differs
+   *[other] files differ
+    } from their package for reasons other than wrapping
+apply-check-failed = { $count } { $count ->
+    [one] wrapper
+   *[other] wrappers
+    } could not be checked
+progress-items-failed = { $failed } of { $total } items failed
+prompt-flush-failed = failed to flush stdout
+prompt-read-failed = failed to read answer from stdin
+mask-already-wrapped = `{ $path }` is already wrapped; unwrap `{ $wrapper }` before masking it
+which-not-found-path = no executable exists at `{ $path }`
+which-not-found = `{ $command }` not found in `$PATH`
+simulate-no-profile = `{ $path }` has no profile named `{ $name }`
+simulate-runtime-command = the command line of a container or snap is only put together when the wrapper runs; use `{ $program } which { $path }` to see how it's launched
+daemon-no-interface = no interface to serve; use `--dbus`
+daemon-register-failed = failed to register service on the system bus
+daemon-no-sender = message has no sender
+daemon-not-authorized = not authorized for `{ $action }`
+diff-run-failed = failed to run diff
+diff-pass-failed = failed to pass regenerated file to diff
+diff-wait-failed = failed to wait for diff
+diff-failed = diff failed for `{ $path }` ({ $status })
+bench-no-runs = the number of runs must be at least 1
+bench-status-differs = the wrapper exited with { $wrapped }, but `{ $path }` exited with { $unwrapped }
+export-invalid-function-name = the name of the executable can't be used as the name of a function
+export-function-params = only arguments and environment variables can be given by a shell function
+export-fish-env = environment variables that reference other variables or run commands can only be used with bash
+export-unknown-format = unknown export format `{ $format }`
+bench-run-failed = failed to run `{ $path }`
+
+## Errors of the system
+
+symlink-no-file-name = symlink path has no file name
+symlink-create-failed = failed to create symlink
+symlink-replace-failed = failed to replace symlink
+symlink-unsupported = symlinks are only supported on unix
+metadata-read-failed = failed to read wrapper metadata
+metadata-parse-failed = failed to parse wrapper metadata
+metadata-write-failed = failed to write wrapper metadata
+metadata-serialize-failed = failed to serialize wrapper metadata
+session-create-dir-failed = failed to create session directory
+git-not-repository = not inside a git repository
+git-run-failed = failed to run git
+git-failed = `git { $args }` failed: { $error }
+pacman-lock-check-failed = failed to check for pacman lock file
+pacman-lock-timeout = timed out waiting for the pacman transaction to finish (lock file `{ $path }` still exists)
+pacman-lock-locked = a pacman transaction is in progress (lock file `{ $path }` exists); wait for it to finish or use `--wait-for-pacman`
+pacman-files-failed = failed to run `pacman -Qkk`
+snapshot-run-failed = failed to run `{ $tool }`
+snapshot-failed = `{ $tool }` failed to create a snapshot ({ $status })
+snapshot-no-tool = a snapshot was requested, but neither `snapper` nor `timeshift` is installed
+mount-read-only = `{ $path }` is on a read-only filesystem mounted at `{ $mount }`, so it can't be replaced with a wrapper; on image-based systems (such as rpm-ostree based distributions), only executables in writable directories like `/usr/local/bin` or `/opt` can be wrapped
+complete-usage = usage: { $program } { $command } <paths|presets|tags> [prefix]
+complete-unknown = unknown completion `{ $kind }`
+registry-no-tagged = no wrapper is tagged { $tags }
+ffi-null-arg = `{ $name }` is null
+ffi-invalid-utf8 = `{ $name }` isn't valid UTF-8
+ffi-parse-failed = failed to parse the wrapper
+ffi-panicked = { $program } panicked
+registry-tag-separator = { " or " }
+
+## Errors of parameters
+
+cpu-not-number = `{ $cpu }` in CPU list `{ $list }` is not a CPU number
+cpu-range-reversed = range `{ $range }` in CPU list `{ $list }` is reversed
+cpu-stride-without-range = stride in CPU list `{ $list }` can only follow a range
+cpu-invalid-stride = stride `{ $stride }` in CPU list `{ $list }` must be a positive number
+missing-separator = missing '=' separator
+limit-unknown-resource = unknown resource `{ $resource }`, expected one of: { $known }
+limit-invalid-value = limit `{ $value }` must be a number or `unlimited`
+hours-invalid-time = `{ $time }` is not a time of day in the format of `08:00`
+hours-invalid = allowed hours `{ $hours }` must be a start and an end, such as `08:00-18:00`
+hours-empty = allowed hours `{ $hours }` start and end at the same time
+jvm-unknown-gc = unknown garbage collector `{ $gc }`, expected one of: { $known }
+jvm-invalid-heap-size = `{ $size }` is not a heap size, such as `512m` or `4g`
+jvm-invalid-option = JVM option `{ $option }` can't be empty or contain whitespace
+env-invalid-name = invalid name for environment variable `{ $name }`
+via-unknown = unknown proxy program `{ $program }`, expected one of: { $known }
+via-not-installed = `{ $program }` is not installed, so nothing can be routed through it
+status-file-relative = status file `{ $path }` must be absolute or start with a variable
+snap-invalid-app = `{ $app }` is not the name of a snap app, such as `firefox` or `lxd.lxc`
+access-invalid-name = `{ $name }` is not a valid user or group name
+access-allow-user = allow user { $name }
+access-allow-group = allow group { $name }
+access-deny-user = deny user { $name }
+access-deny-group = deny group { $name }
+exec-type-elf = an ELF executable
+exec-type-shared-library = a shared library
+exec-type-elf-other = an ELF file that isn't an executable
+exec-type-script = a script
+exec-type-mach-o = a Mach-O binary for macOS
+exec-type-data = a data file
@@ -0,0 +1,271 @@
+use std::fmt::{self, Write};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{i18n::t, shell};
+
+/// Tag of the entries a wrapper writes to the system log when it refuses to run.
+const LOG_TAG: &str = env!("CARGO_PKG_NAME");
+
+/// Exit status of a wrapper that refuses to run, which is what shells use for a command that can't be run.
+const DENIED_EXIT_CODE: u8 = 126;
+
+/// Which users can run a wrapper, on top of the permissions of the file.
+///
+/// Denying a user or one of their groups takes precedence over allowing them. If anyone is allowed, everyone else is
+/// denied.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default, deny_unknown_fields)]
+pub struct Access {
+    /// Users allowed to run the wrapper.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allow_users: Vec<String>,
+    /// Groups whose members are allowed to run the wrapper.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub allow_groups: Vec<String>,
+    /// Users that can't run the wrapper.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny_users: Vec<String>,
+    /// Groups whose members can't run the wrapper.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub deny_groups: Vec<String>,
+}
+
+impl Access {
+    pub fn is_empty(&self) -> bool {
+        self.allow_users.is_empty()
+            && self.allow_groups.is_empty()
+            && self.deny_users.is_empty()
+            && self.deny_groups.is_empty()
+    }
+
+    /// Add the users and groups of `other`, removing any repeated ones.
+    pub fn merge(&mut self, other: Access) {
+        for (names, other) in [
+            (&mut self.allow_users, other.allow_users),
+            (&mut self.allow_groups, other.allow_groups),
+            (&mut self.deny_users, other.deny_users),
+            (&mut self.deny_groups, other.deny_groups),
+        ] {
+            for name in other {
+                if !names.contains(&name) {
+                    names.push(name);
+                }
+            }
+        }
+    }
+
+    pub fn validate(&self) -> anyhow::Result<()> {
+        for name in self
+            .allow_users
+            .iter()
+            .chain(&self.allow_groups)
+            .chain(&self.deny_users)
+            .chain(&self.deny_groups)
+        {
+            validate_name(name)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the bash lines that stop a wrapper of `wrapped_path` from running for users these rules don't allow,
+    /// logging each refusal to the system log with `logger` and printing `denied_message` to stderr.
+    ///
+    /// `denied_message` is already quoted, and can refer to the user as `$access_user`.
+    pub fn write_bash_lines(
+        &self,
+        wrapped_path: &str,
+        denied_message: &str,
+        writer: &mut impl Write,
+    ) -> fmt::Result {
+        if self.is_empty() {
+            return Ok(());
+        }
+
+        let allowed_by_default = self.allow_users.is_empty() && self.allow_groups.is_empty();
+
+        writeln!(writer, "access_allowed={}", u8::from(allowed_by_default))?;
+        writeln!(writer, r#"access_user="$(id -un 2>/dev/null || id -u)""#)?;
+        writeln!(writer, r#"access_groups=" $(id -Gn 2>/dev/null) ""#)?;
+
+        // later checks override earlier ones, so denying comes last
+        write_user_check(&self.allow_users, 1, writer)?;
+        write_group_check(&self.allow_groups, 1, writer)?;
+        write_user_check(&self.deny_users, 0, writer)?;
+        write_group_check(&self.deny_groups, 0, writer)?;
+
+        let log_message = format!("refused to run {wrapped_path} for user ");
+
+        writeln!(writer, r#"if [ "$access_allowed" != 1 ]; then"#)?;
+        writeln!(
+            writer,
+            r#"    logger -t {LOG_TAG} -p auth.warning -- {}"$access_user" 2>/dev/null || true"#,
+            shell::quote(&log_message)
+        )?;
+        writeln!(writer, "    printf '%s\\n' {denied_message} >&2")?;
+        writeln!(writer, "    exit {DENIED_EXIT_CODE}")?;
+        writeln!(writer, "fi")
+    }
+}
+
+impl fmt::Display for Access {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let rules = self
+            .allow_users
+            .iter()
+            .map(|name| t!("access-allow-user", name = name.as_str()))
+            .chain(
+                self.allow_groups
+                    .iter()
+                    .map(|name| t!("access-allow-group", name = name.as_str())),
+            )
+            .chain(
+                self.deny_users
+                    .iter()
+                    .map(|name| t!("access-deny-user", name = name.as_str())),
+            )
+            .chain(
+                self.deny_groups
+                    .iter()
+                    .map(|name| t!("access-deny-group", name = name.as_str())),
+            );
+
+        for (i, rule) in rules.enumerate() {
+            if i > 0 {
+                f.write_str(", ")?;
+            }
+
+            f.write_str(&rule)?;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_user_check(users: &[String], allowed: u8, writer: &mut impl Write) -> fmt::Result {
+    if users.is_empty() {
+        return Ok(());
+    }
+
+    let patterns = users
+        .iter()
+        .map(|user| shell::quote(user))
+        .collect::<Vec<_>>()
+        .join("|");
+
+    writeln!(
+        writer,
+        r#"case "$access_user" in {patterns}) access_allowed={allowed} ;; esac"#
+    )
+}
+
+fn write_group_check(groups: &[String], allowed: u8, writer: &mut impl Write) -> fmt::Result {
+    for group in groups {
+        writeln!(
+            writer,
+            r#"case "$access_groups" in *' '{}' '*) access_allowed={allowed} ;; esac"#,
+            shell::quote(group)
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Check that `name` is a valid user or group name, like `useradd` and `groupadd` accept.
+pub fn validate_name(name: &str) -> anyhow::Result<()> {
+    let valid = !name.is_empty()
+        && !name.starts_with('-')
+        && name
+            .strip_suffix('$')
+            .unwrap_or(name)
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | '.'));
+
+    anyhow::ensure!(valid, t!("access-invalid-name", name = name));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    fn run_as_current_user(access: &Access) -> bool {
+        let mut script = String::new();
+        access
+            .write_bash_lines("/usr/bin/test_bin", "denied", &mut script)
+            .unwrap();
+        script.push_str("echo ran\n");
+
+        let output = Command::new("bash").arg("-c").arg(script).output().unwrap();
+        output.status.success()
+    }
+
+    fn current(flag: &str) -> String {
+        let output = Command::new("id").arg(flag).output().unwrap();
+        String::from_utf8(output.stdout).unwrap().trim().to_owned()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn enforces_rules() {
+        let user = current("-un");
+        let group = current("-gn");
+
+        assert!(run_as_current_user(&Access::default()));
+
+        assert!(run_as_current_user(&Access {
+            allow_users: vec!["nobody-else".into(), user.clone()],
+            ..Default::default()
+        }));
+
+        assert!(!run_as_current_user(&Access {
+            allow_users: vec!["nobody-else".into()],
+            ..Default::default()
+        }));
+
+        assert!(run_as_current_user(&Access {
+            allow_groups: vec![group.clone()],
+            ..Default::default()
+        }));
+
+        // denying wins over allowing
+        assert!(!run_as_current_user(&Access {
+            allow_groups: vec![group.clone()],
+            deny_users: vec![user],
+            ..Default::default()
+        }));
+
+        assert!(!run_as_current_user(&Access {
+            deny_groups: vec![group],
+            ..Default::default()
+        }));
+    }
+
+    #[test]
+    fn names() {
+        assert!(validate_name("wheel").is_ok());
+        assert!(validate_name("build_bot-1.2").is_ok());
+        assert!(validate_name("machine$").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("-rf").is_err());
+        assert!(validate_name("a b").is_err());
+        assert!(validate_name("$(id)").is_err());
+    }
+
+    #[test]
+    fn describes_rules() {
+        let access = Access {
+            allow_groups: vec!["wheel".into()],
+            deny_users: vec!["guest".into(), "intern".into()],
+            ..Default::default()
+        };
+
+        assert_eq!(
+            access.to_string(),
+            "allow group wheel, deny user guest, deny user intern"
+        );
+    }
+}
@@ -0,0 +1,106 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use anyhow::Context;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::i18n::t;
+
+/// A set of CPUs to confine a process to, in the list format accepted by `taskset -c`, such as `0-3,8,10-14:2`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CpuList(String);
+
+impl CpuList {
+    /// Arguments to prefix a command with so it only runs on these CPUs.
+    pub fn launcher(&self) -> Vec<String> {
+        vec!["taskset".into(), "-c".into(), self.0.clone()]
+    }
+}
+
+impl FromStr for CpuList {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let parse_cpu = |cpu: &str| {
+            cpu.parse::<u32>()
+                .with_context(|| t!("cpu-not-number", cpu = cpu, list = s))
+        };
+
+        for item in s.split(',') {
+            let (range, stride) = match item.split_once(':') {
+                Some((range, stride)) => (range, Some(stride)),
+                None => (item, None),
+            };
+
+            match range.split_once('-') {
+                Some((first, last)) => {
+                    anyhow::ensure!(
+                        parse_cpu(first)? <= parse_cpu(last)?,
+                        t!("cpu-range-reversed", range = range, list = s)
+                    );
+                }
+                None => {
+                    parse_cpu(range)?;
+                    anyhow::ensure!(stride.is_none(), t!("cpu-stride-without-range", list = s));
+                }
+            }
+
+            if let Some(stride) = stride {
+                anyhow::ensure!(
+                    stride.parse::<u32>().is_ok_and(|stride| stride > 0),
+                    t!("cpu-invalid-stride", stride = stride, list = s)
+                );
+            }
+        }
+
+        Ok(Self(s.to_owned()))
+    }
+}
+
+impl fmt::Display for CpuList {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl schemars::JsonSchema for CpuList {
+    fn schema_name() -> Cow<'static, str> {
+        "CpuList".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Comma-separated CPU numbers and ranges, such as `0-3,8`.",
+            "type": "string",
+            "pattern": "^[0-9]+(-[0-9]+(:[0-9]+)?)?(,[0-9]+(-[0-9]+(:[0-9]+)?)?)*$",
+        })
+    }
+}
+
+impl Serialize for CpuList {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for CpuList {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_cpu_list() {
+        for valid in ["0", "0-3", "0-3,8", "0-15:2,20"] {
+            assert_eq!(valid.parse::<CpuList>().unwrap().to_string(), valid);
+        }
+
+        for invalid in ["", "a", "3-0", "0-", "0,,1", "4:2", "0-7:0", "0 - 3"] {
+            assert!(invalid.parse::<CpuList>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,277 @@
+use std::{
+    fmt::{self, Write},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::{file, settings};
+
+/// Directory where the kernel exposes the loaded AppArmor policy.
+const SECURITYFS_DIR: &str = "/sys/kernel/security/apparmor";
+
+/// Mode of a profile that only exists to attach to an executable, without confining it.
+const UNCONFINED_MODE: &str = "unconfined";
+
+/// A loaded AppArmor profile.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Profile {
+    pub name: String,
+    /// How the profile is enforced, such as `enforce` or `complain`.
+    pub mode: String,
+    /// Pattern of the executables the profile attaches to, which is its name if it's a path.
+    attach: String,
+}
+
+/// The loaded AppArmor profiles that confine the executable at `path`, which is empty if AppArmor isn't enabled or its
+/// policy can't be read, such as when not running as root.
+pub fn attached_profiles(path: &Path) -> Vec<Profile> {
+    let path = path.to_string_lossy();
+
+    read_profiles(Path::new(SECURITYFS_DIR))
+        .into_iter()
+        .filter(|profile| profile.mode != UNCONFINED_MODE && glob_matches(&profile.attach, &path))
+        .collect()
+}
+
+/// Read the profiles loaded into the AppArmor policy exposed at `dir`.
+fn read_profiles(dir: &Path) -> Vec<Profile> {
+    let Ok(entries) = fs::read_dir(dir.join("policy").join("profiles")) else {
+        return read_profile_list(dir);
+    };
+
+    let read = |dir: &Path, name: &str| {
+        fs::read_to_string(dir.join(name)).map(|content| content.trim().to_owned())
+    };
+
+    entries
+        .filter_map(|entry| {
+            let dir = entry.ok()?.path();
+            let name = read(&dir, "name").ok()?;
+            let mode = read(&dir, "mode").ok()?;
+
+            // profiles that don't attach to anything by path report their name in angle brackets
+            let attach = read(&dir, "attach")
+                .ok()
+                .filter(|attach| !attach.is_empty() && !attach.starts_with('<'))
+                .unwrap_or_else(|| name.clone());
+
+            Some(Profile { name, mode, attach })
+        })
+        .collect()
+}
+
+/// Read the list of profiles, where each line is a name followed by its mode in parentheses, for kernels that don't
+/// expose what each profile attaches to.
+fn read_profile_list(dir: &Path) -> Vec<Profile> {
+    let Ok(list) = fs::read_to_string(dir.join("profiles")) else {
+        return Vec::new();
+    };
+
+    list.lines()
+        .filter_map(|line| {
+            let (name, mode) = line.trim().rsplit_once(" (")?;
+            let mode = mode.strip_suffix(')')?;
+
+            Some(Profile {
+                name: name.to_owned(),
+                mode: mode.to_owned(),
+                attach: name.to_owned(),
+            })
+        })
+        .collect()
+}
+
+/// Whether `path` matches the AppArmor pattern `pattern`, where `*` matches anything but `/`, `**` matches anything,
+/// `?` matches a single character other than `/`, and `{a,b}` matches either alternative.
+fn glob_matches(pattern: &str, path: &str) -> bool {
+    if !pattern.starts_with('/') {
+        return false;
+    }
+
+    expand_alternatives(pattern)
+        .iter()
+        .any(|pattern| wildcard_matches(pattern.as_bytes(), path.as_bytes()))
+}
+
+/// Every pattern `pattern` stands for once each `{a,b}` is replaced by one of its alternatives.
+fn expand_alternatives(pattern: &str) -> Vec<String> {
+    let Some(open) = pattern.find('{') else {
+        return vec![pattern.to_owned()];
+    };
+
+    // the closing brace of this group, skipping over those of nested groups
+    let mut depth = 0;
+    let mut close = None;
+    let mut separators = Vec::new();
+
+    for (index, ch) in pattern[open..].char_indices() {
+        match ch {
+            '{' => depth += 1,
+            '}' if depth == 1 => {
+                close = Some(open + index);
+                break;
+            }
+            '}' => depth -= 1,
+            ',' if depth == 1 => separators.push(open + index),
+            _ => {}
+        }
+    }
+
+    let Some(close) = close else {
+        return vec![pattern.to_owned()];
+    };
+
+    let bounds = std::iter::once(open)
+        .chain(separators)
+        .chain(std::iter::once(close))
+        .collect::<Vec<_>>();
+
+    bounds
+        .windows(2)
+        .flat_map(|bound| {
+            let alternative = &pattern[bound[0] + 1..bound[1]];
+            expand_alternatives(&format!(
+                "{}{alternative}{}",
+                &pattern[..open],
+                &pattern[close + 1..]
+            ))
+        })
+        .collect()
+}
+
+fn wildcard_matches(pattern: &[u8], path: &[u8]) -> bool {
+    match pattern {
+        [] => path.is_empty(),
+        [b'*', b'*', rest @ ..] => {
+            (0..=path.len()).any(|skip| wildcard_matches(rest, &path[skip..]))
+        }
+        [b'*', rest @ ..] => {
+            let segment = path.iter().position(|&ch| ch == b'/').unwrap_or(path.len());
+            (0..=segment).any(|skip| wildcard_matches(rest, &path[skip..]))
+        }
+        [b'?', rest @ ..] => {
+            matches!(path, [ch, ..] if *ch != b'/') && wildcard_matches(rest, &path[1..])
+        }
+        [ch, rest @ ..] => path.first() == Some(ch) && wildcard_matches(rest, &path[1..]),
+    }
+}
+
+/// Write the rules `profile` needs to let the wrapper at `wrapped` run its original executable at `unwrapped` under
+/// the same profile, instead of being denied or leaving it unconfined.
+pub fn write_snippet(
+    profile: &Profile,
+    wrapped: &Path,
+    unwrapped: &Path,
+    mut writer: impl Write,
+) -> fmt::Result {
+    // rules match the paths with symlinks resolved, which is how AppArmor sees executables
+    let resolve = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_owned());
+
+    writeln!(
+        writer,
+        "# /etc/apparmor.d/local/{}",
+        local_override_name(&profile.name)
+    )?;
+    writeln!(writer, "{} r,", resolve(wrapped).display())?;
+
+    for interpreter in interpreters() {
+        writeln!(writer, "{} ix,", resolve(&interpreter).display())?;
+    }
+
+    writeln!(writer, "{} mrix,", resolve(unwrapped).display())
+}
+
+/// Name of the local override of the profile named `name`, which by convention is that of the file the profile is
+/// defined in, such as `usr.bin.foo` for `/usr/bin/foo`.
+fn local_override_name(name: &str) -> String {
+    name.trim_start_matches('/').replace('/', ".")
+}
+
+/// The programs the shebang of the wrapper runs it with, such as `env` followed by the bash it finds.
+fn interpreters() -> Vec<PathBuf> {
+    let shebang = settings::get().shebang();
+    let mut words = shebang.split_whitespace();
+
+    let Some(program) = words.next() else {
+        return Vec::new();
+    };
+
+    let mut interpreters = vec![PathBuf::from(program)];
+
+    if Path::new(program)
+        .file_name()
+        .is_some_and(|name| name == "env")
+        && let Some(shell) = words.next().and_then(file::resolve_command)
+    {
+        interpreters.push(shell);
+    }
+
+    interpreters
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn matches_attachments() {
+        assert!(glob_matches("/usr/bin/foo", "/usr/bin/foo"));
+        assert!(glob_matches("/usr/bin/foo{,-bin}", "/usr/bin/foo-bin"));
+        assert!(glob_matches("/usr/{,s}bin/foo", "/usr/sbin/foo"));
+        assert!(glob_matches(
+            "/usr/lib/firefox/firefox{,.sh,-b{in,ar}}",
+            "/usr/lib/firefox/firefox-bar"
+        ));
+        assert!(glob_matches("/usr/bin/*", "/usr/bin/foo"));
+        assert!(glob_matches("/opt/**", "/opt/app/bin/foo"));
+        assert!(glob_matches("/usr/bin/fo?", "/usr/bin/foo"));
+
+        assert!(!glob_matches("/usr/bin/*", "/usr/bin/nested/foo"));
+        assert!(!glob_matches("/usr/bin/foo", "/usr/bin/foobar"));
+        assert!(!glob_matches("firefox", "/usr/bin/firefox"));
+    }
+
+    #[test]
+    fn reads_loaded_profiles() {
+        let temp_dir = test_util::temp_dir("apparmor");
+        let dir = temp_dir.path();
+
+        fs::write(
+            dir.join("profiles"),
+            "/usr/bin/foo (enforce)\nfirefox (complain)\n",
+        )
+        .unwrap();
+
+        assert_eq!(
+            read_profiles(dir),
+            [
+                Profile {
+                    name: "/usr/bin/foo".to_owned(),
+                    mode: "enforce".to_owned(),
+                    attach: "/usr/bin/foo".to_owned(),
+                },
+                Profile {
+                    name: "firefox".to_owned(),
+                    mode: "complain".to_owned(),
+                    attach: "firefox".to_owned(),
+                },
+            ]
+        );
+
+        let profile = dir.join("policy").join("profiles").join("firefox.1");
+        fs::create_dir_all(&profile).unwrap();
+        fs::write(profile.join("name"), "firefox\n").unwrap();
+        fs::write(profile.join("mode"), "enforce\n").unwrap();
+        fs::write(profile.join("attach"), "/usr/lib/firefox/firefox\n").unwrap();
+
+        assert_eq!(
+            read_profiles(dir),
+            [Profile {
+                name: "firefox".to_owned(),
+                mode: "enforce".to_owned(),
+                attach: "/usr/lib/firefox/firefox".to_owned(),
+            }]
+        );
+    }
+}
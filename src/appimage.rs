@@ -0,0 +1,79 @@
+use std::{fs::File, io::Read, path::Path};
+
+/// Bytes in the padding of the ELF header that identify an AppImage, followed by its type.
+const MAGIC: &[u8] = b"AI";
+
+/// Offset of [`MAGIC`] from the start of the file.
+const MAGIC_OFFSET: usize = 8;
+
+/// Extension that AppImages are recognized by, such as by desktop integration tools.
+const EXTENSION: &str = ".AppImage";
+
+/// Returns true if the file at `path` is an AppImage of either type.
+pub fn is_appimage(path: &Path) -> bool {
+    let mut header = [0; MAGIC_OFFSET + MAGIC.len() + 1];
+
+    let Ok(mut file) = File::open(path) else {
+        return false;
+    };
+
+    file.read_exact(&mut header).is_ok()
+        && header[1..4] == *b"ELF"
+        && header[MAGIC_OFFSET..MAGIC_OFFSET + MAGIC.len()] == *MAGIC
+        && matches!(header[MAGIC_OFFSET + MAGIC.len()], 1 | 2)
+}
+
+/// Filename of the original AppImage named `filename` once it's wrapped.
+///
+/// The name keeps the extension, so the image is still recognized as an AppImage, and stays the same when a new
+/// version of the image replaces the wrapper.
+pub fn unwrapped_filename(filename: &str) -> String {
+    let stem = filename
+        .len()
+        .checked_sub(EXTENSION.len())
+        .filter(|&split| {
+            filename.is_char_boundary(split) && filename[split..].eq_ignore_ascii_case(EXTENSION)
+        })
+        .map_or(filename, |split| &filename[..split]);
+
+    format!(".{stem}-unwrapped{EXTENSION}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn detects_appimages() {
+        let dir = test_util::temp_dir("appimage");
+        let path = dir.path().join("app");
+
+        fs::write(&path, b"\x7fELF\x02\x01\x01\x00AI\x02\x00\x00").unwrap();
+        assert!(is_appimage(&path));
+
+        fs::write(&path, b"\x7fELF\x02\x01\x01\x00\x00\x00\x00\x00").unwrap();
+        assert!(!is_appimage(&path));
+
+        fs::write(&path, b"#!/bin/sh").unwrap();
+        assert!(!is_appimage(&path));
+    }
+
+    #[test]
+    fn unwrapped_filenames() {
+        assert_eq!(
+            unwrapped_filename("Obsidian.AppImage"),
+            ".Obsidian-unwrapped.AppImage"
+        );
+        assert_eq!(
+            unwrapped_filename("kdenlive.appimage"),
+            ".kdenlive-unwrapped.AppImage"
+        );
+        assert_eq!(
+            unwrapped_filename("kdenlive"),
+            ".kdenlive-unwrapped.AppImage"
+        );
+    }
+}
@@ -0,0 +1,97 @@
+pub(crate) mod apply;
+mod bench;
+mod check;
+#[cfg(feature = "dbus")]
+mod daemon;
+mod diff;
+mod edit;
+mod export;
+mod list;
+mod mask;
+mod preflight;
+mod purge;
+mod run;
+mod schema;
+mod simulate;
+mod unwrap;
+mod validate;
+mod verify;
+#[cfg(target_os = "linux")]
+mod watch_config;
+mod which;
+pub(crate) mod wrap;
+
+use std::path::Path;
+
+use argh::FromArgs;
+
+use crate::{git, pacman_hook};
+
+// only a single command is ever parsed, so the size of the largest one doesn't matter
+#[allow(clippy::large_enum_variant)]
+#[derive(FromArgs)]
+#[argh(subcommand)]
+pub enum Command {
+    Wrap(wrap::Args),
+    Run(run::Args),
+    Apply(apply::Args),
+    Check(check::Args),
+    Edit(edit::Args),
+    Validate(validate::Args),
+    Schema(schema::Args),
+    Purge(purge::Args),
+    Unwrap(unwrap::Args),
+    Mask(mask::Args),
+    Verify(verify::Args),
+    List(list::Args),
+    Which(which::Args),
+    Simulate(simulate::Args),
+    Bench(bench::Args),
+    Diff(diff::Args),
+    Export(export::Args),
+    Preflight(preflight::Args),
+    #[cfg(target_os = "linux")]
+    WatchConfig(watch_config::Args),
+    #[cfg(feature = "dbus")]
+    Daemon(daemon::Args),
+}
+
+impl Command {
+    pub fn run(self) -> anyhow::Result<()> {
+        match self {
+            Self::Wrap(args) => args.run(),
+            Self::Run(args) => args.run(),
+            Self::Apply(args) => args.run(),
+            Self::Check(args) => args.run(),
+            Self::Edit(args) => args.run(),
+            Self::Validate(args) => args.run(),
+            Self::Schema(args) => args.run(),
+            Self::Purge(args) => args.run(),
+            Self::Unwrap(args) => args.run(),
+            Self::Mask(args) => args.run(),
+            Self::Verify(args) => args.run(),
+            Self::List(args) => args.run(),
+            Self::Which(args) => args.run(),
+            Self::Simulate(args) => args.run(),
+            Self::Bench(args) => args.run(),
+            Self::Diff(args) => args.run(),
+            Self::Export(args) => args.run(),
+            Self::Preflight(args) => args.run(),
+            #[cfg(target_os = "linux")]
+            Self::WatchConfig(args) => args.run(),
+            #[cfg(feature = "dbus")]
+            Self::Daemon(args) => args.run(),
+        }
+    }
+}
+
+/// Describe running `action` on the executable at `path`, for snapshots and commits that record the change.
+fn change_description(action: &str, path: &Path) -> String {
+    format!("{}: {action} {}", env!("CARGO_PKG_NAME"), path.display())
+}
+
+/// Commit the hooks of the executable at `path` to the git repository containing them after running `action` on it.
+fn commit_hooks(action: &str, path: &Path) -> anyhow::Result<()> {
+    let hook_paths = pacman_hook::all_paths(&pacman_hook::hook_name(path));
+    git::commit(&hook_paths, &change_description(action, path))
+}
@@ -0,0 +1,250 @@
+use std::{
+    num::NonZeroUsize,
+    path::{self, Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use argh::FromArgs;
+
+use crate::{
+    cmd::wrap,
+    error::{ChangesNeeded, IoError},
+    git,
+    i18n::t,
+    manifest::{self, Manifest},
+    pacman_hook, pacman_lock,
+    progress::Progress,
+    wrapper,
+};
+
+#[derive(FromArgs)]
+/// Create wrappers for every entry in a TOML or JSON manifest.
+#[argh(subcommand, name = "apply")]
+pub struct Args {
+    /// path to the manifest, or a directory of manifests merged in lexical order; reads from stdin if omitted or `-`
+    #[argh(positional)]
+    manifest_path: Option<PathBuf>,
+
+    /// only report which wrappers would change, exiting with code 2 if any would, without modifying anything
+    #[argh(switch, long = "check")]
+    check: bool,
+
+    /// wait up to this many seconds for an in-progress pacman transaction to finish instead of aborting
+    #[argh(option, long = "wait-for-pacman")]
+    wait_for_pacman: Option<u64>,
+
+    /// commit the hooks and the manifest to the git repository containing them (such as `/etc` with etckeeper)
+    /// afterwards
+    #[argh(switch, long = "git-commit")]
+    git_commit: bool,
+}
+
+impl Args {
+    /// Arguments that apply the manifest at `manifest_path`, for commands that apply one on their own.
+    #[cfg(target_os = "linux")]
+    pub fn new(manifest_path: PathBuf, wait_for_pacman: Option<u64>, git_commit: bool) -> Self {
+        Self {
+            manifest_path: Some(manifest_path),
+            check: false,
+            wait_for_pacman,
+            git_commit,
+        }
+    }
+
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut manifest = manifest::load(self.manifest_path.as_deref())?;
+
+        for entry in &mut manifest.wrappers {
+            for name in entry.params.dedup() {
+                eprintln!(
+                    "{}",
+                    t!(
+                        "apply-duplicate-env-var",
+                        path = entry.path.display().to_string(),
+                        name = name,
+                    )
+                );
+            }
+        }
+
+        manifest.resolve_defaults();
+
+        let use_pacman_hooks = manifest
+            .wrappers
+            .iter()
+            .map(|entry| {
+                let (use_pacman_hooks, notice) = use_pacman_hooks(entry);
+
+                if let Some(notice) = notice {
+                    println!("{notice}");
+                }
+
+                use_pacman_hooks
+            })
+            .collect::<Vec<_>>();
+
+        if !self.check && use_pacman_hooks.iter().any(|&use_hooks| use_hooks) {
+            pacman_lock::ensure_unlocked(self.wait_for_pacman.map(Duration::from_secs))?;
+        }
+
+        let entries = manifest
+            .wrappers
+            .iter()
+            .zip(use_pacman_hooks)
+            .collect::<Vec<_>>();
+
+        // generating wrappers has no side effects, so that can be done in parallel, but installing them
+        // is done one at a time so that hook and file writes never interleave
+        let staged = parallel_map(&entries, |&(entry, use_pacman_hooks)| {
+            stage(entry, use_pacman_hooks)
+        });
+
+        if self.check {
+            return check(staged);
+        }
+
+        let mut progress = Progress::new(t!("progress-wrapping"), manifest.wrappers.len());
+
+        for (entry, staged) in manifest.wrappers.iter().zip(staged) {
+            progress.start(&entry.path);
+
+            let unchanged = staged.as_ref().is_ok_and(|staged| staged.is_up_to_date());
+            let result = staged.and_then(wrap::install);
+
+            if unchanged {
+                progress.skip_item();
+            } else {
+                progress.finish_item(&entry.path, result);
+            }
+        }
+
+        // whatever was installed is committed even if other entries failed, so the repository matches the system
+        if self.git_commit {
+            commit(&manifest, self.manifest_path.as_deref())?;
+        }
+
+        progress.finish()
+    }
+}
+
+/// Whether the wrapper of `entry` gets `pacman` hooks, along with the notice explaining why it doesn't if it wasn't
+/// told not to.
+pub(crate) fn use_pacman_hooks(entry: &manifest::Entry) -> (bool, Option<String>) {
+    let explicit = wrap::replace_pacman_hooks(
+        entry.hooks,
+        entry.params.user_units,
+        entry.params.backend.as_deref(),
+    );
+
+    wrap::decide_pacman_hooks(&entry.path, explicit)
+}
+
+/// Generate the wrapper of `entry`, which regenerates an existing wrapper with the entry's parameters.
+pub(crate) fn stage(
+    entry: &manifest::Entry,
+    use_pacman_hooks: bool,
+) -> anyhow::Result<wrapper::Staged<'_>> {
+    let mode = if wrapper::ExecPaths::try_from_path(&entry.path)?.is_wrapped()? {
+        wrapper::Mode::Update
+    } else {
+        wrapper::Mode::Create
+    };
+
+    wrap::stage(&entry.path, &entry.params, use_pacman_hooks, mode)
+}
+
+/// Commit the hooks of every entry in `manifest`, along with the manifest itself if it was read from a file.
+fn commit(manifest: &Manifest, manifest_path: Option<&Path>) -> anyhow::Result<()> {
+    let manifest_path = manifest_path.filter(|path| *path != Path::new("-"));
+
+    let mut paths = manifest
+        .wrappers
+        .iter()
+        .flat_map(|entry| pacman_hook::all_paths(&pacman_hook::hook_name(&entry.path)))
+        .collect::<Vec<_>>();
+
+    // a manifest outside of the repository is left out
+    if let Some(manifest_path) = manifest_path {
+        paths.push(
+            path::absolute(manifest_path)
+                .map_err(|err| IoError::new(manifest_path, anyhow::Error::new(err)))?,
+        );
+    }
+
+    let message = match manifest_path {
+        Some(path) => format!("{}: apply {}", env!("CARGO_PKG_NAME"), path.display()),
+        None => format!("{}: apply manifest from stdin", env!("CARGO_PKG_NAME")),
+    };
+
+    git::commit(&paths, &message)
+}
+
+/// Report which staged wrappers would change, returning [`ChangesNeeded`] if any would.
+fn check(staged: Vec<anyhow::Result<wrapper::Staged>>) -> anyhow::Result<()> {
+    let mut changes_needed = false;
+    let mut failed = 0;
+
+    for staged in staged {
+        match staged.and_then(|staged| wrap::check(&staged)) {
+            Ok(()) => {}
+            Err(err) if err.is::<ChangesNeeded>() => changes_needed = true,
+            Err(err) => {
+                eprintln!("{}", t!("error", error = format!("{err:#}")));
+                failed += 1;
+            }
+        }
+    }
+
+    if failed > 0 {
+        anyhow::bail!(t!("apply-check-failed", count = failed));
+    }
+
+    if changes_needed {
+        return Err(ChangesNeeded.into());
+    }
+
+    Ok(())
+}
+
+/// Map every item with `f` across a small pool of threads, preserving the original order in the output.
+fn parallel_map<'a, T, R, F>(items: &'a [T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&'a T) -> R + Sync,
+{
+    let num_threads = thread::available_parallelism().map_or(1, NonZeroUsize::get);
+    let chunk_size = items.len().div_ceil(num_threads).max(1);
+
+    thread::scope(|scope| {
+        let handles = items
+            .chunks(chunk_size)
+            .map(|chunk| scope.spawn(|| chunk.iter().map(&f).collect::<Vec<_>>()))
+            .collect::<Vec<_>>();
+
+        handles
+            .into_iter()
+            .flat_map(|handle| handle.join().expect("staging thread panicked"))
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parallel_map_preserves_order() {
+        let items = (0..100).collect::<Vec<_>>();
+        let result = parallel_map(&items, |i| i * 2);
+
+        assert_eq!(result, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn parallel_map_empty() {
+        let result = parallel_map(&[] as &[u8], |i| *i);
+        assert!(result.is_empty());
+    }
+}
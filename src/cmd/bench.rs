@@ -0,0 +1,164 @@
+#[cfg(unix)]
+use std::os::unix::process::CommandExt;
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, ExitStatus, Stdio},
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+use argh::FromArgs;
+
+use crate::{i18n::t, metadata::Metadata, wrapper};
+
+#[derive(FromArgs)]
+/// Measure how much longer an executable takes to start through its wrapper than without it.
+#[argh(subcommand, name = "bench")]
+pub struct Args {
+    /// absolute path to the wrapped executable
+    #[argh(positional)]
+    executable_path: PathBuf,
+
+    /// arguments both are run with, taken verbatim when placed after `--`, such as `-- --version` for an executable
+    /// that would otherwise wait for input
+    #[argh(positional, greedy)]
+    args: Vec<String>,
+
+    /// number of runs the warm latency is measured over (default: 20)
+    #[argh(option, short = 'n', long = "runs", default = "20")]
+    runs: usize,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        anyhow::ensure!(self.runs > 0, t!("bench-no-runs"));
+
+        let paths = wrapper::ExecPaths::try_from_path(&self.executable_path)?;
+        let params = Metadata::read(&paths.metadata.original)?.params;
+
+        if let Some(message) = wrapper::mask_message(&params, &paths.wrapped_filename) {
+            anyhow::bail!("{}", t!("which-masked", message = message));
+        }
+
+        let wrapped = &paths.wrapped.original;
+        let unwrapped = &paths.unwrapped.original;
+
+        // the original runs first, so the first run of the wrapper doesn't pay for loading the executable
+        let (unwrapped_status, unwrapped_cold) = self.time(unwrapped, wrapped)?;
+        let (wrapped_status, wrapped_cold) = self.time(wrapped, wrapped)?;
+
+        // a wrapper that refuses to run or fails on its own isn't measuring the executable at all
+        anyhow::ensure!(
+            wrapped_status == unwrapped_status,
+            t!(
+                "bench-status-differs",
+                wrapped = wrapped_status.to_string(),
+                path = unwrapped.display().to_string(),
+                unwrapped = unwrapped_status.to_string(),
+            )
+        );
+
+        let mut wrapped_warm = Vec::with_capacity(self.runs);
+        let mut unwrapped_warm = Vec::with_capacity(self.runs);
+
+        // alternating spreads anything else slowing down the system evenly between the two
+        for _ in 0..self.runs {
+            unwrapped_warm.push(self.time(unwrapped, wrapped)?.1);
+            wrapped_warm.push(self.time(wrapped, wrapped)?.1);
+        }
+
+        println!(
+            "{}",
+            t!(
+                "bench-cold",
+                wrapped = format_duration(wrapped_cold),
+                unwrapped = format_duration(unwrapped_cold),
+                overhead = format_overhead(wrapped_cold, unwrapped_cold),
+            )
+        );
+
+        let wrapped_warm = median(wrapped_warm);
+        let unwrapped_warm = median(unwrapped_warm);
+
+        println!(
+            "{}",
+            t!(
+                "bench-warm",
+                runs = self.runs,
+                wrapped = format_duration(wrapped_warm),
+                unwrapped = format_duration(unwrapped_warm),
+                overhead = format_overhead(wrapped_warm, unwrapped_warm),
+            )
+        );
+
+        Ok(())
+    }
+
+    /// Run `path` once with the arguments and wait for it to finish, returning how it exited and how long it took.
+    ///
+    /// `run_as` is the name it sees it was run with, which for the original executable is the path of the wrapper, as
+    /// it would be without a wrapper.
+    fn time(&self, path: &Path, run_as: &Path) -> anyhow::Result<(ExitStatus, Duration)> {
+        let mut command = Command::new(path);
+
+        command
+            .args(&self.args)
+            .env_remove(wrapper::DISABLE_VAR)
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null());
+
+        #[cfg(unix)]
+        command.arg0(run_as);
+        #[cfg(not(unix))]
+        let _ = run_as;
+
+        let start = Instant::now();
+
+        let status = command
+            .status()
+            .with_context(|| t!("bench-run-failed", path = path.display().to_string()))?;
+
+        Ok((status, start.elapsed()))
+    }
+}
+
+/// The middle of `durations`, which can't be empty.
+fn median(mut durations: Vec<Duration>) -> Duration {
+    durations.sort();
+
+    let middle = durations.len() / 2;
+
+    if durations.len().is_multiple_of(2) {
+        (durations[middle - 1] + durations[middle]) / 2
+    } else {
+        durations[middle]
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.2} ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// How much longer `wrapped` took than `unwrapped`, which is negative if it was faster.
+fn format_overhead(wrapped: Duration, unwrapped: Duration) -> String {
+    let overhead = (wrapped.as_secs_f64() - unwrapped.as_secs_f64()) * 1000.0;
+    format!("{overhead:+.2} ms")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarize_durations() {
+        let ms = Duration::from_millis;
+
+        assert_eq!(median(vec![ms(5), ms(1), ms(3)]), ms(3));
+        assert_eq!(median(vec![ms(4), ms(1), ms(2), ms(8)]), ms(3));
+
+        assert_eq!(format_duration(Duration::from_micros(1234)), "1.23 ms");
+        assert_eq!(format_overhead(ms(3), ms(1)), "+2.00 ms");
+        assert_eq!(format_overhead(ms(1), ms(3)), "-2.00 ms");
+    }
+}
@@ -0,0 +1,99 @@
+use std::path::PathBuf;
+
+use argh::FromArgs;
+
+use crate::{
+    i18n::t,
+    manifest::{self, Manifest},
+    wrapper,
+};
+
+#[derive(FromArgs)]
+/// Check a manifest fully and generate every wrapper in it in memory, without reading or changing anything else.
+#[argh(subcommand, name = "check")]
+pub struct Args {
+    /// path to the manifest, or a directory of manifests merged in lexical order; reads from stdin if omitted or `-`
+    #[argh(positional)]
+    manifest_path: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        // each drop-in is checked on its own, so problems can be reported against the file they're in
+        let drop_ins = match self.manifest_path.as_deref().filter(|path| path.is_dir()) {
+            Some(dir) => Some(manifest::drop_in_paths(dir)?),
+            None => None,
+        };
+
+        let is_dir = drop_ins.is_some();
+
+        let sources = match drop_ins {
+            Some(paths) => paths.into_iter().map(Some).collect(),
+            None => vec![self.manifest_path],
+        };
+
+        let mut manifest = Manifest::default();
+        let mut problems = Vec::new();
+
+        for source in sources {
+            let (content, format) = manifest::read(source.as_deref())?;
+
+            let prefix = match &source {
+                Some(path) if is_dir => format!("`{}`: ", path.display()),
+                _ => String::new(),
+            };
+
+            match Manifest::validate(&content, format) {
+                Ok(file_problems) if file_problems.is_empty() => {
+                    manifest.extend(Manifest::parse(&content, format)?);
+                }
+                Ok(file_problems) => problems.extend(
+                    file_problems
+                        .into_iter()
+                        .map(|problem| format!("{prefix}{problem}")),
+                ),
+                // a syntax error only stops the rest of the file from being checked
+                Err(err) => problems.push(format!("{prefix}{err:#}")),
+            }
+        }
+
+        // the wrappers depend on every file, so they can only be generated once all of them are valid
+        if problems.is_empty() {
+            manifest.resolve_defaults();
+
+            for entry in &mut manifest.wrappers {
+                entry.params.dedup();
+
+                if let Err(err) = render(entry) {
+                    problems.push(format!("`{}`: {err:#}", entry.path.display()));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            println!("{}", t!("check-valid", count = manifest.wrappers.len()));
+
+            return Ok(());
+        }
+
+        for problem in &problems {
+            eprintln!("{}", t!("problem", problem = problem.to_string()));
+        }
+
+        anyhow::bail!(t!("manifest-problems", count = problems.len()))
+    }
+}
+
+/// Generate every file of the wrapper of `entry` in memory, returning an error if it can't be created.
+fn render(entry: &manifest::Entry) -> anyhow::Result<()> {
+    anyhow::ensure!(entry.params.changes_launch(), t!("wrap-nothing-to-wrap"));
+
+    // whether hooks are used by default depends on the system, so they're generated unless they can't be used
+    let use_pacman_hooks = entry.hooks.unwrap_or(
+        entry.params.snap.is_none() && !entry.params.user_units && entry.params.backend.is_none(),
+    );
+
+    wrapper::render(&entry.path, &entry.params, use_pacman_hooks)?.files()?;
+
+    Ok(())
+}
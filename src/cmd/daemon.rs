@@ -0,0 +1,164 @@
+use std::{collections::HashMap, path::PathBuf, thread};
+
+use anyhow::Context;
+use argh::FromArgs;
+use zbus::{Connection, fdo, message::Header, zvariant::Value};
+
+use super::wrap;
+use crate::{env, i18n::t, pacman_lock, registry, wrapper};
+
+/// Well-known name the service is registered under on the system bus.
+const BUS_NAME: &str = "io.github.jonathanlmc.Wrapperize";
+const OBJECT_PATH: &str = "/io/github/jonathanlmc/Wrapperize";
+
+/// Polkit action that callers must be authorized for to modify wrappers.
+const POLKIT_MANAGE_ACTION: &str = "io.github.jonathanlmc.wrapperize.manage";
+
+/// Polkit flag allowing an authentication dialog to be shown to the caller.
+const POLKIT_ALLOW_USER_INTERACTION: u32 = 1;
+
+#[derive(FromArgs)]
+/// Run a service that manages wrappers on behalf of other programs.
+#[argh(subcommand, name = "daemon")]
+pub struct Args {
+    /// serve the wrapper management interface on the D-Bus system bus
+    #[argh(switch, long = "dbus")]
+    dbus: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        if !self.dbus {
+            anyhow::bail!(t!("daemon-no-interface"));
+        }
+
+        let _connection = zbus::blocking::connection::Builder::system()
+            .and_then(|builder| builder.name(BUS_NAME))
+            .and_then(|builder| builder.serve_at(OBJECT_PATH, Service))
+            .and_then(|builder| builder.build())
+            .with_context(|| t!("daemon-register-failed"))?;
+
+        println!("{}", t!("daemon-serving", bus = BUS_NAME));
+
+        // requests are handled on the connection's own executor thread
+        loop {
+            thread::park();
+        }
+    }
+}
+
+struct Service;
+
+#[zbus::interface(name = "io.github.jonathanlmc.Wrapperize1")]
+impl Service {
+    /// List the paths of every wrapped executable.
+    async fn list_wrappers(&self) -> fdo::Result<Vec<String>> {
+        let wrapped = registry::find_wrapped().map_err(failed)?;
+
+        Ok(wrapped
+            .into_iter()
+            .map(|path| path.to_string_lossy().into_owned())
+            .collect())
+    }
+
+    /// Wrap the executable at `path` with the given arguments and `NAME=value` environment variables.
+    async fn wrap(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+        path: String,
+        args: Vec<String>,
+        envs: Vec<String>,
+    ) -> fdo::Result<()> {
+        authorize(connection, &header).await?;
+
+        let env_vars = envs
+            .iter()
+            .map(|env| env.parse::<env::Variable<'static>>())
+            .collect::<anyhow::Result<_>>()
+            .map_err(|err| fdo::Error::InvalidArgs(format!("{err:#}")))?;
+
+        let params = wrapper::Params {
+            args,
+            env_vars,
+            ..Default::default()
+        };
+
+        let path = PathBuf::from(path);
+
+        pacman_lock::ensure_unlocked(None).map_err(failed)?;
+
+        let use_pacman_hooks = wrap::resolve_use_pacman_hooks(&path, None);
+        wrap::wrap(&path, &params, use_pacman_hooks).map_err(failed)
+    }
+
+    /// Remove the wrapper of the executable at `path`, restoring the original executable.
+    async fn unwrap(
+        &self,
+        #[zbus(header)] header: Header<'_>,
+        #[zbus(connection)] connection: &Connection,
+        path: String,
+    ) -> fdo::Result<()> {
+        authorize(connection, &header).await?;
+
+        pacman_lock::ensure_unlocked(None).map_err(failed)?;
+
+        wrapper::ExecPaths::try_from_path(&PathBuf::from(path))
+            .and_then(|paths| wrapper::unwrap(&paths, false))
+            .map(|_| ())
+            .map_err(failed)
+    }
+
+    /// Describe everything wrong with the wrapper of the executable at `path`; empty if it's intact.
+    async fn verify(&self, path: String) -> fdo::Result<Vec<String>> {
+        wrapper::ExecPaths::try_from_path(&PathBuf::from(path))
+            .and_then(|paths| wrapper::problems(&paths))
+            .map_err(failed)
+    }
+}
+
+/// Ask polkit whether the sender of the message with `header` may modify wrappers.
+async fn authorize(connection: &Connection, header: &Header<'_>) -> fdo::Result<()> {
+    let sender = header
+        .sender()
+        .ok_or_else(|| fdo::Error::AccessDenied(t!("daemon-no-sender")))?;
+
+    let authority = zbus::Proxy::new(
+        connection,
+        "org.freedesktop.PolicyKit1",
+        "/org/freedesktop/PolicyKit1/Authority",
+        "org.freedesktop.PolicyKit1.Authority",
+    )
+    .await?;
+
+    let subject = (
+        "system-bus-name",
+        HashMap::from([("name", Value::from(sender.as_str()))]),
+    );
+
+    let (authorized, _, _): (bool, bool, HashMap<String, String>) = authority
+        .call(
+            "CheckAuthorization",
+            &(
+                subject,
+                POLKIT_MANAGE_ACTION,
+                HashMap::<&str, &str>::new(),
+                POLKIT_ALLOW_USER_INTERACTION,
+                "",
+            ),
+        )
+        .await?;
+
+    if !authorized {
+        return Err(fdo::Error::AccessDenied(t!(
+            "daemon-not-authorized",
+            action = POLKIT_MANAGE_ACTION,
+        )));
+    }
+
+    Ok(())
+}
+
+fn failed(err: anyhow::Error) -> fdo::Error {
+    fdo::Error::Failed(format!("{err:#}"))
+}
@@ -0,0 +1,167 @@
+use std::{
+    borrow::Cow,
+    env,
+    io::{self, IsTerminal, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::Context;
+use argh::FromArgs;
+
+use crate::{cmd::wrap, error::ChangesNeeded, i18n::t, metadata::Metadata, wrapper};
+
+/// Stand-in for a file that doesn't exist on one side of a diff.
+const NULL_PATH: &str = "/dev/null";
+
+/// Escape sequences that color the lines of a diff in a terminal.
+const ADDED_COLOR: &str = "\x1b[32m";
+const REMOVED_COLOR: &str = "\x1b[31m";
+const HUNK_COLOR: &str = "\x1b[36m";
+const HEADER_COLOR: &str = "\x1b[1m";
+const RESET_COLOR: &str = "\x1b[0m";
+
+#[derive(FromArgs)]
+/// Show how the files of a wrapper would change if it was regenerated from its stored parameters.
+#[argh(subcommand, name = "diff")]
+pub struct Args {
+    /// absolute path to the wrapped executable
+    #[argh(positional)]
+    executable_path: PathBuf,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let paths = wrapper::ExecPaths::try_from_path(&self.executable_path)?;
+        let metadata = Metadata::read(&paths.metadata.original)?;
+
+        let staged = wrap::stage(
+            &self.executable_path,
+            &metadata.params,
+            metadata.use_pacman_hooks,
+            wrapper::Mode::Update,
+        )?;
+
+        if !print_changes(&staged)? {
+            println!(
+                "{}",
+                t!(
+                    "diff-matches",
+                    path = self.executable_path.display().to_string()
+                )
+            );
+            return Ok(());
+        }
+
+        Err(ChangesNeeded.into())
+    }
+}
+
+/// Print a unified diff of every file that installing `staged` would change, colored if stdout is a terminal.
+///
+/// Returns true if there were any differences.
+pub fn print_changes(staged: &wrapper::Staged) -> anyhow::Result<bool> {
+    // https://no-color.org
+    let color =
+        io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none_or(|var| var.is_empty());
+
+    let mut changed = false;
+
+    for (path, content) in staged.files()? {
+        changed |= diff(&path, content.as_deref(), color)?;
+    }
+
+    Ok(changed)
+}
+
+/// Print a unified diff from the file at `path` to `regenerated`, where `None` means the file would be removed.
+///
+/// Returns true if there were any differences.
+fn diff(path: &Path, regenerated: Option<&str>, color: bool) -> anyhow::Result<bool> {
+    let exists = path.exists();
+
+    if !exists && regenerated.is_none() {
+        return Ok(false);
+    }
+
+    let installed_path = if exists { path } else { Path::new(NULL_PATH) };
+
+    let mut child = Command::new("diff")
+        .arg("--unified")
+        .arg(format!("--label=a{}", path.display()))
+        .arg(format!("--label=b{}", path.display()))
+        .arg(installed_path)
+        .arg(if regenerated.is_some() {
+            "-"
+        } else {
+            NULL_PATH
+        })
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| t!("diff-run-failed"))?;
+
+    // the pipe is closed as soon as it's dropped, so diff sees the end of the input
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(regenerated.unwrap_or_default().as_bytes())
+            .with_context(|| t!("diff-pass-failed"))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| t!("diff-wait-failed"))?;
+
+    for line in String::from_utf8_lossy(&output.stdout).lines() {
+        if color {
+            println!("{}", colorize(line));
+        } else {
+            println!("{line}");
+        }
+    }
+
+    // diff exits with 1 if the files differ, and 2 if it failed
+    match output.status.code() {
+        Some(0) => Ok(false),
+        Some(1) => Ok(true),
+        _ => anyhow::bail!(t!(
+            "diff-failed",
+            path = path.display().to_string(),
+            status = output.status.to_string(),
+        )),
+    }
+}
+
+/// Color a line of a unified diff by what it is.
+fn colorize(line: &str) -> Cow<'_, str> {
+    let color = if line.starts_with("+++ ") || line.starts_with("--- ") {
+        HEADER_COLOR
+    } else if line.starts_with('+') {
+        ADDED_COLOR
+    } else if line.starts_with('-') {
+        REMOVED_COLOR
+    } else if line.starts_with("@@") {
+        HUNK_COLOR
+    } else {
+        return line.into();
+    };
+
+    format!("{color}{line}{RESET_COLOR}").into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn colors_lines() {
+        assert_eq!(
+            colorize("--- a/usr/bin/foo"),
+            "\x1b[1m--- a/usr/bin/foo\x1b[0m"
+        );
+        assert_eq!(colorize("+exec foo"), "\x1b[32m+exec foo\x1b[0m");
+        assert_eq!(colorize("-exec bar"), "\x1b[31m-exec bar\x1b[0m");
+        assert_eq!(colorize("@@ -1 +1 @@"), "\x1b[36m@@ -1 +1 @@\x1b[0m");
+        assert_eq!(colorize(" unchanged"), " unchanged");
+    }
+}
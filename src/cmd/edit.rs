@@ -0,0 +1,206 @@
+use std::{
+    env,
+    fs::{self, File},
+    io::{self, Read, Write},
+    path::{Path, PathBuf},
+    process::Command,
+    time::Duration,
+};
+
+use anyhow::Context;
+use argh::FromArgs;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    cmd::wrap, error::IoError, file, i18n::t, metadata::Metadata, pacman_lock, prompt, wrapper,
+};
+
+/// Editor to fall back on when neither `$VISUAL` nor `$EDITOR` are set.
+const DEFAULT_EDITOR: &str = "vi";
+
+#[derive(FromArgs)]
+/// Replace the parameters of an existing wrapper with a TOML document read from stdin or edited in $EDITOR.
+#[argh(subcommand, name = "edit")]
+pub struct Args {
+    /// absolute path to the wrapped executable
+    #[argh(positional)]
+    executable_path: PathBuf,
+
+    /// open the current parameters in $VISUAL or $EDITOR instead of reading them from stdin
+    #[argh(switch)]
+    editor: bool,
+
+    /// apply the parameters without asking for confirmation after showing how the files of the wrapper change
+    #[argh(switch, short = 'y', long = "yes")]
+    yes: bool,
+
+    /// wait up to this many seconds for an in-progress pacman transaction to finish instead of aborting
+    #[argh(option, long = "wait-for-pacman")]
+    wait_for_pacman: Option<u64>,
+
+    /// commit the hooks to the git repository containing them (such as `/etc` with etckeeper) afterwards
+    #[argh(switch, long = "git-commit")]
+    git_commit: bool,
+}
+
+/// The editable parts of a wrapper.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Document {
+    hooks: bool,
+    #[serde(flatten)]
+    params: wrapper::Params,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let paths = wrapper::ExecPaths::try_from_path(&self.executable_path)?;
+
+        let metadata =
+            Metadata::read(&paths.metadata.original).with_context(|| t!("edit-no-metadata"))?;
+
+        let current = Document {
+            hooks: metadata.use_pacman_hooks,
+            params: metadata.params,
+        };
+
+        let edited = if self.editor {
+            edit_in_editor(&self.executable_path, &current)?
+        } else {
+            let mut content = String::new();
+
+            io::stdin()
+                .read_to_string(&mut content)
+                .with_context(|| t!("edit-read-stdin-failed"))?;
+
+            toml::from_str(&content).with_context(|| t!("edit-parse-stdin-failed"))?
+        };
+
+        let Some(mut edited) = edited else {
+            println!("{}", t!("edit-no-changes"));
+            return Ok(());
+        };
+
+        if edited == current {
+            println!("{}", t!("edit-no-changes"));
+            return Ok(());
+        }
+
+        for name in edited.params.dedup() {
+            eprintln!("{}", t!("duplicate-env-var", name = name));
+        }
+
+        if edited.hooks || current.hooks {
+            pacman_lock::ensure_unlocked(self.wait_for_pacman.map(Duration::from_secs))?;
+        }
+
+        let staged = wrap::stage(
+            &self.executable_path,
+            &edited.params,
+            edited.hooks,
+            wrapper::Mode::Update,
+        )?;
+
+        if !wrap::confirm_changes(&staged, self.yes)? {
+            return Ok(());
+        }
+
+        wrap::install(staged)?;
+
+        if self.git_commit {
+            super::commit_hooks("edit", &self.executable_path)?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Open `current` in the user's editor until it's saved as a valid document.
+///
+/// Returns `None` if the user gives up after an invalid edit.
+fn edit_in_editor(executable_path: &Path, current: &Document) -> anyhow::Result<Option<Document>> {
+    // the directory is removed when it's dropped, on every way out of here
+    let temp_dir = file::TempDir::new("edit")?;
+    let temp_path = temp_dir.path().join("wrapper.toml");
+
+    let content = format!(
+        "# {}\n{}",
+        t!(
+            "edit-file-header",
+            path = executable_path.display().to_string(),
+        ),
+        toml::to_string(current).with_context(|| t!("edit-serialize-failed"))?
+    );
+
+    File::create_new(&temp_path)
+        .and_then(|mut file| file.write_all(content.as_bytes()))
+        .with_context(|| IoError::new(&temp_path, t!("edit-write-temp-failed")))?;
+
+    loop {
+        open_editor(&temp_path)?;
+
+        let edited = fs::read_to_string(&temp_path)
+            .with_context(|| IoError::new(&temp_path, t!("edit-read-temp-failed")))
+            .and_then(|content| toml::from_str(&content).map_err(Into::into));
+
+        match edited {
+            Ok(document) => return Ok(Some(document)),
+            Err(err) => {
+                eprintln!("{}", t!("edit-invalid-params", error = format!("{err:#}")));
+
+                if !prompt::confirm(&t!("edit-again"), true)? {
+                    return Ok(None);
+                }
+            }
+        }
+    }
+}
+
+fn open_editor(path: &Path) -> anyhow::Result<()> {
+    let editor = env::var("VISUAL")
+        .or_else(|_| env::var("EDITOR"))
+        .unwrap_or_else(|_| DEFAULT_EDITOR.into());
+
+    // run the editor through the shell, since it's common for it to include arguments (such as `code --wait`)
+    let status = Command::new("/bin/sh")
+        .arg("-c")
+        .arg(format!(r#"{editor} "$1""#))
+        .arg("sh")
+        .arg(path)
+        .status()
+        .with_context(|| t!("edit-launch-editor-failed", editor = editor.as_str()))?;
+
+    anyhow::ensure!(
+        status.success(),
+        t!(
+            "edit-editor-failed",
+            editor = editor.as_str(),
+            status = status.to_string(),
+        )
+    );
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env::Variable;
+
+    #[test]
+    fn document_round_trip() {
+        let document = Document {
+            hooks: false,
+            params: wrapper::Params {
+                args: vec!["--flag".into()],
+                env_vars: vec![Variable::new("ENV", "value")],
+                ..Default::default()
+            },
+        };
+
+        let serialized = toml::to_string(&document).expect("serialization should succeed");
+        let parsed: Document = toml::from_str(&serialized).expect("parsing should succeed");
+
+        assert_eq!(parsed, document);
+    }
+}
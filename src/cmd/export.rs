@@ -0,0 +1,364 @@
+use std::{fmt::Write, str::FromStr};
+
+use anyhow::Context;
+use argh::FromArgs;
+use indoc::formatdoc;
+
+use crate::{
+    i18n::t,
+    manifest,
+    metadata::Metadata,
+    registry, shell, snap,
+    wrapper::{self, LaunchArg},
+};
+
+#[derive(FromArgs)]
+/// Print the wrappers on this system in a format other tools can recreate them from.
+#[argh(subcommand, name = "export")]
+pub struct Args {
+    /// output format: `ansible` prints a list of tasks that apply the wrappers with wrapperize, and `shell` and `fish`
+    /// print functions for bash and zsh, or fish, that launch the executables the same way (default: `ansible`)
+    #[argh(option, long = "format", default = "Format::Ansible")]
+    format: Format,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let mut entries = Vec::new();
+
+        for path in registry::find_wrapped()? {
+            let metadata = match wrapper::ExecPaths::try_from_path(&path)
+                .and_then(|paths| Metadata::read(&paths.metadata.original))
+            {
+                Ok(metadata) => metadata,
+                Err(err) => {
+                    eprintln!(
+                        "{}",
+                        t!(
+                            "export-skipping",
+                            path = path.display().to_string(),
+                            error = format!("{err:#}"),
+                        )
+                    );
+                    continue;
+                }
+            };
+
+            // a dormant wrapper only comes back on the next upgrade, which applying it would skip ahead of
+            if metadata.dormant {
+                eprintln!(
+                    "{}",
+                    t!("export-skipping-dormant", path = path.display().to_string())
+                );
+                continue;
+            }
+
+            entries.push(manifest::Entry {
+                path: metadata.executable,
+                hooks: Some(metadata.use_pacman_hooks),
+                params: metadata.params,
+            });
+        }
+
+        match self.format {
+            Format::Ansible => print!("{}", ansible_tasks(&manifest_json(&entries)?)),
+            Format::Shell => print!("{}", shell_functions(&entries, Shell::Bash)),
+            Format::Fish => print!("{}", shell_functions(&entries, Shell::Fish)),
+        }
+
+        Ok(())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Ansible,
+    Shell,
+    Fish,
+}
+
+/// Shell that functions are generated for.
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Shell {
+    /// Bash, whose functions zsh runs the same.
+    Bash,
+    Fish,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "ansible" => Ok(Self::Ansible),
+            "shell" => Ok(Self::Shell),
+            "fish" => Ok(Self::Fish),
+            _ => Err(t!("export-unknown-format", format = s)),
+        }
+    }
+}
+
+/// Serialize `entries` as a JSON manifest that `wrapperize apply` accepts.
+fn manifest_json(entries: &[manifest::Entry]) -> anyhow::Result<String> {
+    let manifest = serde_json::json!({ "wrapper": entries });
+    Ok(serde_json::to_string_pretty(&manifest)?)
+}
+
+/// Generate Ansible tasks that pass `manifest` to `wrapperize apply`.
+///
+/// The manifest is checked first, so the task applying it only runs, and only reports a change, when a wrapper is
+/// actually out of date.
+fn ansible_tasks(manifest: &str) -> String {
+    // a block scalar keeps the manifest readable, and `!unsafe` stops Ansible from treating `{{` in arguments as a
+    // template
+    let mut manifest_block = String::new();
+
+    for line in manifest.lines() {
+        let _ = writeln!(manifest_block, "      {line}");
+    }
+
+    formatdoc! {r#"
+        # generated by {name} {version}
+        - name: Check wrappers
+          ansible.builtin.command:
+            argv: [{name}, apply, --check]
+            stdin: &manifest !unsafe |
+        {manifest_block}  register: wrapperize_check
+          changed_when: false
+          failed_when: wrapperize_check.rc not in [0, 2]
+
+        - name: Apply wrappers
+          ansible.builtin.command:
+            argv: [{name}, apply]
+            stdin: *manifest
+          when: wrapperize_check.rc == 2
+        "#,
+        name = env!("CARGO_PKG_NAME"),
+        version = env!("CARGO_PKG_VERSION"),
+    }
+}
+
+/// Generate functions for `shell` that launch the executables of `entries` like their wrappers do,
+/// leaving out the wrappers that can't be expressed as one.
+fn shell_functions(entries: &[manifest::Entry], shell: Shell) -> String {
+    let mut functions = format!(
+        "# generated by {} {}\n",
+        env!("CARGO_PKG_NAME"),
+        env!("CARGO_PKG_VERSION")
+    );
+
+    for entry in entries {
+        match shell_function(entry, shell) {
+            Ok(function) => {
+                functions.push('\n');
+                functions.push_str(&function);
+            }
+            Err(err) => eprintln!(
+                "{}",
+                t!(
+                    "export-skipping",
+                    path = entry.path.display().to_string(),
+                    error = format!("{err:#}"),
+                )
+            ),
+        }
+    }
+
+    functions
+}
+
+/// Generate a function for `shell`, named after the executable of `entry`, that launches it with the
+/// arguments and environment variables of its wrapper.
+fn shell_function(entry: &manifest::Entry, shell: Shell) -> anyhow::Result<String> {
+    let params = &entry.params;
+
+    let name = entry
+        .path
+        .file_name()
+        .and_then(|name| name.to_str())
+        .filter(|name| shell::quote(name) == *name && !name.contains('='))
+        .with_context(|| t!("export-invalid-function-name"))?;
+
+    // everything else changes how the executable is launched in ways a function can't
+    let others = wrapper::Params {
+        args: Vec::new(),
+        args_before: Vec::new(),
+        args_after: Vec::new(),
+        env_vars: Vec::new(),
+        ..params.clone()
+    };
+
+    anyhow::ensure!(!others.changes_launch(), t!("export-function-params"));
+
+    // the shim of a snap runs the snap's own command, which is what's left once the shim is removed
+    let target = match &params.snap {
+        Some(app) => snap::command_path(app),
+        None => entry.path.clone(),
+    };
+
+    let target = target.to_string_lossy();
+    let launch_args = wrapper::launch_args(params, None);
+
+    let command = |quote: fn(&str) -> std::borrow::Cow<str>, passthrough| {
+        [quote(&target).into_owned()]
+            .into_iter()
+            .chain(launch_args.iter().map(|arg| match arg {
+                LaunchArg::Fixed(arg) => quote(arg).into_owned(),
+                LaunchArg::Passthrough => String::from(passthrough),
+            }))
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    let mut function = String::new();
+
+    match shell {
+        Shell::Bash if params.env_vars.is_empty() => {
+            let command = command(shell::quote, r#""$@""#);
+            writeln!(function, "{name}() {{\n    {command}\n}}")?;
+        }
+        // the variables are exported in a subshell, so they don't stay set in the shell the function is called from
+        Shell::Bash => {
+            let command = command(shell::quote, r#""$@""#);
+            writeln!(function, "{name}() {{\n    (")?;
+
+            for env in &params.env_vars {
+                function.push_str("        ");
+                env.write_bash_line(&mut function)?;
+            }
+
+            writeln!(function, "        exec {command}\n    )\n}}")?;
+        }
+        Shell::Fish => {
+            anyhow::ensure!(
+                params.env_vars.iter().all(|env| env.is_literal()),
+                t!("export-fish-env")
+            );
+
+            let command = command(shell::quote_fish, "$argv");
+            writeln!(function, "function {name}")?;
+
+            // variables set with `-l` only last until the function returns
+            for env in &params.env_vars {
+                function.push_str("    ");
+                env.write_fish_line(&mut function)?;
+            }
+
+            writeln!(function, "    {command}\nend")?;
+        }
+    }
+
+    Ok(function)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::env;
+
+    #[test]
+    fn ansible_tasks_embed_manifest() {
+        let entries = vec![manifest::Entry {
+            path: "/usr/bin/foo".into(),
+            hooks: Some(false),
+            params: wrapper::Params {
+                args: vec!["--name={{ not a template }}".into()],
+                env_vars: vec![env::Variable::new("ENV", "value")],
+                ..Default::default()
+            },
+        }];
+
+        let manifest = manifest_json(&entries).unwrap();
+        let tasks = ansible_tasks(&manifest);
+
+        // the manifest is everything indented beneath the first `stdin` key
+        let embedded = tasks
+            .lines()
+            .skip_while(|line| !line.contains("stdin:"))
+            .skip(1)
+            .take_while(|line| line.starts_with("      "))
+            .map(|line| &line[6..])
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        assert_eq!(embedded, manifest);
+        assert_eq!(
+            manifest::Manifest::validate(&embedded, manifest::Format::Json).unwrap(),
+            Vec::<String>::new()
+        );
+
+        let parsed = manifest::Manifest::parse(&embedded, manifest::Format::Json).unwrap();
+        assert_eq!(parsed.wrappers, entries);
+
+        assert!(tasks.contains("\n  register: wrapperize_check\n"));
+    }
+
+    #[test]
+    fn shell_functions_launch_like_wrappers() {
+        let entry = manifest::Entry {
+            path: "/usr/bin/foo".into(),
+            hooks: None,
+            params: wrapper::Params {
+                args: vec!["--name=it's".into()],
+                args_after: vec!["--last".into()],
+                env_vars: vec![
+                    env::Variable::new("ENV", "value"),
+                    "PATH+=/opt/foo/bin".parse().unwrap(),
+                ],
+                ..Default::default()
+            },
+        };
+
+        assert_eq!(
+            shell_function(&entry, Shell::Bash).unwrap(),
+            indoc::indoc! {r#"
+                foo() {
+                    (
+                        export ENV="value"
+                        export PATH="${PATH:+$PATH:}/opt/foo/bin"
+                        exec /usr/bin/foo '--name=it'\''s' "$@" --last
+                    )
+                }
+            "#}
+        );
+
+        assert_eq!(
+            shell_function(&entry, Shell::Fish).unwrap(),
+            indoc::indoc! {r#"
+                function foo
+                    set -lx ENV value
+                    set -lx PATH (string join : -- $PATH /opt/foo/bin)
+                    /usr/bin/foo '--name=it\'s' $argv --last
+                end
+            "#}
+        );
+    }
+
+    #[test]
+    fn shell_functions_skip_what_they_cant_express() {
+        let entry = |params| manifest::Entry {
+            path: "/usr/bin/foo".into(),
+            hooks: None,
+            params,
+        };
+
+        let without_env = entry(wrapper::Params::with_args(vec!["--arg".into()]));
+        assert_eq!(
+            shell_function(&without_env, Shell::Bash).unwrap(),
+            "foo() {\n    /usr/bin/foo --arg \"$@\"\n}\n"
+        );
+
+        let journal = entry(wrapper::Params {
+            journal: true,
+            ..without_env.params.clone()
+        });
+        assert!(shell_function(&journal, Shell::Bash).is_err());
+
+        let referencing = entry(wrapper::Params {
+            env_vars: vec![env::Variable::new("CACHE", "$HOME/.cache")],
+            ..Default::default()
+        });
+        assert!(shell_function(&referencing, Shell::Bash).is_ok());
+        assert!(shell_function(&referencing, Shell::Fish).is_err());
+    }
+}
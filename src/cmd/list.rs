@@ -0,0 +1,302 @@
+use std::path::{Path, PathBuf};
+
+use argh::FromArgs;
+
+use crate::{
+    cmd::wrap,
+    i18n::t,
+    metadata::Metadata,
+    pacman_files, registry,
+    table::{self, Table},
+    wrapper,
+};
+
+/// Name of the backend of wrappers recreated by `pacman` hooks.
+const PACMAN_BACKEND: &str = "pacman";
+/// Name of the backend of wrappers recreated by systemd user units.
+const UNITS_BACKEND: &str = "units";
+/// Name of the backend of wrappers that nothing recreates.
+const NO_BACKEND: &str = "none";
+
+#[derive(FromArgs)]
+/// List the wrappers on this system, optionally only those matching filters.
+#[argh(subcommand, name = "list")]
+pub struct Args {
+    /// only list wrappers of executables whose name matches this glob, such as `py*`
+    #[argh(option, long = "name")]
+    name: Option<String>,
+
+    /// only list wrappers of executables owned by this package
+    #[argh(option, long = "package")]
+    package: Option<String>,
+
+    /// only list wrappers with this tag; can be used multiple times to list those with any of them
+    #[argh(option, long = "tag")]
+    tags: Vec<String>,
+
+    /// only list wrappers recreated by this backend: `pacman`, `units`, `none`, or the name of a backend plugin
+    #[argh(option, long = "backend")]
+    backend: Option<String>,
+
+    /// only list wrappers in this state: `ok`, `drifted` if they have problems or would change when regenerated, or
+    /// `orphaned` if the executable or its original is gone
+    #[argh(option, long = "status")]
+    status: Option<Status>,
+
+    /// order of the wrappers: `path`, `name`, `package` or `status` (default: `path`)
+    #[argh(option, long = "sort", default = "Sort::Path")]
+    sort: Sort,
+
+    /// output format: `table`, `csv` or `json` (default: `table` in a terminal, and otherwise a line per wrapper)
+    #[argh(option, long = "format")]
+    format: Option<table::Format>,
+}
+
+/// The state of a wrapper.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+enum Status {
+    Ok,
+    /// The wrapper has problems, or regenerating it from its stored parameters would change it.
+    Drifted,
+    /// The executable or its original no longer exists.
+    Orphaned,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+enum Sort {
+    Path,
+    Name,
+    Package,
+    Status,
+}
+
+/// A wrapper along with what it can be filtered and sorted by.
+struct Entry {
+    path: PathBuf,
+    metadata: Option<Metadata>,
+    status: Status,
+    /// Package owning the executable, which is only looked up when it's needed, since that runs `pacman`.
+    package: Option<String>,
+}
+
+impl Entry {
+    fn name(&self) -> String {
+        self.path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned()
+    }
+
+    fn backend(&self) -> &str {
+        match &self.metadata {
+            Some(metadata) => match &metadata.params.backend {
+                Some(backend) => backend,
+                None if metadata.params.user_units => UNITS_BACKEND,
+                None if metadata.use_pacman_hooks => PACMAN_BACKEND,
+                None => NO_BACKEND,
+            },
+            // a wrapper without metadata can only have been found through its hooks
+            None => PACMAN_BACKEND,
+        }
+    }
+
+    fn tags(&self) -> &[String] {
+        self.metadata
+            .as_ref()
+            .map_or(&[], |metadata| &metadata.params.tags)
+    }
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let needs_package = self.package.is_some() || self.sort == Sort::Package;
+
+        let mut entries = Vec::new();
+
+        for path in registry::find_wrapped()? {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+
+            if self
+                .name
+                .as_ref()
+                .is_some_and(|pattern| !pacman_files::glob_matches(pattern, &name))
+            {
+                continue;
+            }
+
+            let metadata = wrapper::ExecPaths::try_from_path(&path)
+                .and_then(|paths| Metadata::read(&paths.metadata.original))
+                .ok();
+
+            let entry = Entry {
+                status: status(&path, metadata.as_ref()),
+                package: needs_package.then(|| pacman_files::owner(&path)).flatten(),
+                path,
+                metadata,
+            };
+
+            if self.matches(&entry) {
+                entries.push(entry);
+            }
+        }
+
+        // the wrappers are found in order of their paths, which the other orders fall back to
+        match self.sort {
+            Sort::Path => {}
+            Sort::Name => entries.sort_by_key(Entry::name),
+            Sort::Package => entries.sort_by(|a, b| a.package.cmp(&b.package)),
+            Sort::Status => entries.sort_by_key(|entry| entry.status),
+        }
+
+        let format = table::Format::resolve(self.format);
+
+        // other programs reading CSV or JSON are better served by an empty list than a message
+        if entries.is_empty() && matches!(format, None | Some(table::Format::Table)) {
+            eprintln!("{}", t!("list-none"));
+            return Ok(());
+        }
+
+        if let Some(format) = format {
+            return self.table(&entries, needs_package).print(format);
+        }
+
+        for entry in &entries {
+            println!(
+                "{}",
+                t!(
+                    "list-entry",
+                    path = entry.path.display().to_string(),
+                    status = entry.status.to_string(),
+                )
+            );
+        }
+
+        Ok(())
+    }
+
+    /// The wrappers with a column for each thing they can be filtered by, leaving out the package unless it was looked
+    /// up.
+    fn table(&self, entries: &[Entry], with_package: bool) -> Table {
+        let mut columns = vec!["path", "status", "backend", "tags"];
+
+        if with_package {
+            columns.push("package");
+        }
+
+        let mut table = Table::new(columns);
+
+        for entry in entries {
+            let mut row = vec![
+                entry.path.display().to_string(),
+                entry.status.to_string(),
+                entry.backend().to_owned(),
+                entry.tags().join(" "),
+            ];
+
+            if with_package {
+                row.push(entry.package.clone().unwrap_or_default());
+            }
+
+            table.push(row);
+        }
+
+        table
+    }
+
+    /// Whether `entry` passes every filter other than the name, which is checked before anything is read.
+    fn matches(&self, entry: &Entry) -> bool {
+        let package = self
+            .package
+            .as_ref()
+            .is_none_or(|package| entry.package.as_ref() == Some(package));
+
+        let tags = self.tags.is_empty() || entry.tags().iter().any(|tag| self.tags.contains(tag));
+
+        let backend = self
+            .backend
+            .as_ref()
+            .is_none_or(|backend| entry.backend() == backend);
+
+        let status = self.status.is_none_or(|status| entry.status == status);
+
+        package && tags && backend && status
+    }
+}
+
+/// The state of the wrapper of the executable at `path`, which was created with `metadata` if it could be read.
+fn status(path: &Path, metadata: Option<&Metadata>) -> Status {
+    let Ok(paths) = wrapper::ExecPaths::try_from_path(path) else {
+        return Status::Orphaned;
+    };
+
+    let dormant = metadata.is_some_and(|metadata| metadata.dormant);
+
+    // a dormant wrapper has the original executable back in place until the hooks recreate it
+    if !paths.wrapped.original.exists() || (!dormant && !paths.is_wrapped().unwrap_or(false)) {
+        return Status::Orphaned;
+    }
+
+    let Some(metadata) = metadata else {
+        return Status::Drifted;
+    };
+
+    if !wrapper::problems(&paths).is_ok_and(|problems| problems.is_empty()) {
+        return Status::Drifted;
+    }
+
+    if dormant {
+        return Status::Ok;
+    }
+
+    let up_to_date = wrap::stage(
+        path,
+        &metadata.params,
+        metadata.use_pacman_hooks,
+        wrapper::Mode::Update,
+    )
+    .is_ok_and(|staged| staged.is_up_to_date());
+
+    if up_to_date {
+        Status::Ok
+    } else {
+        Status::Drifted
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backend_of_wrapper() {
+        let entry = |params: wrapper::Params, use_pacman_hooks| Entry {
+            path: PathBuf::from("/usr/bin/foo"),
+            metadata: Some(Metadata {
+                version: env!("CARGO_PKG_VERSION").to_owned(),
+                executable: PathBuf::from("/usr/bin/foo"),
+                use_pacman_hooks,
+                dormant: false,
+                params,
+            }),
+            status: Status::Ok,
+            package: None,
+        };
+
+        let units = wrapper::Params {
+            user_units: true,
+            ..Default::default()
+        };
+        let plugin = wrapper::Params {
+            backend: Some("apt".to_owned()),
+            ..Default::default()
+        };
+
+        assert_eq!(entry(Default::default(), true).backend(), "pacman");
+        assert_eq!(entry(Default::default(), false).backend(), "none");
+        assert_eq!(entry(units, false).backend(), "units");
+        assert_eq!(entry(plugin, true).backend(), "apt");
+    }
+}
@@ -0,0 +1,77 @@
+use std::{path::PathBuf, time::Duration};
+
+use argh::FromArgs;
+
+use crate::{cmd::wrap, i18n::t, metadata::Metadata, pacman_lock, wrapper};
+
+#[derive(FromArgs)]
+/// Replace an executable with a wrapper that refuses to run it, keeping the original so unwrapping restores it.
+#[argh(subcommand, name = "mask")]
+pub struct Args {
+    /// absolute path to the executable to mask
+    #[argh(positional)]
+    executable_path: PathBuf,
+
+    /// message printed instead of running the executable, such as which one to use instead
+    #[argh(option, long = "message")]
+    message: Option<String>,
+
+    /// do not generate hooks for pacman, so an upgrade of the executable removes the mask
+    #[argh(switch, long = "nohooks")]
+    skip_pacman_hooks: bool,
+
+    /// only report whether the wrapper would change, exiting with code 2 if it would, without modifying anything
+    #[argh(switch, long = "check")]
+    check: bool,
+
+    /// wait up to this many seconds for an in-progress pacman transaction to finish instead of aborting
+    #[argh(option, long = "wait-for-pacman")]
+    wait_for_pacman: Option<u64>,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let params = wrapper::Params {
+            mask: Some(self.message.unwrap_or_default()),
+            ..Default::default()
+        };
+
+        let use_pacman_hooks = wrap::resolve_use_pacman_hooks(
+            &self.executable_path,
+            self.skip_pacman_hooks.then_some(false),
+        );
+
+        let paths = wrapper::ExecPaths::try_from_path(&self.executable_path)?;
+
+        // masking replaces whatever the executable was wrapped with, which is likely a mistake unless it's a mask
+        let mode = match wrapper::existing_wrapper(&self.executable_path)? {
+            Some(existing)
+                if existing == self.executable_path
+                    && Metadata::read(&paths.metadata.original)?
+                        .params
+                        .mask
+                        .is_some() =>
+            {
+                wrapper::Mode::Update
+            }
+            Some(existing) => anyhow::bail!(t!(
+                "mask-already-wrapped",
+                path = self.executable_path.display().to_string(),
+                wrapper = existing.display().to_string(),
+            )),
+            None => wrapper::Mode::Create,
+        };
+
+        let staged = wrap::stage(&self.executable_path, &params, use_pacman_hooks, mode)?;
+
+        if self.check {
+            return wrap::check(&staged);
+        }
+
+        if use_pacman_hooks {
+            pacman_lock::ensure_unlocked(self.wait_for_pacman.map(Duration::from_secs))?;
+        }
+
+        wrap::install(staged)
+    }
+}
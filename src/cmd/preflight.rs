@@ -0,0 +1,131 @@
+use std::{fs, path::Path, path::PathBuf};
+
+use argh::FromArgs;
+
+use crate::{cmd::wrap, file, i18n::t, mount, pacman_hook, pacman_lock};
+
+#[derive(FromArgs)]
+/// Check that everything needed to wrap an executable is in place, reporting every problem at once.
+#[argh(subcommand, name = "preflight")]
+pub struct Args {
+    /// absolute path to the executable that would be wrapped
+    #[argh(positional)]
+    executable_path: PathBuf,
+
+    /// skip the checks for pacman hooks, as when wrapping with `--nohooks`
+    #[argh(switch, long = "nohooks")]
+    skip_pacman_hooks: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let use_pacman_hooks = wrap::resolve_use_pacman_hooks(
+            &self.executable_path,
+            self.skip_pacman_hooks.then_some(false),
+        );
+
+        let problems = problems(&self.executable_path, use_pacman_hooks)?;
+
+        if problems.is_empty() {
+            println!(
+                "{}",
+                t!(
+                    "preflight-ready",
+                    path = self.executable_path.display().to_string()
+                )
+            );
+            return Ok(());
+        }
+
+        for problem in &problems {
+            eprintln!("{}", t!("problem", problem = problem.to_string()));
+        }
+
+        anyhow::bail!(t!(
+            "preflight-failed",
+            path = self.executable_path.display().to_string(),
+            count = problems.len(),
+        ))
+    }
+}
+
+fn problems(executable_path: &Path, use_pacman_hooks: bool) -> anyhow::Result<Vec<String>> {
+    let mut problems = Vec::new();
+
+    // the remaining checks would be made relative to the current directory instead
+    if !executable_path.is_absolute() {
+        problems.push(t!(
+            "preflight-not-absolute",
+            path = executable_path.display().to_string(),
+        ));
+        return Ok(problems);
+    }
+
+    match fs::metadata(executable_path) {
+        Ok(metadata) if !metadata.is_file() => problems.push(t!(
+            "preflight-not-file",
+            path = executable_path.display().to_string(),
+        )),
+        Ok(_) if !file::is_executable(executable_path)? => problems.push(t!(
+            "preflight-not-executable",
+            path = executable_path.display().to_string(),
+        )),
+        Ok(_) => (),
+        Err(err) => problems.push(format!("`{}`: {err}", executable_path.display())),
+    }
+
+    if let Some(dir) = executable_path.parent() {
+        if let Err(err) = mount::ensure_writable(executable_path) {
+            problems.push(format!("{err:#}"));
+        } else if let Err(err) = file::ensure_dir_writable(dir) {
+            problems.push(format!("`{}`: {err:#}", dir.display()));
+        }
+    }
+
+    if !use_pacman_hooks {
+        return Ok(problems);
+    }
+
+    if let Err(err) = pacman_lock::ensure_unlocked(None) {
+        problems.push(format!("{err:#}"));
+    }
+
+    // the hook directory is created along with the first hooks, so whatever it would be created in has to be writable
+    let hook_dir = pacman_hook::dir()
+        .ancestors()
+        .find(|dir| dir.exists())
+        .unwrap_or(Path::new("/"));
+
+    if let Err(err) = file::ensure_dir_writable(hook_dir) {
+        problems.push(format!("`{}`: {err:#}", hook_dir.display()));
+    }
+
+    for hook in pacman_hook::find_conflicting(executable_path)? {
+        problems.push(t!(
+            "preflight-hook-conflict",
+            hook = hook.display().to_string(),
+            path = executable_path.display().to_string(),
+        ));
+    }
+
+    Ok(problems)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn all_problems_are_reported() {
+        let temp_dir = test_util::temp_dir("preflight");
+        let dir = temp_dir.path();
+
+        let executable = dir.join("executable");
+        test_util::write_with_execute_bit(&executable, b"#!/bin/sh\n").unwrap();
+        assert!(problems(&executable, false).unwrap().is_empty());
+
+        assert_eq!(problems(dir, false).unwrap().len(), 1);
+        assert_eq!(problems(Path::new("relative"), false).unwrap().len(), 1);
+    }
+}
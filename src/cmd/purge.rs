@@ -0,0 +1,127 @@
+use argh::FromArgs;
+
+use crate::{
+    error::IoError, expiry, file, git, i18n::t, pacman_hook, pacman_lock, plugin,
+    progress::Progress, prompt, registry, user_unit, wrapper,
+};
+
+#[derive(FromArgs)]
+/// Remove every wrapper, restoring the original executables, and delete all hooks and files created by this program.
+#[argh(subcommand, name = "purge")]
+pub struct Args {
+    /// don't ask for confirmation
+    #[argh(switch, short = 'y')]
+    yes: bool,
+
+    /// commit the removal of the hooks to the git repository containing them (such as `/etc` with etckeeper)
+    #[argh(switch, long = "git-commit")]
+    git_commit: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        pacman_lock::ensure_unlocked(None)?;
+
+        let wrapped = registry::find_wrapped()?;
+
+        if !wrapped.is_empty() {
+            println!("{}", t!("purge-list"));
+
+            for path in &wrapped {
+                println!("  {}", path.display());
+            }
+        }
+
+        if !self.yes && !prompt::confirm(&t!("purge-confirm"), false)? {
+            anyhow::bail!(t!("aborted"));
+        }
+
+        // unwrapping removes the hooks of each wrapper, so they have to be found beforehand
+        let hook_files = pacman_hook::find_all()?;
+
+        let mut progress = Progress::new(t!("progress-unwrapping"), wrapped.len());
+
+        for path in &wrapped {
+            progress.start(path);
+
+            let result = wrapper::ExecPaths::try_from_path(path)
+                .and_then(|paths| wrapper::unwrap(&paths, false))
+                .map(|_| ());
+            progress.finish_item(path, result);
+        }
+
+        // hooks for wrappers that couldn't be unwrapped, or that no longer have a target, are still removed,
+        // since they'd otherwise keep running on every transaction
+        let mut removed_hook_files = 0;
+
+        for path in pacman_hook::find_all()? {
+            if file::remove_if_exists(&path).map_err(|err| IoError::new(&path, err))? {
+                removed_hook_files += 1;
+            }
+        }
+
+        println!(
+            "{}",
+            t!(
+                "purge-removed",
+                count = removed_hook_files,
+                dir = pacman_hook::dir().display().to_string(),
+            )
+        );
+
+        // units are per user, so only those of the user running this are found
+        let unit_files = user_unit::find_all()?;
+
+        if !unit_files.is_empty() {
+            user_unit::remove(&unit_files)?;
+
+            println!(
+                "{}",
+                t!(
+                    "purge-removed",
+                    count = unit_files.len(),
+                    dir = user_unit::dir()?.display().to_string(),
+                )
+            );
+        }
+
+        let system_unit_files = expiry::find_system_units()?;
+
+        if !system_unit_files.is_empty() {
+            expiry::remove_system_units(&system_unit_files)?;
+
+            println!(
+                "{}",
+                t!(
+                    "purge-removed",
+                    count = system_unit_files.len(),
+                    dir = expiry::SYSTEM_UNIT_DIR,
+                )
+            );
+        }
+
+        // the backends of their wrappers were asked to stop running them when unwrapping
+        let backend_scripts = plugin::find_install_scripts()?;
+
+        if !backend_scripts.is_empty() {
+            for path in &backend_scripts {
+                file::remove_if_exists(path).map_err(|err| IoError::new(path, err))?;
+            }
+
+            println!(
+                "{}",
+                t!(
+                    "purge-removed",
+                    count = backend_scripts.len(),
+                    dir = plugin::BACKEND_SCRIPT_DIR,
+                )
+            );
+        }
+
+        if self.git_commit {
+            git::commit(&hook_files, &format!("{}: purge", env!("CARGO_PKG_NAME")))?;
+        }
+
+        progress.finish()
+    }
+}
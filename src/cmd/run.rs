@@ -0,0 +1,217 @@
+use std::process::Command;
+#[cfg(unix)]
+use std::{env as std_env, path::Path};
+
+use anyhow::Context;
+use argh::FromArgs;
+
+#[cfg(unix)]
+use crate::session;
+use crate::{
+    affinity::CpuList, appimage, env, error::IoError, file, i18n::t, limit::Limit, preset, script,
+    settings, via::Via, wrapper,
+};
+
+#[derive(FromArgs)]
+/// Run an executable once as a wrapper with the given parameters would, without installing anything.
+#[argh(subcommand, name = "run")]
+pub struct Args {
+    /// path to the executable to run, or the name of a command in `$PATH`
+    #[argh(positional)]
+    executable_path: String,
+
+    /// arguments passed to the executable as if they were given to the wrapper, taken verbatim when placed after `--`
+    #[argh(positional, greedy)]
+    trailing_args: Vec<String>,
+
+    /// an additional argument to launch the executable with; can be used multiple times
+    #[argh(option, short = 'a', long = "arg")]
+    args: Vec<String>,
+
+    /// an argument that always precedes the passthrough arguments; can be used multiple times
+    #[argh(option, long = "arg-before")]
+    args_before: Vec<String>,
+
+    /// an argument that always follows the passthrough arguments; can be used multiple times
+    #[argh(option, long = "arg-after")]
+    args_after: Vec<String>,
+
+    /// an environment variable in the format of `ENV=value` to launch the executable with, or `ENV+=value` and `ENV=+value`
+    /// to append and prepend to a list such as `PATH`; can be used multiple times
+    #[argh(option, short = 'e', long = "env")]
+    envs: Vec<env::Variable<'static>>,
+
+    /// place the wrapper arguments after the passthrough arguments, so they are seen last by the executable
+    #[argh(switch, long = "passthrough-args-first")]
+    add_passthrough_args_first: bool,
+
+    /// ignore the arguments after the executable, so it runs with exactly the wrapper arguments
+    #[argh(switch, long = "no-passthrough")]
+    no_passthrough: bool,
+
+    /// run the script with the interpreter from its shebang, which sees the path of the script as the name it was run
+    /// with
+    #[argh(switch, long = "interpreter")]
+    interpreter: bool,
+
+    /// leave out the strict bash options (`set -euo pipefail`) from the generated wrapper
+    #[argh(switch, long = "no-strict")]
+    no_strict: bool,
+
+    /// a built-in set of parameters, out of `gaming`, `gpu-offload`, `wayland` and `proxy`, or the name of a
+    /// `wrapperize-preset-<name>` plugin in `$PATH`; can be used multiple times
+    #[argh(option, long = "preset", from_str_fn(preset::Choice::parse))]
+    presets: Vec<preset::Choice>,
+
+    /// an option for every JVM the executable starts, passed through `JAVA_TOOL_OPTIONS`; can be used multiple times
+    #[argh(option, long = "jvm-option")]
+    jvm_options: Vec<String>,
+
+    /// route the executable's network traffic through `proxychains` or `torsocks`, which must be installed
+    #[argh(option, long = "via", from_str_fn(Via::parse))]
+    via: Option<Via>,
+
+    /// run the executable in its own network namespace with `unshare`, so it has no network access
+    #[argh(switch, long = "no-network")]
+    no_network: bool,
+
+    /// a soft resource limit in the format of `nofile=65536` or `core=0`, set with `ulimit` before the executable launches; can be used multiple times
+    #[argh(option, long = "ulimit")]
+    limits: Vec<Limit>,
+
+    /// CPUs to confine the executable to with `taskset`, such as `0-3,8`
+    #[argh(option, long = "cpus")]
+    cpus: Option<CpuList>,
+
+    /// instead of running the executable, put the wrapper in a directory for the current shell session and print the
+    /// line that adds it to `$PATH`, for use with `eval`; the directory is removed once the shell has exited
+    #[cfg(unix)]
+    #[argh(switch, long = "session")]
+    session: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let executable_path = file::resolve_command(&self.executable_path)
+            .with_context(|| t!("run-not-found", command = self.executable_path.as_str()))?;
+
+        // a wrapper from an earlier session is replaced rather than wrapped again, which would make it run itself
+        #[cfg(unix)]
+        let executable_path = if self.session
+            && executable_path.starts_with(session::dir(std::os::unix::process::parent_id()))
+        {
+            wrapper::existing_wrapper(&executable_path)?
+                .with_context(|| t!("run-unrecognized-session-file"))?
+        } else {
+            executable_path
+        };
+
+        // the output of the executable, or the line to evaluate for a session, is all that goes to stdout
+        if wrapper::existing_wrapper(&executable_path)?.is_some() {
+            eprintln!(
+                "{}",
+                t!(
+                    "run-already-wrapper",
+                    path = executable_path.display().to_string()
+                )
+            );
+        }
+
+        let interpreter = if self.interpreter {
+            script::read_shebang(&executable_path).with_context(|| {
+                IoError::new(&executable_path, t!("wrap-interpreter-no-shebang"))
+            })?
+        } else {
+            Vec::new()
+        };
+
+        // the parameters given here come after those of preset plugins, so they take precedence
+        let (presets, mut wrapper_params) = preset::resolve(self.presets, &executable_path)?;
+
+        wrapper_params.merge(wrapper::Params {
+            args: self.args,
+            args_before: self.args_before,
+            args_after: self.args_after,
+            add_passthrough_args_first: self.add_passthrough_args_first,
+            no_passthrough: self.no_passthrough,
+            no_strict: self.no_strict,
+            appimage: appimage::is_appimage(&executable_path),
+            interpreter,
+            presets,
+            jvm_options: self.jvm_options,
+            via: self.via,
+            no_network: self.no_network,
+            cpus: self.cpus,
+            limits: self.limits,
+            env_vars: self.envs,
+            ..Default::default()
+        });
+
+        let wrapper_script = wrapper::render_in_place(&executable_path, &wrapper_params)?;
+
+        #[cfg(unix)]
+        if self.session {
+            anyhow::ensure!(self.trailing_args.is_empty(), t!("run-session-args"));
+
+            return install_for_session(&executable_path, &wrapper_script);
+        }
+
+        // the script sees the executable as the path it was run with, just like an installed wrapper
+        let mut command = Command::new(settings::get().shell_program());
+        command
+            .arg("-c")
+            .arg(wrapper_script)
+            .arg(&executable_path)
+            .args(self.trailing_args);
+
+        exec(command)
+    }
+}
+
+/// Install `wrapper_script` as the wrapper of the executable at `executable_path` for the session of the shell that
+/// ran this program, and print the line that puts it first in `$PATH`.
+#[cfg(unix)]
+fn install_for_session(executable_path: &Path, wrapper_script: &str) -> anyhow::Result<()> {
+    let shell_pid = std::os::unix::process::parent_id();
+    let dir = session::dir(shell_pid);
+
+    let filename = executable_path
+        .file_name()
+        .with_context(|| t!("run-invalid-executable-path"))?
+        .to_string_lossy();
+
+    let path = session::install(shell_pid, &filename, wrapper_script)?;
+
+    eprintln!(
+        "{}",
+        t!(
+            "run-session-created",
+            path = executable_path.display().to_string(),
+            wrapper = path.display().to_string(),
+        )
+    );
+
+    let in_path = std_env::var_os("PATH")
+        .is_some_and(|paths| std_env::split_paths(&paths).any(|entry| entry == dir));
+
+    if !in_path {
+        println!("{}", session::path_export(&dir));
+    }
+
+    Ok(())
+}
+
+/// Replace this process with `command`, so it receives signals and its exit status is the one this program exits with.
+#[cfg(unix)]
+fn exec(mut command: Command) -> anyhow::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    Err(command.exec()).with_context(|| t!("run-shell-failed"))
+}
+
+/// Run `command` and exit with its exit status.
+#[cfg(not(unix))]
+fn exec(mut command: Command) -> anyhow::Result<()> {
+    let status = command.status().with_context(|| t!("run-shell-failed"))?;
+    std::process::exit(status.code().unwrap_or(1))
+}
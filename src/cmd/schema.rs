@@ -0,0 +1,15 @@
+use argh::FromArgs;
+
+use crate::manifest::Manifest;
+
+#[derive(FromArgs)]
+/// Print the JSON Schema of the manifest format, for use with editors.
+#[argh(subcommand, name = "schema")]
+pub struct Args {}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        println!("{}", serde_json::to_string_pretty(&Manifest::schema())?);
+        Ok(())
+    }
+}
@@ -0,0 +1,135 @@
+use std::{env as std_env, path::PathBuf};
+
+use argh::FromArgs;
+
+use crate::{i18n::t, metadata::Metadata, shell, wrapper};
+
+#[derive(FromArgs)]
+/// Show the arguments and environment a wrapper would launch its executable with when run with the given arguments,
+/// without running anything.
+#[argh(subcommand, name = "simulate")]
+pub struct Args {
+    /// absolute path to the wrapped executable
+    #[argh(positional)]
+    executable_path: PathBuf,
+
+    /// arguments the wrapper is run with, taken verbatim when placed after `--`
+    #[argh(positional, greedy)]
+    args: Vec<String>,
+
+    /// profile to select, as if `WRAPPERIZE_PROFILE` was set to it (default: the value of `WRAPPERIZE_PROFILE`)
+    #[argh(option, long = "profile")]
+    profile: Option<String>,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let paths = wrapper::ExecPaths::try_from_path(&self.executable_path)?;
+        let params = Metadata::read(&paths.metadata.original)?.params;
+
+        if let Some(message) = wrapper::mask_message(&params, &paths.wrapped_filename) {
+            println!("{}", t!("which-masked", message = message));
+            return Ok(());
+        }
+
+        if std_env::var(wrapper::DISABLE_VAR)
+            .is_ok_and(|disabled| wrapper::is_disabled(&disabled, &paths.wrapped_filename))
+        {
+            println!(
+                "{}",
+                t!(
+                    "simulate-disabled",
+                    var = wrapper::DISABLE_VAR,
+                    path = paths.unwrapped.original.display().to_string()
+                )
+            );
+            return Ok(());
+        }
+
+        let profile_name = self
+            .profile
+            .or_else(|| std_env::var(wrapper::PROFILE_VAR).ok());
+
+        let profile = match &profile_name {
+            Some(name) => Some(params.profiles.get(name).ok_or_else(|| {
+                anyhow::anyhow!(t!(
+                    "simulate-no-profile",
+                    path = self.executable_path.display().to_string(),
+                    name = name.as_str(),
+                ))
+            })?),
+            None => None,
+        };
+
+        if let Some(name) = &profile_name {
+            println!("{}", t!("which-profile", name = name.to_string()));
+        }
+
+        let run_as = self.executable_path.to_string_lossy();
+
+        let argv = wrapper::simulate_argv(&paths, &params, profile, &run_as, &self.args)
+            .ok_or_else(|| {
+                anyhow::anyhow!(t!(
+                    "simulate-runtime-command",
+                    program = env!("CARGO_PKG_NAME"),
+                    path = self.executable_path.display().to_string(),
+                ))
+            })?;
+
+        for (index, arg) in argv.iter().enumerate() {
+            println!(
+                "{}",
+                t!(
+                    "simulate-argv",
+                    index = index,
+                    arg = shell::quote(arg).into_owned()
+                )
+            );
+        }
+
+        let env = wrapper::simulate_env(&params, profile, |name| std_env::var(name).ok());
+
+        for (name, value) in env {
+            println!(
+                "{}",
+                t!(
+                    "which-env",
+                    env = format!("{name}={}", shell::quote(&value))
+                )
+            );
+        }
+
+        // these can still change the command and environment, but only once the wrapper runs
+        for preset in &params.presets {
+            println!("{}", t!("which-preset", preset = preset.to_string()));
+        }
+
+        if params.journal {
+            println!("{}", t!("which-journal"));
+        }
+
+        if params.runtime_config {
+            println!(
+                "{}",
+                t!(
+                    "which-runtime-config",
+                    path = wrapper::runtime_config_path(&paths.wrapped_filename)
+                        .display()
+                        .to_string(),
+                )
+            );
+        }
+
+        if params.user_override {
+            println!(
+                "{}",
+                t!(
+                    "which-user-override",
+                    path = wrapper::user_override_path(&paths.wrapped_filename),
+                )
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,156 @@
+use std::{
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use argh::FromArgs;
+
+use crate::{
+    git, i18n::t, pacman_hook, pacman_lock, progress::Progress, prompt, registry, snap, snapshot,
+    wrapper,
+};
+
+#[derive(FromArgs)]
+/// Remove the wrapper of an executable, restoring the original executable.
+#[argh(subcommand, name = "unwrap")]
+pub struct Args {
+    /// absolute path to the wrapped executable
+    #[argh(positional)]
+    executable_path: Option<PathBuf>,
+
+    /// unwrap every wrapper with this tag instead of a single executable; can be used multiple times
+    #[argh(option, long = "tag")]
+    tags: Vec<String>,
+
+    /// don't ask for confirmation before unwrapping the wrappers with a tag
+    #[argh(switch, short = 'y')]
+    yes: bool,
+
+    /// keep the pacman hooks, so the wrapper is recreated the next time the executable is upgraded
+    #[argh(switch, long = "keep-hooks")]
+    keep_hooks: bool,
+
+    /// wait up to this many seconds for an in-progress pacman transaction to finish instead of aborting
+    #[argh(option, long = "wait-for-pacman")]
+    wait_for_pacman: Option<u64>,
+
+    /// take a system snapshot with snapper or timeshift before changing any files
+    #[argh(switch, long = "snapshot")]
+    snapshot: bool,
+
+    /// commit the removal of the hooks to the git repository containing them (such as `/etc` with etckeeper)
+    #[argh(switch, long = "git-commit")]
+    git_commit: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        pacman_lock::ensure_unlocked(self.wait_for_pacman.map(Duration::from_secs))?;
+
+        let executable_path = match &self.executable_path {
+            Some(_) if !self.tags.is_empty() => {
+                anyhow::bail!(t!("executable-and-tag"))
+            }
+            Some(path) => path,
+            None if !self.tags.is_empty() => return self.unwrap_tagged(),
+            None => anyhow::bail!(t!("executable-or-tag")),
+        };
+
+        if self.snapshot {
+            snapshot::create(&super::change_description("unwrap", executable_path))?;
+        }
+
+        self.unwrap(executable_path)?;
+
+        if self.git_commit {
+            super::commit_hooks("unwrap", executable_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// Unwrap every executable whose wrapper has any of the tags, after confirming which they are.
+    fn unwrap_tagged(&self) -> anyhow::Result<()> {
+        let wrapped = registry::find_tagged(&self.tags)?;
+
+        println!("{}", t!("purge-list"));
+
+        for path in &wrapped {
+            println!("  {}", path.display());
+        }
+
+        if !self.yes && !prompt::confirm(&t!("unwrap-tagged-confirm"), false)? {
+            anyhow::bail!(t!("aborted"));
+        }
+
+        let description = format!(
+            "{}: unwrap --tag {}",
+            env!("CARGO_PKG_NAME"),
+            self.tags.join(" --tag ")
+        );
+
+        if self.snapshot {
+            snapshot::create(&description)?;
+        }
+
+        let mut progress = Progress::new(t!("progress-unwrapping"), wrapped.len());
+
+        for path in &wrapped {
+            progress.start(path);
+            progress.finish_item(path, self.unwrap(path));
+        }
+
+        if self.git_commit {
+            let hook_paths = wrapped
+                .iter()
+                .flat_map(|path| pacman_hook::all_paths(&pacman_hook::hook_name(path)))
+                .collect::<Vec<_>>();
+
+            git::commit(&hook_paths, &description)?;
+        }
+
+        progress.finish()
+    }
+
+    /// Unwrap the executable at `executable_path`, reporting what was restored.
+    fn unwrap(&self, executable_path: &Path) -> anyhow::Result<()> {
+        // a snap command is wrapped by a shim in the default directory, unless the path of the shim is given
+        let executable_path = match snap::app_name(executable_path) {
+            Some(app) => Path::new(snap::DEFAULT_SHIM_DIR).join(app),
+            None => executable_path.to_owned(),
+        };
+
+        let paths = wrapper::ExecPaths::try_from_path(&executable_path)?;
+
+        match wrapper::unwrap(&paths, self.keep_hooks)? {
+            wrapper::Unwrapped::Restored => println!(
+                "{}",
+                t!(
+                    "unwrap-restored",
+                    path = paths.wrapped.original.display().to_string()
+                )
+            ),
+            wrapper::Unwrapped::ShimRemoved => println!(
+                "{}",
+                t!(
+                    "unwrap-shim-removed",
+                    path = paths.wrapped.original.display().to_string()
+                )
+            ),
+            wrapper::Unwrapped::Missing => println!(
+                "{}",
+                t!(
+                    "unwrap-missing",
+                    path = paths.wrapped.original.display().to_string(),
+                    original = paths.unwrapped.original.display().to_string(),
+                )
+            ),
+        }
+
+        if self.keep_hooks {
+            println!("{}", t!("unwrap-hooks-kept"));
+        }
+
+        Ok(())
+    }
+}
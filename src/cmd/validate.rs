@@ -0,0 +1,35 @@
+use std::path::PathBuf;
+
+use argh::FromArgs;
+
+use crate::{
+    i18n::t,
+    manifest::{self, Manifest},
+};
+
+#[derive(FromArgs)]
+/// Check a TOML or JSON manifest against the manifest schema without applying it.
+#[argh(subcommand, name = "validate")]
+pub struct Args {
+    /// path to the manifest; reads from stdin if omitted or `-`
+    #[argh(positional)]
+    manifest_path: Option<PathBuf>,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let (content, format) = manifest::read(self.manifest_path.as_deref())?;
+        let problems = Manifest::validate(&content, format)?;
+
+        if problems.is_empty() {
+            println!("{}", t!("validate-valid"));
+            return Ok(());
+        }
+
+        for problem in &problems {
+            eprintln!("{}", t!("problem", problem = problem.to_string()));
+        }
+
+        anyhow::bail!(t!("manifest-problems", count = problems.len()))
+    }
+}
@@ -0,0 +1,186 @@
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use argh::FromArgs;
+
+use crate::{
+    i18n::t,
+    metadata::Metadata,
+    pacman_files, registry,
+    table::{self, Table},
+    wrapper,
+};
+
+#[derive(FromArgs)]
+/// Check that the wrapper of an executable is intact.
+#[argh(subcommand, name = "verify")]
+pub struct Args {
+    /// absolute path to the wrapped executable (default: every wrapper)
+    #[argh(positional)]
+    executable_path: Option<PathBuf>,
+
+    /// also check the packages owning the wrapped executables with `pacman -Qkk`, telling the changes made by
+    /// wrappers apart from any other
+    #[argh(switch, long = "pacman")]
+    pacman: bool,
+
+    /// only check the wrappers with this tag, instead of every wrapper; can be used multiple times
+    #[argh(option, long = "tag")]
+    tags: Vec<String>,
+
+    /// output format of the wrappers: `table`, `csv` or `json` (default: `table` in a terminal, and otherwise a line
+    /// per wrapper and problem)
+    #[argh(option, long = "format")]
+    format: Option<table::Format>,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let executables = match self.executable_path {
+            Some(_) if !self.tags.is_empty() => {
+                anyhow::bail!(t!("executable-and-tag"))
+            }
+            Some(path) => vec![path],
+            None if !self.tags.is_empty() => registry::find_tagged(&self.tags)?,
+            None => registry::find_wrapped()?,
+        };
+
+        let format = table::Format::resolve(self.format);
+        let mut table = Table::new(vec!["path", "status", "problems"]);
+        let mut broken = 0;
+
+        for executable in &executables {
+            let paths = wrapper::ExecPaths::try_from_path(executable)?;
+            let problems = wrapper::problems(&paths)?;
+
+            if !problems.is_empty() {
+                broken += 1;
+            }
+
+            if format.is_some() {
+                let status = if problems.is_empty() {
+                    "intact"
+                } else {
+                    "broken"
+                };
+                let problems = problems.iter().map(ToString::to_string).collect::<Vec<_>>();
+
+                table.push(vec![
+                    executable.display().to_string(),
+                    status.to_owned(),
+                    problems.join("; "),
+                ]);
+
+                continue;
+            }
+
+            if problems.is_empty() {
+                println!(
+                    "{}",
+                    t!("verify-intact", path = executable.display().to_string())
+                );
+                continue;
+            }
+
+            for problem in &problems {
+                eprintln!("{}", t!("problem", problem = problem.to_string()));
+            }
+
+            eprintln!(
+                "{}",
+                t!(
+                    "verify-problems",
+                    path = executable.display().to_string(),
+                    count = problems.len(),
+                )
+            );
+        }
+
+        if let Some(format) = format {
+            table.print(format)?;
+        }
+
+        let unexpected = if self.pacman {
+            check_packages(&executables)?
+        } else {
+            0
+        };
+
+        match (broken, unexpected) {
+            (0, 0) => Ok(()),
+            (0, unexpected) => anyhow::bail!(t!("verify-unexpected", unexpected = unexpected)),
+            (broken, 0) => anyhow::bail!(t!("verify-broken", broken = broken)),
+            (broken, unexpected) => anyhow::bail!(t!(
+                "verify-broken-and-unexpected",
+                broken = broken,
+                unexpected = unexpected,
+            )),
+        }
+    }
+}
+
+/// Check the packages owning `executables` with `pacman -Qkk`, reporting which of the changes it finds were made by
+/// the wrappers. Returns the number of files with other changes, such as from corruption.
+fn check_packages(executables: &[PathBuf]) -> anyhow::Result<usize> {
+    let mut packages = BTreeSet::new();
+    let mut changed_by_wrappers = BTreeSet::new();
+
+    for executable in executables {
+        // a dormant wrapper has put the original executable back, so it should match its package again
+        let paths = wrapper::ExecPaths::try_from_path(executable)?;
+        let dormant =
+            Metadata::read(&paths.metadata.original).is_ok_and(|metadata| metadata.dormant);
+
+        if let Some(package) = pacman_files::owner(executable) {
+            packages.insert(package);
+        }
+
+        if !dormant {
+            changed_by_wrappers.insert(executable.clone());
+        }
+    }
+
+    let packages = packages.into_iter().collect::<Vec<_>>();
+    let mut unexpected = 0;
+
+    for mismatch in pacman_files::mismatches(&packages)? {
+        let description = format!(
+            "{}: `{}` ({})",
+            mismatch.package,
+            mismatch.path.display(),
+            mismatch.reason
+        );
+
+        if is_changed_by_wrapper(&mismatch.path, &changed_by_wrappers) {
+            println!(
+                "{}",
+                t!("verify-expected", description = description.to_string())
+            );
+        } else {
+            eprintln!("{}", t!("problem", problem = description.to_string()));
+            unexpected += 1;
+        }
+    }
+
+    Ok(unexpected)
+}
+
+/// Returns true if the file at `path` is a wrapper, or a symlink that was repointed at the original executable of one
+/// with `--exclude-symlinks`.
+fn is_changed_by_wrapper(path: &Path, wrapped: &BTreeSet<PathBuf>) -> bool {
+    if wrapped.contains(path) {
+        return true;
+    }
+
+    let is_symlink = fs::symlink_metadata(path).is_ok_and(|metadata| metadata.is_symlink());
+
+    is_symlink
+        && wrapped.iter().any(|executable| {
+            wrapper::ExecPaths::try_from_path(executable).is_ok_and(|paths| {
+                fs::canonicalize(path).is_ok_and(|target| target == paths.unwrapped.original)
+            })
+        })
+}
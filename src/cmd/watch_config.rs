@@ -0,0 +1,219 @@
+use std::{
+    ffi::OsStr,
+    path::{self, Path, PathBuf},
+    thread,
+    time::Duration,
+};
+
+use anyhow::Context;
+use argh::FromArgs;
+use inotify::{EventMask, Inotify, WatchMask};
+
+use super::apply;
+use crate::{error::IoError, i18n::t, manifest::Format};
+
+/// How long to wait for changes to settle by default, so a sync that touches several files is applied once.
+const DEFAULT_DEBOUNCE_MS: u64 = 500;
+
+#[derive(FromArgs)]
+/// Apply a manifest or drop-in directory, and apply it again whenever it changes.
+#[argh(subcommand, name = "watch-config")]
+pub struct Args {
+    /// path to the manifest, or a directory of manifests merged in lexical order
+    #[argh(positional)]
+    manifest_path: PathBuf,
+
+    /// milliseconds to wait after a change for further changes before applying (default: 500)
+    #[argh(option, long = "debounce")]
+    debounce: Option<u64>,
+
+    /// wait up to this many seconds for an in-progress pacman transaction to finish instead of skipping the change
+    #[argh(option, long = "wait-for-pacman")]
+    wait_for_pacman: Option<u64>,
+
+    /// commit the hooks and the manifest to the git repository containing them (such as `/etc` with etckeeper) after
+    /// every change
+    #[argh(switch, long = "git-commit")]
+    git_commit: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        // the path is also what's reported in commits, which are made from wherever the service runs
+        let manifest_path = path::absolute(&self.manifest_path).with_context(|| {
+            IoError::new(&self.manifest_path, t!("watch-invalid-manifest-path"))
+        })?;
+
+        let watched = Watched::new(&manifest_path)?;
+
+        let mut inotify = Inotify::init().with_context(|| t!("watch-inotify-init-failed"))?;
+
+        // editors and sync tools usually replace files by renaming over them, so the directory is watched rather than
+        // the files themselves
+        inotify
+            .watches()
+            .add(
+                watched.dir(),
+                WatchMask::CLOSE_WRITE
+                    | WatchMask::MOVED_TO
+                    | WatchMask::MOVED_FROM
+                    | WatchMask::DELETE
+                    | WatchMask::DELETE_SELF
+                    | WatchMask::MOVE_SELF,
+            )
+            .with_context(|| IoError::new(watched.dir(), t!("watch-dir-failed")))?;
+
+        let debounce = Duration::from_millis(self.debounce.unwrap_or(DEFAULT_DEBOUNCE_MS));
+        let mut buffer = [0; 4096];
+
+        // changes made while the service wasn't running are picked up right away
+        self.apply(&manifest_path);
+
+        println!(
+            "{}",
+            t!(
+                "watch-config-watching",
+                path = manifest_path.display().to_string()
+            )
+        );
+
+        loop {
+            let mut changed = false;
+
+            let events = inotify
+                .read_events_blocking(&mut buffer)
+                .with_context(|| t!("watch-read-events-failed"))?;
+
+            for event in events {
+                // the watch is gone along with the directory, so the service has to be restarted to watch it again
+                if event
+                    .mask
+                    .intersects(EventMask::DELETE_SELF | EventMask::MOVE_SELF | EventMask::IGNORED)
+                {
+                    anyhow::bail!(t!(
+                        "watch-dir-gone",
+                        path = watched.dir().display().to_string(),
+                    ));
+                }
+
+                // if events were dropped, any of them may have been relevant
+                changed |=
+                    event.mask.contains(EventMask::Q_OVERFLOW) || watched.is_relevant(event.name);
+            }
+
+            if !changed {
+                continue;
+            }
+
+            // more changes are likely to follow, such as the rest of the files updated by a `git pull`
+            thread::sleep(debounce);
+
+            match inotify.read_events(&mut buffer) {
+                Ok(events) => events.for_each(drop),
+                Err(err) if err.kind() == std::io::ErrorKind::WouldBlock => {}
+                Err(err) => return Err(err).with_context(|| t!("watch-read-events-failed")),
+            }
+
+            println!(
+                "{}",
+                t!(
+                    "watch-config-changed",
+                    path = manifest_path.display().to_string()
+                )
+            );
+            self.apply(&manifest_path);
+        }
+    }
+
+    /// Apply the manifest at `manifest_path`, reporting rather than returning failures so the service keeps running
+    /// until the manifest is fixed.
+    fn apply(&self, manifest_path: &Path) {
+        let args = apply::Args::new(
+            manifest_path.to_owned(),
+            self.wait_for_pacman,
+            self.git_commit,
+        );
+
+        if let Err(err) = args.run() {
+            eprintln!("{}", t!("error", error = format!("{err:#}")));
+        }
+    }
+}
+
+/// What is watched for changes.
+#[derive(Debug)]
+enum Watched {
+    /// A single manifest, which changes along with the file of the same name in its directory.
+    File { dir: PathBuf, name: PathBuf },
+    /// A drop-in directory, which changes along with any manifest in it.
+    DropIns(PathBuf),
+}
+
+impl Watched {
+    fn new(manifest_path: &Path) -> anyhow::Result<Self> {
+        if manifest_path.is_dir() {
+            return Ok(Self::DropIns(manifest_path.to_owned()));
+        }
+
+        anyhow::ensure!(
+            manifest_path.is_file(),
+            IoError::new(manifest_path, t!("watch-no-manifest"))
+        );
+
+        let dir = manifest_path
+            .parent()
+            .with_context(|| t!("watch-invalid-manifest-path"))?;
+        let name = manifest_path
+            .file_name()
+            .with_context(|| t!("watch-invalid-manifest-path"))?;
+
+        Ok(Self::File {
+            dir: dir.to_owned(),
+            name: name.into(),
+        })
+    }
+
+    fn dir(&self) -> &Path {
+        match self {
+            Self::File { dir, .. } => dir,
+            Self::DropIns(dir) => dir,
+        }
+    }
+
+    /// Whether a change to the file named `name` in the watched directory changes the manifest.
+    fn is_relevant(&self, name: Option<&OsStr>) -> bool {
+        let Some(name) = name.map(Path::new) else {
+            return false;
+        };
+
+        match self {
+            Self::File { name: manifest, .. } => name == manifest,
+            // files with other extensions, such as editor backups, aren't loaded
+            Self::DropIns(_) => Format::from_path(name).is_some(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn relevant_changes() {
+        let file = Watched::File {
+            dir: "/etc".into(),
+            name: "wrappers.toml".into(),
+        };
+
+        assert!(file.is_relevant(Some(OsStr::new("wrappers.toml"))));
+        assert!(!file.is_relevant(Some(OsStr::new("other.toml"))));
+        assert!(!file.is_relevant(None));
+
+        let drop_ins = Watched::DropIns("/etc/wrapperize.d".into());
+
+        assert!(drop_ins.is_relevant(Some(OsStr::new("10-vim.toml"))));
+        assert!(drop_ins.is_relevant(Some(OsStr::new("20-tools.json"))));
+        assert!(!drop_ins.is_relevant(Some(OsStr::new("10-vim.toml~"))));
+        assert!(!drop_ins.is_relevant(Some(OsStr::new(".10-vim.toml.swp"))));
+    }
+}
@@ -0,0 +1,203 @@
+use std::{env as std_env, fs};
+
+use anyhow::Context;
+use argh::FromArgs;
+
+use crate::{
+    error::IoError,
+    file,
+    i18n::t,
+    metadata::Metadata,
+    wrapper::{self, ExecPaths},
+};
+
+#[derive(FromArgs)]
+/// Show which executable a command runs, and what a wrapper launches it with.
+#[argh(subcommand, name = "which")]
+pub struct Args {
+    /// name of the command, searched for in `$PATH` like a shell does, or a path to it
+    #[argh(positional)]
+    command: String,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let path = file::resolve_command(&self.command).with_context(|| {
+            if self.command.contains('/') {
+                t!("which-not-found-path", path = self.command.as_str())
+            } else {
+                t!("which-not-found", command = self.command.as_str())
+            }
+        })?;
+
+        let target = fs::canonicalize(&path)
+            .with_context(|| IoError::new(&path, t!("wrap-resolve-path-failed")))?;
+
+        if target != path {
+            println!(
+                "{}",
+                t!(
+                    "which-symlink",
+                    path = path.display().to_string(),
+                    target = target.display().to_string(),
+                )
+            );
+        }
+
+        let Some(wrapper_path) = wrapper::existing_wrapper(&target)? else {
+            let paths = ExecPaths::try_from_path(&target)?;
+
+            // a dormant wrapper has the original executable back in place, but keeps its metadata
+            if paths.metadata.original.is_file()
+                && Metadata::read(&paths.metadata.original)?.dormant
+            {
+                println!(
+                    "{}",
+                    t!("which-dormant", path = target.display().to_string())
+                );
+            } else {
+                println!(
+                    "{}",
+                    t!("which-not-wrapper", path = target.display().to_string())
+                );
+            }
+
+            return Ok(());
+        };
+
+        if wrapper_path != target {
+            let paths = ExecPaths::try_from_path(&wrapper_path)?;
+
+            // symlinks repointed with `--exclude-symlinks` lead here
+            if paths.unwrapped.original == target {
+                println!(
+                    "{}",
+                    t!(
+                        "which-bypassed",
+                        path = target.display().to_string(),
+                        wrapper = wrapper_path.display().to_string(),
+                    )
+                );
+            } else {
+                println!(
+                    "{}",
+                    t!(
+                        "which-copy",
+                        path = target.display().to_string(),
+                        wrapper = wrapper_path.display().to_string(),
+                    )
+                );
+            }
+
+            return Ok(());
+        }
+
+        let paths = ExecPaths::try_from_path(&target)?;
+        let metadata = Metadata::read(&paths.metadata.original)?;
+
+        println!(
+            "{}",
+            t!(
+                "which-wrapper",
+                path = target.display().to_string(),
+                original = paths.unwrapped.original.display().to_string(),
+            )
+        );
+
+        let params = &metadata.params;
+
+        // a profile is selected by the environment of whoever runs the wrapper, which is this one here
+        let profile = std_env::var(wrapper::PROFILE_VAR)
+            .ok()
+            .and_then(|name| Some((params.profiles.get(&name)?, name)));
+
+        if let Some((_, name)) = &profile {
+            println!("{}", t!("which-profile", name = name.to_string()));
+        }
+
+        let profile = profile.map(|(profile, _)| profile);
+
+        // a masked executable is never run, so there's no command to describe
+        let description = match wrapper::mask_message(params, &paths.wrapped_filename) {
+            Some(message) => t!("which-masked", message = message),
+            None => t!(
+                "which-command",
+                command = wrapper::describe_command(&paths, params, profile),
+            ),
+        };
+
+        println!("{description}");
+
+        if let Some(reason) = &params.reason {
+            println!("{}", t!("which-reason", reason = reason.clone()));
+        }
+
+        if !params.tags.is_empty() {
+            println!("{}", t!("which-tags", tags = params.tags.join(", ")));
+        }
+
+        let env_vars = params
+            .env_vars
+            .iter()
+            .chain(profile.into_iter().flat_map(|profile| &profile.env_vars));
+
+        for env in env_vars {
+            println!("{}", t!("which-env", env = env.to_string()));
+        }
+
+        if !params.jvm_options.is_empty() {
+            println!(
+                "{}",
+                t!("which-jvm-options", options = params.jvm_options.join(" "))
+            );
+        }
+
+        for limit in &params.limits {
+            println!("{}", t!("which-ulimit", limit = limit.to_string()));
+        }
+
+        if !params.access.is_empty() {
+            println!("{}", t!("which-access", rules = params.access.to_string()));
+        }
+
+        if let Some(hours) = &params.allowed_hours {
+            println!("{}", t!("which-allowed-hours", hours = hours.to_string()));
+        }
+
+        if let Some(expires) = params.expires {
+            println!("{}", t!("which-expires", expires = expires.to_string()));
+        }
+
+        for preset in &params.presets {
+            println!("{}", t!("which-preset", preset = preset.to_string()));
+        }
+
+        if params.journal {
+            println!("{}", t!("which-journal"));
+        }
+
+        if params.runtime_config {
+            println!(
+                "{}",
+                t!(
+                    "which-runtime-config",
+                    path = wrapper::runtime_config_path(&paths.wrapped_filename)
+                        .display()
+                        .to_string(),
+                )
+            );
+        }
+
+        if params.user_override {
+            println!(
+                "{}",
+                t!(
+                    "which-user-override",
+                    path = wrapper::user_override_path(&paths.wrapped_filename),
+                )
+            );
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,1266 @@
+use std::{
+    cell::RefCell,
+    collections::BTreeMap,
+    fs,
+    io::{self, IsTerminal},
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use anyhow::Context;
+use argh::FromArgs;
+
+use super::diff;
+use crate::{
+    access::Access,
+    affinity::CpuList,
+    apparmor, appimage,
+    container::{self, Container, HostContainer, HostContainerTool},
+    env,
+    error::{ChangesNeeded, IoError},
+    exec_type::ExecType,
+    expiry::Expiry,
+    file::FileMode,
+    hours::AllowedHours,
+    i18n::t,
+    jvm::{self, GarbageCollector},
+    limit::Limit,
+    metadata::Metadata,
+    pacman_files, pacman_hook, pacman_lock, plugin, preset, prompt, script,
+    settings::{self, Settings},
+    snap, snapshot, symlink,
+    via::Via,
+    wrapper,
+};
+
+#[derive(FromArgs)]
+/// Wrap an executable to always execute with additional arguments and/or environment variables.
+#[argh(subcommand, name = "wrap")]
+pub struct Args {
+    /// absolute path to the executable to wrap
+    #[argh(positional)]
+    executable_path: PathBuf,
+
+    /// additional arguments to launch the executable with, taken verbatim when placed after `--`
+    #[argh(positional, greedy)]
+    trailing_args: Vec<String>,
+
+    /// an additional argument to launch the executable with; can be used multiple times
+    #[argh(option, short = 'a', long = "arg")]
+    args: Vec<String>,
+
+    /// an argument that always precedes the passthrough arguments; can be used multiple times
+    #[argh(option, long = "arg-before")]
+    args_before: Vec<String>,
+
+    /// an argument that always follows the passthrough arguments; can be used multiple times
+    #[argh(option, long = "arg-after")]
+    args_after: Vec<String>,
+
+    /// an environment variable in the format of `ENV=value` to launch the executable with, or `ENV+=value` and `ENV=+value`
+    /// to append and prepend to a list such as `PATH`; can be used multiple times
+    #[argh(option, short = 'e', long = "env")]
+    envs: Vec<env::Variable<'static>>,
+
+    /// do not generate hooks for pacman; intended to be used for paths not managed by pacman (such as `/home`)
+    #[argh(switch, long = "nohooks")]
+    skip_pacman_hooks: bool,
+
+    /// generate hooks for pacman even if the executable is in a location pacman doesn't manage
+    #[argh(switch, long = "hooks")]
+    force_pacman_hooks: bool,
+
+    /// place the wrapper arguments after the passthrough arguments, so they are seen last by the wrapped executable
+    #[argh(switch, long = "passthrough-args-first")]
+    add_passthrough_args_first: bool,
+
+    /// ignore any arguments passed to the wrapper, so the executable always runs with exactly the wrapper arguments
+    #[argh(switch, long = "no-passthrough")]
+    no_passthrough: bool,
+
+    /// absolute path to a directory to move the original executable into, instead of a hidden file next to the wrapper
+    #[argh(option, long = "store-dir")]
+    store_dir: Option<PathBuf>,
+
+    /// filename of the original executable, where `{name}` is replaced by the name of the executable (default: `.{name}-unwrapped`)
+    #[argh(option, long = "unwrapped-pattern")]
+    unwrapped_pattern: Option<String>,
+
+    /// add the arguments from `-a` and environment variables from `-e` to this profile, which is applied on top of the other parameters when the wrapper runs with `WRAPPERIZE_PROFILE` set to its name
+    #[argh(option, long = "profile")]
+    profile: Option<String>,
+
+    /// source `/etc/wrapperize/conf.d/<name>.conf` when the wrapper runs, if it exists, so its arguments and environment variables can be adjusted without rewrapping
+    #[argh(switch, long = "runtime-config")]
+    runtime_config: bool,
+
+    /// source `$XDG_CONFIG_HOME/wrapperize/<name>.override` of the user running the wrapper after the runtime configuration, if it exists, so users can add their own arguments and environment variables without root
+    #[argh(switch, long = "user-override")]
+    user_override: bool,
+
+    /// wrap the file even if it doesn't look like an executable, such as a shared library or a data file
+    #[argh(switch, long = "allow-non-executable")]
+    allow_non_executable: bool,
+
+    /// run the original script with the interpreter from its shebang, which sees the path of the wrapper as the name
+    /// it was run with
+    #[argh(switch, long = "interpreter")]
+    interpreter: bool,
+
+    /// add a visible `<name>-unwrapped` symlink next to the wrapper, so the original executable can be run directly
+    #[argh(switch, long = "expose-original")]
+    expose_original: bool,
+
+    /// leave out the strict bash options (`set -euo pipefail`) from the generated wrapper and install scripts
+    #[argh(switch, long = "no-strict")]
+    no_strict: bool,
+
+    /// keep the strict bash options even if the settings leave them out
+    #[argh(switch, long = "strict")]
+    strict: bool,
+
+    /// octal permissions of the wrapper, such as `0750` (default: those of the original executable)
+    #[argh(option, long = "wrapper-mode")]
+    wrapper_mode: Option<FileMode>,
+
+    /// octal permissions of the pacman hooks, such as `0640`, with execute permission added for the install script (default: `0644`)
+    #[argh(option, long = "hook-mode")]
+    hook_mode: Option<FileMode>,
+
+    /// a built-in set of parameters applied when the wrapper runs, out of `gaming`, `gpu-offload`, `wayland` and `proxy`,
+    /// or the name of a `wrapperize-preset-<name>` plugin in `$PATH` whose parameters are added now; can be used
+    /// multiple times
+    #[argh(option, long = "preset", from_str_fn(parse_preset))]
+    presets: Vec<preset::Choice>,
+
+    /// DRI device for the `gpu-offload` preset to render on, such as `pci-0000_01_00_0` (default: the one Mesa or the NVIDIA driver picks)
+    #[argh(option, long = "gpu-device")]
+    gpu_device: Option<String>,
+
+    /// a flag for the `wayland` preset to add instead of its defaults, for Chromium versions that need different ones; can be used multiple times
+    #[argh(option, long = "wayland-flag")]
+    wayland_flags: Vec<String>,
+
+    /// URL of the proxy for the `proxy` preset to route the executable through, such as `http://proxy.example.com:3128`
+    #[argh(option, long = "proxy")]
+    proxy: Option<String>,
+
+    /// comma-separated hosts for the `proxy` preset to exclude from the proxy, such as `localhost,.internal.example.com`
+    #[argh(option, long = "no-proxy")]
+    no_proxy: Option<String>,
+
+    /// maximum heap size of every JVM the executable starts, such as `4g`
+    #[argh(option, long = "jvm-max-heap", from_str_fn(parse_max_heap))]
+    jvm_max_heap: Option<String>,
+
+    /// initial heap size of every JVM the executable starts, such as `1g`
+    #[argh(option, long = "jvm-min-heap", from_str_fn(parse_min_heap))]
+    jvm_min_heap: Option<String>,
+
+    /// garbage collector of every JVM the executable starts, out of `g1`, `z`, `shenandoah`, `parallel` and `serial`
+    #[argh(option, long = "jvm-gc", from_str_fn(parse_gc))]
+    jvm_gc: Option<GarbageCollector>,
+
+    /// an option for every JVM the executable starts, passed through `JAVA_TOOL_OPTIONS`; can be used multiple times
+    #[argh(option, long = "jvm-option")]
+    jvm_options: Vec<String>,
+
+    /// run this container image in place of the original executable, such as `docker.io/library/python:3.12`
+    #[argh(option, long = "container-image")]
+    container_image: Option<String>,
+
+    /// program that runs the container, out of `podman` and `docker` (default: `podman`)
+    #[argh(option, long = "container-engine")]
+    container_engine: Option<container::Engine>,
+
+    /// command to run in the container (default: the image's entrypoint, or the name of the executable in a distrobox or toolbox container)
+    #[argh(option, long = "container-command")]
+    container_command: Option<String>,
+
+    /// a volume to mount in the container, in the format of `/host/path:/container/path[:options]`; can be used multiple times
+    #[argh(option, long = "container-volume")]
+    container_volumes: Vec<String>,
+
+    /// the name of an environment variable to pass from the host into the container; can be used multiple times
+    #[argh(option, long = "container-env")]
+    container_envs: Vec<String>,
+
+    /// mount the current directory at the same path in the container and run the command there
+    #[argh(switch, long = "container-cwd")]
+    container_cwd: bool,
+
+    /// run the executable in this existing distrobox container, where it has the same name, instead of the original
+    #[argh(option, long = "distrobox")]
+    distrobox: Option<String>,
+
+    /// run the executable in this existing toolbox container, where it has the same name, instead of the original
+    #[argh(option, long = "toolbox")]
+    toolbox: Option<String>,
+
+    /// route the executable's network traffic through `proxychains` or `torsocks`, which must be installed
+    #[argh(option, long = "via", from_str_fn(parse_via))]
+    via: Option<Via>,
+
+    /// send the output of the executable to the journal with `systemd-cat` when it isn't run in a terminal, such as
+    /// when launched from a desktop environment
+    #[argh(switch, long = "journal")]
+    journal: bool,
+
+    /// write the exit status, process ID and start and finish times of the executable to this file after every run,
+    /// where `{name}` is replaced by the name of the executable and variables such as `$HOME` are expanded at runtime
+    #[argh(option, long = "status-file")]
+    status_file: Option<String>,
+
+    /// run the executable in its own network namespace with `unshare`, so it has no network access
+    #[argh(switch, long = "no-network")]
+    no_network: bool,
+
+    /// a soft resource limit in the format of `nofile=65536` or `core=0`, set with `ulimit` before the executable launches; can be used multiple times
+    #[argh(option, long = "ulimit")]
+    limits: Vec<Limit>,
+
+    /// CPUs to confine the executable to with `taskset`, such as `0-3,8`
+    #[argh(option, long = "cpus")]
+    cpus: Option<CpuList>,
+
+    /// repoint symlinks to the executable at the unwrapped executable, so they bypass the wrapper
+    #[argh(switch, long = "exclude-symlinks")]
+    exclude_symlinks: bool,
+
+    /// print the rules that let the wrapper run the original executable under the AppArmor profiles confining the executable, for their local overrides
+    #[argh(switch, long = "apparmor-snippet")]
+    apparmor_snippet: bool,
+
+    /// the name of a symlink to the executable, such as `ls` for BusyBox, to wrap instead of the executable itself, for
+    /// multi-call binaries that decide what to run by the name they were run with; can be used multiple times
+    #[argh(option, long = "applet")]
+    applets: Vec<String>,
+
+    /// directory to place the shim in when wrapping a snap command, which must come before `/snap/bin` in `$PATH`
+    /// (default: `/usr/local/bin`)
+    #[argh(option, long = "shim-dir")]
+    shim_dir: Option<PathBuf>,
+
+    /// recreate the wrapper with systemd user units whenever the executable is replaced, such as when it updates itself,
+    /// instead of with pacman hooks; for executables in paths the user owns
+    #[argh(switch, long = "user-units")]
+    user_units: bool,
+
+    /// recreate the wrapper with the `wrapperize-backend-<name>` plugin in `$PATH` whenever its package manager replaces
+    /// the executable, instead of with pacman hooks
+    #[argh(option, long = "backend", from_str_fn(parse_backend))]
+    backend: Option<String>,
+
+    /// remove the wrapper with a systemd timer once this date passes, such as `2025-12-31`, or after this long, such as
+    /// `12h` or `7d`; times are in UTC
+    #[argh(option, long = "expires", from_str_fn(parse_expiry))]
+    expires: Option<Expiry>,
+
+    /// why the executable is wrapped, such as "work VPN requires this proxy", which is kept with the wrapper and shown by `which`
+    #[argh(option, long = "reason")]
+    reason: Option<String>,
+
+    /// a group the wrapper belongs to, such as `gaming`, so it can be unwrapped or verified along with the others with `--tag`; can be used multiple times
+    #[argh(option, long = "tag")]
+    tags: Vec<String>,
+
+    /// only let this user run the wrapper, along with any others allowed; can be used multiple times
+    #[argh(option, long = "allow-user")]
+    allow_users: Vec<String>,
+
+    /// only let members of this group run the wrapper, along with any others allowed; can be used multiple times
+    #[argh(option, long = "allow-group")]
+    allow_groups: Vec<String>,
+
+    /// refuse to run the wrapper for this user, even if allowed otherwise; can be used multiple times
+    #[argh(option, long = "deny-user")]
+    deny_users: Vec<String>,
+
+    /// refuse to run the wrapper for members of this group, even if allowed otherwise; can be used multiple times
+    #[argh(option, long = "deny-group")]
+    deny_groups: Vec<String>,
+
+    /// only run the executable in this time of day, such as `08:00-18:00`, in the local time of the system; set
+    /// `WRAPPERIZE_IGNORE_HOURS=1` to run it anyway
+    #[argh(option, long = "allowed-hours")]
+    allowed_hours: Option<AllowedHours>,
+
+    /// comma-separated pacman operations that recreate the wrapper, out of `install` and `upgrade` (default: both)
+    #[argh(option, long = "on", from_str_fn(parse_operations))]
+    hook_operations: Option<Vec<pacman_hook::Operation>>,
+
+    /// merge the arguments and environment variables into the existing wrapper for the executable instead of creating a new one
+    #[argh(switch, long = "update")]
+    update: bool,
+
+    /// only report whether the wrapper would change, exiting with code 2 if it would, without modifying anything
+    #[argh(switch, long = "check")]
+    check: bool,
+
+    /// run the install script in a throwaway bubblewrap overlay of the directories it changes, and report which files it
+    /// would create, rename or modify, without modifying anything
+    #[cfg(target_os = "linux")]
+    #[argh(switch, long = "preview-exec")]
+    preview_exec: bool,
+
+    /// wait up to this many seconds for an in-progress pacman transaction to finish instead of aborting
+    #[argh(option, long = "wait-for-pacman")]
+    wait_for_pacman: Option<u64>,
+
+    /// apply changes to an existing wrapper without asking for confirmation after showing how its files change
+    #[argh(switch, short = 'y', long = "yes")]
+    yes: bool,
+
+    /// take a system snapshot with snapper or timeshift before changing any files
+    #[argh(switch, long = "snapshot")]
+    snapshot: bool,
+
+    /// commit the hooks to the git repository containing them (such as `/etc` with etckeeper) afterwards
+    #[argh(switch, long = "git-commit")]
+    git_commit: bool,
+}
+
+impl Args {
+    pub fn run(self) -> anyhow::Result<()> {
+        let executable_path = self.executable_path.clone();
+        let git_commit = self.git_commit && self.dry_run()?.is_none();
+
+        self.create_or_update()?;
+
+        if git_commit {
+            super::commit_hooks("wrap", &executable_path)?;
+        }
+
+        Ok(())
+    }
+
+    /// How to report what the command would do instead of doing it, if at all.
+    fn dry_run(&self) -> anyhow::Result<Option<DryRun>> {
+        #[cfg(target_os = "linux")]
+        if self.preview_exec {
+            anyhow::ensure!(!self.check, t!("wrap-check-and-preview-exec"));
+
+            return Ok(Some(DryRun::PreviewExec));
+        }
+
+        Ok(self.check.then_some(DryRun::Check))
+    }
+
+    fn create_or_update(mut self) -> anyhow::Result<()> {
+        self.args.append(&mut self.trailing_args);
+        let dry_run = self.dry_run()?;
+
+        if self.strict && self.no_strict {
+            anyhow::bail!(t!("wrap-strict-and-no-strict"));
+        }
+
+        // updating a wrapper shouldn't change what it was created with
+        if !self.update {
+            self.apply_settings(settings::get());
+        }
+
+        let explicit_pacman_hooks = match (self.skip_pacman_hooks, self.force_pacman_hooks) {
+            (true, true) => anyhow::bail!(t!("wrap-nohooks-and-hooks")),
+            (true, false) => Some(false),
+            (false, true) => Some(true),
+            (false, false) => None,
+        };
+
+        let explicit_pacman_hooks = replace_pacman_hooks(
+            explicit_pacman_hooks,
+            self.user_units,
+            self.backend.as_deref(),
+        );
+
+        // the commands of snaps can't be replaced, so a shim that comes before them in `$PATH` runs them instead
+        let snap_app = snap::app_name(&self.executable_path).map(str::to_owned);
+
+        let explicit_pacman_hooks = match &snap_app {
+            Some(app) => {
+                let shim_dir = self
+                    .shim_dir
+                    .take()
+                    .unwrap_or_else(|| snap::DEFAULT_SHIM_DIR.into());
+
+                if !snap::shim_dir_takes_precedence(&shim_dir) {
+                    eprintln!(
+                        "{}",
+                        t!("wrap-snap-path-order", dir = shim_dir.display().to_string())
+                    );
+                }
+
+                self.executable_path = shim_dir.join(app);
+                println!(
+                    "{}",
+                    t!(
+                        "wrap-snap-shim",
+                        app = app.to_string(),
+                        path = self.executable_path.display().to_string(),
+                    )
+                );
+
+                Some(false)
+            }
+            None => explicit_pacman_hooks,
+        };
+
+        if self.shim_dir.is_some() {
+            anyhow::bail!(t!("wrap-shim-dir-not-snap"));
+        }
+
+        // a profile takes the arguments and environment variables that would otherwise be the defaults
+        let (args, envs, profiles) = match self.profile {
+            Some(name) => {
+                if !self.args_before.is_empty() || !self.args_after.is_empty() {
+                    anyhow::bail!(t!("wrap-args-and-profile"));
+                }
+
+                let profile = wrapper::Profile {
+                    args: self.args,
+                    env_vars: self.envs,
+                };
+
+                (Vec::new(), Vec::new(), BTreeMap::from([(name, profile)]))
+            }
+            None => (self.args, self.envs, BTreeMap::new()),
+        };
+
+        let host_container = match (self.distrobox, self.toolbox) {
+            (Some(_), Some(_)) => {
+                anyhow::bail!(t!("wrap-distrobox-and-toolbox"))
+            }
+            (Some(name), None) => Some((HostContainerTool::Distrobox, name)),
+            (None, Some(name)) => Some((HostContainerTool::Toolbox, name)),
+            (None, None) => None,
+        };
+
+        // the command applies to whichever kind of container is used
+        let host_container = host_container.map(|(tool, name)| HostContainer {
+            tool,
+            name,
+            command: self.container_command.clone(),
+        });
+
+        let container = match self.container_image {
+            Some(image) => Some(Container {
+                image,
+                engine: self.container_engine.unwrap_or_default(),
+                command: self.container_command,
+                volumes: self.container_volumes,
+                envs: self.container_envs,
+                mount_cwd: self.container_cwd,
+            }),
+            None if self.container_engine.is_some()
+                || (self.container_command.is_some() && host_container.is_none())
+                || !self.container_volumes.is_empty()
+                || !self.container_envs.is_empty()
+                || self.container_cwd =>
+            {
+                anyhow::bail!(t!("wrap-container-options"));
+            }
+            None => None,
+        };
+
+        let jvm_options = [self.jvm_max_heap, self.jvm_min_heap]
+            .into_iter()
+            .flatten()
+            .chain(self.jvm_gc.map(GarbageCollector::option))
+            .chain(self.jvm_options)
+            .collect();
+
+        // each applet gets a wrapper of its own, and the executable they share is left alone
+        let applet_paths = if self.applets.is_empty() {
+            Vec::new()
+        } else {
+            find_applets(&self.executable_path, &self.applets)?
+        };
+
+        let appimage = appimage::is_appimage(&self.executable_path);
+
+        let interpreter = if self.interpreter {
+            script::read_shebang(&self.executable_path).with_context(|| {
+                IoError::new(&self.executable_path, t!("wrap-interpreter-no-shebang"))
+            })?
+        } else {
+            Vec::new()
+        };
+
+        if appimage {
+            println!(
+                "{}",
+                t!(
+                    "wrap-appimage",
+                    path = self.executable_path.display().to_string()
+                )
+            );
+        }
+
+        // an upgrade leaves the wrapper in place as if it was edited configuration
+        let config_protection = pacman_files::config_protection(&self.executable_path);
+
+        if let Some(reason) = &config_protection {
+            eprintln!(
+                "{}",
+                t!(
+                    "wrap-config-protected",
+                    path = self.executable_path.display().to_string(),
+                    reason = reason.as_str(),
+                )
+            );
+        }
+
+        // the parameters given here come after those of preset plugins, so they take precedence
+        let (presets, mut wrapper_params) = preset::resolve(self.presets, &self.executable_path)?;
+
+        wrapper_params.merge(wrapper::Params {
+            args,
+            args_before: self.args_before,
+            args_after: self.args_after,
+            add_passthrough_args_first: self.add_passthrough_args_first,
+            no_passthrough: self.no_passthrough,
+            store_dir: self.store_dir,
+            unwrapped_pattern: self.unwrapped_pattern,
+            runtime_config: self.runtime_config,
+            user_override: self.user_override,
+            expose_original: self.expose_original,
+            no_strict: self.no_strict,
+            appimage,
+            applet: !applet_paths.is_empty(),
+            config_protected: config_protection.is_some(),
+            allow_non_executable: self.allow_non_executable,
+            interpreter,
+            wrapper_mode: self.wrapper_mode,
+            hook_mode: self.hook_mode,
+            presets,
+            gpu_device: self.gpu_device,
+            wayland_flags: self.wayland_flags,
+            proxy: self.proxy,
+            no_proxy: self.no_proxy,
+            jvm_options,
+            container,
+            host_container,
+            snap: snap_app,
+            via: self.via,
+            no_network: self.no_network,
+            journal: self.journal,
+            status_file: self.status_file,
+            cpus: self.cpus,
+            limits: self.limits,
+            hook_operations: self.hook_operations.unwrap_or_default(),
+            user_units: self.user_units,
+            backend: self.backend,
+            expires: self.expires,
+            reason: self.reason,
+            tags: self.tags,
+            mask: None,
+            access: Access {
+                allow_users: self.allow_users,
+                allow_groups: self.allow_groups,
+                deny_users: self.deny_users,
+                deny_groups: self.deny_groups,
+            },
+            allowed_hours: self.allowed_hours,
+            env_vars: envs,
+            profiles,
+        });
+
+        let options = TargetOptions {
+            explicit_pacman_hooks,
+            update: self.update,
+            dry_run,
+            yes: self.yes,
+            exclude_symlinks: self.exclude_symlinks,
+            apparmor_snippet: self.apparmor_snippet,
+            wait_for_pacman: self.wait_for_pacman.map(Duration::from_secs),
+            snapshot: RefCell::new(
+                self.snapshot
+                    .then(|| super::change_description("wrap", &self.executable_path)),
+            ),
+        };
+
+        if applet_paths.is_empty() {
+            return create_or_update_target(&self.executable_path, wrapper_params, &options);
+        }
+
+        for path in &applet_paths {
+            create_or_update_target(path, wrapper_params.clone(), &options)?;
+        }
+
+        Ok(())
+    }
+
+    /// Fill in the options that weren't given with the defaults from `settings`.
+    fn apply_settings(&mut self, settings: &Settings) {
+        if self.store_dir.is_none() {
+            self.store_dir.clone_from(&settings.store_dir);
+        }
+
+        if !self.strict && settings.strict == Some(false) {
+            self.no_strict = true;
+        }
+
+        // choosing how the wrapper is recreated on the command line replaces the default backend
+        let recreation_chosen = self.backend.is_some()
+            || self.user_units
+            || self.skip_pacman_hooks
+            || self.force_pacman_hooks
+            || snap::app_name(&self.executable_path).is_some();
+
+        if !recreation_chosen {
+            self.backend.clone_from(&settings.backend);
+        }
+    }
+}
+
+/// How `wrap` reports what it would do to an executable instead of doing it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum DryRun {
+    /// Only report whether the wrapper would change.
+    Check,
+    /// Run the install script in a sandbox and report which files it would change.
+    #[cfg(target_os = "linux")]
+    PreviewExec,
+}
+
+/// Options of `wrap` that apply to each executable it wraps.
+struct TargetOptions {
+    explicit_pacman_hooks: Option<bool>,
+    update: bool,
+    dry_run: Option<DryRun>,
+    yes: bool,
+    exclude_symlinks: bool,
+    apparmor_snippet: bool,
+    wait_for_pacman: Option<Duration>,
+    /// Description of the system snapshot to take before the first change, until it's been taken.
+    snapshot: RefCell<Option<String>>,
+}
+
+/// Get ready to install `staged` by making sure no `pacman` transaction is running if it has hooks, and taking the
+/// snapshot in `options` if it hasn't been taken yet, unless nothing would change.
+fn prepare_install(staged: &wrapper::Staged, options: &TargetOptions) -> anyhow::Result<()> {
+    if staged.is_up_to_date() {
+        return Ok(());
+    }
+
+    // executables without hooks aren't managed by pacman, so a transaction can't interfere with them
+    if staged.uses_pacman_hooks() {
+        pacman_lock::ensure_unlocked(options.wait_for_pacman)?;
+    }
+
+    if let Some(description) = options.snapshot.take() {
+        snapshot::create(&description)?;
+    }
+
+    Ok(())
+}
+
+/// Create, update or check the wrapper for the executable at `executable_path`.
+fn create_or_update_target(
+    executable_path: &Path,
+    wrapper_params: wrapper::Params,
+    options: &TargetOptions,
+) -> anyhow::Result<()> {
+    let explicit_pacman_hooks = options.explicit_pacman_hooks;
+
+    if options.update {
+        return update(
+            executable_path,
+            wrapper_params,
+            explicit_pacman_hooks,
+            options,
+        );
+    }
+
+    let use_pacman_hooks = resolve_use_pacman_hooks(executable_path, explicit_pacman_hooks);
+
+    if let Some(existing) = wrapper::existing_wrapper(executable_path)? {
+        // rerunning the same command is fine, and is reported as unchanged
+        let identical = existing == executable_path
+            && stage(
+                &existing,
+                &wrapper_params,
+                use_pacman_hooks,
+                wrapper::Mode::Create,
+            )
+            .is_ok_and(|staged| staged.is_up_to_date());
+
+        if !identical {
+            return on_existing_wrapper(
+                executable_path,
+                &existing,
+                wrapper_params,
+                explicit_pacman_hooks,
+                options,
+            );
+        }
+    }
+
+    if let Some(dry_run) = options.dry_run {
+        let staged = stage(
+            executable_path,
+            &wrapper_params,
+            use_pacman_hooks,
+            wrapper::Mode::Create,
+        )?;
+
+        return report(&staged, dry_run);
+    }
+
+    warn_about_self_references(executable_path);
+
+    let is_symlink = fs::symlink_metadata(executable_path).is_ok_and(|meta| meta.is_symlink());
+
+    if wrapper_params.applet && is_symlink {
+        let staged = stage(
+            executable_path,
+            &wrapper_params,
+            use_pacman_hooks,
+            wrapper::Mode::Create,
+        )?;
+
+        // a relative symlink would no longer resolve once moved into a store directory
+        let target = fs::canonicalize(executable_path)
+            .with_context(|| IoError::new(executable_path, t!("wrap-resolve-applet-failed")))?;
+
+        prepare_install(&staged, options)?;
+        symlink::repoint(executable_path, &target)?;
+        install(staged)?;
+    } else {
+        let staged = stage(
+            executable_path,
+            &wrapper_params,
+            use_pacman_hooks,
+            wrapper::Mode::Create,
+        )?;
+
+        prepare_install(&staged, options)?;
+        install(staged)?;
+    }
+
+    warn_about_apparmor(executable_path, options.apparmor_snippet)?;
+    handle_symlinks(executable_path, options.exclude_symlinks)
+}
+
+/// Find the symlinks named after `applets` that point to the multi-call binary at `executable_path`, or the wrappers
+/// that have taken their place.
+fn find_applets(executable_path: &Path, applets: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+    // the symlinks of a wrapped binary would lead to its wrapper rather than the binary
+    if wrapper::existing_wrapper(executable_path)?.is_some() {
+        anyhow::bail!(t!(
+            "wrap-applets-of-wrapped",
+            path = executable_path.display().to_string(),
+        ));
+    }
+
+    let target = fs::canonicalize(executable_path)
+        .with_context(|| IoError::new(executable_path, t!("wrap-resolve-path-failed")))?;
+
+    // applets are commonly installed next to the binary
+    let mut dirs = symlink::search_dirs();
+
+    if let Some(dir) = target
+        .parent()
+        .filter(|dir| !dirs.iter().any(|other| other == dir))
+    {
+        dirs.insert(0, dir.to_owned());
+    }
+
+    let is_applet = |path: &Path| {
+        let is_symlink = fs::symlink_metadata(path).is_ok_and(|meta| meta.is_symlink());
+
+        let original = if is_symlink {
+            path.to_owned()
+        } else if wrapper::existing_wrapper(path)
+            .is_ok_and(|existing| existing.as_deref() == Some(path))
+        {
+            match wrapper::ExecPaths::try_from_path(path) {
+                Ok(paths) => paths.unwrapped.original,
+                Err(_) => return false,
+            }
+        } else {
+            return false;
+        };
+
+        fs::canonicalize(original).is_ok_and(|original| original == target)
+    };
+
+    applets
+        .iter()
+        .map(|name| {
+            dirs.iter()
+                .map(|dir| dir.join(name))
+                .find(|path| is_applet(path))
+                .with_context(|| {
+                    t!(
+                        "wrap-applet-not-found",
+                        name = name,
+                        path = executable_path.display().to_string(),
+                    )
+                })
+        })
+        .collect()
+}
+
+/// The explicit choice of whether to generate `pacman` hooks, which is not to if user units or a backend recreate the
+/// wrapper, unless the hooks were asked for anyway.
+pub fn replace_pacman_hooks(
+    explicit: Option<bool>,
+    user_units: bool,
+    backend: Option<&str>,
+) -> Option<bool> {
+    // the units and backends take the place of the hooks
+    explicit.or((user_units || backend.is_some()).then_some(false))
+}
+
+/// Determine whether hooks should be generated for the executable at `executable_path`, using `explicit` if the
+/// user chose, or otherwise based on whether the executable could be managed by `pacman`.
+pub fn resolve_use_pacman_hooks(executable_path: &Path, explicit: Option<bool>) -> bool {
+    let (use_pacman_hooks, notice) = decide_pacman_hooks(executable_path, explicit);
+
+    if let Some(notice) = notice {
+        println!("{notice}");
+    }
+
+    use_pacman_hooks
+}
+
+/// Like [`resolve_use_pacman_hooks`], but returns the notice explaining why no hooks are generated instead of printing
+/// it, if that wasn't the user's choice.
+pub fn decide_pacman_hooks(
+    executable_path: &Path,
+    explicit: Option<bool>,
+) -> (bool, Option<String>) {
+    if let Some(use_pacman_hooks) = explicit {
+        return (use_pacman_hooks, None);
+    }
+
+    match pacman_hook::unmanaged_reason(executable_path) {
+        Some(reason) => {
+            let notice = t!(
+                "wrap-no-pacman-hooks",
+                path = executable_path.display().to_string(),
+                reason = reason,
+            );
+            (false, Some(notice))
+        }
+        None => (true, None),
+    }
+}
+
+/// Warn if the executable at `executable_path` is a script that refers to its own path, since that will be the path of
+/// the original once it's moved out of the way.
+fn warn_about_self_references(executable_path: &Path) {
+    if script::read_shebang(executable_path).is_none() {
+        return;
+    }
+
+    let references = script::self_references(executable_path);
+
+    if references.is_empty() {
+        return;
+    }
+
+    let references = references
+        .iter()
+        .map(|reference| format!("`{reference}`"))
+        .collect::<Vec<_>>();
+
+    eprintln!(
+        "{}",
+        t!(
+            "wrap-script-self-reference",
+            path = executable_path.display().to_string(),
+            references = references.join(", "),
+        )
+    );
+}
+
+/// Warn about the AppArmor profiles confining the newly wrapped executable at `executable_path`, which now confine its
+/// wrapper instead and may not let it run the original, printing the rules that would if `snippet` is set.
+fn warn_about_apparmor(executable_path: &Path, snippet: bool) -> anyhow::Result<()> {
+    let profiles = apparmor::attached_profiles(executable_path);
+
+    if profiles.is_empty() {
+        return Ok(());
+    }
+
+    let paths = wrapper::ExecPaths::try_from_path(executable_path)?;
+
+    for profile in &profiles {
+        eprintln!(
+            "{}",
+            t!(
+                "wrap-apparmor",
+                profile = profile.name.clone(),
+                mode = profile.mode.clone(),
+                path = executable_path.display().to_string(),
+                unwrapped = paths.unwrapped.original.display().to_string(),
+            )
+        );
+
+        if snippet {
+            let mut rules = String::new();
+            apparmor::write_snippet(
+                profile,
+                executable_path,
+                &paths.unwrapped.original,
+                &mut rules,
+            )?;
+
+            print!("{rules}");
+        }
+    }
+
+    if !snippet {
+        eprintln!("{}", t!("wrap-apparmor-snippet-hint"));
+    }
+
+    Ok(())
+}
+
+/// Report symlinks that point to the wrapped executable, since they'll now go through the wrapper as well,
+/// and repoint them to the unwrapped executable if `exclude` is set.
+fn handle_symlinks(executable_path: &Path, exclude: bool) -> anyhow::Result<()> {
+    let links = symlink::find_pointing_at(executable_path, &symlink::search_dirs());
+
+    if links.is_empty() {
+        return Ok(());
+    }
+
+    if !exclude {
+        eprintln!("{}", t!("wrap-symlinks"));
+
+        for link in &links {
+            eprintln!("  {}", link.display());
+        }
+
+        eprintln!("{}", t!("wrap-symlinks-exclude"));
+
+        // the wrapper runs the executable under its own name, which a multi-call binary can't dispatch on
+        eprintln!("{}", t!("wrap-symlinks-applet"));
+        return Ok(());
+    }
+
+    let paths = wrapper::ExecPaths::try_from_path(executable_path)?;
+
+    for link in &links {
+        symlink::repoint(link, &paths.unwrapped.original)?;
+        println!(
+            "{}",
+            t!(
+                "wrap-repointed",
+                link = link.display().to_string(),
+                target = paths.unwrapped.original.display().to_string(),
+            )
+        );
+    }
+
+    Ok(())
+}
+
+fn parse_max_heap(value: &str) -> Result<String, String> {
+    jvm::heap_option("-Xmx", value).map_err(|err| err.to_string())
+}
+
+fn parse_min_heap(value: &str) -> Result<String, String> {
+    jvm::heap_option("-Xms", value).map_err(|err| err.to_string())
+}
+
+fn parse_gc(value: &str) -> Result<GarbageCollector, String> {
+    GarbageCollector::parse(value)
+}
+
+fn parse_via(value: &str) -> Result<Via, String> {
+    Via::parse(value)
+}
+
+fn parse_preset(value: &str) -> Result<preset::Choice, String> {
+    preset::Choice::parse(value)
+}
+
+fn parse_backend(value: &str) -> Result<String, String> {
+    plugin::validate_name(value).map_err(|err| err.to_string())?;
+
+    if plugin::find(plugin::Kind::Backend, value).is_none() {
+        let known = plugin::find_all(plugin::Kind::Backend);
+
+        return Err(if known.is_empty() {
+            t!("wrap-backend-no-plugins", backend = value)
+        } else {
+            t!(
+                "wrap-backend-not-found",
+                backend = value,
+                known = known.join(", "),
+            )
+        });
+    }
+
+    Ok(value.to_owned())
+}
+
+fn parse_expiry(value: &str) -> Result<Expiry, String> {
+    Expiry::parse(value)
+}
+
+fn parse_operations(value: &str) -> Result<Vec<pacman_hook::Operation>, String> {
+    pacman_hook::Operation::parse_list(value)
+}
+
+/// Handle `executable_path` belonging to the `existing` wrapper, so it isn't wrapped a second time. The parameters
+/// are merged into the existing wrapper if the user agrees to it interactively, and an error is returned otherwise.
+fn on_existing_wrapper(
+    executable_path: &Path,
+    existing: &Path,
+    new_params: wrapper::Params,
+    explicit_pacman_hooks: Option<bool>,
+    options: &TargetOptions,
+) -> anyhow::Result<()> {
+    let problem = if existing == executable_path {
+        t!(
+            "wrap-already-wrapped",
+            path = existing.display().to_string()
+        )
+    } else {
+        t!(
+            "wrap-belongs-to-wrapper",
+            path = executable_path.display().to_string(),
+            wrapper = existing.display().to_string(),
+        )
+    };
+
+    let interactive = options.dry_run.is_none() && io::stdin().is_terminal();
+
+    let question = if existing == executable_path {
+        t!("wrap-merge-wrapped", path = existing.display().to_string())
+    } else {
+        t!(
+            "wrap-merge-belongs",
+            path = executable_path.display().to_string(),
+            wrapper = existing.display().to_string(),
+        )
+    };
+
+    if interactive && prompt::confirm(&question, false)? {
+        return update(existing, new_params, explicit_pacman_hooks, options);
+    }
+
+    anyhow::bail!(t!(
+        "wrap-merge-hint",
+        problem = problem,
+        program = env!("CARGO_PKG_NAME"),
+        path = existing.display().to_string(),
+    ))
+}
+
+/// Merge `new_params` into the stored parameters of the existing wrapper for `executable_path` and regenerate it,
+/// asking for confirmation after showing the changes unless `--yes` was given in `options`.
+fn update(
+    executable_path: &Path,
+    new_params: wrapper::Params,
+    explicit_pacman_hooks: Option<bool>,
+    options: &TargetOptions,
+) -> anyhow::Result<()> {
+    let paths = wrapper::ExecPaths::try_from_path(executable_path)?;
+
+    let mut metadata =
+        Metadata::read(&paths.metadata.original).with_context(|| t!("wrap-update-no-metadata"))?;
+
+    for arg in metadata.params.repeated_args(&new_params) {
+        eprintln!("{}", t!("wrap-repeated-arg", arg = arg));
+    }
+
+    for name in metadata.params.merge(new_params) {
+        eprintln!("{}", t!("wrap-replacing-env-var", name = name));
+    }
+
+    let use_pacman_hooks = explicit_pacman_hooks.unwrap_or(metadata.use_pacman_hooks);
+
+    let staged = stage(
+        executable_path,
+        &metadata.params,
+        use_pacman_hooks,
+        wrapper::Mode::Update,
+    )?;
+
+    if let Some(dry_run) = options.dry_run {
+        return report(&staged, dry_run);
+    }
+
+    if !confirm_changes(&staged, options.yes)? {
+        return Ok(());
+    }
+
+    prepare_install(&staged, options)?;
+    install(staged)
+}
+
+/// Show how installing `staged` would change the files of an existing wrapper, and ask whether to go ahead, unless
+/// `yes` is set or stdin isn't a terminal to ask on.
+///
+/// Returns false if the user declined, in which case nothing should be installed.
+pub fn confirm_changes(staged: &wrapper::Staged, yes: bool) -> anyhow::Result<bool> {
+    if staged.is_up_to_date() || !diff::print_changes(staged)? {
+        return Ok(true);
+    }
+
+    if yes || !io::stdin().is_terminal() {
+        return Ok(true);
+    }
+
+    let wrapped_path = staged.paths.wrapped.original.display().to_string();
+
+    if prompt::confirm(&t!("wrap-apply-changes"), true)? {
+        return Ok(true);
+    }
+
+    println!("{}", t!("wrap-declined", path = wrapped_path));
+    Ok(false)
+}
+
+/// Verify the given parameters, then create and install a wrapper for the executable at `executable_path`.
+#[cfg(feature = "dbus")]
+pub fn wrap(
+    executable_path: &Path,
+    wrapper_params: &wrapper::Params,
+    use_pacman_hooks: bool,
+) -> anyhow::Result<()> {
+    install(stage(
+        executable_path,
+        wrapper_params,
+        use_pacman_hooks,
+        wrapper::Mode::Create,
+    )?)
+}
+
+/// Verify the given parameters and generate everything needed to install a wrapper, without touching the disk.
+pub fn stage<'a>(
+    executable_path: &Path,
+    wrapper_params: &'a wrapper::Params,
+    use_pacman_hooks: bool,
+    mode: wrapper::Mode,
+) -> anyhow::Result<wrapper::Staged<'a>> {
+    verify(executable_path, wrapper_params)?;
+
+    let wrapper_paths = wrapper::ExecPaths::try_from_path(executable_path)?;
+    wrapper::stage(wrapper_paths, wrapper_params, use_pacman_hooks, mode)
+}
+
+/// Report whether installing a staged wrapper would change anything, returning [`ChangesNeeded`] if it would.
+pub fn check(staged: &wrapper::Staged) -> anyhow::Result<()> {
+    let wrapped_path = staged.paths.wrapped.original.display().to_string();
+
+    if staged.is_up_to_date() {
+        println!("{}", t!("wrap-unchanged", path = wrapped_path));
+        return Ok(());
+    }
+
+    let message = match staged.mode {
+        wrapper::Mode::Create => t!("wrap-would-create", path = wrapped_path),
+        wrapper::Mode::Update => t!("wrap-would-update", path = wrapped_path),
+    };
+
+    println!("{message}");
+    Err(ChangesNeeded.into())
+}
+
+/// Report what installing a staged wrapper would do in the way chosen by `dry_run`.
+fn report(staged: &wrapper::Staged, dry_run: DryRun) -> anyhow::Result<()> {
+    match dry_run {
+        DryRun::Check => check(staged),
+        #[cfg(target_os = "linux")]
+        DryRun::PreviewExec => preview_exec(staged),
+    }
+}
+
+/// Run the install script of a staged wrapper in a sandbox, and print which files it would change.
+#[cfg(target_os = "linux")]
+fn preview_exec(staged: &wrapper::Staged) -> anyhow::Result<()> {
+    let wrapped_path = staged.paths.wrapped.original.display().to_string();
+    let changes = staged.preview_install_script()?;
+
+    if changes.is_empty() {
+        println!("{}", t!("preview-unchanged", path = wrapped_path));
+        return Ok(());
+    }
+
+    println!("{}", t!("preview-changes", path = wrapped_path));
+
+    for change in &changes {
+        println!("  {}", change.describe());
+    }
+
+    Ok(())
+}
+
+/// Write a staged wrapper to disk and run its install script, unless it's already up to date.
+pub fn install(staged: wrapper::Staged) -> anyhow::Result<()> {
+    let wrapped_path = staged.paths.wrapped.original.display().to_string();
+
+    if staged.is_up_to_date() {
+        println!("{}", t!("wrap-unchanged", path = wrapped_path));
+        return Ok(());
+    }
+
+    let message = match staged.mode {
+        wrapper::Mode::Create => t!("wrap-created", path = wrapped_path),
+        wrapper::Mode::Update => t!("wrap-updated", path = wrapped_path),
+    };
+
+    staged.install()?;
+
+    println!("{message}");
+
+    Ok(())
+}
+
+fn verify(executable_path: &Path, wrapper_params: &wrapper::Params) -> anyhow::Result<()> {
+    if !wrapper_params.changes_launch() {
+        anyhow::bail!(t!("wrap-nothing-to-wrap"));
+    }
+
+    if let Some(app) = &wrapper_params.snap {
+        let command_path = snap::command_path(app);
+
+        if !command_path.exists() {
+            return Err(IoError::new(&command_path, t!("wrap-snap-command-missing")).into());
+        }
+
+        // the shim is created rather than replacing an existing executable
+        if !executable_path.is_absolute() {
+            return Err(IoError::new(executable_path, t!("wrap-shim-path-relative")).into());
+        }
+
+        return Ok(());
+    }
+
+    let executable_exists = executable_path
+        .try_exists()
+        .with_context(|| IoError::new(executable_path, t!("io-check-exists")))?;
+
+    if !executable_exists {
+        return Err(IoError::new(executable_path, t!("wrap-path-missing")).into());
+    }
+
+    if !executable_path.is_file() {
+        return Err(IoError::new(executable_path, t!("wrap-path-not-file")).into());
+    }
+
+    if !executable_path.is_absolute() {
+        return Err(IoError::new(executable_path, t!("wrap-path-relative")).into());
+    }
+
+    if !wrapper_params.allow_non_executable {
+        let exec_type = ExecType::detect(executable_path)
+            .map_err(|err| IoError::new(executable_path, err.context(t!("io-read-file"))))?;
+
+        if !exec_type.is_executable() {
+            return Err(IoError::new(
+                executable_path,
+                t!("wrap-not-executable", kind = exec_type.to_string()),
+            )
+            .into());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,76 @@
+use strum::IntoEnumIterator;
+
+use crate::{i18n::t, plugin, preset::Preset, registry};
+
+/// Name of the command the shell completions run to complete values that depend on the wrappers on the system.
+///
+/// It isn't a regular subcommand, so it stays out of `--help`.
+pub const COMMAND: &str = "__complete";
+
+/// What is being completed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+enum Kind {
+    /// Paths of the wrapped executables.
+    Paths,
+    /// Names of the built-in presets and of preset plugins.
+    Presets,
+    /// Tags given to wrappers.
+    Tags,
+}
+
+/// Print each candidate of the kind named by the first of `args` that starts with the second, if given, on its own
+/// line.
+pub fn run(args: &[String]) -> anyhow::Result<()> {
+    let (kind, prefix) = match args {
+        [kind] => (kind, ""),
+        [kind, prefix] => (kind, prefix.as_str()),
+        _ => anyhow::bail!(t!(
+            "complete-usage",
+            program = env!("CARGO_PKG_NAME"),
+            command = COMMAND,
+        )),
+    };
+
+    let kind = kind
+        .parse()
+        .map_err(|_| anyhow::anyhow!(t!("complete-unknown", kind = kind.as_str())))?;
+
+    for candidate in candidates(kind)? {
+        if candidate.starts_with(prefix) {
+            println!("{candidate}");
+        }
+    }
+
+    Ok(())
+}
+
+fn candidates(kind: Kind) -> anyhow::Result<Vec<String>> {
+    let candidates = match kind {
+        Kind::Paths => registry::find_wrapped()?
+            .into_iter()
+            .map(|path| path.display().to_string())
+            .collect(),
+        Kind::Presets => Preset::iter()
+            .map(|preset| preset.to_string())
+            .chain(plugin::find_all(plugin::Kind::Preset))
+            .collect(),
+        Kind::Tags => registry::find_tags()?.into_iter().collect(),
+    };
+
+    Ok(candidates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn completes_built_in_presets() {
+        let presets = candidates(Kind::Presets).unwrap();
+
+        assert!(presets.contains(&"gaming".to_owned()));
+        assert!(presets.contains(&"gpu-offload".to_owned()));
+        assert_eq!("tags".parse(), Ok(Kind::Tags));
+    }
+}
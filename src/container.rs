@@ -0,0 +1,287 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{file, i18n::t, metadata::is_false, shell};
+
+/// Name of the array in the wrapper script holding the options for the container engine's `run` command.
+const CONTAINER_ARGS_VAR: &str = "container_args";
+
+/// A program that runs containers.
+#[derive(
+    Debug,
+    Default,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Engine {
+    #[default]
+    Podman,
+    Docker,
+}
+
+impl Engine {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+}
+
+/// A container image that runs in place of the original executable.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Container {
+    /// Image to run, such as `docker.io/library/python:3.12`.
+    pub image: String,
+    /// Program that runs the container.
+    #[serde(default, skip_serializing_if = "Engine::is_default")]
+    pub engine: Engine,
+    /// Command to run in the container, instead of the image's entrypoint.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+    /// Volumes to mount in the container, in the format of `/host/path:/container/path[:options]`.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volumes: Vec<String>,
+    /// Names of environment variables to pass from the host into the container, in addition to the wrapper's own.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub envs: Vec<String>,
+    /// Mount the current directory at the same path in the container and run the command there.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub mount_cwd: bool,
+}
+
+impl Container {
+    /// Check the parts of the container that the schema can't.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(!self.image.is_empty(), t!("container-empty-image"));
+
+        for volume in &self.volumes {
+            anyhow::ensure!(
+                volume.contains(':'),
+                t!("container-invalid-volume", volume = volume.as_str())
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Check that the container engine is installed, since the wrapper would otherwise fail every time it runs.
+    pub fn ensure_engine_installed(&self) -> anyhow::Result<()> {
+        let engine = self.engine.to_string();
+
+        if file::find_program(&engine).is_none() {
+            anyhow::bail!(t!("container-engine-missing", engine = engine));
+        }
+
+        Ok(())
+    }
+
+    /// Write the lines of the wrapper script that collect the options for running the container, which passes the
+    /// environment variables named in `env_names` through along with the ones configured for the container.
+    pub fn write_args<'a>(
+        &'a self,
+        env_names: impl IntoIterator<Item = &'a str>,
+        mut writer: impl fmt::Write,
+    ) -> fmt::Result {
+        // the container is removed once the command exits, just like a process would be gone
+        let mut args = vec!["--rm".into(), "--interactive".into()];
+
+        for volume in &self.volumes {
+            args.push(format!("--volume={}", shell::quote(volume)));
+        }
+
+        let mut names = Vec::new();
+
+        for name in env_names
+            .into_iter()
+            .chain(self.envs.iter().map(String::as_str))
+        {
+            if !names.contains(&name) {
+                names.push(name);
+            }
+        }
+
+        // only naming a variable passes its value through, if it's set
+        for name in names {
+            args.push(format!("--env={}", shell::quote(name)));
+        }
+
+        if self.mount_cwd {
+            args.push(r#""--volume=$PWD:$PWD""#.into());
+            args.push(r#""--workdir=$PWD""#.into());
+        }
+
+        writeln!(writer, "{CONTAINER_ARGS_VAR}=({})", args.join(" "))?;
+
+        // a TTY is only allocated when there's a terminal, so output can still be piped
+        writeln!(writer, "if [ -t 0 ] && [ -t 1 ]; then")?;
+        writeln!(writer, "    {CONTAINER_ARGS_VAR}+=(--tty)")?;
+        writeln!(writer, "fi")
+    }
+
+    /// Write the command that runs the container, which the arguments for the command are appended to.
+    pub fn write_command(&self, mut writer: impl fmt::Write) -> fmt::Result {
+        write!(
+            writer,
+            r#"{} run "${{{CONTAINER_ARGS_VAR}[@]}}" {}"#,
+            self.engine,
+            shell::quote(&self.image)
+        )?;
+
+        if let Some(command) = &self.command {
+            write!(writer, " {}", shell::quote(command))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// A program that manages containers integrated with the host, sharing the user's home directory and session.
+#[derive(
+    Debug, Clone, Copy, PartialEq, Eq, strum::Display, Serialize, Deserialize, schemars::JsonSchema,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum HostContainerTool {
+    Distrobox,
+    Toolbox,
+}
+
+/// An existing distrobox or toolbox container that the executable runs in.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct HostContainer {
+    /// Program that manages the container.
+    pub tool: HostContainerTool,
+    /// Name of the container.
+    pub name: String,
+    /// Command to run in the container, instead of the name of the wrapped executable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub command: Option<String>,
+}
+
+impl HostContainer {
+    /// Check the parts of the container that the schema can't.
+    pub fn validate(&self) -> anyhow::Result<()> {
+        anyhow::ensure!(
+            !self.name.is_empty(),
+            t!("container-empty-name", tool = self.tool.to_string())
+        );
+
+        Ok(())
+    }
+
+    /// Check that the tool is installed, since the wrapper would otherwise fail every time it runs.
+    pub fn ensure_tool_installed(&self) -> anyhow::Result<()> {
+        let tool = self.tool.to_string();
+
+        if file::find_program(&tool).is_none() {
+            anyhow::bail!(t!("container-tool-missing", tool = tool));
+        }
+
+        Ok(())
+    }
+
+    /// Write the command that runs the executable named `wrapped_filename` in the container, which the arguments for
+    /// the command are appended to.
+    pub fn write_command(
+        &self,
+        wrapped_filename: &str,
+        mut writer: impl fmt::Write,
+    ) -> fmt::Result {
+        let name = shell::quote(&self.name);
+
+        match self.tool {
+            HostContainerTool::Distrobox => write!(writer, "distrobox enter -n {name} --")?,
+            HostContainerTool::Toolbox => write!(writer, "toolbox run -c {name}")?,
+        }
+
+        // the executable has the same name in the container, but not necessarily the same path
+        let command = self.command.as_deref().unwrap_or(wrapped_filename);
+        write!(writer, " {}", shell::quote(command))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    fn container() -> Container {
+        Container {
+            image: "docker.io/library/python:3.12".into(),
+            engine: Engine::Docker,
+            command: Some("python3".into()),
+            volumes: vec!["/srv/data:/data:ro".into()],
+            envs: vec!["DISPLAY".into(), "PYTHONPATH".into()],
+            mount_cwd: true,
+        }
+    }
+
+    #[test]
+    fn container_script() {
+        let container = container();
+
+        let mut script = String::new();
+        container
+            .write_args(["PYTHONPATH", "DEBUG", "DEBUG"], &mut script)
+            .unwrap();
+        container.write_command(&mut script).unwrap();
+
+        assert_eq!(
+            script,
+            indoc! {r#"
+                container_args=(--rm --interactive --volume=/srv/data:/data:ro --env=PYTHONPATH --env=DEBUG --env=DISPLAY "--volume=$PWD:$PWD" "--workdir=$PWD")
+                if [ -t 0 ] && [ -t 1 ]; then
+                    container_args+=(--tty)
+                fi
+                docker run "${container_args[@]}" docker.io/library/python:3.12 python3"#}
+        );
+    }
+
+    #[test]
+    fn invalid_containers() {
+        assert!(container().validate().is_ok());
+
+        let no_image = Container {
+            image: String::new(),
+            ..container()
+        };
+        assert!(no_image.validate().is_err());
+
+        let bad_volume = Container {
+            volumes: vec!["/srv/data".into()],
+            ..container()
+        };
+        assert!(bad_volume.validate().is_err());
+    }
+
+    #[test]
+    fn host_container_command() {
+        let mut container = HostContainer {
+            tool: HostContainerTool::Distrobox,
+            name: "arch".into(),
+            command: None,
+        };
+
+        let mut command = String::new();
+        container.write_command("vim", &mut command).unwrap();
+        assert_eq!(command, "distrobox enter -n arch -- vim");
+
+        container.tool = HostContainerTool::Toolbox;
+        container.command = Some("nvim".into());
+
+        let mut command = String::new();
+        container.write_command("vim", &mut command).unwrap();
+        assert_eq!(command, "toolbox run -c arch nvim");
+    }
+}
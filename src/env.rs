@@ -1,11 +1,27 @@
 use std::{borrow::Cow, fmt, str::FromStr};
 
 use anyhow::Context;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
-#[derive(Debug, PartialEq)]
+use crate::{i18n::t, shell};
+
+#[derive(Debug, Clone, PartialEq)]
 pub struct Variable<'a> {
     pub name: Cow<'a, str>,
     pub value: Cow<'a, str>,
+    pub op: Operation,
+}
+
+/// How the value of a variable is combined with the one it already has when the wrapper runs.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Replace the value, written as `NAME=value`.
+    #[default]
+    Set,
+    /// Add the value to the end of a colon-separated list such as `PATH`, written as `NAME+=value`.
+    Append,
+    /// Add the value to the start of a colon-separated list such as `PATH`, written as `NAME=+value`.
+    Prepend,
 }
 
 impl<'a> Variable<'a> {
@@ -14,38 +30,85 @@ impl<'a> Variable<'a> {
         Self {
             name: name.into(),
             value: value.into(),
+            op: Operation::Set,
         }
     }
 
+    /// Parse a variable in the format of `NAME=value`, `NAME+=value` to append to a list, or `NAME=+value` to prepend
+    /// to one. A value that starts with a literal `+` or `\` is escaped with another `\`, as in `NAME=\+value`.
     pub fn parse<'b>(value: &'b str) -> anyhow::Result<Variable<'b>> {
-        let (name, value) = value.split_once('=').context("missing '=' separator")?;
+        let (name, value) = value
+            .split_once('=')
+            .with_context(|| t!("missing-separator"))?;
+
+        let (name, value, op) = if let Some(name) = name.strip_suffix('+') {
+            (name, value, Operation::Append)
+        } else if let Some(value) = value.strip_prefix('+') {
+            (name, value, Operation::Prepend)
+        } else {
+            let value = unescape(value).unwrap_or(value);
+
+            (name, value, Operation::Set)
+        };
 
         anyhow::ensure!(
             Self::is_valid_name(name),
-            "invalid name for environment variable `{name}`",
+            t!("env-invalid-name", name = name),
         );
 
         Ok(Variable {
             name: name.into(),
             value: value.into(),
+            op,
         })
     }
 
     pub fn write_bash_line(&self, mut writer: impl fmt::Write) -> fmt::Result {
         let escaped_value = self.value.replace('"', "\\\"");
+        let name = &self.name;
 
-        writeln!(
-            writer,
-            r#"export {name}="{value}""#,
-            name = self.name,
-            value = escaped_value
-        )
+        // the separator is left out while the list is empty, since an empty entry in `PATH` means the current directory
+        match self.op {
+            Operation::Set => writeln!(writer, r#"export {name}="{escaped_value}""#),
+            Operation::Append => writeln!(
+                writer,
+                r#"export {name}="${{{name}:+${name}:}}{escaped_value}""#
+            ),
+            Operation::Prepend => writeln!(
+                writer,
+                r#"export {name}="{escaped_value}${{{name}:+:${name}}}""#
+            ),
+        }
+    }
+
+    /// Whether the value is taken literally, rather than referencing other variables or running commands when the
+    /// wrapper runs, so it means the same in shells other than bash.
+    pub fn is_literal(&self) -> bool {
+        !self.value.contains(['$', '`', '\\'])
+    }
+
+    /// Write the line that sets the variable for the rest of a fish function, which only works for a literal value.
+    pub fn write_fish_line(&self, mut writer: impl fmt::Write) -> fmt::Result {
+        let name = &self.name;
+        let value = shell::quote_fish(&self.value);
+
+        // an unset variable expands to no words at all, so no separator is added for it
+        match self.op {
+            Operation::Set => writeln!(writer, "set -lx {name} {value}"),
+            Operation::Append => {
+                writeln!(writer, "set -lx {name} (string join : -- ${name} {value})")
+            }
+            Operation::Prepend => {
+                writeln!(writer, "set -lx {name} (string join : -- {value} ${name})")
+            }
+        }
     }
 
     pub fn into_owned(self) -> Variable<'static> {
         Variable {
             name: self.name.clone().into_owned().into(),
             value: self.value.clone().into_owned().into(),
+            op: self.op,
         }
     }
 
@@ -72,6 +135,18 @@ impl<'a> Variable<'a> {
     }
 }
 
+/// Returns `value` without the `\` it was escaped with, if it was escaped.
+fn unescape(value: &str) -> Option<&str> {
+    value
+        .strip_prefix('\\')
+        .filter(|value| value.starts_with(['+', '\\']))
+}
+
+/// Whether a value to set has to be escaped so it isn't read as one to prepend, or as an escaped one.
+fn needs_escape(value: &str) -> bool {
+    value.starts_with('+') || unescape(value).is_some()
+}
+
 impl<'a> TryFrom<&'a str> for Variable<'a> {
     type Error = anyhow::Error;
 
@@ -80,6 +155,21 @@ impl<'a> TryFrom<&'a str> for Variable<'a> {
     }
 }
 
+impl schemars::JsonSchema for Variable<'_> {
+    fn schema_name() -> Cow<'static, str> {
+        "EnvironmentVariable".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "An environment variable in the format of `NAME=value`, `NAME+=value` to append to a \
+                colon-separated list such as `PATH`, or `NAME=+value` to prepend to one.",
+            "type": "string",
+            "pattern": r"^[\p{L}_][\p{L}\p{N}_]*\+?=",
+        })
+    }
+}
+
 impl FromStr for Variable<'_> {
     type Err = anyhow::Error;
 
@@ -88,6 +178,33 @@ impl FromStr for Variable<'_> {
     }
 }
 
+impl fmt::Display for Variable<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.op {
+            Operation::Set if needs_escape(&self.value) => {
+                write!(f, "{}=\\{}", self.name, self.value)
+            }
+            Operation::Set => write!(f, "{}={}", self.name, self.value),
+            Operation::Append => write!(f, "{}+={}", self.name, self.value),
+            Operation::Prepend => write!(f, "{}=+{}", self.name, self.value),
+        }
+    }
+}
+
+/// Serialized in the same `ENV=value` format accepted on the command line.
+impl Serialize for Variable<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Variable<'static> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -97,17 +214,17 @@ mod tests {
 
         #[test]
         fn is_valid_name() {
-            assert_eq!(Variable::is_valid_name("HELLO"), true);
-            assert_eq!(Variable::is_valid_name("hello"), true);
-            assert_eq!(Variable::is_valid_name("_HELLO"), true);
-            assert_eq!(Variable::is_valid_name("HELLO1"), true);
-            assert_eq!(Variable::is_valid_name("HELLO1_WORLD"), true);
-            assert_eq!(Variable::is_valid_name("____"), true);
-
-            assert_eq!(Variable::is_valid_name(""), false);
-            assert_eq!(Variable::is_valid_name("1HELLO"), false);
-            assert_eq!(Variable::is_valid_name("@HELLO"), false);
-            assert_eq!(Variable::is_valid_name("HELL@"), false);
+            assert!(Variable::is_valid_name("HELLO"));
+            assert!(Variable::is_valid_name("hello"));
+            assert!(Variable::is_valid_name("_HELLO"));
+            assert!(Variable::is_valid_name("HELLO1"));
+            assert!(Variable::is_valid_name("HELLO1_WORLD"));
+            assert!(Variable::is_valid_name("____"));
+
+            assert!(!Variable::is_valid_name(""));
+            assert!(!Variable::is_valid_name("1HELLO"));
+            assert!(!Variable::is_valid_name("@HELLO"));
+            assert!(!Variable::is_valid_name("HELL@"));
         }
 
         #[test]
@@ -142,5 +259,64 @@ mod tests {
         fn parse_from_str_without_sep_fails() {
             assert!(Variable::parse("ENVvalue").is_err());
         }
+
+        #[test]
+        fn parse_append_and_prepend() {
+            let cases = [
+                (
+                    "PATH+=/opt/foo/bin",
+                    "PATH",
+                    "/opt/foo/bin",
+                    Operation::Append,
+                ),
+                (
+                    "PATH=+/opt/foo/bin",
+                    "PATH",
+                    "/opt/foo/bin",
+                    Operation::Prepend,
+                ),
+                (r"LESS=\+F", "LESS", "+F", Operation::Set),
+                (r"VALUE=\\+", "VALUE", r"\+", Operation::Set),
+                (r"VALUE=\x", "VALUE", r"\x", Operation::Set),
+            ];
+
+            for (input, name, value, op) in cases {
+                let env = Variable::parse(input).expect("env parsing should succeed");
+                assert_eq!(
+                    (env.name.as_ref(), env.value.as_ref(), env.op),
+                    (name, value, op)
+                );
+                assert_eq!(env.to_string(), input);
+            }
+
+            assert!(Variable::parse("+=value").is_err());
+        }
+
+        #[test]
+        fn bash_lines_append_and_prepend() {
+            let mut buffer = String::new();
+
+            for env in ["PATH+=/opt/foo/bin", "PATH=+/opt/foo/bin"] {
+                Variable::parse(env)
+                    .unwrap()
+                    .write_bash_line(&mut buffer)
+                    .expect("write bash line should succeed");
+            }
+
+            assert_eq!(
+                buffer,
+                indoc::indoc! {r#"
+                    export PATH="${PATH:+$PATH:}/opt/foo/bin"
+                    export PATH="/opt/foo/bin${PATH:+:$PATH}"
+                "#}
+            );
+        }
+
+        #[test]
+        fn display_round_trips() {
+            let env = Variable::new("ENV", "value=with=seps");
+            let parsed: Variable = env.to_string().parse().expect("env parsing should succeed");
+            assert_eq!(parsed, env);
+        }
     }
 }
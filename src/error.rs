@@ -18,6 +18,16 @@ impl IoError {
     }
 }
 
+/// Returned by `--check` when running the command for real would change something.
+#[derive(Debug, thiserror::Error)]
+#[error("changes are needed")]
+pub struct ChangesNeeded;
+
+impl ChangesNeeded {
+    /// Exit code used instead of the generic failure code, so callers can tell the two apart.
+    pub const EXIT_CODE: u8 = 2;
+}
+
 pub struct IoErrorMessage(anyhow::Error);
 
 impl From<anyhow::Error> for IoErrorMessage {
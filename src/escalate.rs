@@ -0,0 +1,122 @@
+use std::{
+    env,
+    io::{self, IsTerminal},
+    process::Command,
+};
+
+use anyhow::Context;
+use serde::Deserialize;
+use strum::IntoEnumIterator;
+
+use crate::{file, i18n::t, prompt, settings};
+
+/// A program that runs a command as root.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::EnumIter,
+    strum::EnumString,
+    strum::Display,
+    Deserialize,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Tool {
+    Sudo,
+    Doas,
+}
+
+impl Tool {
+    /// The first of these that's installed, so `doas` is picked on systems where it has replaced `sudo`.
+    fn detect() -> Option<Self> {
+        Self::iter().find(|tool| file::resolve_command(&tool.to_string()).is_some())
+    }
+}
+
+/// Whether `err` was caused by being denied access to a file.
+pub fn is_permission_denied(err: &anyhow::Error) -> bool {
+    err.chain().any(|cause| {
+        cause
+            .downcast_ref::<io::Error>()
+            .is_some_and(|err| err.kind() == io::ErrorKind::PermissionDenied)
+    })
+}
+
+/// Whether this program runs as root.
+fn is_root() -> anyhow::Result<bool> {
+    let output = Command::new("id")
+        .arg("-u")
+        .output()
+        .with_context(|| t!("escalate-id-failed"))?;
+
+    Ok(output.status.success() && output.stdout.trim_ascii() == b"0")
+}
+
+/// Offer to run this program again as root with `tool`, or the one from the settings or that's installed if it's not
+/// given, after it was denied access to a file.
+///
+/// Only returns if it wasn't run again, which is always the case when it's already root or stdin isn't a terminal,
+/// where how to run it as root is printed instead.
+pub fn offer_rerun(tool: Option<Tool>) -> anyhow::Result<()> {
+    if is_root()? {
+        return Ok(());
+    }
+
+    let Some(tool) = tool.or(settings::get().escalate).or_else(Tool::detect) else {
+        return Ok(());
+    };
+
+    let command = format!("{tool} {}", env!("CARGO_PKG_NAME"));
+
+    if !io::stdin().is_terminal() {
+        eprintln!("{}", t!("escalate-hint", command = command));
+        return Ok(());
+    }
+
+    if !prompt::confirm(&t!("escalate-confirm", command = command), true)? {
+        return Ok(());
+    }
+
+    let program = env::current_exe().with_context(|| t!("program-path-failed"))?;
+
+    let mut rerun = Command::new(tool.to_string());
+    rerun.arg(program).args(env::args_os().skip(1));
+
+    run(rerun).with_context(|| t!("escalate-run-failed", tool = tool.to_string()))
+}
+
+/// Replace this process with `command`, which only returns if it couldn't be started.
+#[cfg(unix)]
+fn run(mut command: Command) -> anyhow::Result<()> {
+    use std::os::unix::process::CommandExt;
+
+    Err(command.exec().into())
+}
+
+/// Run `command` and exit with its status, since other platforms can't replace a process.
+#[cfg(not(unix))]
+fn run(mut command: Command) -> anyhow::Result<()> {
+    let status = command.status()?;
+    std::process::exit(status.code().unwrap_or(1));
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::error::IoError;
+
+    use super::*;
+
+    #[test]
+    fn permission_denied_anywhere_in_chain() {
+        let denied = anyhow::Error::new(io::Error::from(io::ErrorKind::PermissionDenied))
+            .context(IoError::new("/usr/bin/foo", "failed to create wrapper"));
+        let missing = anyhow::Error::new(io::Error::from(io::ErrorKind::NotFound));
+
+        assert!(is_permission_denied(&denied));
+        assert!(!is_permission_denied(&missing));
+        assert!(!is_permission_denied(&anyhow::anyhow!("permission denied")));
+    }
+}
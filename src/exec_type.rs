@@ -0,0 +1,229 @@
+use std::{
+    fmt,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::i18n::t;
+
+/// Longest program header table that is read to tell executables and shared libraries apart.
+const MAX_PROGRAM_HEADERS_LEN: u64 = 64 * 1024;
+
+/// The kind of file, as told by its first bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExecType {
+    /// An ELF executable, which may be position-independent.
+    Elf,
+    /// An ELF shared library, such as a `.so` file.
+    SharedLibrary,
+    /// An ELF file that can't be run, such as an object file or a core dump.
+    ElfOther,
+    /// A script starting with a shebang.
+    Script,
+    /// A Mach-O binary, which only runs on macOS.
+    MachO,
+    /// Anything else, such as a configuration file.
+    Data,
+}
+
+impl ExecType {
+    /// Detect the type of the file at `path` from its magic bytes.
+    pub fn detect(path: &Path) -> anyhow::Result<Self> {
+        let mut file = File::open(path)?;
+
+        let mut header = Vec::with_capacity(64);
+        file.by_ref().take(64).read_to_end(&mut header)?;
+
+        if header.starts_with(b"#!") {
+            return Ok(Self::Script);
+        }
+
+        if header.starts_with(b"\x7fELF") {
+            return Ok(Elf::parse(&header).map_or(Self::ElfOther, |elf| elf.exec_type(&mut file)));
+        }
+
+        let is_mach_o = header.get(..4).is_some_and(|magic| {
+            matches!(
+                magic,
+                [0xfe, 0xed, 0xfa, 0xce | 0xcf] | [0xce | 0xcf, 0xfa, 0xed, 0xfe]
+            )
+        });
+
+        Ok(if is_mach_o { Self::MachO } else { Self::Data })
+    }
+
+    /// Whether the file can be run as an executable.
+    pub fn is_executable(self) -> bool {
+        matches!(self, Self::Elf | Self::Script)
+    }
+}
+
+impl fmt::Display for ExecType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&match self {
+            Self::Elf => t!("exec-type-elf"),
+            Self::SharedLibrary => t!("exec-type-shared-library"),
+            Self::ElfOther => t!("exec-type-elf-other"),
+            Self::Script => t!("exec-type-script"),
+            Self::MachO => t!("exec-type-mach-o"),
+            Self::Data => t!("exec-type-data"),
+        })
+    }
+}
+
+/// The fields of an ELF header needed to tell its type.
+struct Elf {
+    is_little_endian: bool,
+    kind: u16,
+    program_headers_offset: u64,
+    program_header_size: u16,
+    program_header_count: u16,
+}
+
+impl Elf {
+    /// Object type of an executable at a fixed address.
+    const EXECUTABLE: u16 = 2;
+    /// Object type shared by shared libraries and position-independent executables.
+    const SHARED: u16 = 3;
+    /// Program header type naming the dynamic linker, which only executables have.
+    const INTERPRETER: u32 = 3;
+
+    fn parse(header: &[u8]) -> Option<Self> {
+        let is_64 = match header.get(4)? {
+            1 => false,
+            2 => true,
+            _ => return None,
+        };
+
+        let is_little_endian = match header.get(5)? {
+            1 => true,
+            2 => false,
+            _ => return None,
+        };
+
+        let mut elf = Self {
+            is_little_endian,
+            kind: 0,
+            program_headers_offset: 0,
+            program_header_size: 0,
+            program_header_count: 0,
+        };
+
+        elf.kind = elf.read_u16(header, 16)?;
+
+        if is_64 {
+            elf.program_headers_offset = elf.read_u64(header, 32)?;
+            elf.program_header_size = elf.read_u16(header, 54)?;
+            elf.program_header_count = elf.read_u16(header, 56)?;
+        } else {
+            elf.program_headers_offset = u64::from(elf.read_u32(header, 28)?);
+            elf.program_header_size = elf.read_u16(header, 42)?;
+            elf.program_header_count = elf.read_u16(header, 44)?;
+        }
+
+        Some(elf)
+    }
+
+    fn exec_type(&self, file: &mut File) -> ExecType {
+        match self.kind {
+            Self::EXECUTABLE => ExecType::Elf,
+            // position-independent executables are only told apart from libraries by naming a dynamic linker
+            Self::SHARED if self.has_interpreter(file) => ExecType::Elf,
+            Self::SHARED => ExecType::SharedLibrary,
+            _ => ExecType::ElfOther,
+        }
+    }
+
+    fn has_interpreter(&self, file: &mut File) -> bool {
+        let size = usize::from(self.program_header_size);
+        let len = u64::from(self.program_header_size) * u64::from(self.program_header_count);
+
+        if size < 4 || len > MAX_PROGRAM_HEADERS_LEN {
+            return false;
+        }
+
+        let mut headers = Vec::new();
+
+        let read = file
+            .seek(SeekFrom::Start(self.program_headers_offset))
+            .and_then(|_| file.take(len).read_to_end(&mut headers));
+
+        if read.is_err() {
+            return false;
+        }
+
+        headers
+            .chunks_exact(size)
+            .any(|header| self.read_u32(header, 0) == Some(Self::INTERPRETER))
+    }
+
+    fn read_u16(&self, bytes: &[u8], offset: usize) -> Option<u16> {
+        let bytes = bytes.get(offset..offset + 2)?.try_into().ok()?;
+
+        Some(match self.is_little_endian {
+            true => u16::from_le_bytes(bytes),
+            false => u16::from_be_bytes(bytes),
+        })
+    }
+
+    fn read_u32(&self, bytes: &[u8], offset: usize) -> Option<u32> {
+        let bytes = bytes.get(offset..offset + 4)?.try_into().ok()?;
+
+        Some(match self.is_little_endian {
+            true => u32::from_le_bytes(bytes),
+            false => u32::from_be_bytes(bytes),
+        })
+    }
+
+    fn read_u64(&self, bytes: &[u8], offset: usize) -> Option<u64> {
+        let bytes = bytes.get(offset..offset + 8)?.try_into().ok()?;
+
+        Some(match self.is_little_endian {
+            true => u64::from_le_bytes(bytes),
+            false => u64::from_be_bytes(bytes),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn detects_types() {
+        let dir = test_util::temp_dir("exec-type");
+        let path = dir.path().join("executable");
+
+        let cases: [(&[u8], ExecType); 4] = [
+            (b"#!/bin/sh\n", ExecType::Script),
+            (b"\xcf\xfa\xed\xfe\x07\x00\x00\x01", ExecType::MachO),
+            (b"[section]\nkey = value\n", ExecType::Data),
+            (b"", ExecType::Data),
+        ];
+
+        for (contents, expected) in cases {
+            fs::write(&path, contents).unwrap();
+            assert_eq!(ExecType::detect(&path).unwrap(), expected);
+        }
+    }
+
+    #[test]
+    fn tells_executables_from_libraries() {
+        let current_exe = env::current_exe().unwrap();
+        assert_eq!(ExecType::detect(&current_exe).unwrap(), ExecType::Elf);
+
+        // unlike libc, which can be run to print its version, libm doesn't name a dynamic linker
+        let library = ["/usr/lib/x86_64-linux-gnu", "/usr/lib64", "/usr/lib"]
+            .into_iter()
+            .map(|dir| Path::new(dir).join("libm.so.6"))
+            .find(|path| path.exists());
+
+        if let Some(library) = library {
+            assert_eq!(ExecType::detect(&library).unwrap(), ExecType::SharedLibrary);
+        }
+    }
+}
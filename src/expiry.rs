@@ -0,0 +1,542 @@
+use std::{
+    borrow::Cow,
+    fmt, fs,
+    path::{Path, PathBuf},
+    process::Command,
+    str::FromStr,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use anyhow::Context;
+use indoc::formatdoc;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::{error::IoError, file, header::Header, i18n::t, user_unit, wrapper};
+
+/// Directory of the systemd units of the system, which expire wrappers that aren't managed by user units.
+pub const SYSTEM_UNIT_DIR: &str = "/etc/systemd/system";
+
+const SECONDS_PER_DAY: u64 = 24 * 60 * 60;
+
+/// A point in time after which a wrapper is removed, in seconds since the Unix epoch.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Expiry(u64);
+
+impl Expiry {
+    /// Parse an expiry given on the command line, which can also be a duration from now, such as `12h` or `7d`.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        let Some(seconds) = parse_duration(value) else {
+            return value.parse().map_err(|err: anyhow::Error| err.to_string());
+        };
+
+        now()
+            .checked_add(seconds)
+            .map(Self)
+            .ok_or_else(|| t!("expiry-too-long", duration = value))
+    }
+
+    /// Whether the expiry has passed.
+    pub fn is_past(self) -> bool {
+        now() >= self.0
+    }
+
+    /// The expiry in the format of the `OnCalendar` setting of a systemd timer.
+    fn on_calendar(self) -> String {
+        let (date, time) = self.date_and_time();
+        format!("{date} {time} UTC")
+    }
+
+    /// The date and time of the expiry in UTC, formatted as `2025-12-31` and `18:00:00`.
+    fn date_and_time(self) -> (String, String) {
+        let (year, month, day) = civil_from_days(self.0 / SECONDS_PER_DAY);
+        let seconds = self.0 % SECONDS_PER_DAY;
+
+        (
+            format!("{year:04}-{month:02}-{day:02}"),
+            format!(
+                "{:02}:{:02}:{:02}",
+                seconds / 3600,
+                seconds / 60 % 60,
+                seconds % 60
+            ),
+        )
+    }
+}
+
+/// Parses a date such as `2025-12-31`, which expires once that day is over, or a date and time such as
+/// `2025-12-31T18:00` or `2025-12-31T18:00:00Z`. Both are in UTC.
+impl FromStr for Expiry {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || anyhow::anyhow!(t!("expiry-invalid", expiry = s));
+
+        let (date, time) = match s.split_once(['T', ' ']) {
+            Some((date, time)) => (date, Some(time.strip_suffix('Z').unwrap_or(time))),
+            None => (s, None),
+        };
+
+        let mut date_parts = date.splitn(3, '-').map(str::parse::<u64>);
+
+        let (Some(Ok(year)), Some(Ok(month)), Some(Ok(day))) =
+            (date_parts.next(), date_parts.next(), date_parts.next())
+        else {
+            return Err(invalid());
+        };
+
+        let valid_date = (1970..=9999).contains(&year)
+            && (1..=12).contains(&month)
+            && (1..=days_in_month(year, month)).contains(&day);
+
+        if !valid_date {
+            return Err(invalid());
+        }
+
+        let days = days_from_civil(year, month, day);
+
+        let Some(time) = time else {
+            return Ok(Self((days + 1) * SECONDS_PER_DAY));
+        };
+
+        let time_parts = time
+            .split(':')
+            .map(str::parse::<u64>)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| invalid())?;
+
+        let (hours, minutes, seconds) = match time_parts[..] {
+            [hours, minutes] => (hours, minutes, 0),
+            [hours, minutes, seconds] => (hours, minutes, seconds),
+            _ => return Err(invalid()),
+        };
+
+        if hours > 23 || minutes > 59 || seconds > 59 {
+            return Err(invalid());
+        }
+
+        Ok(Self(
+            days * SECONDS_PER_DAY + hours * 3600 + minutes * 60 + seconds,
+        ))
+    }
+}
+
+impl fmt::Display for Expiry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (date, time) = self.date_and_time();
+        write!(f, "{date}T{time}Z")
+    }
+}
+
+impl schemars::JsonSchema for Expiry {
+    fn schema_name() -> Cow<'static, str> {
+        "Expiry".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "A date in UTC such as `2025-12-31`, which expires once that day is over, or a date and \
+                time in UTC such as `2025-12-31T18:00:00Z`.",
+            "type": "string",
+            "pattern": r"^[0-9]{4}-[0-9]{2}-[0-9]{2}([T ][0-9]{2}:[0-9]{2}(:[0-9]{2})?Z?)?$",
+        })
+    }
+}
+
+/// Serialized as a date and time in UTC, since a duration would be relative to whenever the manifest is applied.
+impl Serialize for Expiry {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expiry {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Directory of the timer of a wrapper, which is the one of the user for a wrapper managed by user units.
+fn unit_dir(user: bool) -> anyhow::Result<PathBuf> {
+    if user {
+        user_unit::dir()
+    } else {
+        Ok(PathBuf::from(SYSTEM_UNIT_DIR))
+    }
+}
+
+/// Name of the unit of the given type that removes the wrapper with the given hook name, such as `timer`.
+fn unit_name(hook_name: &str, unit_type: &str) -> String {
+    user_unit::unit_name(&format!("expire-{hook_name}"), unit_type)
+}
+
+/// Paths of the timer and the service that remove the wrapper with the given hook name, in that order.
+pub fn all_paths(hook_name: &str, user: bool) -> anyhow::Result<[PathBuf; 2]> {
+    let dir = unit_dir(user)?;
+
+    Ok(["timer", "service"].map(|unit_type| dir.join(unit_name(hook_name, unit_type))))
+}
+
+/// Generate the timer that starts the service removing the wrapper at `paths` once `expiry` has passed, or as soon as
+/// possible afterwards if the system was off at the time.
+pub fn generate_timer(paths: &wrapper::ExecPaths, header: &Header, expiry: Expiry) -> String {
+    formatdoc! {r#"
+        {header}
+
+        [Unit]
+        Description=Remove the wrapper of {wrapped} once it expires
+
+        [Timer]
+        OnCalendar={on_calendar}
+        Persistent=true
+        Unit={service}
+
+        [Install]
+        WantedBy=timers.target
+        "#,
+        wrapped = user_unit::escape_specifiers(&paths.wrapped.original.to_string_lossy()),
+        on_calendar = expiry.on_calendar(),
+        service = unit_name(&paths.hook_name, "service"),
+    }
+}
+
+/// Generate the service that unwraps the executable at `paths` with the program at `program_path`.
+///
+/// The program is given by its absolute path since systemd only searches a few system directories for commands, which
+/// leaves out installations like `~/.cargo/bin`.
+pub fn generate_service(
+    paths: &wrapper::ExecPaths,
+    header: &Header,
+    program_path: &Path,
+) -> String {
+    formatdoc! {r#"
+        {header}
+
+        [Unit]
+        Description=Remove the expired wrapper of {wrapped}
+
+        [Service]
+        Type=oneshot
+        ExecStart={program} unwrap --wait-for-pacman 600 {wrapped_arg}
+        "#,
+        wrapped = user_unit::escape_specifiers(&paths.wrapped.original.to_string_lossy()),
+        program = user_unit::quote_exec_arg(program_path),
+        wrapped_arg = user_unit::quote_exec_arg(&paths.wrapped.original),
+    }
+}
+
+/// Path of this program, which the service runs to remove the wrapper.
+pub fn program_path() -> anyhow::Result<PathBuf> {
+    std::env::current_exe().with_context(|| t!("program-path-failed"))
+}
+
+/// Read the path of the program that the service at `service_path` runs, if it's a service generated by this program.
+pub fn read_program(service_path: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(service_path).ok()?;
+    let quoted = content
+        .lines()
+        .find_map(|line| line.strip_prefix("ExecStart=\""))?;
+
+    // undo `user_unit::quote_exec_arg`
+    let mut program = String::new();
+    let mut chars = quoted.chars();
+
+    loop {
+        match chars.next()? {
+            '"' => return Some(PathBuf::from(program)),
+            '\\' => program.push(chars.next()?),
+            '%' => program.push(chars.next().filter(|&c| c == '%')?),
+            c => program.push(c),
+        }
+    }
+}
+
+/// Write the timer and service that remove the wrapper at `paths` once `expiry` has passed with the permissions in
+/// `mode`, and start the timer.
+pub fn install(
+    paths: &wrapper::ExecPaths,
+    header: &Header,
+    expiry: Expiry,
+    user: bool,
+    mode: u32,
+) -> anyhow::Result<()> {
+    let dir = unit_dir(user)?;
+
+    fs::create_dir_all(&dir)
+        .with_context(|| IoError::new(&dir, t!("expiry-create-unit-dir-failed")))?;
+
+    let [timer_path, service_path] = all_paths(&paths.hook_name, user)?;
+
+    let units = [
+        (timer_path, generate_timer(paths, header, expiry)),
+        (
+            service_path,
+            generate_service(paths, header, &program_path()?),
+        ),
+    ];
+
+    for (path, content) in units {
+        file::write_atomic(&path, content.as_bytes(), mode)
+            .with_context(|| IoError::new(&path, t!("expiry-write-unit-failed")))?;
+    }
+
+    let timer = unit_name(&paths.hook_name, "timer");
+
+    let result = systemctl(user, &["daemon-reload"])
+        .and_then(|()| systemctl(user, &["enable", "--now", &timer]));
+
+    // the wrapper itself is already in place, and `verify` reports it once it has expired
+    if let Err(err) = result {
+        let systemctl = if user {
+            "systemctl --user"
+        } else {
+            "systemctl"
+        };
+
+        eprintln!(
+            "{}",
+            t!(
+                "expiry-enable-failed",
+                error = format!("{err:#}"),
+                systemctl = systemctl,
+                timer = timer,
+            )
+        );
+    }
+
+    Ok(())
+}
+
+/// Stop and remove the timer and service of the wrapper at `paths`, for both the system and the current user, if they
+/// exist.
+pub fn remove_all(paths: &wrapper::ExecPaths) -> anyhow::Result<()> {
+    for user in [false, true] {
+        // without a home directory, there can't be any units of the user
+        let Ok(unit_paths) = all_paths(&paths.hook_name, user) else {
+            continue;
+        };
+
+        if !unit_paths.iter().any(|path| path.exists()) {
+            continue;
+        }
+
+        let [timer_path, _] = &unit_paths;
+
+        if timer_path.exists() {
+            let name = timer_path.file_name().unwrap_or_default().to_string_lossy();
+            let _ = systemctl(user, &["disable", "--now", &name]);
+        }
+
+        for path in &unit_paths {
+            file::remove_if_exists(path).map_err(|err| IoError::new(path, err))?;
+        }
+
+        let _ = systemctl(user, &["daemon-reload"]);
+    }
+
+    Ok(())
+}
+
+/// Find every unit in the unit directory of the system that was created by this program.
+pub fn find_system_units() -> anyhow::Result<Vec<PathBuf>> {
+    user_unit::find_in(Path::new(SYSTEM_UNIT_DIR))
+}
+
+/// Remove the units of the system at `unit_paths`, disabling the timers among them first.
+pub fn remove_system_units(unit_paths: &[PathBuf]) -> anyhow::Result<()> {
+    for path in unit_paths {
+        // the service manager may not be running, in which case nothing is being triggered anyway
+        if path.extension().is_some_and(|ext| ext == "timer") {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let _ = systemctl(false, &["disable", "--now", &name]);
+        }
+    }
+
+    for path in unit_paths {
+        file::remove_if_exists(path).map_err(|err| IoError::new(path, err))?;
+    }
+
+    let _ = systemctl(false, &["daemon-reload"]);
+
+    Ok(())
+}
+
+/// Run `systemctl` with `args`, for the service manager of the current user if `user` is set.
+fn systemctl(user: bool, args: &[&str]) -> anyhow::Result<()> {
+    let args = if user {
+        [&["--user"], args].concat()
+    } else {
+        args.to_vec()
+    };
+
+    let status = Command::new("systemctl")
+        .args(&args)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .with_context(|| t!("systemctl-run-failed"))?;
+
+    anyhow::ensure!(
+        status.success(),
+        t!(
+            "systemctl-failed",
+            args = args.join(" "),
+            status = status.to_string(),
+        )
+    );
+
+    Ok(())
+}
+
+/// Seconds in a duration such as `30m`, `12h`, `7d` or `2w`, or `None` if `value` isn't one.
+fn parse_duration(value: &str) -> Option<u64> {
+    let unit_index = value.len().checked_sub(1)?;
+    let (count, unit) = value.split_at(unit_index);
+
+    let unit_seconds = match unit {
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => SECONDS_PER_DAY,
+        "w" => 7 * SECONDS_PER_DAY,
+        _ => return None,
+    };
+
+    count.parse::<u64>().ok()?.checked_mul(unit_seconds)
+}
+
+/// Seconds since the Unix epoch.
+fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+fn is_leap_year(year: u64) -> bool {
+    year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400))
+}
+
+fn days_in_month(year: u64, month: u64) -> u64 {
+    match month {
+        2 if is_leap_year(year) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Days between the Unix epoch and the given date.
+fn days_from_civil(year: u64, month: u64, day: u64) -> u64 {
+    let years_before = (1970..year)
+        .map(|year| if is_leap_year(year) { 366 } else { 365 })
+        .sum::<u64>();
+
+    let months_before = (1..month)
+        .map(|month| days_in_month(year, month))
+        .sum::<u64>();
+
+    years_before + months_before + day - 1
+}
+
+/// The date that is `days` days after the Unix epoch, as a year, month and day.
+fn civil_from_days(mut days: u64) -> (u64, u64, u64) {
+    let mut year = 1970;
+
+    loop {
+        let year_days = if is_leap_year(year) { 366 } else { 365 };
+
+        if days < year_days {
+            break;
+        }
+
+        days -= year_days;
+        year += 1;
+    }
+
+    let mut month = 1;
+
+    while days >= days_in_month(year, month) {
+        days -= days_in_month(year, month);
+        month += 1;
+    }
+
+    (year, month, days + 1)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn parses_dates_and_times() {
+        let expiry = "2025-12-31".parse::<Expiry>().unwrap();
+        assert_eq!(expiry.to_string(), "2026-01-01T00:00:00Z");
+        assert_eq!(expiry.on_calendar(), "2026-01-01 00:00:00 UTC");
+
+        let expiry = "2024-02-29T18:30".parse::<Expiry>().unwrap();
+        assert_eq!(expiry.to_string(), "2024-02-29T18:30:00Z");
+
+        // the serialized format parses back to the same time
+        assert_eq!(expiry.to_string().parse::<Expiry>().unwrap(), expiry);
+
+        for invalid in [
+            "2025-02-29",
+            "2025-13-01",
+            "2025-12",
+            "2025-12-31T24:00",
+            "2025-12-31T18",
+            "1969-12-31",
+            "7d",
+            "tomorrow",
+        ] {
+            assert!(invalid.parse::<Expiry>().is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn parses_durations_from_now() {
+        let expiry = Expiry::parse("2h").unwrap();
+        assert!(!expiry.is_past());
+        assert!(expiry.0 - now() <= 2 * 60 * 60);
+
+        assert!(Expiry::parse("1970-01-02").unwrap().is_past());
+        assert!(Expiry::parse("2x").is_err());
+        assert!(Expiry::parse("h").is_err());
+    }
+
+    #[test]
+    fn timer_runs_unwrap() {
+        let paths = wrapper::ExecPaths::try_from_path(Path::new("/usr/bin/my app")).unwrap();
+        let header = Header::new(&paths.wrapped.original, &wrapper::Params::default());
+        let expiry = "2025-12-31T18:00".parse().unwrap();
+
+        let timer = generate_timer(&paths, &header, expiry);
+
+        assert!(timer.starts_with(&header.to_string()));
+        assert!(timer.contains("\nOnCalendar=2025-12-31 18:00:00 UTC\n"));
+        assert!(timer.contains(&format!(
+            "\nUnit={}\n",
+            unit_name(&paths.hook_name, "service")
+        )));
+
+        let service = generate_service(&paths, &header, Path::new("/usr/bin/wrapperize"));
+
+        assert!(service.contains(
+            "\nExecStart=\"/usr/bin/wrapperize\" unwrap --wait-for-pacman 600 \"/usr/bin/my app\"\n"
+        ));
+    }
+
+    #[test]
+    fn reads_program_of_service() {
+        let dir = test_util::temp_dir("expiry-program");
+        let paths = wrapper::ExecPaths::try_from_path(Path::new("/usr/bin/app")).unwrap();
+        let header = Header::new(&paths.wrapped.original, &wrapper::Params::default());
+        let program = Path::new("/home/me/.cargo/bin/wrap\"per%ize");
+        let service_path = dir.path().join("app.service");
+
+        fs::write(&service_path, generate_service(&paths, &header, program)).unwrap();
+
+        assert_eq!(read_program(&service_path).as_deref(), Some(program));
+        assert_eq!(read_program(&dir.path().join("missing.service")), None);
+    }
+}
@@ -0,0 +1,216 @@
+//! C interface for creating and listing wrappers without running this program, built with the `ffi` feature.
+//!
+//! Functions report failure through their return value, after which `wrapperize_last_error` describes what went
+//! wrong. Strings returned to the caller are owned by it and released with `wrapperize_string_free`.
+
+use std::{
+    cell::RefCell,
+    ffi::{CStr, CString, c_char, c_int},
+    panic::{self, AssertUnwindSafe},
+    path::PathBuf,
+    ptr,
+};
+
+use anyhow::Context;
+
+use crate::{cmd::apply, i18n::t, manifest, pacman_lock, registry, settings};
+
+thread_local! {
+    /// Description of the error of the last call on this thread that failed.
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+/// Wrap an executable as described by `entry`, a TOML table in the format of a `[[wrapper]]` entry of a manifest, such
+/// as `path = "/usr/bin/vim"` followed by `args = ["-u", "NONE"]`. An existing wrapper is regenerated with it.
+///
+/// Returns 0 if the wrapper was created or already up to date, and -1 otherwise. If `notice` isn't null, it's set to a
+/// note for the user, such as why no `pacman` hooks were generated, or to null if there is none. The note has to be
+/// released with `wrapperize_string_free`.
+///
+/// # Safety
+///
+/// `entry` has to point to a string terminated by a null byte, and `notice` has to be null or valid for writes.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wrapperize_wrap(entry: *const c_char, notice: *mut *mut c_char) -> c_int {
+    // SAFETY: the caller guarantees that `entry` is a null-terminated string
+    let entry = unsafe { string_arg(entry, "entry") };
+
+    let (status, note) = match call(|| wrap_entry(entry?)) {
+        Some(note) => (0, note),
+        None => (-1, None),
+    };
+
+    if !notice.is_null() {
+        // a note containing a null byte would be cut off, so those are replaced
+        let note = note.and_then(|note| CString::new(note.replace('\0', " ")).ok());
+
+        // SAFETY: the caller guarantees that `notice` is valid for writes
+        unsafe { *notice = note.map_or(ptr::null_mut(), CString::into_raw) };
+    }
+
+    status
+}
+
+/// The paths of every wrapped executable, each followed by a line break, or null if they couldn't be found.
+///
+/// The string has to be released with `wrapperize_string_free`.
+#[unsafe(no_mangle)]
+pub extern "C" fn wrapperize_list() -> *mut c_char {
+    list(registry::find_wrapped)
+}
+
+/// Description of the error of the last call on this thread that failed, or null if none has.
+///
+/// The string is owned by this library and stays valid until the next call on this thread.
+#[unsafe(no_mangle)]
+pub extern "C" fn wrapperize_last_error() -> *const c_char {
+    LAST_ERROR.with_borrow(|error| error.as_ref().map_or(ptr::null(), |error| error.as_ptr()))
+}
+
+/// Release a string returned by this library. Does nothing if `string` is null.
+///
+/// # Safety
+///
+/// `string` has to be null or returned by this library, and can't be used afterwards.
+#[unsafe(no_mangle)]
+pub unsafe extern "C" fn wrapperize_string_free(string: *mut c_char) {
+    if !string.is_null() {
+        // SAFETY: the caller guarantees that `string` was created by `CString::into_raw` and isn't used anymore
+        drop(unsafe { CString::from_raw(string) });
+    }
+}
+
+/// Create or regenerate the wrapper described by the manifest entry `entry`, like `apply` does, returning the notice
+/// `apply` would print.
+fn wrap_entry(entry: &str) -> anyhow::Result<Option<String>> {
+    let mut entry: manifest::Entry =
+        toml::from_str(entry).with_context(|| t!("ffi-parse-failed"))?;
+    entry.params.dedup();
+
+    let (use_pacman_hooks, notice) = apply::use_pacman_hooks(&entry);
+
+    if use_pacman_hooks {
+        pacman_lock::ensure_unlocked(None)?;
+    }
+
+    let staged = apply::stage(&entry, use_pacman_hooks)?;
+
+    if !staged.is_up_to_date() {
+        staged.install()?;
+    }
+
+    Ok(notice)
+}
+
+/// The paths of the wrapped executables found by `find`, each followed by a line break, or null if they couldn't be.
+fn list(find: impl FnOnce() -> anyhow::Result<Vec<PathBuf>>) -> *mut c_char {
+    let list = call(|| {
+        let list = find()?
+            .iter()
+            .map(|path| format!("{}\n", path.display()))
+            .collect::<String>();
+
+        Ok(CString::new(list)?)
+    });
+
+    list.map_or(ptr::null_mut(), CString::into_raw)
+}
+
+/// Read the string argument `name` from `arg`.
+///
+/// # Safety
+///
+/// `arg` has to be null or point to a string terminated by a null byte.
+unsafe fn string_arg<'a>(arg: *const c_char, name: &str) -> anyhow::Result<&'a str> {
+    anyhow::ensure!(!arg.is_null(), t!("ffi-null-arg", name = name));
+
+    // SAFETY: the caller guarantees that `arg` is a null-terminated string
+    unsafe { CStr::from_ptr(arg) }
+        .to_str()
+        .with_context(|| t!("ffi-invalid-utf8", name = name))
+}
+
+/// Run `f` with the settings loaded, which are read by the first call and kept for the rest of the process, recording its error, or that it panicked, for `wrapperize_last_error`.
+fn call<T>(f: impl FnOnce() -> anyhow::Result<T>) -> Option<T> {
+    // unwinding into C is undefined behavior
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        settings::init_once().and_then(|()| f())
+    }))
+    .unwrap_or_else(|_| {
+        Err(anyhow::anyhow!(t!(
+            "ffi-panicked",
+            program = env!("CARGO_PKG_NAME")
+        )))
+    });
+
+    let (value, error) = match result {
+        Ok(value) => (Some(value), None),
+        // an error containing a null byte would cut off its description, so those are replaced
+        Err(err) => (
+            None,
+            CString::new(format!("{err:#}").replace('\0', " ")).ok(),
+        ),
+    };
+
+    LAST_ERROR.set(error);
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::{test_util, wrapper};
+
+    #[test]
+    fn reports_errors() {
+        let mut notice = ptr::dangling_mut();
+
+        // SAFETY: the entry is a null-terminated string and the notice can be written
+        let status = unsafe { wrapperize_wrap(c"path = 1".as_ptr(), &mut notice) };
+        assert_eq!(status, -1);
+        assert!(notice.is_null());
+
+        // SAFETY: the error is a null-terminated string that stays valid until the next call
+        let error = unsafe { CStr::from_ptr(wrapperize_last_error()) };
+        assert!(
+            error
+                .to_str()
+                .unwrap()
+                .starts_with("failed to parse the wrapper")
+        );
+
+        // SAFETY: null is allowed for both
+        let status = unsafe { wrapperize_wrap(ptr::null(), ptr::null_mut()) };
+        assert_eq!(status, -1);
+    }
+
+    #[test]
+    fn lists_wrappers() {
+        let dir = test_util::temp_dir("ffi-list");
+        let paths = wrapper::ExecPaths::try_from_path(&dir.path().join("app")).unwrap();
+        fs::write(&paths.metadata.original, "").unwrap();
+
+        let list = list(|| Ok(registry::find_wrapped_in(&[dir.path().to_owned()], &[])));
+        assert!(!list.is_null());
+        assert!(wrapperize_last_error().is_null());
+
+        // SAFETY: the list is a null-terminated string returned by this library
+        let listed = unsafe { CStr::from_ptr(list) }.to_str().unwrap().to_owned();
+        assert_eq!(listed, format!("{}\n", dir.path().join("app").display()));
+
+        // SAFETY: the list was returned by this library and isn't used afterwards
+        unsafe { wrapperize_string_free(list) };
+    }
+
+    #[test]
+    fn header_is_up_to_date() {
+        assert_eq!(
+            include_str!("../include/wrapperize.h"),
+            include_str!(concat!(env!("OUT_DIR"), "/wrapperize.h")),
+            "the header in `include/` should be replaced by the one generated in `{}`",
+            env!("OUT_DIR")
+        );
+    }
+}
@@ -1,23 +1,396 @@
-use std::{fs::File, io::Write, os::unix::fs::PermissionsExt, path::Path};
+use std::{
+    borrow::Cow,
+    env,
+    ffi::OsString,
+    fmt,
+    fs::{self, File, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process,
+    str::FromStr,
+    sync::atomic::{AtomicU32, Ordering},
+    time::{SystemTime, UNIX_EPOCH},
+};
 
 use anyhow::Context;
-use tap::Tap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 
-pub fn write_with_execute_bit(path: &Path, content: &[u8]) -> anyhow::Result<()> {
-    let mut file = File::create(path).context("failed to create file")?;
-    file.write_all(content).context("failed to write to file")?;
+use crate::{error::IoError, i18n::t, symlink};
 
-    let file_perms = file
-        .metadata()
-        .context("failed to get metadata for created file")?
-        .permissions()
-        .tap_mut(|p| {
-            // add the execute bit to the current file permissions
-            p.set_mode(p.mode() | 0o700)
-        });
+/// Mode that generated files other than the wrapper, such as hooks and metadata, are written with by default.
+pub const REGULAR_MODE: FileMode = FileMode(0o644);
+
+/// Number of names tried for a temporary file or directory before giving up, each of which only fails to be created
+/// if it already exists.
+const TEMP_NAME_ATTEMPTS: u32 = 16;
+
+/// A directory only the current user can access, created in the temporary directory under a name nothing else had
+/// taken, and removed along with its contents when dropped.
+///
+/// Since it's never an existing directory, files in it can't have been planted by other users.
+#[derive(Debug)]
+pub struct TempDir {
+    path: PathBuf,
+}
+
+impl TempDir {
+    /// Create a directory whose name includes `purpose`, such as `edit`.
+    pub fn new(purpose: &str) -> anyhow::Result<Self> {
+        for _ in 0..TEMP_NAME_ATTEMPTS {
+            let path = env::temp_dir().join(format!(
+                "{}-{purpose}-{}",
+                env!("CARGO_PKG_NAME"),
+                unique_suffix()
+            ));
+
+            match create_private_dir(&path) {
+                Ok(()) => return Ok(Self { path }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => {
+                    return Err(anyhow::Error::new(err))
+                        .with_context(|| IoError::new(&path, t!("file-create-temp-dir-failed")));
+                }
+            }
+        }
+
+        anyhow::bail!(t!("file-no-temp-name"))
+    }
+
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        // a leftover directory is harmless in the temporary directory
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// A suffix for names of temporary files that differs between processes, calls and runs.
+fn unique_suffix() -> String {
+    static COUNTER: AtomicU32 = AtomicU32::new(0);
+
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.subsec_nanos());
+
+    format!(
+        "{}-{}-{nanos:08x}",
+        process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+/// Create the directory at `path` with access for the current user only, failing if anything exists at `path`, even
+/// a symlink.
+#[cfg(unix)]
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+
+    fs::DirBuilder::new().mode(0o700).create(path)
+}
+
+/// Other platforms have no permission modes, so the directory is created as usual.
+#[cfg(not(unix))]
+fn create_private_dir(path: &Path) -> io::Result<()> {
+    fs::create_dir(path)
+}
+
+/// Access permissions of a file, written in octal like `0755` as accepted by `chmod`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FileMode(pub u32);
+
+impl FileMode {
+    /// This mode with execute permission added for everyone who can read the file.
+    pub fn with_execute_for_readers(self) -> Self {
+        Self(self.0 | (self.0 & 0o444) >> 2)
+    }
+}
+
+impl FromStr for FileMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mode =
+            u32::from_str_radix(s, 8).with_context(|| t!("file-mode-not-octal", mode = s))?;
+
+        // special bits such as setuid have no effect on scripts
+        anyhow::ensure!(mode <= 0o777, t!("file-mode-special-bits", mode = s));
+
+        Ok(Self(mode))
+    }
+}
+
+impl fmt::Display for FileMode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:04o}", self.0)
+    }
+}
+
+impl schemars::JsonSchema for FileMode {
+    fn schema_name() -> Cow<'static, str> {
+        "FileMode".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Access permissions in octal, such as `0755`.",
+            "type": "string",
+            "pattern": "^0?[0-7]{3}$",
+        })
+    }
+}
+
+/// Serialized in the same octal format accepted on the command line.
+impl Serialize for FileMode {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for FileMode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+/// Write `content` to `path` with exactly the permissions in `mode`.
+///
+/// The content goes into a temporary file next to `path` whose permissions are set before anything is written to it,
+/// which is then renamed over `path`, so `path` never exists with other permissions or partial contents.
+pub fn write_atomic(path: &Path, content: &[u8], mode: u32) -> anyhow::Result<()> {
+    let (temp_path, mut file) =
+        create_temp_file(path, mode).with_context(|| t!("file-create-failed"))?;
+
+    let mut write = || {
+        // the mode given on creation is reduced by the umask
+        set_mode(&file, mode).with_context(|| t!("file-set-mode-failed"))?;
+
+        file.write_all(content)
+            .with_context(|| t!("file-write-failed"))?;
+        fs::rename(&temp_path, path).with_context(|| t!("file-move-failed"))
+    };
+
+    let result = write();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&temp_path);
+    }
+
+    result
+}
+
+/// Create a temporary file next to `path` with `mode`, under a name nothing had taken, so it can't be a file or
+/// symlink that was left there.
+fn create_temp_file(path: &Path, mode: u32) -> io::Result<(PathBuf, File)> {
+    for _ in 0..TEMP_NAME_ATTEMPTS {
+        let mut temp_name = OsString::from(".");
+        temp_name.push(path.file_name().unwrap_or_default());
+        temp_name.push(format!(
+            "-{}-{}.tmp",
+            env!("CARGO_PKG_NAME"),
+            unique_suffix()
+        ));
+        let temp_path = path.with_file_name(temp_name);
+
+        match create_new_with_mode(&temp_path, mode) {
+            Ok(file) => return Ok((temp_path, file)),
+            Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+            Err(err) => return Err(err),
+        }
+    }
+
+    Err(io::Error::new(
+        io::ErrorKind::AlreadyExists,
+        t!("file-no-temp-file-name"),
+    ))
+}
 
-    file.set_permissions(file_perms)
-        .context("failed to set execute bit for file")?;
+/// Create the file at `path` with `mode`, failing if anything exists at `path`, even a symlink.
+#[cfg(unix)]
+fn create_new_with_mode(path: &Path, mode: u32) -> io::Result<File> {
+    use std::os::unix::fs::OpenOptionsExt;
 
+    OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .mode(mode)
+        .open(path)
+}
+
+/// Other platforms have no permission modes, so the file is created as usual.
+#[cfg(not(unix))]
+fn create_new_with_mode(path: &Path, _mode: u32) -> io::Result<File> {
+    OpenOptions::new().write(true).create_new(true).open(path)
+}
+
+#[cfg(unix)]
+fn set_mode(file: &File, mode: u32) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    file.set_permissions(fs::Permissions::from_mode(mode))
+}
+
+/// Other platforms have no permission modes, so there's nothing to set.
+#[cfg(not(unix))]
+fn set_mode(_file: &File, _mode: u32) -> io::Result<()> {
     Ok(())
 }
+
+/// Returns true if anyone is allowed to execute the file at `path`.
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> anyhow::Result<bool> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)
+        .with_context(|| t!("file-metadata-failed"))?
+        .permissions()
+        .mode();
+
+    Ok(mode & 0o111 != 0)
+}
+
+/// Other platforms have no execute bit, so every file counts as executable.
+#[cfg(not(unix))]
+pub fn is_executable(_path: &Path) -> anyhow::Result<bool> {
+    Ok(true)
+}
+
+/// Find the executable named `name` in the directories that commonly hold executables, including those in `$PATH`.
+pub fn find_program(name: &str) -> Option<PathBuf> {
+    symlink::search_dirs()
+        .into_iter()
+        .map(|dir| dir.join(name))
+        .find(|path| path.is_file() && is_executable(path).unwrap_or(false))
+}
+
+/// Find the executable that running `name` would start, by searching `$PATH` in order like a shell does, or `name`
+/// itself if it's a path.
+pub fn resolve_command(name: &str) -> Option<PathBuf> {
+    let is_runnable = |path: &Path| path.is_file() && is_executable(path).unwrap_or(false);
+
+    if name.contains('/') {
+        return Some(PathBuf::from(name)).filter(|path| is_runnable(path));
+    }
+
+    env::split_paths(&env::var_os("PATH")?)
+        .map(|dir| dir.join(name))
+        .find(|path| is_runnable(path))
+}
+
+/// Check that files can be created in `dir` by creating and removing an empty file in it, which accounts for
+/// everything that can deny access, unlike the permission bits alone.
+pub fn ensure_dir_writable(dir: &Path) -> anyhow::Result<()> {
+    let probe = dir.join(format!(
+        ".{}-probe-{}",
+        env!("CARGO_PKG_NAME"),
+        process::id()
+    ));
+
+    File::create_new(&probe).with_context(|| t!("file-dir-not-writable"))?;
+    fs::remove_file(&probe).with_context(|| t!("file-remove-probe-failed"))
+}
+
+/// The access permissions of the file at `path`, leaving out any special bits such as setuid.
+#[cfg(unix)]
+pub fn mode(path: &Path) -> anyhow::Result<u32> {
+    use std::os::unix::fs::PermissionsExt;
+
+    let mode = fs::metadata(path)
+        .with_context(|| t!("file-metadata-failed"))?
+        .permissions()
+        .mode();
+
+    Ok(mode & 0o777)
+}
+
+/// Other platforms have no permission modes, so every file is treated as an executable.
+#[cfg(not(unix))]
+pub fn mode(_path: &Path) -> anyhow::Result<u32> {
+    Ok(REGULAR_MODE.with_execute_for_readers().0)
+}
+
+/// Remove the file at `path`, treating a file that doesn't exist as already removed.
+///
+/// Returns true if a file was removed.
+pub fn remove_if_exists(path: &Path) -> anyhow::Result<bool> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(true),
+        Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(false),
+        Err(err) => Err(anyhow::Error::new(err).context(t!("file-remove-failed"))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[cfg(unix)]
+    #[test]
+    fn find_program_in_search_dirs() {
+        assert!(find_program("sh").is_some());
+        assert!(find_program(concat!(env!("CARGO_PKG_NAME"), "-nonexistent")).is_none());
+    }
+
+    #[test]
+    fn parse_file_mode() {
+        assert_eq!("0750".parse::<FileMode>().unwrap(), FileMode(0o750));
+        assert_eq!("644".parse::<FileMode>().unwrap(), FileMode(0o644));
+        assert_eq!(FileMode(0o644).to_string(), "0644");
+
+        assert!("0758".parse::<FileMode>().is_err());
+        assert!("4755".parse::<FileMode>().is_err());
+        assert!("rwx".parse::<FileMode>().is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn private_temp_dir() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("test").unwrap();
+        let other = TempDir::new("test").unwrap();
+        assert_ne!(dir.path(), other.path());
+
+        let mode = fs::metadata(dir.path()).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        assert!(create_private_dir(dir.path()).is_err());
+
+        let path = dir.path().to_owned();
+        fs::write(path.join("file"), "content").unwrap();
+        drop(dir);
+        assert!(!path.exists());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn write_atomic_with_mode() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = TempDir::new("test").unwrap();
+        let path = dir.path().join("file");
+
+        write_atomic(&path, b"first", 0o640).unwrap();
+        write_atomic(&path, b"second", 0o750).unwrap();
+
+        assert_eq!(fs::read(&path).unwrap(), b"second");
+        assert_eq!(
+            fs::metadata(&path).unwrap().permissions().mode() & 0o777,
+            0o750
+        );
+        assert_eq!(fs::read_dir(dir.path()).unwrap().count(), 1);
+
+        let (temp_path, _) = create_temp_file(&path, 0o600).unwrap();
+        assert!(create_new_with_mode(&temp_path, 0o600).is_err());
+    }
+
+    #[test]
+    fn execute_for_readers() {
+        assert_eq!(FileMode(0o640).with_execute_for_readers(), FileMode(0o750));
+        assert_eq!(FileMode(0o644).with_execute_for_readers(), FileMode(0o755));
+        assert_eq!(FileMode(0o600).with_execute_for_readers(), FileMode(0o700));
+    }
+}
@@ -0,0 +1,149 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Output},
+    slice,
+};
+
+use anyhow::Context;
+
+use crate::{error::IoError, i18n::t};
+
+/// Record the current state of `paths` as a single commit in the git repository containing them, such as `/etc` when
+/// managed by etckeeper, so changes made by this program can be reviewed and reverted later.
+///
+/// Paths that no longer exist are recorded as removed, and paths outside of the repository are left out. Other changes
+/// in the repository are left uncommitted.
+pub fn commit(paths: &[PathBuf], message: &str) -> anyhow::Result<()> {
+    let Some(first) = paths.first() else {
+        return Ok(());
+    };
+
+    // the directory of a removed file may be gone as well
+    let dir = first
+        .ancestors()
+        .skip(1)
+        .find(|dir| dir.is_dir())
+        .unwrap_or(Path::new("/"));
+
+    let top_level = git(dir, &["rev-parse", "--show-toplevel"], &[])
+        .map_err(|err| IoError::new(dir, err.context(t!("git-not-repository"))))?;
+    let repo = PathBuf::from(String::from_utf8_lossy(&top_level.stdout).trim_end());
+
+    // git refuses paths outside of the repository
+    let paths = paths
+        .iter()
+        .filter(|path| path.starts_with(&repo))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    // without any paths, git would act on the whole repository instead
+    if paths.is_empty() {
+        return Ok(());
+    }
+
+    for path in &paths {
+        if path.exists() {
+            git(&repo, &["add", "--"], slice::from_ref(path))?;
+        } else {
+            git(
+                &repo,
+                &["rm", "--cached", "--ignore-unmatch", "-q", "--"],
+                slice::from_ref(path),
+            )?;
+        }
+    }
+
+    let changed = git(
+        &repo,
+        &["diff", "--cached", "--name-only", "-z", "--"],
+        &paths,
+    )?;
+    let changed = String::from_utf8_lossy(&changed.stdout)
+        .split_terminator('\0')
+        .map(PathBuf::from)
+        .collect::<Vec<_>>();
+
+    if changed.is_empty() {
+        return Ok(());
+    }
+
+    // only the changed paths are committed, both because git rejects paths it doesn't know about and in case other
+    // changes were already staged
+    git(
+        &repo,
+        &["commit", "-q", "-m", message, "--only", "--"],
+        &changed,
+    )?;
+
+    println!("{}", t!("git-committed", repo = repo.display().to_string()));
+    Ok(())
+}
+
+/// Run git in `dir` with `args` followed by `paths`, returning an error with git's output if it fails.
+fn git(dir: &Path, args: &[&str], paths: &[PathBuf]) -> anyhow::Result<Output> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .args(paths)
+        .output()
+        .with_context(|| t!("git-run-failed"))?;
+
+    if !output.status.success() {
+        anyhow::bail!(t!(
+            "git-failed",
+            args = args.join(" "),
+            error = String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+
+    Ok(output)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn commits_only_given_paths() {
+        let dir = test_util::temp_dir("git");
+        let repo = dir.path();
+
+        let run = |args: &[&str]| {
+            let output = git(repo, args, &[]).unwrap();
+            String::from_utf8_lossy(&output.stdout).trim().to_owned()
+        };
+
+        run(&["init", "-q"]);
+        run(&["config", "user.name", "test"]);
+        run(&["config", "user.email", "test@example.com"]);
+
+        let hook = repo.join("test.hook");
+        let other = repo.join("other");
+        fs::write(&hook, "hook").unwrap();
+        fs::write(&other, "other").unwrap();
+        run(&["add", "other"]);
+
+        // paths that never existed or are outside of the repository are skipped
+        let missing = repo.join("missing.hook");
+        let outside = env::temp_dir();
+        commit(&[hook.clone(), missing, outside], "add hook").unwrap();
+        assert_eq!(run(&["rev-list", "--count", "HEAD"]), "1");
+        assert_eq!(run(&["ls-tree", "--name-only", "HEAD"]), "test.hook");
+
+        // nothing changed, so there's nothing to commit
+        commit(slice::from_ref(&hook), "unchanged").unwrap();
+        assert_eq!(run(&["rev-list", "--count", "HEAD"]), "1");
+
+        fs::remove_file(&hook).unwrap();
+        commit(slice::from_ref(&hook), "remove hook").unwrap();
+        assert_eq!(run(&["rev-list", "--count", "HEAD"]), "2");
+        assert_eq!(run(&["ls-tree", "--name-only", "HEAD"]), "");
+
+        // changes staged by someone else are left for them to commit
+        assert_eq!(run(&["diff", "--cached", "--name-only"]), "other");
+    }
+}
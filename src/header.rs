@@ -0,0 +1,120 @@
+use std::{
+    fmt::{self, Display},
+    path::{Path, PathBuf},
+};
+
+use crate::wrapper;
+
+/// Version of the layout of generated files, bumped whenever they change in a way that matters to tools reading them.
+pub const FORMAT_VERSION: u32 = 1;
+
+/// Start of the header line, which is a comment in every kind of generated file.
+const PREFIX: &str = concat!("# ", env!("CARGO_PKG_NAME"), ": ");
+
+/// Structured comment at the top of every generated file, identifying what created it and for which wrapper.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Header {
+    pub format: u32,
+    pub version: String,
+    /// Hash of the parameters the wrapper was generated with.
+    pub params_hash: String,
+    /// Path to the wrapped executable.
+    pub path: PathBuf,
+}
+
+impl Header {
+    pub fn new(path: &Path, params: &wrapper::Params) -> Self {
+        Self {
+            format: FORMAT_VERSION,
+            version: env!("CARGO_PKG_VERSION").into(),
+            params_hash: params.hash(),
+            path: path.into(),
+        }
+    }
+
+    /// Find the header in the contents of a generated file.
+    pub fn find(content: &str) -> Option<Self> {
+        content.lines().find_map(Self::parse)
+    }
+
+    fn parse(line: &str) -> Option<Self> {
+        let fields = line.strip_prefix(PREFIX)?;
+
+        // the path comes last since it's the only field that can contain spaces
+        let (fields, path) = fields.split_once(" path=")?;
+
+        let mut format = None;
+        let mut version = None;
+        let mut params_hash = None;
+
+        for field in fields.split(' ') {
+            match field.split_once('=')? {
+                ("format", value) => format = value.parse().ok(),
+                ("version", value) => version = Some(value.to_owned()),
+                ("params", value) => params_hash = Some(value.to_owned()),
+                // fields added by newer versions are ignored
+                _ => {}
+            }
+        }
+
+        Some(Self {
+            format: format?,
+            version: version?,
+            params_hash: params_hash?,
+            path: path.into(),
+        })
+    }
+}
+
+impl Display for Header {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{PREFIX}format={} version={} params={} path={}",
+            self.format,
+            self.version,
+            self.params_hash,
+            self.path.display()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let header = Header::new(
+            Path::new("/opt/some app/bin"),
+            &wrapper::Params::with_args(vec!["--arg".into()]),
+        );
+
+        let content = format!("#!/usr/bin/env bash\n{header}\nexec true\n");
+        assert_eq!(Header::find(&content), Some(header));
+    }
+
+    #[test]
+    fn params_hash_changes_with_params() {
+        let path = Path::new("/usr/bin/test_bin");
+        let header = Header::new(path, &wrapper::Params::with_args(vec!["--a".into()]));
+
+        assert_eq!(
+            header.params_hash,
+            Header::new(path, &wrapper::Params::with_args(vec!["--a".into()])).params_hash
+        );
+        assert_ne!(
+            header.params_hash,
+            Header::new(path, &wrapper::Params::with_args(vec!["--b".into()])).params_hash
+        );
+    }
+
+    #[test]
+    fn ignores_unrelated_lines() {
+        assert_eq!(Header::find("# some comment\nexec true\n"), None);
+        assert_eq!(
+            Header::find(concat!("# ", env!("CARGO_PKG_NAME"), ": garbage")),
+            None
+        );
+    }
+}
@@ -0,0 +1,187 @@
+use std::{
+    borrow::Cow,
+    fmt::{self, Write},
+    str::FromStr,
+};
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+
+use crate::i18n::t;
+
+/// Environment variable that lets a wrapper run outside its allowed hours, for emergencies.
+const IGNORE_VAR: &str = "WRAPPERIZE_IGNORE_HOURS";
+
+/// Exit status of a wrapper run outside its allowed hours, which is what shells use for a command that can't be run.
+const DENIED_EXIT_CODE: u8 = 126;
+
+/// The time of day a wrapper can be run in, such as `08:00-18:00`, in the local time of the system.
+///
+/// The start is included and the end isn't, and a window whose end comes before its start spans midnight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct AllowedHours {
+    /// Start of the window, as `HHMM`.
+    start: u16,
+    /// End of the window, as `HHMM`, which can be `2400` for the end of the day.
+    end: u16,
+}
+
+impl AllowedHours {
+    /// Write the bash lines that stop a wrapper from running outside these hours, unless `IGNORE_VAR` is set to `1`,
+    /// printing `denied_message` to stderr.
+    ///
+    /// `denied_message` is already quoted.
+    pub fn write_bash_lines(&self, denied_message: &str, writer: &mut impl Write) -> fmt::Result {
+        let Self { start, end } = *self;
+
+        let allowed = if start < end {
+            format!(r#"[ "$hours_now" -ge {start} ] && [ "$hours_now" -lt {end} ]"#)
+        } else {
+            format!(r#"[ "$hours_now" -ge {start} ] || [ "$hours_now" -lt {end} ]"#)
+        };
+
+        writeln!(writer, r#"if [ "${{{IGNORE_VAR}:-}}" != 1 ]; then"#)?;
+        // the leading zero would otherwise make the time an octal number
+        writeln!(writer, r#"    hours_now="$((10#$(date +%H%M)))""#)?;
+        writeln!(writer, "    if ! {{ {allowed}; }}; then")?;
+        writeln!(writer, "        printf '%s\\n' {denied_message} >&2")?;
+        writeln!(writer, "        exit {DENIED_EXIT_CODE}")?;
+        writeln!(writer, "    fi")?;
+        writeln!(writer, "fi")
+    }
+}
+
+/// Parse a time of day in the format of `08:00` into `HHMM`, allowing `24:00` only if `end` is set.
+fn parse_time(time: &str, end: bool) -> anyhow::Result<u16> {
+    let parsed = time
+        .split_once(':')
+        .filter(|(hours, minutes)| hours.len() == 2 && minutes.len() == 2)
+        .and_then(|(hours, minutes)| {
+            Some((hours.parse::<u16>().ok()?, minutes.parse::<u16>().ok()?))
+        })
+        .filter(|&(hours, minutes)| {
+            (hours < 24 && minutes < 60) || (end && hours == 24 && minutes == 0)
+        });
+
+    let Some((hours, minutes)) = parsed else {
+        anyhow::bail!(t!("hours-invalid-time", time = time));
+    };
+
+    Ok(hours * 100 + minutes)
+}
+
+impl FromStr for AllowedHours {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let Some((start, end)) = s.split_once('-') else {
+            anyhow::bail!(t!("hours-invalid", hours = s));
+        };
+
+        let start = parse_time(start, false)?;
+        let end = parse_time(end, true)?;
+
+        anyhow::ensure!(start != end, t!("hours-empty", hours = s));
+
+        Ok(Self { start, end })
+    }
+}
+
+impl fmt::Display for AllowedHours {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:02}:{:02}-{:02}:{:02}",
+            self.start / 100,
+            self.start % 100,
+            self.end / 100,
+            self.end % 100
+        )
+    }
+}
+
+impl schemars::JsonSchema for AllowedHours {
+    fn schema_name() -> Cow<'static, str> {
+        "AllowedHours".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        schemars::json_schema!({
+            "description": "Start and end of a time of day, such as `08:00-18:00`.",
+            "type": "string",
+            "pattern": "^[0-9]{2}:[0-9]{2}-[0-9]{2}:[0-9]{2}$",
+        })
+    }
+}
+
+impl Serialize for AllowedHours {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for AllowedHours {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn parse_allowed_hours() {
+        for valid in ["08:00-18:00", "22:30-06:00", "00:00-24:00"] {
+            assert_eq!(valid.parse::<AllowedHours>().unwrap().to_string(), valid);
+        }
+
+        for invalid in [
+            "",
+            "08:00",
+            "8:00-18:00",
+            "08:00-18:60",
+            "24:00-08:00",
+            "08:00-08:00",
+            "08-18",
+        ] {
+            assert!(invalid.parse::<AllowedHours>().is_err(), "{invalid}");
+        }
+    }
+
+    fn runs_now(hours: &str, ignore: bool) -> bool {
+        let mut script = String::new();
+        let hours: AllowedHours = hours.parse().unwrap();
+        hours.write_bash_lines("denied", &mut script).unwrap();
+
+        let mut command = Command::new("bash");
+        command.arg("-c").arg(script).env_remove(IGNORE_VAR);
+
+        if ignore {
+            command.env(IGNORE_VAR, "1");
+        }
+
+        command.output().unwrap().status.success()
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn enforces_window() {
+        let output = Command::new("date").arg("+%H").output().unwrap();
+        let hour: u16 = String::from_utf8(output.stdout)
+            .unwrap()
+            .trim()
+            .parse()
+            .unwrap();
+
+        // hours well away from the current one, so the test doesn't depend on when it runs
+        let later = |offset: u16| format!("{:02}:00", (hour + offset) % 24);
+
+        assert!(runs_now("00:00-24:00", false));
+        assert!(runs_now(&format!("{}-{}", later(23), later(1)), false));
+        assert!(!runs_now(&format!("{}-{}", later(2), later(4)), false));
+        assert!(runs_now(&format!("{}-{}", later(2), later(4)), true));
+    }
+}
@@ -0,0 +1,241 @@
+use std::{cell::RefCell, env};
+
+use fluent_bundle::{FluentArgs, FluentBundle, FluentResource};
+use unic_langid::LanguageIdentifier;
+
+/// Catalogs of the messages shown to users, by language, with English first since it's the fallback for the others.
+const CATALOGS: &[(&str, &str)] = &[
+    ("en", include_str!("../locales/en.ftl")),
+    ("de", include_str!("../locales/de.ftl")),
+];
+
+thread_local! {
+    /// The catalog of the language of the user, followed by the English one for messages it doesn't have.
+    static BUNDLES: RefCell<Vec<FluentBundle<FluentResource>>> =
+        RefCell::new(load_bundles(requested_language()));
+}
+
+/// Format the message with the given ID from the catalog, in the language of the user if it has one.
+macro_rules! t {
+    ($id:literal $(, $name:ident = $value:expr)* $(,)?) => {{
+        #[allow(unused_mut)]
+        let mut args = fluent_bundle::FluentArgs::new();
+        $(args.set(stringify!($name), $value);)*
+        $crate::i18n::message($id, &args)
+    }};
+}
+
+pub(crate) use t;
+
+/// Format the message `id` with `args`, which is used by `t!`.
+pub fn message(id: &str, args: &FluentArgs) -> String {
+    BUNDLES.with_borrow(|bundles| {
+        bundles
+            .iter()
+            .find_map(|bundle| {
+                let pattern = bundle.get_message(id)?.value()?;
+                let mut errors = Vec::new();
+
+                Some(
+                    bundle
+                        .format_pattern(pattern, Some(args), &mut errors)
+                        .into_owned(),
+                )
+            })
+            // every ID used is checked to be in the English catalog, so this is only a last resort
+            .unwrap_or_else(|| id.to_owned())
+    })
+}
+
+/// Show the messages of the current thread in `language` from now on, since tests otherwise always get English.
+#[cfg(test)]
+pub fn set_language(language: &str) {
+    BUNDLES.set(load_bundles(language.parse().ok()));
+}
+
+/// Load the bundles of the `requested` language and of English, or only the latter if it's the same.
+fn load_bundles(requested: Option<LanguageIdentifier>) -> Vec<FluentBundle<FluentResource>> {
+    let english = CATALOGS[0].0;
+
+    let language = requested
+        .and_then(|requested| find_catalog(&requested))
+        .filter(|&language| language != english);
+
+    language
+        .into_iter()
+        .chain([english])
+        .map(|language| {
+            let (_, source) = CATALOGS
+                .iter()
+                .find(|(name, _)| *name == language)
+                .expect("language should have a catalog");
+
+            let resource = FluentResource::try_new(source.to_string())
+                .unwrap_or_else(|(resource, _)| resource);
+
+            let mut bundle = FluentBundle::new(vec![language.parse().unwrap_or_default()]);
+
+            // isolation marks around arguments only matter for right-to-left text, and show up in some terminals
+            bundle.set_use_isolating(false);
+
+            // a duplicate message would already fail the tests
+            let _ = bundle.add_resource(resource);
+            bundle
+        })
+        .collect()
+}
+
+/// The language of messages set in the environment, like gettext does, or `None` if it isn't set or can't be parsed.
+fn requested_language() -> Option<LanguageIdentifier> {
+    // the output of the tests is always compared against English
+    if cfg!(test) {
+        return None;
+    }
+
+    let locale = ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .filter_map(|name| env::var(name).ok())
+        .find(|value| !value.is_empty())?;
+
+    parse_locale(&locale)
+}
+
+/// Parse a POSIX locale such as `de_DE.UTF-8`, returning `None` for the `C` and `POSIX` locales.
+fn parse_locale(locale: &str) -> Option<LanguageIdentifier> {
+    let name = locale.split(['.', '@']).next()?;
+
+    if matches!(name, "C" | "POSIX") {
+        return None;
+    }
+
+    name.replace('_', "-").parse().ok()
+}
+
+/// The language of the catalog that best matches `requested`, which only needs to share its language.
+fn find_catalog(requested: &LanguageIdentifier) -> Option<&'static str> {
+    CATALOGS
+        .iter()
+        .map(|(language, _)| *language)
+        .find(|language| {
+            language
+                .parse::<LanguageIdentifier>()
+                .is_ok_and(|language| language.language == requested.language)
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{BTreeMap, BTreeSet},
+        fs,
+        path::Path,
+    };
+
+    use fluent_bundle::FluentValue;
+
+    use super::*;
+
+    /// IDs of the messages in a catalog, along with the names of the arguments they use.
+    fn messages(source: &str) -> BTreeMap<String, BTreeSet<String>> {
+        if let Err((_, errors)) = FluentResource::try_new(source.to_owned()) {
+            panic!("catalog should parse: {errors:?}");
+        }
+
+        let mut messages = BTreeMap::<_, BTreeSet<_>>::new();
+        let mut current = String::new();
+
+        for line in source.lines() {
+            if line.starts_with('#') || line.trim().is_empty() {
+                continue;
+            }
+
+            // a message starts at the beginning of a line, and continues on indented ones
+            if !line.starts_with(char::is_whitespace) {
+                let (id, _) = line.split_once(" =").expect("message should have an ID");
+                current = id.to_owned();
+            }
+
+            let args = line.split('$').skip(1).filter_map(|rest| {
+                rest.split(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_' && ch != '-')
+                    .next()
+                    .map(str::to_owned)
+            });
+
+            messages.entry(current.clone()).or_default().extend(args);
+        }
+
+        messages
+    }
+
+    #[test]
+    fn catalogs_have_the_same_messages() {
+        let english = messages(CATALOGS[0].1);
+
+        for (language, source) in &CATALOGS[1..] {
+            assert_eq!(messages(source), english, "catalog `{language}`");
+        }
+    }
+
+    #[test]
+    fn every_message_used_is_in_the_catalog() {
+        let english = messages(CATALOGS[0].1);
+
+        let mut dirs = vec![Path::new(env!("CARGO_MANIFEST_DIR")).join("src")];
+
+        while let Some(dir) = dirs.pop() {
+            for entry in fs::read_dir(&dir).unwrap().flatten() {
+                let path = entry.path();
+
+                if path.is_dir() {
+                    dirs.push(path);
+                    continue;
+                }
+
+                let source = fs::read_to_string(&path).unwrap();
+
+                // other macros such as `format!` end the same way
+                let uses = source.match_indices("t!(\"").filter(|(index, _)| {
+                    !source[..*index].ends_with(|ch: char| ch.is_ascii_alphanumeric() || ch == '_')
+                });
+
+                for (index, _) in uses {
+                    let id = source[index + 4..].split('"').next().unwrap();
+                    assert!(
+                        english.contains_key(id),
+                        "`{id}` in `{}` is missing from the catalog",
+                        path.display()
+                    );
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn formats_arguments() {
+        let mut args = FluentArgs::new();
+        args.set("path", "/usr/bin/vim");
+        args.set("count", FluentValue::from(2));
+
+        assert_eq!(
+            message("verify-problems", &args),
+            "wrapper for `/usr/bin/vim` has 2 problems"
+        );
+
+        args.set("count", FluentValue::from(1));
+        assert_eq!(
+            message("verify-problems", &args),
+            "wrapper for `/usr/bin/vim` has 1 problem"
+        );
+    }
+
+    #[test]
+    fn parses_locales() {
+        assert_eq!(parse_locale("de_DE.UTF-8"), Some("de-DE".parse().unwrap()));
+        assert_eq!(parse_locale("sr_RS@latin"), Some("sr-RS".parse().unwrap()));
+        assert_eq!(parse_locale("C.UTF-8"), None);
+        assert_eq!(parse_locale("POSIX"), None);
+
+        assert_eq!(find_catalog(&"de-AT".parse().unwrap()), Some("de"));
+        assert_eq!(find_catalog(&"fr".parse().unwrap()), None);
+    }
+}
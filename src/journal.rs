@@ -0,0 +1,21 @@
+use std::fmt;
+
+use crate::{preset::LAUNCHER_VAR, shell};
+
+/// Write the lines of the wrapper script that send the output of the executable to the journal, tagged with `tag`.
+///
+/// Output only goes to the journal when it isn't shown in a terminal, such as when the executable is launched from a
+/// desktop environment, where it would otherwise be lost. `systemd-cat` runs the executable in its own place, so
+/// signals still reach it directly.
+pub fn write_bash(tag: &str, mut writer: impl fmt::Write) -> fmt::Result {
+    writeln!(
+        writer,
+        "if [ ! -t 1 ] && command -v systemd-cat >/dev/null; then"
+    )?;
+    writeln!(
+        writer,
+        "    {LAUNCHER_VAR}+=(systemd-cat -t {} --stderr-priority=warning)",
+        shell::quote(tag)
+    )?;
+    writeln!(writer, "fi")
+}
@@ -0,0 +1,120 @@
+use std::fmt;
+
+use strum::IntoEnumIterator;
+
+use crate::{i18n::t, shell};
+
+/// Environment variable the JVM reads additional options from.
+pub const TOOL_OPTIONS_VAR: &str = "JAVA_TOOL_OPTIONS";
+
+/// A garbage collector built into the JVM.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum GarbageCollector {
+    G1,
+    Z,
+    Shenandoah,
+    Parallel,
+    Serial,
+}
+
+impl GarbageCollector {
+    /// Parse a collector name, listing the known collectors if it isn't one of them.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        value.parse().map_err(|_| {
+            let known = Self::iter().map(|gc| gc.to_string()).collect::<Vec<_>>();
+            t!("jvm-unknown-gc", gc = value, known = known.join(", "),)
+        })
+    }
+
+    /// The option that selects this collector.
+    pub fn option(self) -> String {
+        let name = match self {
+            Self::G1 => "G1",
+            Self::Z => "Z",
+            Self::Shenandoah => "Shenandoah",
+            Self::Parallel => "Parallel",
+            Self::Serial => "Serial",
+        };
+
+        format!("-XX:+Use{name}GC")
+    }
+}
+
+/// The option for a heap size such as `4g`, after `prefix` (`-Xmx` or `-Xms`).
+pub fn heap_option(prefix: &str, size: &str) -> anyhow::Result<String> {
+    let digits = size.trim_end_matches(['k', 'K', 'm', 'M', 'g', 'G', 't', 'T']);
+
+    anyhow::ensure!(
+        !digits.is_empty()
+            && digits.len() + 1 >= size.len()
+            && digits.chars().all(|ch| ch.is_ascii_digit()),
+        t!("jvm-invalid-heap-size", size = size)
+    );
+
+    Ok(format!("{prefix}{size}"))
+}
+
+/// Check that `option` can be passed through `JAVA_TOOL_OPTIONS`, which splits options on whitespace.
+pub fn validate_option(option: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !option.is_empty() && !option.contains(char::is_whitespace),
+        t!("jvm-invalid-option", option = option)
+    );
+
+    Ok(())
+}
+
+/// Write the line that passes `options` to every JVM the executable starts.
+///
+/// The options go through `JAVA_TOOL_OPTIONS` rather than arguments, since many Java programs are started by a shell
+/// script instead of the `java` binary itself. Options the caller already set come after these, so they take
+/// precedence.
+pub fn write_options_line(options: &[String], mut writer: impl fmt::Write) -> fmt::Result {
+    writeln!(
+        writer,
+        r#"export {TOOL_OPTIONS_VAR}={}"${{{TOOL_OPTIONS_VAR}:+ ${TOOL_OPTIONS_VAR}}}""#,
+        shell::quote(&options.join(" "))
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+
+    #[test]
+    fn heap_sizes() {
+        assert_eq!(heap_option("-Xmx", "4g").unwrap(), "-Xmx4g");
+        assert_eq!(heap_option("-Xms", "1048576").unwrap(), "-Xms1048576");
+
+        for invalid in ["", "g", "4gb", "4 g", "-4g"] {
+            assert!(heap_option("-Xmx", invalid).is_err(), "{invalid}");
+        }
+    }
+
+    #[test]
+    fn options_line_keeps_caller_options_last() {
+        let options = vec!["-Xmx4g".into(), GarbageCollector::Z.option()];
+
+        let mut script = String::new();
+        write_options_line(&options, &mut script).unwrap();
+        script.push_str(r#"printf '%s' "$JAVA_TOOL_OPTIONS""#);
+
+        let run = |caller_options: Option<&str>| {
+            let mut command = Command::new("bash");
+            command.arg("-c").arg(&script);
+
+            match caller_options {
+                Some(value) => command.env(TOOL_OPTIONS_VAR, value),
+                None => command.env_remove(TOOL_OPTIONS_VAR),
+            };
+
+            String::from_utf8(command.output().unwrap().stdout).unwrap()
+        };
+
+        assert_eq!(run(None), "-Xmx4g -XX:+UseZGC");
+        assert_eq!(run(Some("-Xmx8g")), "-Xmx4g -XX:+UseZGC -Xmx8g");
+    }
+}
@@ -0,0 +1,212 @@
+mod access;
+mod affinity;
+mod apparmor;
+mod appimage;
+mod cmd;
+mod complete;
+mod container;
+mod env;
+mod error;
+mod escalate;
+mod exec_type;
+mod expiry;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+mod file;
+mod git;
+mod hash;
+mod header;
+mod hours;
+mod i18n;
+mod journal;
+mod jvm;
+mod limit;
+mod manifest;
+mod metadata;
+mod mount;
+mod pacman_files;
+mod pacman_hook;
+mod pacman_lock;
+mod path;
+mod plugin;
+mod preset;
+#[cfg(target_os = "linux")]
+mod preview;
+mod progress;
+mod prompt;
+mod registry;
+mod script;
+#[cfg(unix)]
+mod session;
+mod settings;
+mod shell;
+mod signal;
+mod snap;
+mod snapshot;
+mod status_file;
+mod symlink;
+mod table;
+#[cfg(test)]
+mod test_util;
+mod user_unit;
+mod via;
+mod wrapper;
+
+use std::{path::Path, process::ExitCode};
+
+use argh::FromArgs;
+
+#[derive(FromArgs)]
+/// Wrap executables to always execute with additional arguments and/or environment variables.
+struct Args {
+    /// program to run this one again as root with when it's denied access to a file, `sudo` or `doas` (default: the one that's installed, preferring `sudo`)
+    #[argh(option, long = "escalate")]
+    escalate: Option<escalate::Tool>,
+
+    #[argh(subcommand)]
+    command: cmd::Command,
+}
+
+/// Run the command line interface with the arguments of this process.
+pub fn main() -> ExitCode {
+    let raw_args = std::env::args().skip(1).collect::<Vec<_>>();
+
+    // completions are printed for a shell to read, so anything going wrong only means there's nothing to offer
+    if let Some((command, args)) = raw_args.split_first()
+        && command == complete::COMMAND
+    {
+        return match settings::init().and_then(|()| complete::run(args)) {
+            Ok(()) => ExitCode::SUCCESS,
+            Err(_) => ExitCode::FAILURE,
+        };
+    }
+
+    let raw_args = compat_args(raw_args);
+    let program = std::env::args().next().unwrap_or_default();
+    let program = Path::new(&program)
+        .file_name()
+        .map_or(program.clone(), |name| name.to_string_lossy().into_owned());
+
+    let args = match Args::from_args(
+        &[&program],
+        &raw_args.iter().map(String::as_str).collect::<Vec<_>>(),
+    ) {
+        Ok(args) => args,
+        // the same output argh gives when it reads the arguments itself
+        Err(early_exit) => {
+            return match early_exit.status {
+                Ok(()) => {
+                    println!("{}", early_exit.output);
+                    ExitCode::SUCCESS
+                }
+                Err(()) => {
+                    eprintln!(
+                        "{}\nRun {program} --help for more information.",
+                        early_exit.output
+                    );
+                    ExitCode::FAILURE
+                }
+            };
+        }
+    };
+
+    let escalate = args.escalate;
+
+    match settings::init().and_then(|()| args.command.run()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) if err.is::<error::ChangesNeeded>() => {
+            ExitCode::from(error::ChangesNeeded::EXIT_CODE)
+        }
+        Err(err) => {
+            eprintln!("{}", i18n::t!("error-exit", error = format!("{err:?}")));
+
+            if escalate::is_permission_denied(&err)
+                && let Err(err) = escalate::offer_rerun(escalate)
+            {
+                eprintln!("{}", i18n::t!("error-exit", error = format!("{err:?}")));
+            }
+
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Adjust `args` for what argh can't parse on its own.
+///
+/// The original interface, `wrapperize <executable_path> [options]`, is run as `wrap`, so scripts written for it keep
+/// working. A `-` naming stdin as the manifest of `apply` is moved behind `--`, since argh takes anything starting with
+/// `-` for an option.
+fn compat_args(mut args: Vec<String>) -> Vec<String> {
+    // the only option that comes before the command takes a value
+    let mut command = 0;
+
+    while args.get(command).is_some_and(|arg| arg == "--escalate") {
+        command += 2;
+    }
+
+    let Some(name) = args.get(command) else {
+        return args;
+    };
+
+    if Path::new(name).is_absolute() {
+        args.insert(command, "wrap".to_owned());
+    } else if name == "apply" && !args.iter().any(|arg| arg == "--") {
+        let stdin = args
+            .iter()
+            .enumerate()
+            .skip(command + 1)
+            .position(|(index, arg)| arg == "-" && args[index - 1] != "--wait-for-pacman");
+
+        if let Some(stdin) = stdin {
+            args.remove(command + 1 + stdin);
+            args.extend(["--".to_owned(), "-".to_owned()]);
+        }
+    }
+
+    args
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn original_interface_runs_wrap() {
+        assert_eq!(
+            compat_args(args(&["/usr/bin/vim", "-a", "-n"])),
+            args(&["wrap", "/usr/bin/vim", "-a", "-n"])
+        );
+        assert_eq!(
+            compat_args(args(&["--escalate", "doas", "/usr/bin/vim"])),
+            args(&["--escalate", "doas", "wrap", "/usr/bin/vim"])
+        );
+        assert_eq!(
+            compat_args(args(&["wrap", "/usr/bin/vim"])),
+            args(&["wrap", "/usr/bin/vim"])
+        );
+    }
+
+    #[test]
+    fn apply_reads_stdin_from_dash() {
+        let parse = |raw: &[&str]| {
+            let raw = compat_args(args(raw));
+            Args::from_args(
+                &["wrapperize"],
+                &raw.iter().map(String::as_str).collect::<Vec<_>>(),
+            )
+        };
+
+        assert_eq!(
+            compat_args(args(&["apply", "-", "--check"])),
+            args(&["apply", "--check", "--", "-"])
+        );
+        assert!(parse(&["apply", "-", "--check"]).is_ok());
+        assert!(parse(&["apply", "--wait-for-pacman", "5", "-"]).is_ok());
+        assert!(parse(&["apply", "--", "-"]).is_ok());
+        assert!(parse(&["/usr/bin/vim", "-a", "-n", "--nohooks"]).is_ok());
+    }
+}
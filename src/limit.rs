@@ -0,0 +1,164 @@
+use std::{borrow::Cow, fmt, str::FromStr};
+
+use anyhow::Context;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use strum::IntoEnumIterator;
+
+use crate::i18n::t;
+
+/// A resource that can be limited with bash's `ulimit`, named as in `limits.conf`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumIter, strum::EnumString, strum::Display)]
+#[strum(serialize_all = "lowercase")]
+pub enum Resource {
+    Core,
+    Data,
+    Fsize,
+    Memlock,
+    Nofile,
+    Rss,
+    Stack,
+    Cpu,
+    Nproc,
+    As,
+    Locks,
+    Sigpending,
+    Msgqueue,
+    Nice,
+    Rtprio,
+}
+
+impl Resource {
+    /// Option that selects this resource in `ulimit`.
+    fn ulimit_flag(self) -> char {
+        match self {
+            Self::Core => 'c',
+            Self::Data => 'd',
+            Self::Fsize => 'f',
+            Self::Memlock => 'l',
+            Self::Nofile => 'n',
+            Self::Rss => 'm',
+            Self::Stack => 's',
+            Self::Cpu => 't',
+            Self::Nproc => 'u',
+            Self::As => 'v',
+            Self::Locks => 'x',
+            Self::Sigpending => 'i',
+            Self::Msgqueue => 'q',
+            Self::Nice => 'e',
+            Self::Rtprio => 'r',
+        }
+    }
+}
+
+/// A resource limit in the format of `nofile=65536`, where the value is a number in the unit `ulimit` uses for the
+/// resource, or `unlimited`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Limit {
+    pub resource: Resource,
+    value: String,
+}
+
+impl Limit {
+    /// Write the command that sets the soft limit, which is the one that's enforced.
+    ///
+    /// The hard limit is left alone, so lowering a limit can't prevent the executable from raising it again if it
+    /// needs to, and a limit can only be raised as far as the hard limit allows.
+    pub fn write_bash_line(&self, mut writer: impl fmt::Write) -> fmt::Result {
+        writeln!(
+            writer,
+            "ulimit -S -{} {}",
+            self.resource.ulimit_flag(),
+            self.value
+        )
+    }
+}
+
+impl FromStr for Limit {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (resource, value) = s.split_once('=').with_context(|| t!("missing-separator"))?;
+
+        let resource = resource.parse().map_err(|_| {
+            let known = Resource::iter()
+                .map(|resource| resource.to_string())
+                .collect::<Vec<_>>();
+
+            anyhow::anyhow!(t!(
+                "limit-unknown-resource",
+                resource = resource,
+                known = known.join(", "),
+            ))
+        })?;
+
+        anyhow::ensure!(
+            value == "unlimited" || value.parse::<u64>().is_ok(),
+            t!("limit-invalid-value", value = value)
+        );
+
+        Ok(Self {
+            resource,
+            value: value.to_owned(),
+        })
+    }
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}={}", self.resource, self.value)
+    }
+}
+
+impl schemars::JsonSchema for Limit {
+    fn schema_name() -> Cow<'static, str> {
+        "ResourceLimit".into()
+    }
+
+    fn json_schema(_generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        let resources = Resource::iter()
+            .map(|resource| resource.to_string())
+            .collect::<Vec<_>>();
+
+        schemars::json_schema!({
+            "description": "A resource limit in the format of `nofile=65536`, or `core=unlimited`.",
+            "type": "string",
+            "pattern": format!("^({})=([0-9]+|unlimited)$", resources.join("|")),
+        })
+    }
+}
+
+/// Serialized in the same `resource=value` format accepted on the command line.
+impl Serialize for Limit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Limit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = String::deserialize(deserializer)?;
+        value.parse().map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_limit() {
+        let limit = "nofile=65536".parse::<Limit>().unwrap();
+        assert_eq!(limit.resource, Resource::Nofile);
+        assert_eq!(limit.to_string(), "nofile=65536");
+
+        let mut line = String::new();
+        limit.write_bash_line(&mut line).unwrap();
+        assert_eq!(line, "ulimit -S -n 65536\n");
+
+        assert!("core=unlimited".parse::<Limit>().is_ok());
+
+        for invalid in ["nofile", "files=10", "nofile=-1", "nofile=many", "nofile="] {
+            assert!(invalid.parse::<Limit>().is_err(), "{invalid}");
+        }
+    }
+}
@@ -0,0 +1,443 @@
+use std::{
+    fs,
+    io::{self, Read},
+    path::{Component, Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::IoError, i18n::t, wrapper};
+
+/// Path that refers to stdin instead of a file.
+const STDIN_PATH: &str = "-";
+
+/// A declarative list of wrappers to create.
+#[derive(Debug, Default, PartialEq, Deserialize, schemars::JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct Manifest {
+    /// Parameters every wrapper is created with, beneath the wrapper's own.
+    #[serde(default)]
+    pub defaults: wrapper::Params,
+    #[serde(default, rename = "wrapper")]
+    pub wrappers: Vec<Entry>,
+}
+
+impl Manifest {
+    pub fn parse(content: &str, format: Format) -> anyhow::Result<Self> {
+        match format {
+            Format::Toml => toml::from_str(content).map_err(Into::into),
+            Format::Json => serde_json::from_str(content).map_err(Into::into),
+        }
+    }
+
+    /// Merge a manifest that comes after this one into it.
+    ///
+    /// The defaults of `other` are merged into these defaults, and a wrapper for an executable that's already listed
+    /// has its parameters merged into the existing entry, so later manifests can extend earlier ones.
+    pub fn extend(&mut self, other: Manifest) {
+        self.defaults.merge(other.defaults);
+
+        for entry in other.wrappers {
+            match self
+                .wrappers
+                .iter_mut()
+                .find(|existing| existing.path == entry.path)
+            {
+                Some(existing) => {
+                    existing.hooks = entry.hooks.or(existing.hooks);
+                    existing.params.merge(entry.params);
+                }
+                None => self.wrappers.push(entry),
+            }
+        }
+    }
+
+    /// Merge the parameters of every wrapper on top of the defaults, leaving the defaults empty.
+    pub fn resolve_defaults(&mut self) {
+        let defaults = std::mem::take(&mut self.defaults);
+
+        for entry in &mut self.wrappers {
+            let params = std::mem::replace(&mut entry.params, defaults.clone());
+            entry.params.merge(params);
+        }
+    }
+
+    /// JSON Schema describing the manifest format, generated from the types it's parsed into.
+    pub fn schema() -> schemars::Schema {
+        schemars::schema_for!(Manifest)
+    }
+
+    /// Check `content` against the manifest schema and parse it, returning a description of every problem found.
+    ///
+    /// Syntax errors are returned as an error instead, since the rest of the document can't be checked.
+    pub fn validate(content: &str, format: Format) -> anyhow::Result<Vec<String>> {
+        let document: serde_json::Value = match format {
+            Format::Toml => toml::from_str(content)?,
+            Format::Json => serde_json::from_str(content)?,
+        };
+
+        let validator = jsonschema::validator_for(Self::schema().as_value())
+            .map_err(|err| anyhow::anyhow!("{err}"))
+            .with_context(|| t!("manifest-schema-invalid"))?;
+
+        let problems = validator
+            .iter_errors(&document)
+            .map(|err| match err.instance_path().as_str() {
+                "" => err.to_string(),
+                location => format!("`{location}`: {err}"),
+            })
+            .collect::<Vec<_>>();
+
+        if !problems.is_empty() {
+            return Ok(problems);
+        }
+
+        // the schema can't express everything, such as which names are valid for environment variables
+        let manifest = match Self::parse(content, format) {
+            Ok(manifest) => manifest,
+            Err(err) => return Ok(vec![format!("{err:#}")]),
+        };
+
+        let problems = manifest
+            .wrappers
+            .iter()
+            .enumerate()
+            .flat_map(|(i, entry)| {
+                let invalid_path = path_problem(&entry.path)
+                    .map(|problem| format!("`/wrapper/{i}/path`: {problem}"));
+
+                // within a single manifest, a repeated path is more likely a mistake than an intended merge
+                let duplicate = manifest.wrappers[..i]
+                    .iter()
+                    .position(|other| other.path == entry.path)
+                    .map(|j| {
+                        t!(
+                            "manifest-duplicate-path",
+                            index = i,
+                            path = entry.path.display().to_string(),
+                            other = j,
+                        )
+                    });
+
+                let relative_store_dir = entry
+                    .params
+                    .store_dir
+                    .as_ref()
+                    .filter(|dir| !dir.is_absolute())
+                    .map(|_| format!("`/wrapper/{i}/store_dir`: {}", t!("manifest-path-relative")));
+
+                invalid_path
+                    .into_iter()
+                    .chain(duplicate)
+                    .chain(relative_store_dir)
+            })
+            .collect();
+
+        Ok(problems)
+    }
+}
+
+/// Describe what's wrong with `path` as the path of an executable to wrap, if anything.
+fn path_problem(path: &Path) -> Option<String> {
+    if !path.is_absolute() {
+        return Some(t!("manifest-path-relative"));
+    }
+
+    // the same executable would otherwise be listed under several paths, and `..` could point anywhere
+    let is_normal = path
+        .components()
+        .skip(1)
+        .all(|component| matches!(component, Component::Normal(_)));
+
+    if !is_normal
+        || path.file_name().is_none()
+        || path.as_os_str().as_encoded_bytes().ends_with(b"/")
+    {
+        return Some(t!("manifest-path-not-normal"));
+    }
+
+    None
+}
+
+/// Read and parse a manifest from `path`, or from stdin if it's unset or `-`.
+///
+/// If `path` is a directory, every manifest in it is loaded as a drop-in instead.
+pub fn load(path: Option<&Path>) -> anyhow::Result<Manifest> {
+    if let Some(dir) = path.filter(|path| path.is_dir()) {
+        return load_dir(dir);
+    }
+
+    let (content, format) = read(path)?;
+
+    let parse = || {
+        let problems = Manifest::validate(&content, format)?;
+        anyhow::ensure!(problems.is_empty(), "{}", problems.join("; "));
+
+        Manifest::parse(&content, format)
+    };
+
+    match path.filter(|path| path.as_os_str() != STDIN_PATH) {
+        Some(path) => parse().with_context(|| IoError::new(path, t!("manifest-parse-failed"))),
+        None => parse().with_context(|| t!("manifest-parse-stdin-failed")),
+    }
+}
+
+/// Load every TOML or JSON manifest directly inside `dir` in lexical order of their filenames, extending the first
+/// with each that follows, so packages and admins can each contribute a file instead of editing a shared one.
+fn load_dir(dir: &Path) -> anyhow::Result<Manifest> {
+    let mut manifest = Manifest::default();
+
+    for path in drop_in_paths(dir)? {
+        manifest.extend(load(Some(&path))?);
+    }
+
+    Ok(manifest)
+}
+
+/// Paths of every TOML or JSON manifest directly inside `dir`, in the lexical order they're loaded in.
+pub fn drop_in_paths(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let mut paths = fs::read_dir(dir)
+        .with_context(|| IoError::new(dir, t!("manifest-read-dir-failed")))?
+        .map(|entry| entry.map(|entry| entry.path()))
+        .collect::<io::Result<Vec<_>>>()
+        .with_context(|| IoError::new(dir, t!("manifest-read-dir-failed")))?;
+
+    // files such as editor backups are skipped, since they'd otherwise apply outdated wrappers
+    paths.retain(|path| path.is_file() && Format::from_path(path).is_some());
+    paths.sort();
+
+    Ok(paths)
+}
+
+/// Read a manifest from `path`, or from stdin if it's unset or `-`, along with the format it's in.
+pub fn read(path: Option<&Path>) -> anyhow::Result<(String, Format)> {
+    // argh treats a bare `-` as an option, so it can only be passed after `--`
+    let path = path.filter(|path| path.as_os_str() != STDIN_PATH);
+
+    let Some(path) = path else {
+        let mut content = String::new();
+
+        io::stdin()
+            .read_to_string(&mut content)
+            .with_context(|| t!("manifest-read-stdin-failed"))?;
+
+        let format = Format::detect(&content);
+        return Ok((content, format));
+    };
+
+    let content =
+        fs::read_to_string(path).with_context(|| IoError::new(path, t!("manifest-read-failed")))?;
+
+    let format = Format::from_path(path).unwrap_or_else(|| Format::detect(&content));
+    Ok((content, format))
+}
+
+/// A single wrapper definition in a manifest.
+#[derive(Debug, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[schemars(deny_unknown_fields)]
+pub struct Entry {
+    /// Absolute path to the executable to wrap.
+    pub path: PathBuf,
+    /// Whether `pacman` hooks should be generated for the wrapper.
+    /// If unset, this is based on whether the executable could be managed by `pacman`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<bool>,
+    #[serde(flatten)]
+    pub params: wrapper::Params,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Format {
+    Toml,
+    Json,
+}
+
+impl Format {
+    /// Determine the format from a file extension, if it has a recognized one.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(Self::Toml),
+            "json" => Some(Self::Json),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the content itself, for when there's no file extension to go off of (such as stdin).
+    pub fn detect(content: &str) -> Self {
+        if content.trim_start().starts_with('{') {
+            Self::Json
+        } else {
+            Self::Toml
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{env, test_util};
+    use indoc::indoc;
+
+    fn expected_manifest() -> Manifest {
+        Manifest {
+            defaults: wrapper::Params::default(),
+            wrappers: vec![
+                Entry {
+                    path: "/usr/bin/foo".into(),
+                    hooks: None,
+                    params: wrapper::Params {
+                        args: vec!["--flag".into()],
+                        env_vars: vec![env::Variable::new("ENV", "value")],
+                        ..Default::default()
+                    },
+                },
+                Entry {
+                    path: "/home/user/bar".into(),
+                    hooks: Some(false),
+                    params: wrapper::Params {
+                        add_passthrough_args_first: true,
+                        ..Default::default()
+                    },
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn parse_toml() {
+        let content = indoc! { r#"
+            [[wrapper]]
+            path = "/usr/bin/foo"
+            args = ["--flag"]
+            envs = ["ENV=value"]
+
+            [[wrapper]]
+            path = "/home/user/bar"
+            hooks = false
+            add_passthrough_args_first = true
+        "# };
+
+        let manifest = Manifest::parse(content, Format::detect(content))
+            .expect("manifest parsing should succeed");
+
+        assert_eq!(manifest, expected_manifest());
+    }
+
+    #[test]
+    fn parse_json() {
+        let content = indoc! { r#"
+            {
+                "wrapper": [
+                    { "path": "/usr/bin/foo", "args": ["--flag"], "envs": ["ENV=value"] },
+                    { "path": "/home/user/bar", "hooks": false, "add_passthrough_args_first": true }
+                ]
+            }
+        "# };
+
+        let manifest = Manifest::parse(content, Format::detect(content))
+            .expect("manifest parsing should succeed");
+
+        assert_eq!(manifest, expected_manifest());
+    }
+
+    #[test]
+    fn drop_ins_are_merged_in_order() {
+        let temp_dir = test_util::temp_dir("drop-ins");
+        let dir = temp_dir.path();
+
+        let write = |name: &str, content: &str| fs::write(dir.join(name), content).unwrap();
+
+        write(
+            "10-base.toml",
+            indoc! { r#"
+                [defaults]
+                envs = ["LANG=C"]
+
+                [[wrapper]]
+                path = "/usr/bin/foo"
+                args = ["--flag"]
+            "# },
+        );
+        write(
+            "20-local.json",
+            r#"{ "wrapper": [{ "path": "/usr/bin/foo", "args": ["--other"], "envs": ["LANG=en_US.UTF-8"] }] }"#,
+        );
+        write("30-ignored.toml~", "not a manifest");
+
+        let mut manifest = load(Some(dir)).unwrap();
+        manifest.resolve_defaults();
+
+        assert_eq!(
+            manifest,
+            Manifest {
+                defaults: wrapper::Params::default(),
+                wrappers: vec![Entry {
+                    path: "/usr/bin/foo".into(),
+                    hooks: None,
+                    params: wrapper::Params {
+                        args: vec!["--flag".into(), "--other".into()],
+                        env_vars: vec![env::Variable::new("LANG", "en_US.UTF-8")],
+                        ..Default::default()
+                    },
+                }],
+            }
+        );
+    }
+
+    #[test]
+    fn format_from_path() {
+        assert_eq!(Format::from_path(Path::new("a.toml")), Some(Format::Toml));
+        assert_eq!(Format::from_path(Path::new("a.json")), Some(Format::Json));
+        assert_eq!(Format::from_path(Path::new("a")), None);
+    }
+
+    #[test]
+    fn schema_file_is_up_to_date() {
+        let schema = serde_json::to_string_pretty(&Manifest::schema()).unwrap();
+
+        assert_eq!(
+            include_str!("../schema/manifest.schema.json"),
+            format!("{schema}\n"),
+            "regenerate the schema file with `wrapperize schema`"
+        );
+    }
+
+    #[test]
+    fn validate_reports_unknown_fields() {
+        let content = indoc! {r#"
+            [[wrapper]]
+            path = "/usr/bin/foo"
+            argz = ["--flag"]
+        "#};
+
+        let problems = Manifest::validate(content, Format::Toml).unwrap();
+        assert_eq!(problems.len(), 1);
+        assert!(problems[0].starts_with("`/wrapper/0`"));
+    }
+
+    #[test]
+    fn validate_reports_invalid_and_duplicate_paths() {
+        let content = indoc! {r#"
+            [[wrapper]]
+            path = "/usr/bin/foo"
+            args = ["--flag"]
+
+            [[wrapper]]
+            path = "/usr/bin/../bin/bar"
+            args = ["--flag"]
+
+            [[wrapper]]
+            path = "/usr/bin/foo"
+            args = ["--other"]
+        "#};
+
+        let problems = Manifest::validate(content, Format::Toml).unwrap();
+        assert_eq!(problems.len(), 2);
+        assert!(problems[0].starts_with("`/wrapper/1/path`: path must lead to a file"));
+        assert_eq!(
+            problems[1],
+            "`/wrapper/2/path`: `/usr/bin/foo` is already listed at `/wrapper/0`"
+        );
+    }
+}
@@ -0,0 +1,130 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize};
+
+use crate::{error::IoError, file, i18n::t, wrapper};
+
+/// Everything needed to rebuild a wrapper without parsing the generated script.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Metadata {
+    /// Version of the program that created the wrapper.
+    pub version: String,
+    /// Path to the wrapped executable.
+    pub executable: PathBuf,
+    pub use_pacman_hooks: bool,
+    /// Set while the original executable is restored but the hooks are kept, so the wrapper comes back on the
+    /// next upgrade. Cleared by the install script once the wrapper is recreated.
+    #[serde(default, skip_serializing_if = "is_false")]
+    pub dormant: bool,
+    pub params: wrapper::Params,
+}
+
+/// Line the `dormant` flag is serialized as, so shell scripts can clear it without parsing TOML.
+pub const DORMANT_LINE: &str = "dormant = true";
+
+impl Metadata {
+    pub fn new(
+        executable: impl Into<PathBuf>,
+        params: wrapper::Params,
+        use_pacman_hooks: bool,
+    ) -> Self {
+        Self {
+            version: env!("CARGO_PKG_VERSION").into(),
+            executable: executable.into(),
+            use_pacman_hooks,
+            dormant: false,
+            params,
+        }
+    }
+
+    pub fn read(path: &Path) -> anyhow::Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| IoError::new(path, t!("metadata-read-failed")))?;
+
+        Self::parse(&content).with_context(|| IoError::new(path, t!("metadata-parse-failed")))
+    }
+
+    pub fn write(&self, path: &Path) -> anyhow::Result<()> {
+        let content = self.serialize()?;
+
+        file::write_atomic(path, content.as_bytes(), file::REGULAR_MODE.0)
+            .with_context(|| IoError::new(path, t!("metadata-write-failed")))
+    }
+
+    fn parse(content: &str) -> anyhow::Result<Self> {
+        toml::from_str(content).map_err(Into::into)
+    }
+
+    fn serialize(&self) -> anyhow::Result<String> {
+        toml::to_string(self).with_context(|| t!("metadata-serialize-failed"))
+    }
+}
+
+pub fn is_false(value: &bool) -> bool {
+    !value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        container::{Container, Engine},
+        env,
+    };
+
+    #[test]
+    fn round_trip() {
+        let metadata = Metadata::new(
+            "/usr/bin/test_bin",
+            wrapper::Params {
+                args: vec!["--arg1".into(), "--arg \"2\"".into()],
+                add_passthrough_args_first: true,
+                env_vars: vec![env::Variable::new("ENV1", "val=1")],
+                container: Some(Container {
+                    image: "docker.io/library/alpine".into(),
+                    engine: Engine::Podman,
+                    command: None,
+                    volumes: vec!["/srv:/srv".into()],
+                    envs: Vec::new(),
+                    mount_cwd: true,
+                }),
+                ..Default::default()
+            },
+            true,
+        );
+
+        let serialized = metadata.serialize().expect("serialization should succeed");
+        let parsed = Metadata::parse(&serialized).expect("parsing should succeed");
+
+        assert_eq!(parsed, metadata);
+    }
+
+    #[test]
+    fn parse_rejects_invalid_env() {
+        let content = indoc::indoc! { r#"
+            version = "0.1.0"
+            executable = "/usr/bin/test_bin"
+            use_pacman_hooks = false
+
+            [params]
+            envs = ["1INVALID=value"]
+        "# };
+
+        assert!(Metadata::parse(content).is_err());
+    }
+
+    #[test]
+    fn dormant_line() {
+        let mut metadata = Metadata::new("/usr/bin/test_bin", wrapper::Params::default(), true);
+        let serialized = metadata.serialize().unwrap();
+        assert!(!serialized.lines().any(|line| line == DORMANT_LINE));
+
+        metadata.dormant = true;
+        let serialized = metadata.serialize().unwrap();
+        assert!(serialized.lines().any(|line| line == DORMANT_LINE));
+    }
+}
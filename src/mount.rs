@@ -0,0 +1,114 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use crate::i18n::t;
+
+/// Mount table of the current process, listing the options of every mount point.
+const MOUNTINFO_PATH: &str = "/proc/self/mountinfo";
+
+/// Return an error explaining the limitation if `path` is on a read-only mount, as is the case for `/usr`
+/// on image-based systems such as Fedora Silverblue or other rpm-ostree based distributions.
+///
+/// Without this check, the first failing rename or write would surface as a generic I/O error
+/// partway through wrapping.
+pub fn ensure_writable(path: &Path) -> anyhow::Result<()> {
+    // the mount table is only used for a more helpful error, so failing to read it isn't fatal
+    let Ok(mountinfo) = fs::read_to_string(MOUNTINFO_PATH) else {
+        return Ok(());
+    };
+
+    let dir = path.parent().unwrap_or(path);
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+
+    if let Some(mount_point) = read_only_mount(&mountinfo, &dir) {
+        anyhow::bail!(t!(
+            "mount-read-only",
+            path = path.display().to_string(),
+            mount = mount_point.display().to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Find the mount point `dir` belongs to in `mountinfo` and return it if it's mounted read-only.
+fn read_only_mount(mountinfo: &str, dir: &Path) -> Option<PathBuf> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            // fields: ID, parent ID, major:minor, root, mount point, mount options, ...
+            let mut fields = line.split(' ').skip(4);
+            let mount_point = PathBuf::from(unescape(fields.next()?));
+            let read_only = fields.next()?.split(',').any(|opt| opt == "ro");
+            Some((mount_point, read_only))
+        })
+        .filter(|(mount_point, _)| dir.starts_with(mount_point))
+        // later entries are mounted on top of earlier ones with the same mount point
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+        .filter(|&(_, read_only)| read_only)
+        .map(|(mount_point, _)| mount_point)
+}
+
+/// Decode the octal escapes (such as `\040` for a space) used for special characters in mount points.
+fn unescape(field: &str) -> String {
+    let mut unescaped = String::with_capacity(field.len());
+    let mut rest = field;
+
+    while let Some(pos) = rest.find('\\') {
+        unescaped.push_str(&rest[..pos]);
+
+        let code = rest
+            .get(pos + 1..pos + 4)
+            .and_then(|digits| u8::from_str_radix(digits, 8).ok());
+
+        match code {
+            Some(code) => {
+                unescaped.push(char::from(code));
+                rest = &rest[pos + 4..];
+            }
+            None => {
+                unescaped.push('\\');
+                rest = &rest[pos + 1..];
+            }
+        }
+    }
+
+    unescaped.push_str(rest);
+    unescaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTINFO: &str = "\
+        22 1 0:21 / / rw,relatime shared:1 - ext4 /dev/sda1 rw\n\
+        23 22 0:22 / /usr ro,relatime shared:2 - ext4 /dev/sda2 ro\n\
+        24 23 0:23 / /usr/local rw,relatime shared:3 - ext4 /dev/sda3 rw\n\
+        25 22 0:24 / /opt/My\\040App rw,relatime shared:4 - ext4 /dev/sda4 rw\n\
+        26 22 0:25 / /opt/My\\040App ro,relatime shared:5 - ext4 /dev/sda5 ro";
+
+    #[test]
+    fn read_only_mounts() {
+        assert_eq!(
+            read_only_mount(MOUNTINFO, Path::new("/usr/bin")),
+            Some(PathBuf::from("/usr"))
+        );
+        assert_eq!(
+            read_only_mount(MOUNTINFO, Path::new("/usr/local/bin")),
+            None
+        );
+        assert_eq!(read_only_mount(MOUNTINFO, Path::new("/etc")), None);
+        assert_eq!(read_only_mount(MOUNTINFO, Path::new("/usrx")), None);
+    }
+
+    #[test]
+    fn overmounted_and_escaped_mount_points() {
+        assert_eq!(
+            read_only_mount(MOUNTINFO, Path::new("/opt/My App/bin")),
+            Some(PathBuf::from("/opt/My App"))
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use std::{
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::Context;
+
+use crate::i18n::t;
+
+/// A file that `pacman -Qkk` reports as differing from how its package installed it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub package: String,
+    pub path: PathBuf,
+    /// What differs, such as `SHA256 checksum mismatch`.
+    pub reason: String,
+}
+
+/// Returns the name of the installed package that owns the file at `path`, if any.
+pub fn owner(path: &Path) -> Option<String> {
+    let output = Command::new("pacman")
+        .arg("-Qqo")
+        .arg(path)
+        .stderr(Stdio::null())
+        .output()
+        .ok()
+        .filter(|output| output.status.success())?;
+
+    let owner = String::from_utf8_lossy(&output.stdout).trim().to_owned();
+    (!owner.is_empty()).then_some(owner)
+}
+
+/// Returns why `pacman` protects the file at `path` as configuration, leaving the new version from an upgrade in a
+/// `.pacnew` file next to it instead of replacing it, or `None` if it doesn't.
+pub fn config_protection(path: &Path) -> Option<String> {
+    let package = owner(path)?;
+
+    if backup_files(&package).iter().any(|file| file == path) {
+        return Some(t!("pacman-backup-array", package = package));
+    }
+
+    // the patterns are relative to the root, just like the paths in packages
+    let relative = path.strip_prefix("/").ok()?.to_string_lossy();
+
+    let patterns = no_upgrade_patterns();
+    let pattern = matching_pattern(&patterns, &relative)?;
+    Some(t!("pacman-no-upgrade", pattern = pattern))
+}
+
+/// Returns the files in the `backup` array of the installed package `package`.
+fn backup_files(package: &str) -> Vec<PathBuf> {
+    // the field names are translated otherwise
+    let output = Command::new("pacman")
+        .arg("-Qii")
+        .arg(package)
+        .env("LC_ALL", "C")
+        .stderr(Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => {
+            parse_backup_files(&String::from_utf8_lossy(&output.stdout))
+        }
+        _ => Vec::new(),
+    }
+}
+
+/// Returns the `NoUpgrade` patterns of the pacman configuration.
+fn no_upgrade_patterns() -> Vec<String> {
+    let output = Command::new("pacman-conf")
+        .arg("NoUpgrade")
+        .stderr(Stdio::null())
+        .output();
+
+    match output {
+        Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .map(str::to_owned)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Parse the `Backup Files` field in the output of `pacman -Qii`, which lists a file and its state on each line, such
+/// as `/etc/pacman.conf\t[modified]`.
+fn parse_backup_files(output: &str) -> Vec<PathBuf> {
+    let mut lines = output.lines();
+
+    let Some(first) = lines.find_map(|line| {
+        let (name, value) = line.split_once(':')?;
+        (name.trim_end() == "Backup Files").then_some(value)
+    }) else {
+        return Vec::new();
+    };
+
+    // the remaining files are indented to line up with the first one
+    let rest = lines.take_while(|line| line.starts_with(char::is_whitespace));
+
+    [first]
+        .into_iter()
+        .chain(rest)
+        .filter_map(|line| {
+            let file = line.split('\t').next()?.trim();
+            file.starts_with('/').then(|| PathBuf::from(file))
+        })
+        .collect()
+}
+
+/// Returns the pattern out of `patterns` that decides whether `path` matches, the way `pacman` does, where the last
+/// pattern matching wins, and one starting with `!` stops `path` from matching.
+fn matching_pattern<'a>(patterns: &'a [String], path: &str) -> Option<&'a str> {
+    patterns
+        .iter()
+        .rev()
+        .find_map(|pattern| match pattern.strip_prefix('!') {
+            Some(negated) if glob_matches(negated, path) => Some(None),
+            Some(_) => None,
+            None if glob_matches(pattern, path) => Some(Some(pattern.as_str())),
+            None => None,
+        })?
+}
+
+/// Whether `path` matches the shell glob `pattern`, where `*` also matches `/` like it does for `pacman`. Character
+/// classes are rare in these patterns and are matched literally.
+pub fn glob_matches(pattern: &str, path: &str) -> bool {
+    fn matches(pattern: &[char], path: &[char]) -> bool {
+        match pattern.split_first() {
+            None => path.is_empty(),
+            Some(('*', rest)) => (0..=path.len()).any(|skip| matches(rest, &path[skip..])),
+            Some(('?', rest)) => !path.is_empty() && matches(rest, &path[1..]),
+            Some((ch, rest)) => path.first() == Some(ch) && matches(rest, &path[1..]),
+        }
+    }
+
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let path = path.chars().collect::<Vec<_>>();
+    matches(&pattern, &path)
+}
+
+/// Check every file of `packages` with `pacman -Qkk`, returning the ones that differ from how they were installed.
+pub fn mismatches(packages: &[String]) -> anyhow::Result<Vec<Mismatch>> {
+    if packages.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // the exit status only tells whether anything differs, which the warnings already do
+    let output = Command::new("pacman")
+        .arg("-Qkk")
+        .args(packages)
+        .output()
+        .with_context(|| t!("pacman-files-failed"))?;
+
+    // the warnings are printed to stderr and the totals to stdout, depending on the version
+    let mut mismatches = parse(&String::from_utf8_lossy(&output.stderr));
+    mismatches.extend(parse(&String::from_utf8_lossy(&output.stdout)));
+
+    Ok(mismatches)
+}
+
+/// Parse the warnings in the output of `pacman -Qkk`, such as `warning: vim: /usr/bin/vim (Size mismatch)`.
+fn parse(output: &str) -> Vec<Mismatch> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let (package, rest) = line.strip_prefix("warning: ")?.split_once(": ")?;
+            let (path, reason) = rest.strip_suffix(')')?.rsplit_once(" (")?;
+
+            Some(Mismatch {
+                package: package.to_owned(),
+                path: path.into(),
+                reason: reason.to_owned(),
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_warnings() {
+        let output = "\
+            warning: vim: /usr/bin/vim (Size mismatch)\n\
+            warning: vim: /usr/bin/vim (SHA256 checksum mismatch)\n\
+            warning: my-pkg: /usr/share/my pkg/data (file) (No such file or directory)\n\
+            vim: 1917 total files, 1 altered file\n";
+
+        let mismatches = parse(output);
+
+        assert_eq!(mismatches.len(), 3);
+        assert_eq!(mismatches[1].package, "vim");
+        assert_eq!(mismatches[1].path, Path::new("/usr/bin/vim"));
+        assert_eq!(mismatches[1].reason, "SHA256 checksum mismatch");
+        assert_eq!(
+            mismatches[2].path,
+            Path::new("/usr/share/my pkg/data (file)")
+        );
+        assert_eq!(mismatches[2].reason, "No such file or directory");
+    }
+
+    #[test]
+    fn parses_backup_files() {
+        let output = "\
+            Name            : pacman\n\
+            Backup Files    : /etc/makepkg.conf\t[unmodified]\n                  \
+            /etc/pacman.conf\t[modified]\n\
+            Validated By    : Signature\n";
+
+        assert_eq!(
+            parse_backup_files(output),
+            [
+                Path::new("/etc/makepkg.conf"),
+                Path::new("/etc/pacman.conf")
+            ]
+        );
+
+        let output = "Name            : vim\nBackup Files    : None\n";
+        assert_eq!(parse_backup_files(output), Vec::<PathBuf>::new());
+    }
+
+    #[test]
+    fn matches_no_upgrade_patterns() {
+        let patterns = ["usr/bin/*".to_owned(), "!usr/bin/vi?".to_owned()];
+
+        assert_eq!(matching_pattern(&patterns, "usr/bin/vim"), None);
+        assert_eq!(
+            matching_pattern(&patterns, "usr/bin/vimdiff"),
+            Some("usr/bin/*")
+        );
+        assert_eq!(
+            matching_pattern(&patterns, "usr/bin/sub/dir"),
+            Some("usr/bin/*")
+        );
+        assert_eq!(matching_pattern(&patterns, "usr/lib/vim"), None);
+    }
+}
@@ -1,34 +1,309 @@
 use std::{
+    env,
     fmt::Write,
-    fs,
+    fs, io,
     path::{Path, PathBuf},
 };
 
 use anyhow::Context;
-use indoc::{concatdoc, formatdoc};
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
 use strum::IntoEnumIterator;
 use tap::Tap;
 
-use crate::{error::IoError, path, wrapper};
+use crate::{
+    error::IoError, file, hash, header::Header, i18n::t, pacman_files, path, settings, wrapper,
+};
+
+/// Points to the user `pacman` hook directory, unless the settings choose another one.
+pub const DEFAULT_HOOK_DIR: &str = "/etc/pacman.d/hooks";
+
+/// Prefixes that `pacman` never installs files into.
+const UNMANAGED_PREFIXES: &[&str] = &["/usr/local", "/home"];
+
+/// Prefixes that only sometimes contain files owned by a package.
+const MAYBE_UNMANAGED_PREFIXES: &[&str] = &["/opt"];
+
+/// Returns the reason hooks would never be triggered for the executable at `path`,
+/// or `None` if it may be managed by `pacman`.
+pub fn unmanaged_reason(path: &Path) -> Option<String> {
+    // a home directory of `/` (such as for some service users) would make every path unmanaged
+    let home = env::var_os("HOME")
+        .map(PathBuf::from)
+        .filter(|home| home != Path::new("/"));
+
+    let unmanaged_prefix = UNMANAGED_PREFIXES
+        .iter()
+        .map(Path::new)
+        .chain(home.as_deref())
+        .find(|prefix| path.starts_with(prefix));
+
+    if let Some(prefix) = unmanaged_prefix {
+        return Some(t!(
+            "pacman-unmanaged-prefix",
+            prefix = prefix.display().to_string()
+        ));
+    }
 
-/// Points to the user `pacman` hook directory.
-pub const HOOK_DIR: &str = "/etc/pacman.d/hooks";
+    let maybe_unmanaged = MAYBE_UNMANAGED_PREFIXES
+        .iter()
+        .any(|prefix| path.starts_with(prefix));
+
+    if maybe_unmanaged && !is_owned_by_package(path) {
+        return Some(t!("pacman-unowned", path = path.display().to_string()));
+    }
+
+    None
+}
+
+/// Returns true if `pacman` reports that the file at `path` belongs to an installed package.
+fn is_owned_by_package(path: &Path) -> bool {
+    // if pacman can't be run, nothing can be owned by it
+    pacman_files::owner(path).is_some()
+}
 
 /// Create the user `pacman` hook directory if it doesn't exist.
 /// Returns an error if the directory couldn't be created (likely due to permissions).
 pub fn create_dir() -> anyhow::Result<()> {
-    fs::create_dir_all(HOOK_DIR)
-        .with_context(|| format!("failed to create pacman user hook directory at `{HOOK_DIR}`"))
+    let dir = dir();
+
+    fs::create_dir_all(dir).with_context(|| {
+        t!(
+            "pacman-hook-create-dir-failed",
+            path = dir.display().to_string(),
+        )
+    })
+}
+
+/// Directory the hooks are written to.
+pub fn dir() -> &'static Path {
+    settings::get().hook_dir()
+}
+
+/// Remove all hooks and the wrapper install script for a wrapper, if they exist.
+pub fn remove_all(paths: &wrapper::ExecPaths) -> anyhow::Result<()> {
+    remove_legacy(paths)?;
+    remove_paths(&all_paths(&paths.hook_name))
+}
+
+/// Remove hooks and the wrapper install script for a wrapper that were named after only the filename of the wrapped
+/// executable, as done by older versions.
+pub fn remove_legacy(paths: &wrapper::ExecPaths) -> anyhow::Result<()> {
+    if paths.hook_name == paths.wrapped_filename {
+        return Ok(());
+    }
+
+    let legacy_paths = all_paths(&paths.wrapped_filename);
+
+    // executables with the same filename in different directories shared the same hook names,
+    // so only remove them if they're for this executable
+    let belongs_to_wrapper = legacy_paths
+        .iter()
+        .filter_map(|path| read_target(path))
+        .any(|target| target == paths.wrapped.original);
+
+    if !belongs_to_wrapper {
+        return Ok(());
+    }
+
+    remove_paths(&legacy_paths)
+}
+
+fn remove_paths(paths: &[PathBuf]) -> anyhow::Result<()> {
+    for path in paths {
+        file::remove_if_exists(path).map_err(|err| IoError::new(path, err))?;
+    }
+
+    Ok(())
+}
+
+/// Paths of all hooks and the wrapper install script for the given hook name.
+pub fn all_paths(hook_name: &str) -> Vec<PathBuf> {
+    TriggerAction::iter()
+        .map(|action| get_path(hook_name, &action))
+        .chain([install_script_path(hook_name)])
+        .collect()
+}
+
+/// Derive the name of the hooks for the executable at `wrapped_path`.
+///
+/// The filename is kept readable where possible, with anything other than ASCII letters, digits, `-`, `_`, `.`, and
+/// `+` percent-encoded so it can be decoded back. A hash of the full path is appended, since executables in different
+/// directories can have the same filename.
+pub fn hook_name(wrapped_path: &Path) -> String {
+    let filename = wrapped_path.file_name().unwrap_or_default();
+    let mut name = String::new();
+
+    for &byte in filename.as_encoded_bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'+') {
+            name.push(char::from(byte));
+        } else {
+            name.push_str(&format!("%{byte:02X}"));
+        }
+    }
+
+    let path_hash = hash::fnv1a(wrapped_path.as_os_str().as_encoded_bytes());
+
+    // 32 bits is plenty to tell apart the few executables that share a filename
+    format!("{name}-{:08x}", path_hash as u32)
+}
+
+/// Find every file in the hook directory that was created by this program.
+pub fn find_all() -> anyhow::Result<Vec<PathBuf>> {
+    let marker = format!("-{}-", env!("CARGO_PKG_NAME"));
+
+    let entries = match fs::read_dir(dir()) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(IoError::new(dir(), anyhow::Error::new(err)).into());
+        }
+    };
+
+    let mut paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().contains(&marker))
+        })
+        .collect::<Vec<_>>();
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Find hooks that would get in the way of the hooks for the executable at `wrapped_path`: files occupying their
+/// paths that belong to something else, and hooks not created by this program that target the same file.
+pub fn find_conflicting(wrapped_path: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let own_paths = all_paths(&hook_name(wrapped_path));
+
+    let mut conflicting = own_paths
+        .iter()
+        .filter(|path| path.exists() && read_target(path).as_deref() != Some(wrapped_path))
+        .cloned()
+        .collect::<Vec<_>>();
+
+    let entries = match fs::read_dir(dir()) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(conflicting),
+        Err(err) => {
+            return Err(IoError::new(dir(), anyhow::Error::new(err)).into());
+        }
+    };
+
+    let generated = find_all()?;
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.extension().is_none_or(|ext| ext != "hook") || generated.contains(&path) {
+            continue;
+        }
+
+        let targets_same_file =
+            fs::read_to_string(&path).is_ok_and(|content| has_target(&content, wrapped_path));
+
+        if targets_same_file {
+            conflicting.push(path);
+        }
+    }
+
+    conflicting.sort();
+    Ok(conflicting)
+}
+
+/// Returns true if the hook `content` has a target that matches exactly the file at `path`.
+fn has_target(content: &str, path: &Path) -> bool {
+    content
+        .lines()
+        .filter_map(|line| line.strip_prefix("Target"))
+        .filter_map(|rest| rest.trim_start().strip_prefix('='))
+        .any(|target| Path::new("/").join(unescape_glob(target.trim())) == path)
+}
+
+/// Read the absolute path of the target from an existing hook file.
+pub fn read_target(hook_path: &Path) -> Option<PathBuf> {
+    let content = fs::read_to_string(hook_path).ok()?;
+
+    if let Some(header) = Header::find(&content) {
+        return Some(header.path);
+    }
+
+    // hooks generated before headers were added only have the target itself
+    content
+        .lines()
+        .find_map(|line| line.strip_prefix("Target = "))
+        .map(|target| Path::new("/").join(unescape_glob(target)))
+}
+
+/// Path of the script that reinstalls the wrapper with the given hook name when its install hook runs.
+pub fn install_script_path(hook_name: &str) -> PathBuf {
+    // the wrapper install script uses the same path / filename as the pacman install hook but with a different
+    // extension
+    let trigger_action = TriggerAction::InstallOrUpdate {
+        operations: Vec::new(),
+    };
+
+    get_path(hook_name, &trigger_action).tap_mut(|p| {
+        p.set_extension("sh");
+    })
+}
+
+/// A `pacman` operation that can trigger the install hook.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::EnumIter,
+    strum::EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[strum(serialize_all = "lowercase", ascii_case_insensitive)]
+#[serde(rename_all = "lowercase")]
+pub enum Operation {
+    Install,
+    Upgrade,
+}
+
+impl Operation {
+    /// Returns the operation name as used in hook files.
+    fn hook_name(&self) -> &'static str {
+        match self {
+            Self::Install => "Install",
+            Self::Upgrade => "Upgrade",
+        }
+    }
+
+    /// Parse a comma-separated list of operations, such as `install,upgrade`.
+    pub fn parse_list(value: &str) -> Result<Vec<Self>, String> {
+        value
+            .split(',')
+            .map(|op| {
+                op.trim()
+                    .parse()
+                    .map_err(|_| t!("pacman-hook-unknown-operation", operation = op))
+            })
+            .collect()
+    }
 }
 
 /// A trigger for a hook's target.
 #[derive(Debug, strum::EnumIter)]
 pub enum TriggerAction {
     /// The hook target was installed or updated.
-    InstallOrUpdate,
+    InstallOrUpdate {
+        /// The operations that trigger the hook; all of them if empty.
+        operations: Vec<Operation>,
+    },
     /// The hook target was uninstalled / removed.
     Removal {
         wrapper_install_script_path: PathBuf,
+        /// Symlink exposing the original executable, if the wrapper has one.
+        exposed_original_path: Option<PathBuf>,
     },
 }
 
@@ -36,19 +311,29 @@ impl TriggerAction {
     /// Returns the verb form of the action for use in paths.
     fn path_verb(&self) -> &'static str {
         match self {
-            Self::InstallOrUpdate => "install",
+            Self::InstallOrUpdate { .. } => "install",
             Self::Removal { .. } => "remove",
         }
     }
 
-    fn operations_str(&self) -> &'static str {
+    fn operations_str(&self) -> String {
         match self {
-            Self::InstallOrUpdate => concatdoc! { "
-                Operation = Install
-                Operation = Upgrade" },
-            Self::Removal { .. } => "Operation = Remove",
+            Self::InstallOrUpdate { operations } if operations.is_empty() => {
+                Self::format_operations(Operation::iter())
+            }
+            Self::InstallOrUpdate { operations } => {
+                Self::format_operations(operations.iter().copied())
+            }
+            Self::Removal { .. } => "Operation = Remove".into(),
         }
     }
+
+    fn format_operations(operations: impl Iterator<Item = Operation>) -> String {
+        operations
+            .map(|op| format!("Operation = {}", op.hook_name()))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
 }
 
 pub struct Hook {
@@ -57,8 +342,8 @@ pub struct Hook {
 }
 
 impl Hook {
-    pub fn new(target_filename: &str, trigger_action: TriggerAction) -> Self {
-        let path = get_path(target_filename, &trigger_action);
+    pub fn new(hook_name: &str, trigger_action: TriggerAction) -> Self {
+        let path = get_path(hook_name, &trigger_action);
 
         Self {
             trigger_action,
@@ -66,32 +351,64 @@ impl Hook {
         }
     }
 
-    pub fn generate_and_write_to_disk(self, paths: &wrapper::ExecPaths) -> anyhow::Result<()> {
-        // `trigger_action` is moved below, so we need to get this now for error messages
-        let trigger_path_verb = self.trigger_action.path_verb();
-
-        let content = match self.trigger_action {
-            TriggerAction::InstallOrUpdate => generate_install_and_update(paths, &self.path),
+    /// Generate the contents of the hook.
+    pub fn generate(&self, paths: &wrapper::ExecPaths, header: &Header) -> anyhow::Result<String> {
+        match &self.trigger_action {
+            TriggerAction::InstallOrUpdate { operations } => {
+                // the install hook runs the wrapper install script that sits next to it
+                Ok(generate_install_and_update(
+                    paths,
+                    header,
+                    &self.path.with_extension("sh"),
+                    operations.clone(),
+                ))
+            }
             TriggerAction::Removal {
                 wrapper_install_script_path,
-            } => generate_removal(paths, wrapper_install_script_path)
-                .context("failed to generate content for pacman removal hook")?,
-        };
+                exposed_original_path,
+            } => generate_removal(
+                paths,
+                header,
+                wrapper_install_script_path.clone(),
+                exposed_original_path.clone(),
+            )
+            .with_context(|| t!("pacman-hook-generate-removal-failed")),
+        }
+    }
+
+    pub fn generate_and_write_to_disk(
+        self,
+        paths: &wrapper::ExecPaths,
+        header: &Header,
+        mode: u32,
+    ) -> anyhow::Result<()> {
+        let content = self.generate(paths, header)?;
 
-        fs::write(&self.path, content).with_context(|| {
+        file::write_atomic(&self.path, content.as_bytes(), mode).with_context(|| {
             IoError::new(
                 &self.path,
-                format!("failed to write pacman {trigger_path_verb} hook",),
+                t!(
+                    "pacman-hook-write-failed",
+                    action = self.trigger_action.path_verb(),
+                ),
             )
         })
     }
 }
 
+/// Quote a path for use as a single argument in a hook's `Exec` line.
+///
+/// `pacman` splits `Exec` into arguments itself without involving a shell, so only double quotes need escaping
+/// inside a double-quoted argument; spaces and any other characters are kept as-is.
+fn quote_exec_arg(path: &Path) -> String {
+    format!(r#""{}""#, path.to_string_lossy().replace('"', "\\\""))
+}
+
 /// Generate the full path for a `pacman` hook script.
-fn get_path(target_filename: &str, trigger_action: &TriggerAction) -> PathBuf {
-    PathBuf::from(HOOK_DIR).tap_mut(|p| {
+fn get_path(hook_name: &str, trigger_action: &TriggerAction) -> PathBuf {
+    dir().to_path_buf().tap_mut(|p| {
         p.push(format!(
-            "{target_filename}-{program_name}-{trigger_action}.hook",
+            "{hook_name}-{program_name}-{trigger_action}.hook",
             program_name = env!("CARGO_PKG_NAME"),
             trigger_action = trigger_action.path_verb(),
         ))
@@ -106,16 +423,53 @@ fn trim_path_root(path: impl Into<PathBuf>) -> PathBuf {
     path_str.strip_prefix('/').map(Into::into).unwrap_or(path)
 }
 
+/// Escape characters that `pacman` would interpret as a glob pattern in a hook `Target`, so it only matches `target`.
+fn escape_glob(target: &str) -> String {
+    let mut escaped = String::with_capacity(target.len());
+
+    for (i, ch) in target.chars().enumerate() {
+        // a leading `!` negates the target instead of matching it
+        if matches!(ch, '*' | '?' | '[' | ']' | '\\') || (i == 0 && ch == '!') {
+            escaped.push('\\');
+        }
+
+        escaped.push(ch);
+    }
+
+    escaped
+}
+
+/// Reverse [`escape_glob`].
+fn unescape_glob(target: &str) -> String {
+    let mut unescaped = String::with_capacity(target.len());
+    let mut chars = target.chars();
+
+    while let Some(ch) = chars.next() {
+        match ch {
+            '\\' => unescaped.extend(chars.next()),
+            ch => unescaped.push(ch),
+        }
+    }
+
+    unescaped
+}
+
 /// Generate a `pacman` hook to execute the script at the path given by
 /// `hook_script_path` when the provided wrapped executable is installed or updated.
 ///
 /// Returns the generated hook string.
-pub fn generate_install_and_update(paths: &wrapper::ExecPaths, hook_script_path: &Path) -> String {
+pub fn generate_install_and_update(
+    paths: &wrapper::ExecPaths,
+    header: &Header,
+    hook_script_path: &Path,
+    operations: Vec<Operation>,
+) -> String {
     generate(
         &paths.wrapped,
-        TriggerAction::InstallOrUpdate,
+        header,
+        TriggerAction::InstallOrUpdate { operations },
         &format!("Wrapping {}...", paths.wrapped_filename),
-        &hook_script_path.to_string_lossy(),
+        &quote_exec_arg(hook_script_path),
     )
 }
 
@@ -123,40 +477,84 @@ pub fn generate_install_and_update(paths: &wrapper::ExecPaths, hook_script_path:
 /// Returns the generated hook string.
 pub fn generate_removal(
     paths: &wrapper::ExecPaths,
+    header: &Header,
     wrapper_install_script_path: PathBuf,
+    exposed_original_path: Option<PathBuf>,
 ) -> anyhow::Result<String> {
     let mut remove_cmd = String::from("/usr/bin/rm");
 
     // add all hook target paths for the wrapped executable to the remove command
     for action in TriggerAction::iter() {
-        let path = get_path(&paths.wrapped_filename, &action)
-            .to_string_lossy()
-            // escape double quotes, since we'll be wrapping the path in our own
-            .replace('"', "\\\"");
+        let path = get_path(&paths.hook_name, &action);
 
-        write!(&mut remove_cmd, r#" "{}""#, path)
-            .with_context(|| format!("failed to append path for `{action:?}` pacman hook"))?;
+        write!(&mut remove_cmd, " {}", quote_exec_arg(&path)).with_context(|| {
+            t!(
+                "pacman-hook-append-failed",
+                path = path.display().to_string()
+            )
+        })?;
     }
 
     // also add the wrapper install script for removal
     write!(
         &mut remove_cmd,
-        r#" "{}""#,
-        wrapper_install_script_path
-            .to_string_lossy()
-            // escape double quotes since we're wrapping it with our own
-            .replace('"', "\\\"")
+        " {}",
+        quote_exec_arg(&wrapper_install_script_path)
     )
-    .context("failed to append wrapper install script path")?;
+    .with_context(|| {
+        t!(
+            "pacman-hook-append-failed",
+            path = wrapper_install_script_path.display().to_string()
+        )
+    })?;
 
     // include the unwrapped executable path since it isn't managed by pacman
-    write!(&mut remove_cmd, r#" "{}""#, paths.unwrapped.escaped)
-        .context("failed to append unwrapped executable path")?;
+    write!(
+        &mut remove_cmd,
+        " {}",
+        quote_exec_arg(&paths.unwrapped.original)
+    )
+    .with_context(|| {
+        t!(
+            "pacman-hook-append-failed",
+            path = paths.unwrapped.original.display().to_string()
+        )
+    })?;
+
+    // the wrapper metadata isn't managed by pacman either
+    write!(
+        &mut remove_cmd,
+        " {}",
+        quote_exec_arg(&paths.metadata.original)
+    )
+    .with_context(|| {
+        t!(
+            "pacman-hook-append-failed",
+            path = paths.metadata.original.display().to_string()
+        )
+    })?;
+
+    // the symlink exposing the original would be left dangling
+    if let Some(exposed_original_path) = &exposed_original_path {
+        write!(
+            &mut remove_cmd,
+            " {}",
+            quote_exec_arg(exposed_original_path)
+        )
+        .with_context(|| {
+            t!(
+                "pacman-hook-append-failed",
+                path = exposed_original_path.display().to_string()
+            )
+        })?;
+    }
 
     let hook = generate(
         &paths.wrapped,
+        header,
         TriggerAction::Removal {
             wrapper_install_script_path,
+            exposed_original_path,
         },
         &format!(
             "Removing traces of wrapper for {}...",
@@ -170,6 +568,7 @@ pub fn generate_removal(
 
 fn generate(
     target_path: &path::Escaped,
+    header: &Header,
     trigger: TriggerAction,
     description: &str,
     exec_str: &str,
@@ -177,6 +576,8 @@ fn generate(
     let trimmed_target_path = trim_path_root(&target_path.original);
 
     formatdoc! { r#"
+        {header}
+
         [Trigger]
         Type = File
         {operations}
@@ -188,7 +589,7 @@ fn generate(
         Exec = {exec_str}
         "#,
         operations = trigger.operations_str(),
-        trimmed_target_path = trimmed_target_path.display(),
+        trimmed_target_path = escape_glob(&trimmed_target_path.to_string_lossy()),
     }
 }
 
@@ -214,32 +615,58 @@ mod tests {
         }
     }
 
+    #[test]
+    fn unmanaged_prefixes() {
+        assert!(unmanaged_reason(Path::new("/usr/local/bin/foo")).is_some());
+        assert!(unmanaged_reason(Path::new("/home/user/foo")).is_some());
+        assert!(unmanaged_reason(Path::new("/usr/bin/foo")).is_none());
+    }
+
+    #[test]
+    fn foreign_hook_targets() {
+        let content = indoc::indoc! {"
+            [Trigger]
+            Type = Path
+            Target=usr/bin/foo\\[1\\]
+            Target = usr/bin/bar
+        "};
+
+        assert!(has_target(content, Path::new("/usr/bin/foo[1]")));
+        assert!(has_target(content, Path::new("/usr/bin/bar")));
+        assert!(!has_target(content, Path::new("/usr/bin/baz")));
+    }
+
     mod get_hook_path_tests {
         use super::*;
 
         fn test_get_hook_path_helper(
-            target_filename: &str,
+            hook_name: &str,
             trigger_action: &TriggerAction,
             expected_suffix: &str,
         ) {
             let expected_program_name = env!("CARGO_PKG_NAME");
             let expected_path = format!(
-                "{HOOK_DIR}/{target_filename}-{expected_program_name}-{expected_suffix}.hook"
+                "{DEFAULT_HOOK_DIR}/{hook_name}-{expected_program_name}-{expected_suffix}.hook"
             );
 
-            let result = get_path(target_filename, trigger_action);
+            let result = get_path(hook_name, trigger_action);
             assert_eq!(result.to_string_lossy(), expected_path);
         }
 
         fn gen_remove_trigger() -> TriggerAction {
             TriggerAction::Removal {
                 wrapper_install_script_path: PathBuf::from("/wrapper/install/path"),
+                exposed_original_path: None,
             }
         }
 
         #[test]
         fn test_install_or_update() {
-            test_get_hook_path_helper("test_binary", &TriggerAction::InstallOrUpdate, "install");
+            let trigger = TriggerAction::InstallOrUpdate {
+                operations: Vec::new(),
+            };
+
+            test_get_hook_path_helper("test_binary", &trigger, "install");
         }
 
         #[test]
@@ -254,13 +681,18 @@ mod tests {
             unwrapped: path::Escaped::new("/usr/bin/original_executable"),
             wrapped: path::Escaped::new("/usr/bin/test_executable"),
             wrapped_filename: "test_executable".to_string(),
+            hook_name: "test_executable-0123abcd".to_string(),
+            metadata: path::Escaped::new("/usr/bin/.test_executable-wrapperize.toml"),
         };
 
         let hook_script_path = PathBuf::from("/etc/test_script.sh");
 
-        let result = generate_install_and_update(&paths, &hook_script_path);
+        let header = Header::new(&paths.wrapped.original, &wrapper::Params::default());
+        let result = generate_install_and_update(&paths, &header, &hook_script_path, Vec::new());
 
         let expected = formatdoc! { r#"
+              {header}
+
               [Trigger]
               Type = File
               Operation = Install
@@ -270,25 +702,147 @@ mod tests {
               [Action]
               Description = Wrapping test_executable...
               When = PostTransaction
-              Exec = /etc/test_script.sh
+              Exec = "/etc/test_script.sh"
               "#
         };
 
         assert_eq!(result, expected);
     }
 
+    #[test]
+    fn test_generate_install_with_operations() {
+        let paths = wrapper::ExecPaths::try_from_path(Path::new("/usr/bin/test_executable"))
+            .expect("paths should be valid");
+
+        let result = generate_install_and_update(
+            &paths,
+            &Header::new(&paths.wrapped.original, &wrapper::Params::default()),
+            Path::new("/etc/test_script.sh"),
+            vec![Operation::Upgrade],
+        );
+
+        assert!(result.contains("Operation = Upgrade\n"));
+        assert!(!result.contains("Operation = Install"));
+    }
+
+    mod glob {
+        use super::*;
+
+        #[test]
+        fn escapes_metacharacters() {
+            assert_eq!(escape_glob("usr/bin/foo"), "usr/bin/foo");
+            assert_eq!(
+                escape_glob("opt/a*b?c/[x]\\y"),
+                "opt/a\\*b\\?c/\\[x\\]\\\\y"
+            );
+            assert_eq!(escape_glob("!opt/!foo"), "\\!opt/!foo");
+        }
+
+        #[test]
+        fn round_trips() {
+            for target in ["usr/bin/foo", "opt/a*b?c/[x]\\y", "!opt/!foo"] {
+                assert_eq!(unescape_glob(&escape_glob(target)), target);
+            }
+        }
+
+        #[test]
+        fn hook_targets_are_escaped() {
+            let paths = wrapper::ExecPaths::try_from_path(Path::new("/opt/app[1]/bin/to*l"))
+                .expect("paths should be valid");
+
+            let header = Header::new(&paths.wrapped.original, &wrapper::Params::default());
+            let install =
+                generate_install_and_update(&paths, &header, Path::new("/script"), Vec::new());
+            let removal =
+                generate_removal(&paths, &header, PathBuf::from("/script"), None).unwrap();
+
+            for hook in [install, removal] {
+                assert!(hook.contains("Target = opt/app\\[1\\]/bin/to\\*l\n"));
+            }
+        }
+    }
+
+    mod hook_name {
+        use super::*;
+
+        /// Reverse the percent-encoding of the filename in a hook name.
+        fn decode_filename(hook_name: &str) -> Vec<u8> {
+            let (encoded, _) = hook_name.rsplit_once('-').unwrap();
+            let mut bytes = encoded.bytes();
+            let mut decoded = Vec::new();
+
+            while let Some(byte) = bytes.next() {
+                if byte == b'%' {
+                    let hex = [bytes.next().unwrap(), bytes.next().unwrap()];
+                    let hex = std::str::from_utf8(&hex).unwrap();
+                    decoded.push(u8::from_str_radix(hex, 16).unwrap());
+                } else {
+                    decoded.push(byte);
+                }
+            }
+
+            decoded
+        }
+
+        #[test]
+        fn keeps_common_names_readable() {
+            let name = hook_name(Path::new("/usr/bin/gtk-query-immodules-3.0"));
+            assert!(name.starts_with("gtk-query-immodules-3.0-"));
+        }
+
+        #[test]
+        fn encodes_odd_characters_reversibly() {
+            for filename in ["my app", "100%", "tab\there", "ünïcode", "a-b-c"] {
+                let name = hook_name(&Path::new("/opt/bin").join(filename));
+
+                assert!(
+                    name.bytes()
+                        .all(|byte| byte.is_ascii_alphanumeric() || b"-_.+%".contains(&byte)),
+                    "`{name}` contains unsafe characters"
+                );
+                assert_eq!(decode_filename(&name), filename.as_bytes());
+            }
+        }
+
+        #[test]
+        fn same_filename_in_different_dirs() {
+            assert_ne!(
+                hook_name(Path::new("/usr/bin/foo")),
+                hook_name(Path::new("/opt/foo/bin/foo"))
+            );
+        }
+    }
+
+    #[test]
+    fn parse_operation_list() {
+        assert_eq!(
+            Operation::parse_list("install, Upgrade"),
+            Ok(vec![Operation::Install, Operation::Upgrade])
+        );
+        assert_eq!(
+            Operation::parse_list("upgrade"),
+            Ok(vec![Operation::Upgrade])
+        );
+        assert!(Operation::parse_list("remove").is_err());
+    }
+
     #[test]
     fn test_generate_removal() {
         let bin_info = wrapper::ExecPaths {
             unwrapped: path::Escaped::new("/usr/bin/original_exec"),
             wrapped: path::Escaped::new("/usr/bin/wrapped_exec"),
             wrapped_filename: "wrapped_exec".to_string(),
+            hook_name: "wrapped_exec-0123abcd".to_string(),
+            metadata: path::Escaped::new("/usr/bin/.wrapped_exec-wrapperize.toml"),
         };
 
-        let result = generate_removal(&bin_info, PathBuf::from("install/script"))
+        let header = Header::new(&bin_info.wrapped.original, &wrapper::Params::default());
+        let result = generate_removal(&bin_info, &header, PathBuf::from("install/script"), None)
             .expect("expected generation to succeed");
 
         let expected = formatdoc! { r#"
+              {header}
+
               [Trigger]
               Type = File
               Operation = Remove
@@ -297,10 +851,59 @@ mod tests {
               [Action]
               Description = Removing traces of wrapper for wrapped_exec...
               When = PostTransaction
-              Exec = /usr/bin/rm "/etc/pacman.d/hooks/wrapped_exec-wrapperize-install.hook" "/etc/pacman.d/hooks/wrapped_exec-wrapperize-remove.hook" "install/script" "/usr/bin/original_exec"
+              Exec = /usr/bin/rm "/etc/pacman.d/hooks/wrapped_exec-0123abcd-wrapperize-install.hook" "/etc/pacman.d/hooks/wrapped_exec-0123abcd-wrapperize-remove.hook" "install/script" "/usr/bin/original_exec" "/usr/bin/.wrapped_exec-wrapperize.toml"
               "#
         };
 
         assert_eq!(result, expected);
     }
+
+    #[test]
+    fn removal_quotes_paths_with_spaces() {
+        let paths = wrapper::ExecPaths::try_from_path(Path::new("/opt/My \"App\"/bin/tööl"))
+            .expect("paths should be valid");
+
+        let header = Header::new(&paths.wrapped.original, &wrapper::Params::default());
+        let result = generate_removal(&paths, &header, install_script_path(&paths.hook_name), None)
+            .expect("expected generation to succeed");
+
+        assert!(result.contains("Target = opt/My \"App\"/bin/tööl\n"));
+        assert!(result.contains(r#" "/opt/My \"App\"/bin/.tööl-unwrapped" "#));
+        assert!(result.ends_with(
+            r#" "/opt/My \"App\"/bin/.tööl-wrapperize.toml"
+"#
+        ));
+    }
+
+    #[test]
+    fn removal_includes_exposed_original() {
+        let paths = wrapper::ExecPaths::try_from_path(Path::new("/usr/bin/test_executable"))
+            .expect("paths should be valid");
+
+        let header = Header::new(&paths.wrapped.original, &wrapper::Params::default());
+        let result = generate_removal(
+            &paths,
+            &header,
+            install_script_path(&paths.hook_name),
+            Some(paths.exposed_original_path()),
+        )
+        .expect("expected generation to succeed");
+
+        assert!(result.ends_with(" \"/usr/bin/test_executable-unwrapped\"\n"));
+    }
+
+    #[test]
+    fn install_hook_runs_install_script() {
+        let hook = Hook::new(
+            "test_binary",
+            TriggerAction::InstallOrUpdate {
+                operations: Vec::new(),
+            },
+        );
+
+        assert_eq!(
+            hook.path.with_extension("sh"),
+            install_script_path("test_binary")
+        );
+    }
 }
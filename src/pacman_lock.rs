@@ -0,0 +1,69 @@
+use std::{
+    path::Path,
+    thread,
+    time::{Duration, Instant},
+};
+
+use anyhow::Context;
+
+use crate::{error::IoError, i18n::t};
+
+/// Lock file that exists while a `pacman` transaction is in progress.
+pub const LOCK_PATH: &str = "/var/lib/pacman/db.lck";
+
+/// How often to check the lock file while waiting for it to be removed.
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Return an error if a `pacman` transaction is in progress, since modifying files that `pacman` may be
+/// extracting at the same time could leave a broken wrapper behind.
+///
+/// If `wait` is set, the transaction is given that long to finish before returning an error.
+pub fn ensure_unlocked(wait: Option<Duration>) -> anyhow::Result<()> {
+    ensure_path_unlocked(Path::new(LOCK_PATH), wait)
+}
+
+fn ensure_path_unlocked(lock_path: &Path, wait: Option<Duration>) -> anyhow::Result<()> {
+    let is_locked = || {
+        lock_path
+            .try_exists()
+            .with_context(|| IoError::new(lock_path, t!("pacman-lock-check-failed")))
+    };
+
+    let deadline = wait.map(|wait| Instant::now() + wait);
+
+    while is_locked()? {
+        match deadline {
+            Some(deadline) if Instant::now() < deadline => thread::sleep(POLL_INTERVAL),
+            Some(_) => anyhow::bail!(t!(
+                "pacman-lock-timeout",
+                path = lock_path.display().to_string(),
+            )),
+            None => anyhow::bail!(t!(
+                "pacman-lock-locked",
+                path = lock_path.display().to_string(),
+            )),
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn locked_and_unlocked() {
+        let dir = test_util::temp_dir("lock");
+        let lock_path = dir.path().join("db.lck");
+
+        assert!(ensure_path_unlocked(&lock_path, None).is_ok());
+
+        fs::write(&lock_path, "").unwrap();
+        assert!(ensure_path_unlocked(&lock_path, None).is_err());
+        assert!(ensure_path_unlocked(&lock_path, Some(Duration::ZERO)).is_err());
+    }
+}
@@ -1,22 +1,30 @@
 use std::path::PathBuf;
 
+/// A path along with a version of it that can be placed inside double quotes in a shell script.
 pub struct Escaped {
     pub original: PathBuf,
     pub escaped: String,
 }
 
 impl Escaped {
+    /// The quote character that `escaped` is meant to be wrapped in.
     pub const ESCAPE_CHAR: char = '"';
 
-    // should be the escaped version of `ESCAPE_CHAR`
-    const ESCAPE_CHAR_REPLACEMENT: &str = "\\\"";
+    /// Characters that keep their special meaning inside double quotes and need a backslash in front of them.
+    const SPECIAL_CHARS: [char; 4] = [Self::ESCAPE_CHAR, '\\', '$', '`'];
 
     pub fn new(path: impl Into<PathBuf>) -> Self {
         let path = path.into();
 
-        let escaped = path
-            .to_string_lossy()
-            .replace(Self::ESCAPE_CHAR, Self::ESCAPE_CHAR_REPLACEMENT);
+        let mut escaped = String::new();
+
+        for ch in path.to_string_lossy().chars() {
+            if Self::SPECIAL_CHARS.contains(&ch) {
+                escaped.push('\\');
+            }
+
+            escaped.push(ch);
+        }
 
         Self {
             original: path,
@@ -24,3 +32,24 @@ impl Escaped {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain_path_is_unchanged() {
+        assert_eq!(
+            Escaped::new("/opt/My App/bin/tööl").escaped,
+            "/opt/My App/bin/tööl"
+        );
+    }
+
+    #[test]
+    fn special_chars_are_escaped() {
+        assert_eq!(
+            Escaped::new(r#"/opt/"a"/$b/`c`/\d"#).escaped,
+            r#"/opt/\"a\"/\$b/\`c\`/\\d"#
+        );
+    }
+}
@@ -0,0 +1,300 @@
+use std::{
+    collections::BTreeSet,
+    env, fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    process::{Command, Stdio},
+};
+
+use anyhow::Context;
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+
+use crate::{error::IoError, file, header::Header, i18n::t, wrapper};
+
+/// Version of the JSON protocol spoken with plugins, which is sent along with every request.
+pub const PROTOCOL_VERSION: u32 = 1;
+
+/// Directory of the install scripts that backends run once their package manager has replaced a wrapped executable.
+pub const BACKEND_SCRIPT_DIR: &str = concat!("/etc/", env!("CARGO_PKG_NAME"), "/backends");
+
+/// What a plugin provides, which decides the prefix of its executable.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Kind {
+    /// A package manager other than `pacman`, which recreates wrappers when it replaces their executables.
+    Backend,
+    /// A set of parameters, like the built-in presets.
+    Preset,
+}
+
+impl Kind {
+    /// Start of the names of the executables of plugins of this kind, such as `wrapperize-backend-`.
+    fn prefix(self) -> &'static str {
+        match self {
+            Self::Backend => concat!(env!("CARGO_PKG_NAME"), "-backend-"),
+            Self::Preset => concat!(env!("CARGO_PKG_NAME"), "-preset-"),
+        }
+    }
+}
+
+/// A request sent to a plugin on its stdin.
+#[derive(Debug, Serialize)]
+#[serde(tag = "request", rename_all = "kebab-case")]
+enum Request<'a> {
+    /// Ask a preset plugin for the parameters to wrap `executable` with.
+    Preset { executable: &'a Path },
+    /// Ask a backend to run `install_script` whenever its package manager replaces `executable`.
+    Install {
+        executable: &'a Path,
+        hook_name: &'a str,
+        install_script: &'a Path,
+    },
+    /// Ask a backend to stop running `install_script` for `executable`.
+    Remove {
+        executable: &'a Path,
+        hook_name: &'a str,
+        install_script: &'a Path,
+    },
+}
+
+/// Every request, along with the version of the protocol it's in.
+#[derive(Debug, Serialize)]
+struct Envelope<'a> {
+    protocol: u32,
+    #[serde(flatten)]
+    request: Request<'a>,
+}
+
+/// The response of a plugin that reports a failure instead of answering the request.
+#[derive(Debug, Deserialize)]
+struct Failure {
+    error: String,
+}
+
+/// The response to [`Request::Preset`].
+#[derive(Debug, Deserialize)]
+struct PresetResponse {
+    params: wrapper::Params,
+}
+
+/// Check that `name` can be part of the name of a plugin executable.
+pub fn validate_name(name: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '_')),
+        t!("plugin-invalid-name", name = name)
+    );
+
+    Ok(())
+}
+
+/// Find the executable of the plugin of `kind` named `name` in `$PATH`.
+pub fn find(kind: Kind, name: &str) -> Option<PathBuf> {
+    validate_name(name).ok()?;
+    file::resolve_command(&format!("{}{name}", kind.prefix()))
+}
+
+/// Names of every plugin of `kind` in `$PATH`, in order.
+pub fn find_all(kind: Kind) -> Vec<String> {
+    let Some(paths) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+
+    let mut names = BTreeSet::new();
+
+    for dir in env::split_paths(&paths) {
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let filename = entry.file_name().to_string_lossy().into_owned();
+
+            let Some(name) = filename.strip_prefix(kind.prefix()) else {
+                continue;
+            };
+
+            if validate_name(name).is_ok() && file::is_executable(&entry.path()).unwrap_or(false) {
+                names.insert(name.to_owned());
+            }
+        }
+    }
+
+    names.into_iter().collect()
+}
+
+/// Ask the preset plugin named `name` for the parameters to wrap the executable at `executable` with.
+pub fn preset_params(name: &str, executable: &Path) -> anyhow::Result<wrapper::Params> {
+    let response: PresetResponse = call(Kind::Preset, name, Request::Preset { executable })?;
+    Ok(response.params)
+}
+
+/// Path of the install script a backend runs for the wrapper with the given hook name.
+pub fn install_script_path(hook_name: &str) -> PathBuf {
+    Path::new(BACKEND_SCRIPT_DIR).join(format!("{hook_name}.sh"))
+}
+
+/// Find every install script of a backend, including those of wrappers that no longer exist.
+pub fn find_install_scripts() -> anyhow::Result<Vec<PathBuf>> {
+    let dir = Path::new(BACKEND_SCRIPT_DIR);
+
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(IoError::new(dir, anyhow::Error::new(err)).into()),
+    };
+
+    let mut paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.extension().is_some_and(|ext| ext == "sh")
+                && fs::read_to_string(path).is_ok_and(|content| Header::find(&content).is_some())
+        })
+        .collect::<Vec<_>>();
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Ask the backend named `name` to run the install script of the wrapper at `paths` whenever its package manager
+/// replaces the wrapped executable.
+pub fn install_backend(name: &str, paths: &wrapper::ExecPaths) -> anyhow::Result<()> {
+    let install_script = install_script_path(&paths.hook_name);
+
+    call::<serde::de::IgnoredAny>(
+        Kind::Backend,
+        name,
+        Request::Install {
+            executable: &paths.wrapped.original,
+            hook_name: &paths.hook_name,
+            install_script: &install_script,
+        },
+    )?;
+
+    Ok(())
+}
+
+/// Ask the backend named `name` to stop recreating the wrapper at `paths`, and remove its install script.
+pub fn remove_backend(name: &str, paths: &wrapper::ExecPaths) -> anyhow::Result<()> {
+    let install_script = install_script_path(&paths.hook_name);
+
+    call::<serde::de::IgnoredAny>(
+        Kind::Backend,
+        name,
+        Request::Remove {
+            executable: &paths.wrapped.original,
+            hook_name: &paths.hook_name,
+            install_script: &install_script,
+        },
+    )?;
+
+    file::remove_if_exists(&install_script).map_err(|err| IoError::new(&install_script, err))?;
+
+    Ok(())
+}
+
+/// Send `request` to the plugin of `kind` named `name`, and parse its response.
+///
+/// The plugin's stderr is passed through, so it can report what it's doing.
+fn call<R: DeserializeOwned>(kind: Kind, name: &str, request: Request) -> anyhow::Result<R> {
+    let program = find(kind, name).with_context(|| {
+        t!(
+            "plugin-not-found",
+            name = format!("{}{name}", kind.prefix())
+        )
+    })?;
+
+    let request = serde_json::to_vec(&Envelope {
+        protocol: PROTOCOL_VERSION,
+        request,
+    })
+    .with_context(|| t!("plugin-serialize-failed"))?;
+
+    let mut child = Command::new(&program)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .spawn()
+        .with_context(|| IoError::new(&program, t!("plugin-run-failed")))?;
+
+    // the pipe is closed as soon as it's dropped, so the plugin sees the end of the request
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin
+            .write_all(&request)
+            .with_context(|| IoError::new(&program, t!("plugin-send-failed")))?;
+    }
+
+    let output = child
+        .wait_with_output()
+        .with_context(|| IoError::new(&program, t!("plugin-wait-failed")))?;
+
+    // a failure the plugin explains is more useful than its exit status
+    let reported_failure = serde_json::from_slice::<Failure>(&output.stdout).is_ok();
+
+    let result = if output.status.success() || reported_failure {
+        parse_response(&output.stdout)
+    } else {
+        Err(anyhow::anyhow!(t!(
+            "plugin-exited",
+            status = output.status.to_string()
+        )))
+    };
+
+    result.with_context(|| t!("plugin-failed", path = program.display().to_string()))
+}
+
+/// Parse the response of a plugin, turning a reported failure into an error.
+fn parse_response<R: DeserializeOwned>(stdout: &[u8]) -> anyhow::Result<R> {
+    if let Ok(failure) = serde_json::from_slice::<Failure>(stdout) {
+        anyhow::bail!("{}", failure.error);
+    }
+
+    serde_json::from_slice(stdout).with_context(|| t!("plugin-invalid-response"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn requests_are_tagged_with_the_protocol() {
+        let request = Envelope {
+            protocol: PROTOCOL_VERSION,
+            request: Request::Install {
+                executable: Path::new("/usr/bin/vim"),
+                hook_name: "vim-0123abcd",
+                install_script: Path::new("/etc/wrapperize/backends/vim-0123abcd.sh"),
+            },
+        };
+
+        assert_eq!(
+            serde_json::to_string(&request).unwrap(),
+            r#"{"protocol":1,"request":"install","executable":"/usr/bin/vim","hook_name":"vim-0123abcd","install_script":"/etc/wrapperize/backends/vim-0123abcd.sh"}"#
+        );
+    }
+
+    #[test]
+    fn parses_responses() {
+        let response: PresetResponse =
+            parse_response(br#"{"params": {"args": ["--fast"], "envs": ["FOO=bar"]}}"#).unwrap();
+
+        assert_eq!(response.params.args, ["--fast"]);
+        assert_eq!(response.params.env_vars.len(), 1);
+
+        let err = parse_response::<PresetResponse>(br#"{"error": "unsupported executable"}"#)
+            .unwrap_err();
+
+        assert_eq!(err.to_string(), "unsupported executable");
+        assert!(parse_response::<PresetResponse>(b"not json").is_err());
+    }
+
+    #[test]
+    fn plugin_names() {
+        assert!(validate_name("apt").is_ok());
+        assert!(validate_name("my_preset-2").is_ok());
+        assert!(validate_name("").is_err());
+        assert!(validate_name("../apt").is_err());
+        assert!(find(Kind::Backend, "../../bin/sh").is_none());
+    }
+}
@@ -0,0 +1,409 @@
+use std::{fmt, path::Path};
+
+use anyhow::Context;
+
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    i18n::t,
+    plugin, shell,
+    wrapper::{ARGS_BEFORE_VAR, Params},
+};
+
+/// Name of the array in the wrapper script holding the commands that presets launch the executable through.
+pub const LAUNCHER_VAR: &str = "launcher";
+
+/// A built-in set of parameters for a common reason to wrap an executable.
+///
+/// Presets are resolved when the wrapper runs, so they only use tools that are installed at that point, and the
+/// environment variables they set can be overridden by the caller or by the wrapper's own variables.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    strum::EnumIter,
+    strum::EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[strum(serialize_all = "kebab-case")]
+#[serde(rename_all = "kebab-case")]
+pub enum Preset {
+    /// Launch through `gamemoderun` and enable the MangoHud overlay, if they're installed.
+    Gaming,
+    /// Render on the discrete GPU of a hybrid graphics system, through `prime-run` or the PRIME render offload
+    /// environment variables.
+    GpuOffload,
+    /// Run Chromium and Electron apps natively on Wayland when the wrapper runs in a Wayland session.
+    Wayland,
+    /// Route the executable through a proxy with the standard proxy environment variables.
+    Proxy,
+}
+
+impl Preset {
+    /// Whether this preset adds arguments for the executable, which requires every argument array to exist.
+    pub fn adds_args(self) -> bool {
+        matches!(self, Self::Wayland)
+    }
+
+    /// Write the lines of the wrapper script that apply this preset, with the options in `params`.
+    pub fn write_bash(self, params: &Params, mut writer: impl fmt::Write) -> fmt::Result {
+        match self {
+            Self::Gaming => {
+                write_if_installed("gamemoderun", &[&launch_with("gamemoderun")], &mut writer)?;
+                write_if_installed("mangohud", &[&export_default("MANGOHUD", "1")], &mut writer)
+            }
+            Self::GpuOffload => write_gpu_offload(params.gpu_device.as_deref(), writer),
+            Self::Wayland => write_wayland(&params.wayland_flags, writer),
+            Self::Proxy => write_proxy(params, writer),
+        }
+    }
+}
+
+/// A preset named on the command line, which is either built in or provided by a plugin.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Choice {
+    BuiltIn(Preset),
+    /// Name of a `wrapperize-preset-<name>` plugin in `$PATH`, which is asked for its parameters when the wrapper is
+    /// generated.
+    Plugin(String),
+}
+
+impl Choice {
+    /// Parse the name of a built-in preset or of a preset plugin, listing both if it's neither.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        if let Ok(preset) = value.parse() {
+            return Ok(Self::BuiltIn(preset));
+        }
+
+        if plugin::find(plugin::Kind::Preset, value).is_some() {
+            return Ok(Self::Plugin(value.to_owned()));
+        }
+
+        let known = Preset::iter()
+            .map(|preset| preset.to_string())
+            .chain(plugin::find_all(plugin::Kind::Preset))
+            .collect::<Vec<_>>();
+
+        Err(t!(
+            "preset-unknown",
+            preset = value,
+            known = known.join(", "),
+        ))
+    }
+}
+
+/// Split `choices` into the built-in presets, and the parameters the plugins among them provide for the executable at
+/// `executable`, merged in order.
+pub fn resolve(choices: Vec<Choice>, executable: &Path) -> anyhow::Result<(Vec<Preset>, Params)> {
+    let mut presets = Vec::new();
+    let mut plugin_params = Params::default();
+
+    for choice in choices {
+        match choice {
+            Choice::BuiltIn(preset) => presets.push(preset),
+            Choice::Plugin(name) => {
+                let params = plugin::preset_params(&name, executable)
+                    .with_context(|| t!("preset-plugin-failed", preset = name.as_str()))?;
+
+                plugin_params.merge(params);
+            }
+        }
+    }
+
+    Ok((presets, plugin_params))
+}
+
+/// Check that the options for presets in `params` are valid, and only set along with their preset.
+pub fn validate_options(params: &Params) -> anyhow::Result<()> {
+    let has = |preset| params.presets.contains(&preset);
+
+    if let Some(device) = &params.gpu_device {
+        anyhow::ensure!(
+            has(Preset::GpuOffload),
+            t!("preset-gpu-device-without-preset")
+        );
+
+        validate_gpu_device(device)?;
+    }
+
+    anyhow::ensure!(
+        params.wayland_flags.is_empty() || has(Preset::Wayland),
+        t!("preset-wayland-flags-without-preset")
+    );
+
+    anyhow::ensure!(
+        (params.proxy.is_none() && params.no_proxy.is_none()) || has(Preset::Proxy),
+        t!("preset-proxy-without-preset")
+    );
+
+    if has(Preset::Proxy) {
+        let proxy = params
+            .proxy
+            .as_deref()
+            .with_context(|| t!("preset-proxy-missing"))?;
+
+        for value in [Some(proxy), params.no_proxy.as_deref()]
+            .into_iter()
+            .flatten()
+        {
+            validate_default_value(value)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that `value` can be written into the default value of a variable expansion as-is, which rules out
+/// whitespace and characters that bash would expand or that would end the expansion.
+fn validate_default_value(value: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !value.is_empty()
+            && !value
+                .chars()
+                .any(|ch| ch.is_whitespace() || matches!(ch, '"' | '$' | '`' | '\\' | '}')),
+        t!("preset-invalid-default-value", value = value)
+    );
+
+    Ok(())
+}
+
+/// Check that `device` is something Mesa accepts in `DRI_PRIME`, such as `1`, `pci-0000_01_00_0`, or `1002:6a2b`.
+pub fn validate_gpu_device(device: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !device.is_empty()
+            && device
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '_' | '-' | ':')),
+        t!("preset-invalid-gpu-device", device = device)
+    );
+
+    Ok(())
+}
+
+fn write_gpu_offload(device: Option<&str>, mut writer: impl fmt::Write) -> fmt::Result {
+    // a specific device can only be selected through Mesa
+    if let Some(device) = device {
+        return writeln!(writer, "{}", export_default("DRI_PRIME", device));
+    }
+
+    // `prime-run` and the variables it sets are specific to the NVIDIA driver, and would break rendering without it
+    writeln!(writer, "if command -v prime-run >/dev/null; then")?;
+    writeln!(writer, "    {}", launch_with("prime-run"))?;
+    writeln!(writer, "elif [ -e /proc/driver/nvidia/version ]; then")?;
+
+    for (name, value) in [
+        ("__NV_PRIME_RENDER_OFFLOAD", "1"),
+        ("__VK_LAYER_NV_optimus", "NVIDIA_only"),
+        ("__GLX_VENDOR_LIBRARY_NAME", "nvidia"),
+    ] {
+        writeln!(writer, "    {}", export_default(name, value))?;
+    }
+
+    writeln!(writer, "else")?;
+    writeln!(writer, "    {}", export_default("DRI_PRIME", "1"))?;
+    writeln!(writer, "fi")
+}
+
+/// Flags the `wayland` preset adds unless others are given.
+const DEFAULT_WAYLAND_FLAGS: [&str; 2] = [
+    "--ozone-platform-hint=auto",
+    "--enable-features=WaylandWindowDecorations",
+];
+
+fn write_wayland(flags: &[String], mut writer: impl fmt::Write) -> fmt::Result {
+    let flags = if flags.is_empty() {
+        DEFAULT_WAYLAND_FLAGS.map(shell::quote).to_vec()
+    } else {
+        flags.iter().map(|flag| shell::quote(flag)).collect()
+    };
+
+    // the flags come before the passthrough arguments, so they don't end up after a `--` passed to the wrapper
+    writeln!(writer, r#"if [ -n "${{WAYLAND_DISPLAY:-}}" ]; then"#)?;
+    writeln!(writer, "    {ARGS_BEFORE_VAR}+=({})", flags.join(" "))?;
+    writeln!(writer, "fi")
+}
+
+fn write_proxy(params: &Params, mut writer: impl fmt::Write) -> fmt::Result {
+    let proxy = params.proxy.as_deref().unwrap_or_default();
+
+    // most programs read the lowercase names, but some only read the uppercase ones
+    for name in ["http_proxy", "https_proxy", "HTTP_PROXY", "HTTPS_PROXY"] {
+        writeln!(writer, "{}", export_default(name, proxy))?;
+    }
+
+    if let Some(no_proxy) = &params.no_proxy {
+        for name in ["no_proxy", "NO_PROXY"] {
+            writeln!(writer, "{}", export_default(name, no_proxy))?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Write `lines` so they only run if `program` is in `$PATH` when the wrapper runs.
+fn write_if_installed(program: &str, lines: &[&str], mut writer: impl fmt::Write) -> fmt::Result {
+    writeln!(writer, "if command -v {program} >/dev/null; then")?;
+
+    for line in lines {
+        writeln!(writer, "    {line}")?;
+    }
+
+    writeln!(writer, "fi")
+}
+
+/// A line that adds `program` to the commands the executable is launched through.
+fn launch_with(program: &str) -> String {
+    format!("{LAUNCHER_VAR}+=({program})")
+}
+
+/// A line that exports `name` as `value` unless it's already set, so the caller's value wins.
+fn export_default(name: &str, value: &str) -> String {
+    format!(r#"export {name}="${{{name}:-{value}}}""#)
+}
+
+#[cfg(test)]
+mod tests {
+    use indoc::indoc;
+
+    use super::*;
+
+    #[test]
+    fn gaming() {
+        let mut script = String::new();
+        Preset::Gaming
+            .write_bash(&Params::default(), &mut script)
+            .unwrap();
+
+        assert_eq!(
+            script,
+            indoc! {r#"
+                if command -v gamemoderun >/dev/null; then
+                    launcher+=(gamemoderun)
+                fi
+                if command -v mangohud >/dev/null; then
+                    export MANGOHUD="${MANGOHUD:-1}"
+                fi
+            "#}
+        );
+    }
+
+    #[test]
+    fn gpu_offload() {
+        let mut script = String::new();
+        Preset::GpuOffload
+            .write_bash(&Params::default(), &mut script)
+            .unwrap();
+
+        assert_eq!(
+            script,
+            indoc! {r#"
+                if command -v prime-run >/dev/null; then
+                    launcher+=(prime-run)
+                elif [ -e /proc/driver/nvidia/version ]; then
+                    export __NV_PRIME_RENDER_OFFLOAD="${__NV_PRIME_RENDER_OFFLOAD:-1}"
+                    export __VK_LAYER_NV_optimus="${__VK_LAYER_NV_optimus:-NVIDIA_only}"
+                    export __GLX_VENDOR_LIBRARY_NAME="${__GLX_VENDOR_LIBRARY_NAME:-nvidia}"
+                else
+                    export DRI_PRIME="${DRI_PRIME:-1}"
+                fi
+            "#}
+        );
+
+        let params = Params {
+            gpu_device: Some("pci-0000_01_00_0".into()),
+            ..Default::default()
+        };
+
+        let mut script = String::new();
+        Preset::GpuOffload.write_bash(&params, &mut script).unwrap();
+        assert_eq!(
+            script,
+            "export DRI_PRIME=\"${DRI_PRIME:-pci-0000_01_00_0}\"\n"
+        );
+
+        assert!(validate_gpu_device("1002:6a2b").is_ok());
+        assert!(validate_gpu_device("").is_err());
+        assert!(validate_gpu_device("$(reboot)").is_err());
+    }
+
+    #[test]
+    fn wayland() {
+        let mut script = String::new();
+        Preset::Wayland
+            .write_bash(&Params::default(), &mut script)
+            .unwrap();
+
+        assert_eq!(
+            script,
+            indoc! {r#"
+                if [ -n "${WAYLAND_DISPLAY:-}" ]; then
+                    args_before+=(--ozone-platform-hint=auto --enable-features=WaylandWindowDecorations)
+                fi
+            "#}
+        );
+
+        let params = Params {
+            wayland_flags: vec![
+                "--ozone-platform=wayland".into(),
+                "--flag with space".into(),
+            ],
+            ..Default::default()
+        };
+
+        let mut script = String::new();
+        Preset::Wayland.write_bash(&params, &mut script).unwrap();
+        assert!(script.contains("args_before+=(--ozone-platform=wayland '--flag with space')"));
+    }
+
+    #[test]
+    fn proxy() {
+        let params = Params {
+            presets: vec![Preset::Proxy],
+            proxy: Some("http://proxy.example.com:3128".into()),
+            no_proxy: Some("localhost,.example.com".into()),
+            ..Default::default()
+        };
+        assert!(validate_options(&params).is_ok());
+
+        let mut script = String::new();
+        Preset::Proxy.write_bash(&params, &mut script).unwrap();
+
+        assert_eq!(
+            script,
+            indoc! {r#"
+                export http_proxy="${http_proxy:-http://proxy.example.com:3128}"
+                export https_proxy="${https_proxy:-http://proxy.example.com:3128}"
+                export HTTP_PROXY="${HTTP_PROXY:-http://proxy.example.com:3128}"
+                export HTTPS_PROXY="${HTTPS_PROXY:-http://proxy.example.com:3128}"
+                export no_proxy="${no_proxy:-localhost,.example.com}"
+                export NO_PROXY="${NO_PROXY:-localhost,.example.com}"
+            "#}
+        );
+
+        let invalid = [
+            Params {
+                proxy: None,
+                ..params.clone()
+            },
+            Params {
+                proxy: Some("http://$(reboot)".into()),
+                ..params.clone()
+            },
+            Params {
+                presets: Vec::new(),
+                ..params.clone()
+            },
+        ];
+
+        for params in invalid {
+            assert!(validate_options(&params).is_err(), "{params:?}");
+        }
+    }
+}
@@ -0,0 +1,301 @@
+use std::{
+    fs, io,
+    os::unix::fs::{FileTypeExt, MetadataExt},
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context;
+
+use crate::{error::IoError, file, i18n::t};
+
+/// A change a script made to the files of a directory it ran on.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Change {
+    Created(PathBuf),
+    Modified(PathBuf),
+    Removed(PathBuf),
+    Renamed { from: PathBuf, to: PathBuf },
+}
+
+impl Change {
+    /// The path the change was made to, which is where a file was renamed from.
+    fn path(&self) -> &Path {
+        match self {
+            Self::Created(path) | Self::Modified(path) | Self::Removed(path) => path,
+            Self::Renamed { from, .. } => from,
+        }
+    }
+
+    /// Describe the change to users.
+    pub fn describe(&self) -> String {
+        let path = |path: &Path| path.display().to_string();
+
+        match self {
+            Self::Created(created) => t!("preview-created", path = path(created)),
+            Self::Modified(modified) => t!("preview-modified", path = path(modified)),
+            Self::Removed(removed) => t!("preview-removed", path = path(removed)),
+            Self::Renamed { from, to } => t!("preview-renamed", from = path(from), to = path(to)),
+        }
+    }
+}
+
+/// Run `script` with bash in a `bwrap` sandbox where each of `dirs` is a throwaway overlay of itself, and return what
+/// it changed in them, leaving the real directories untouched.
+///
+/// Directories that don't exist are replaced by their closest existing ancestor, since only existing directories can be
+/// overlaid.
+pub fn run(script: &str, dirs: &[PathBuf]) -> anyhow::Result<Vec<Change>> {
+    let dirs = overlay_dirs(dirs);
+
+    // the changes are read back from here, so it can't be a directory someone else prepared
+    let temp_dir = file::TempDir::new("preview")?;
+
+    run_in(temp_dir.path(), script, &dirs)
+}
+
+fn run_in(temp_dir: &Path, script: &str, dirs: &[PathBuf]) -> anyhow::Result<Vec<Change>> {
+    let mut command = Command::new("bwrap");
+
+    // everything else is shared with the system, so the script sees the same files it would outside
+    command.args(["--dev-bind", "/", "/"]).args([
+        "--unshare-net",
+        "--unshare-pid",
+        "--die-with-parent",
+    ]);
+
+    let mut uppers = Vec::with_capacity(dirs.len());
+
+    for (index, dir) in dirs.iter().enumerate() {
+        let upper = temp_dir.join(format!("upper-{index}"));
+        let work = temp_dir.join(format!("work-{index}"));
+
+        for path in [&upper, &work] {
+            fs::create_dir(path)
+                .with_context(|| IoError::new(path, t!("preview-create-dir-failed")))?;
+        }
+
+        command
+            .arg("--overlay-src")
+            .arg(dir)
+            .arg("--overlay")
+            .arg(&upper)
+            .arg(&work)
+            .arg(dir);
+
+        uppers.push(upper);
+    }
+
+    let status = match command.args(["bash", "-c", script]).status() {
+        Ok(status) => status,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {
+            anyhow::bail!(t!("preview-no-bwrap"))
+        }
+        Err(err) => return Err(err).with_context(|| t!("preview-bwrap-failed")),
+    };
+
+    anyhow::ensure!(
+        status.success(),
+        t!("preview-script-failed", status = status.to_string())
+    );
+
+    let mut changes = Vec::new();
+
+    for (dir, upper) in dirs.iter().zip(&uppers) {
+        changes.extend(
+            overlay_changes(dir, upper)
+                .with_context(|| IoError::new(upper, t!("preview-read-changes-failed")))?,
+        );
+    }
+
+    Ok(pair_renames(changes, dirs, &uppers))
+}
+
+/// The closest existing ancestor of each of `dirs`, leaving out any that are inside another one.
+fn overlay_dirs(dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let mut existing: Vec<PathBuf> = dirs
+        .iter()
+        .filter_map(|dir| dir.ancestors().find(|ancestor| ancestor.is_dir()))
+        .map(Path::to_path_buf)
+        .collect();
+
+    existing.sort();
+    existing.dedup();
+
+    existing
+        .iter()
+        .filter(|dir| {
+            !existing
+                .iter()
+                .any(|other| other != *dir && dir.starts_with(other))
+        })
+        .cloned()
+        .collect()
+}
+
+/// The changes recorded in the upper directory `upper` of an overlay of `lower`.
+fn overlay_changes(lower: &Path, upper: &Path) -> io::Result<Vec<Change>> {
+    let mut changes = Vec::new();
+
+    for entry in fs::read_dir(upper)? {
+        let entry = entry?;
+        let changed = entry.path();
+        let original = lower.join(entry.file_name());
+        let metadata = entry.metadata()?;
+        let file_type = metadata.file_type();
+
+        let original_metadata = match fs::symlink_metadata(&original) {
+            Ok(metadata) => Some(metadata),
+            Err(err) if err.kind() == io::ErrorKind::NotFound => None,
+            Err(err) => return Err(err),
+        };
+
+        // a removed file is recorded as a character device with the device number 0
+        if file_type.is_char_device() && metadata.rdev() == 0 {
+            changes.push(Change::Removed(original));
+            continue;
+        }
+
+        match original_metadata {
+            None => {
+                changes.push(Change::Created(original.clone()));
+
+                if file_type.is_dir() {
+                    changes.extend(overlay_changes(&original, &changed)?);
+                }
+            }
+            Some(original_metadata) if file_type.is_dir() && original_metadata.is_dir() => {
+                changes.extend(overlay_changes(&original, &changed)?);
+            }
+            // files are copied up when only their metadata is touched, which doesn't count as a change
+            Some(original_metadata) => {
+                if original_metadata.mode() != metadata.mode()
+                    || !same_contents(&original, &changed)?
+                {
+                    changes.push(Change::Modified(original));
+                }
+            }
+        }
+    }
+
+    changes.sort_by(|a, b| a.path().cmp(b.path()));
+    Ok(changes)
+}
+
+/// Whether the files or symlinks at `a` and `b` have the same contents.
+fn same_contents(a: &Path, b: &Path) -> io::Result<bool> {
+    if fs::symlink_metadata(a)?.is_symlink() || fs::symlink_metadata(b)?.is_symlink() {
+        return Ok(fs::read_link(a).ok() == fs::read_link(b).ok());
+    }
+
+    Ok(fs::read(a)? == fs::read(b)?)
+}
+
+/// Replace each removed file that reappears as a created file with the same contents by the rename that moved it,
+/// reading the created files from `uppers`, the upper directories of the overlays of `dirs`.
+fn pair_renames(changes: Vec<Change>, dirs: &[PathBuf], uppers: &[PathBuf]) -> Vec<Change> {
+    // the created files only exist inside the sandbox, so they're read back from where the overlay recorded them
+    let in_upper = |path: &Path| {
+        dirs.iter().zip(uppers).find_map(|(dir, upper)| {
+            path.strip_prefix(dir)
+                .ok()
+                .map(|relative| upper.join(relative))
+        })
+    };
+
+    let mut created: Vec<PathBuf> = changes
+        .iter()
+        .filter_map(|change| match change {
+            Change::Created(path) => Some(path.clone()),
+            _ => None,
+        })
+        .collect();
+
+    let mut renamed = Vec::new();
+
+    let mut paired: Vec<Change> = changes
+        .into_iter()
+        .map(|change| {
+            let Change::Removed(from) = &change else {
+                return change;
+            };
+
+            let Some(index) = created.iter().position(|to| {
+                in_upper(to).is_some_and(|to| same_contents(from, &to).unwrap_or(false))
+            }) else {
+                return change;
+            };
+
+            let to = created.remove(index);
+            renamed.push(to.clone());
+
+            Change::Renamed {
+                from: from.clone(),
+                to,
+            }
+        })
+        .collect();
+
+    paired.retain(|change| !matches!(change, Change::Created(path) if renamed.contains(path)));
+    paired
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{env, slice};
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn dirs_are_deduplicated() {
+        let temp = env::temp_dir();
+
+        assert_eq!(
+            overlay_dirs(&[
+                temp.join("missing").join("nested"),
+                temp.clone(),
+                temp.clone()
+            ]),
+            [temp]
+        );
+    }
+
+    #[test]
+    fn changes_in_upper_dir() {
+        let lower_dir = test_util::temp_dir("preview-lower");
+        let upper_dir = test_util::temp_dir("preview-upper");
+        let (lower, upper) = (lower_dir.path().to_owned(), upper_dir.path().to_owned());
+
+        fs::write(lower.join("copied-up"), "same").unwrap();
+        fs::write(upper.join("copied-up"), "same").unwrap();
+        fs::write(lower.join("modified"), "before").unwrap();
+        fs::write(upper.join("modified"), "after").unwrap();
+        fs::create_dir(upper.join("new-dir")).unwrap();
+        fs::write(upper.join("new-dir").join("new"), "new").unwrap();
+
+        assert_eq!(
+            overlay_changes(&lower, &upper).unwrap(),
+            [
+                Change::Modified(lower.join("modified")),
+                Change::Created(lower.join("new-dir")),
+                Change::Created(lower.join("new-dir").join("new")),
+            ]
+        );
+
+        // a removed file is only recorded by the overlay, so it's paired with what was created from it here
+        let changes = vec![
+            Change::Removed(lower.join("modified")),
+            Change::Created(lower.join("moved")),
+        ];
+        fs::write(upper.join("moved"), "before").unwrap();
+
+        assert_eq!(
+            pair_renames(changes, slice::from_ref(&lower), slice::from_ref(&upper)),
+            [Change::Renamed {
+                from: lower.join("modified"),
+                to: lower.join("moved"),
+            }]
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use std::path::{Path, PathBuf};
+
+use crate::i18n::t;
+
+/// Tracks and reports the progress of an operation spanning multiple executables.
+pub struct Progress {
+    verb: String,
+    total: usize,
+    current: usize,
+    succeeded: usize,
+    /// Items that were already as they should be, so nothing was done to them.
+    skipped: usize,
+    failed: Vec<PathBuf>,
+}
+
+impl Progress {
+    /// `verb` describes the operation being done to each item, such as `wrapping`.
+    pub fn new(verb: String, total: usize) -> Self {
+        Self {
+            verb,
+            total,
+            current: 0,
+            succeeded: 0,
+            skipped: 0,
+            failed: Vec::new(),
+        }
+    }
+
+    /// Report that work on the next item has started.
+    pub fn start(&mut self, path: &Path) {
+        self.current += 1;
+
+        println!(
+            "[{}/{}] {} {}",
+            self.current,
+            self.total,
+            self.verb,
+            path.display()
+        );
+    }
+
+    /// Record the result of the item that was last started.
+    pub fn finish_item(&mut self, path: &Path, result: anyhow::Result<()>) {
+        match result {
+            Ok(()) => self.succeeded += 1,
+            Err(err) => {
+                eprintln!("{}", t!("error", error = format!("{err:#}")));
+                self.failed.push(path.to_path_buf());
+            }
+        }
+    }
+
+    /// Record that the item that was last started needed no changes.
+    pub fn skip_item(&mut self) {
+        self.skipped += 1;
+    }
+
+    /// Print a summary of all items and return an error if any of them failed.
+    pub fn finish(self) -> anyhow::Result<()> {
+        println!();
+        println!("{:<10} {}", t!("progress-succeeded"), self.succeeded);
+        println!("{:<10} {}", t!("progress-skipped"), self.skipped);
+        println!("{:<10} {}", t!("progress-failed"), self.failed.len());
+
+        for path in &self.failed {
+            println!("  {}", path.display());
+        }
+
+        if !self.failed.is_empty() {
+            anyhow::bail!(t!(
+                "progress-items-failed",
+                failed = self.failed.len(),
+                total = self.total,
+            ));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn counts_each_outcome() {
+        let mut progress = Progress::new("testing".into(), 3);
+
+        for (path, outcome) in [
+            ("/a", Some(Ok(()))),
+            ("/b", None),
+            ("/c", Some(Err(anyhow::anyhow!("failed")))),
+        ] {
+            progress.start(Path::new(path));
+
+            match outcome {
+                Some(result) => progress.finish_item(Path::new(path), result),
+                None => progress.skip_item(),
+            }
+        }
+
+        assert_eq!(progress.succeeded, 1);
+        assert_eq!(progress.skipped, 1);
+        assert_eq!(progress.failed, [PathBuf::from("/c")]);
+        assert!(progress.finish().is_err());
+    }
+}
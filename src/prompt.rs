@@ -0,0 +1,38 @@
+use std::io::{self, Write};
+
+use anyhow::Context;
+
+use crate::i18n::t;
+
+/// Ask a yes/no question on stdin, returning `default` if the answer is empty.
+pub fn confirm(question: &str, default: bool) -> anyhow::Result<bool> {
+    let options = if default {
+        t!("prompt-default-yes")
+    } else {
+        t!("prompt-default-no")
+    };
+
+    print!("{question} {options} ");
+    io::stdout()
+        .flush()
+        .with_context(|| t!("prompt-flush-failed"))?;
+
+    let mut answer = String::new();
+    io::stdin()
+        .read_line(&mut answer)
+        .with_context(|| t!("prompt-read-failed"))?;
+
+    let answer = answer.trim();
+
+    if answer.is_empty() {
+        return Ok(default);
+    }
+
+    // English answers are accepted in every language, since they're what scripts piping answers in use
+    let yes_answers = t!("prompt-yes-answers");
+
+    Ok(["y", "yes"]
+        .into_iter()
+        .chain(yes_answers.split(',').map(str::trim))
+        .any(|yes| answer.eq_ignore_ascii_case(yes)))
+}
@@ -0,0 +1,102 @@
+use std::{collections::BTreeSet, fs, path::PathBuf};
+
+use crate::{i18n::t, metadata::Metadata, pacman_hook, symlink, wrapper};
+
+/// Find the paths of every executable wrapped by this program.
+///
+/// Wrappers are found through their metadata files in the directories that commonly hold executables, and through
+/// their `pacman` hooks, which can point anywhere.
+pub fn find_wrapped() -> anyhow::Result<Vec<PathBuf>> {
+    Ok(find_wrapped_in(
+        &symlink::search_dirs(),
+        &pacman_hook::find_all()?,
+    ))
+}
+
+/// Find the paths of the executables wrapped by this program with a metadata file in one of `dirs` or one of the
+/// `pacman` hooks at `hook_paths`.
+pub(crate) fn find_wrapped_in(dirs: &[PathBuf], hook_paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut wrapped = BTreeSet::new();
+
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let filename = entry.file_name();
+
+            let Some(wrapped_filename) =
+                wrapper::ExecPaths::wrapped_filename_from_metadata(&filename.to_string_lossy())
+                    .map(str::to_owned)
+            else {
+                continue;
+            };
+
+            // prefer the recorded path, but the metadata's own location is enough to find the wrapper
+            let path = Metadata::read(&entry.path())
+                .map(|metadata| metadata.executable)
+                .unwrap_or_else(|_| dir.join(wrapped_filename));
+
+            wrapped.insert(path);
+        }
+    }
+
+    for hook_path in hook_paths {
+        let is_hook = hook_path.extension().is_some_and(|ext| ext == "hook");
+
+        if let Some(target) = is_hook
+            .then(|| pacman_hook::read_target(hook_path))
+            .flatten()
+        {
+            wrapped.insert(target);
+        }
+    }
+
+    wrapped.into_iter().collect()
+}
+
+/// Find the paths of every executable whose wrapper has any of `tags`.
+pub fn find_tagged(tags: &[String]) -> anyhow::Result<Vec<PathBuf>> {
+    let mut tagged = Vec::new();
+
+    for path in find_wrapped()? {
+        // wrappers without readable metadata have no tags to go by
+        let has_tag = wrapper::ExecPaths::try_from_path(&path)
+            .and_then(|paths| Metadata::read(&paths.metadata.original))
+            .is_ok_and(|metadata| metadata.params.tags.iter().any(|tag| tags.contains(tag)));
+
+        if has_tag {
+            tagged.push(path);
+        }
+    }
+
+    anyhow::ensure!(
+        !tagged.is_empty(),
+        t!(
+            "registry-no-tagged",
+            tags = tags
+                .iter()
+                .map(|tag| format!("`{tag}`"))
+                .collect::<Vec<_>>()
+                .join(&t!("registry-tag-separator")),
+        )
+    );
+
+    Ok(tagged)
+}
+
+/// Every tag given to a wrapper, in order.
+pub fn find_tags() -> anyhow::Result<BTreeSet<String>> {
+    let mut tags = BTreeSet::new();
+
+    for path in find_wrapped()? {
+        if let Ok(metadata) = wrapper::ExecPaths::try_from_path(&path)
+            .and_then(|paths| Metadata::read(&paths.metadata.original))
+        {
+            tags.extend(metadata.params.tags);
+        }
+    }
+
+    Ok(tags)
+}
@@ -0,0 +1,122 @@
+use std::{
+    fs::File,
+    io::{BufRead, BufReader, Read},
+    path::Path,
+};
+
+/// Longest shebang line the kernel reads, past which the interpreter line is cut off.
+const MAX_SHEBANG_LEN: u64 = 256;
+
+/// Longest script that is searched for references to its own path, since larger files are unlikely to be scripts.
+const MAX_SCRIPT_LEN: u64 = 1024 * 1024;
+
+/// Ways scripts commonly find their own path, such as to locate data next to them.
+const SELF_REFERENCES: &[&str] = &[
+    "$0",
+    "${0",
+    "BASH_SOURCE",
+    "__FILE__",
+    "__dir__",
+    "__file__",
+    "sys.argv[0]",
+    "$PROGRAM_NAME",
+    "FindBin",
+    "process.argv[1]",
+];
+
+/// Returns the interpreter of the script at `path` along with its argument, if it starts with a shebang.
+///
+/// Like the kernel, everything after the interpreter is passed to it as a single argument.
+pub fn read_shebang(path: &Path) -> Option<Vec<String>> {
+    let file = File::open(path).ok()?;
+
+    let mut line = Vec::new();
+    BufReader::new(file.take(MAX_SHEBANG_LEN))
+        .read_until(b'\n', &mut line)
+        .ok()?;
+
+    let line = std::str::from_utf8(line.strip_prefix(b"#!")?).ok()?.trim();
+    let (interpreter, arg) = line
+        .split_once([' ', '\t'])
+        .map_or((line, ""), |(interpreter, arg)| (interpreter, arg.trim()));
+
+    if interpreter.is_empty() {
+        return None;
+    }
+
+    Some(
+        [interpreter, arg]
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .map(str::to_owned)
+            .collect(),
+    )
+}
+
+/// Returns the ways the script at `path` refers to its own path, which will be that of the original script once it's
+/// moved out of the way.
+pub fn self_references(path: &Path) -> Vec<&'static str> {
+    let mut contents = Vec::new();
+
+    let read =
+        File::open(path).and_then(|file| file.take(MAX_SCRIPT_LEN).read_to_end(&mut contents));
+
+    if read.is_err() {
+        return Vec::new();
+    }
+
+    let contents = String::from_utf8_lossy(&contents);
+
+    SELF_REFERENCES
+        .iter()
+        .copied()
+        .filter(|pattern| contents.contains(pattern))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{fs, path::PathBuf};
+
+    use super::*;
+    use crate::{file::TempDir, test_util};
+
+    /// A script with `contents`, in a directory that's removed when the returned one is dropped.
+    fn script(name: &str, contents: &str) -> (TempDir, PathBuf) {
+        let dir = test_util::temp_dir(name);
+        let path = dir.path().join("script");
+
+        fs::write(&path, contents).unwrap();
+        (dir, path)
+    }
+
+    #[test]
+    fn shebangs() {
+        let cases = [
+            ("#!/bin/sh\n", Some(vec!["/bin/sh"])),
+            (
+                "#! /usr/bin/env  python3 -u\n",
+                Some(vec!["/usr/bin/env", "python3 -u"]),
+            ),
+            ("#!\n", None),
+            ("\x7fELF", None),
+        ];
+
+        for (contents, expected) in cases {
+            let (_dir, path) = script("shebang", contents);
+            let expected = expected.map(|parts| parts.into_iter().map(String::from).collect());
+
+            assert_eq!(read_shebang(&path), expected, "{contents:?}");
+        }
+    }
+
+    #[test]
+    fn finds_self_references() {
+        let (_dir, path) = script(
+            "self-references",
+            "#!/bin/bash\ndata=\"$(dirname \"${BASH_SOURCE[0]}\")/data\"\n",
+        );
+
+        assert_eq!(self_references(&path), ["BASH_SOURCE"]);
+    }
+}
@@ -0,0 +1,100 @@
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context;
+
+use crate::{error::IoError, file, i18n::t, shell};
+
+/// Prefix of the names of session directories, which end with the process ID of the shell they belong to.
+const DIR_PREFIX: &str = concat!(env!("CARGO_PKG_NAME"), "-session-");
+
+/// Directory holding the session directories, which is cleared at logout if it's the runtime directory of the user.
+fn base_dir() -> PathBuf {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .unwrap_or_else(env::temp_dir)
+}
+
+/// Directory of the wrappers of the shell with the process ID `shell_pid`.
+pub fn dir(shell_pid: u32) -> PathBuf {
+    base_dir().join(format!("{DIR_PREFIX}{shell_pid}"))
+}
+
+/// Write `wrapper_script` as the wrapper named `filename` in the session of the shell with the process ID `shell_pid`,
+/// replacing any it already has, and return its path.
+pub fn install(shell_pid: u32, filename: &str, wrapper_script: &str) -> anyhow::Result<PathBuf> {
+    remove_stale();
+
+    let dir = dir(shell_pid);
+
+    fs::create_dir_all(&dir)
+        .with_context(|| IoError::new(&dir, t!("session-create-dir-failed")))?;
+
+    let path = dir.join(filename);
+
+    // only the user running the session can use its wrappers
+    file::write_atomic(&path, wrapper_script.as_bytes(), 0o700)
+        .with_context(|| IoError::new(&path, t!("wrapper-write-failed")))?;
+
+    Ok(path)
+}
+
+/// The line that puts `dir` first in `$PATH` when evaluated by a shell.
+pub fn path_export(dir: &Path) -> String {
+    format!(
+        r#"export PATH={}:"$PATH""#,
+        shell::quote(&dir.to_string_lossy())
+    )
+}
+
+/// Remove the session directories of shells that have exited. Failures are ignored, since the directories may belong
+/// to other users.
+fn remove_stale() {
+    let Ok(entries) = fs::read_dir(base_dir()) else {
+        return;
+    };
+
+    for entry in entries.flatten() {
+        let name = entry.file_name();
+
+        let pid = name
+            .to_str()
+            .and_then(|name| name.strip_prefix(DIR_PREFIX))
+            .and_then(|pid| pid.parse::<u32>().ok());
+
+        if pid.is_some_and(|pid| !is_running(pid)) {
+            let _ = fs::remove_dir_all(entry.path());
+        }
+    }
+}
+
+/// Whether a process with the ID `pid` is running.
+fn is_running(pid: u32) -> bool {
+    // `kill` fails for processes of other users too, whose directories couldn't be removed anyway
+    Command::new("kill")
+        .args(["-0", &pid.to_string()])
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exports_quoted_path() {
+        assert_eq!(
+            path_export(Path::new("/run/user/1000/wrapperize-session-42")),
+            r#"export PATH=/run/user/1000/wrapperize-session-42:"$PATH""#
+        );
+        assert_eq!(
+            path_export(Path::new("/tmp/a dir")),
+            r#"export PATH='/tmp/a dir':"$PATH""#
+        );
+    }
+}
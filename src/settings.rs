@@ -0,0 +1,284 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    sync::OnceLock,
+};
+
+use anyhow::Context;
+use serde::Deserialize;
+
+use crate::{error::IoError, escalate, i18n::t, pacman_hook, plugin};
+
+/// Config file with the defaults of every user.
+pub const SYSTEM_CONFIG_PATH: &str = concat!("/etc/", env!("CARGO_PKG_NAME"), "/config.toml");
+
+/// Start of the names of the environment variables that override the config files.
+const ENV_PREFIX: &str = "WRAPPERIZE_";
+
+/// Shebang interpreter of generated scripts when no shell is set.
+const DEFAULT_SHELL: &str = "/usr/bin/env bash";
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Defaults for options that aren't given on the command line.
+///
+/// Each one is taken from the first of these that sets it:
+/// 1. a `WRAPPERIZE_*` environment variable, such as `WRAPPERIZE_STORE_DIR`
+/// 2. the config file of the user, at `$XDG_CONFIG_HOME/wrapperize/config.toml`
+/// 3. the system config file, at `/etc/wrapperize/config.toml`
+#[derive(Debug, Default, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Settings {
+    /// Directory the `pacman` hooks are written to, which has to be a `HookDir` in the `pacman` configuration.
+    pub hook_dir: Option<PathBuf>,
+    /// Backend plugin that recreates new wrappers instead of `pacman` hooks.
+    pub backend: Option<String>,
+    /// Absolute path of the bash the generated scripts run with.
+    pub shell: Option<PathBuf>,
+    /// Directory new wrappers move their original executable into.
+    pub store_dir: Option<PathBuf>,
+    /// Whether new wrappers use the strict bash options.
+    pub strict: Option<bool>,
+    /// Program to run this one again as root with when it's denied access to a file.
+    pub escalate: Option<escalate::Tool>,
+}
+
+impl Settings {
+    /// Read the settings from the config files and the environment.
+    fn load() -> anyhow::Result<Self> {
+        let mut settings = Self::default();
+
+        for path in [Some(PathBuf::from(SYSTEM_CONFIG_PATH)), user_config_path()]
+            .into_iter()
+            .flatten()
+        {
+            if let Some(file) = Self::read(&path)? {
+                settings.merge(file);
+            }
+        }
+
+        settings.merge(Self::from_env(|name| env::var(name).ok())?);
+        Ok(settings)
+    }
+
+    /// Read the config file at `path`, or return `None` if it doesn't exist.
+    fn read(path: &Path) -> anyhow::Result<Option<Self>> {
+        let content = match fs::read_to_string(path) {
+            Ok(content) => content,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(None),
+            Err(err) => return Err(IoError::new(path, anyhow::Error::new(err)).into()),
+        };
+
+        let settings: Self = toml::from_str(&content)
+            .map_err(anyhow::Error::new)
+            .and_then(|settings: Self| settings.validate().map(|()| settings))
+            .with_context(|| IoError::new(path, t!("settings-invalid-config")))?;
+
+        Ok(Some(settings))
+    }
+
+    /// Read the settings set by `WRAPPERIZE_*` environment variables, which are looked up with `var`.
+    fn from_env(var: impl Fn(&str) -> Option<String>) -> anyhow::Result<Self> {
+        let var =
+            |name: &str| var(&format!("{ENV_PREFIX}{name}")).filter(|value| !value.is_empty());
+
+        let strict = var("STRICT")
+            .map(|value| match value.as_str() {
+                "1" | "true" | "yes" => Ok(true),
+                "0" | "false" | "no" => Ok(false),
+                _ => Err(anyhow::anyhow!(t!(
+                    "settings-invalid-strict",
+                    var = format!("{ENV_PREFIX}STRICT"),
+                    value = value.as_str(),
+                ))),
+            })
+            .transpose()?;
+
+        let escalate = var("ESCALATE")
+            .map(|value| {
+                value.parse().map_err(|_| {
+                    anyhow::anyhow!(t!(
+                        "settings-invalid-escalate",
+                        var = format!("{ENV_PREFIX}ESCALATE"),
+                        value = value.as_str(),
+                    ))
+                })
+            })
+            .transpose()?;
+
+        let settings = Self {
+            hook_dir: var("HOOK_DIR").map(PathBuf::from),
+            backend: var("BACKEND"),
+            shell: var("SHELL").map(PathBuf::from),
+            store_dir: var("STORE_DIR").map(PathBuf::from),
+            strict,
+            escalate,
+        };
+
+        settings
+            .validate()
+            .with_context(|| t!("settings-invalid-env"))?;
+        Ok(settings)
+    }
+
+    /// Replace every setting that `other` sets.
+    fn merge(&mut self, other: Self) {
+        let Self {
+            hook_dir,
+            backend,
+            shell,
+            store_dir,
+            strict,
+            escalate,
+        } = other;
+
+        self.hook_dir = hook_dir.or(self.hook_dir.take());
+        self.backend = backend.or(self.backend.take());
+        self.shell = shell.or(self.shell.take());
+        self.store_dir = store_dir.or(self.store_dir.take());
+        self.strict = strict.or(self.strict);
+        self.escalate = escalate.or(self.escalate);
+    }
+
+    fn validate(&self) -> anyhow::Result<()> {
+        for (name, path) in [
+            ("hook_dir", &self.hook_dir),
+            ("shell", &self.shell),
+            ("store_dir", &self.store_dir),
+        ] {
+            if let Some(path) = path {
+                anyhow::ensure!(
+                    path.is_absolute(),
+                    t!(
+                        "settings-path-relative",
+                        name = name,
+                        path = path.display().to_string(),
+                    )
+                );
+            }
+        }
+
+        if let Some(backend) = &self.backend {
+            plugin::validate_name(backend)?;
+        }
+
+        Ok(())
+    }
+
+    /// Directory of the `pacman` hooks.
+    pub fn hook_dir(&self) -> &Path {
+        self.hook_dir
+            .as_deref()
+            .unwrap_or(Path::new(pacman_hook::DEFAULT_HOOK_DIR))
+    }
+
+    /// Shebang line of generated scripts, without the `#!`.
+    pub fn shebang(&self) -> String {
+        self.shell.as_deref().map_or_else(
+            || DEFAULT_SHELL.to_owned(),
+            |shell| shell.display().to_string(),
+        )
+    }
+
+    /// Program that runs generated scripts directly.
+    pub fn shell_program(&self) -> &Path {
+        self.shell.as_deref().unwrap_or(Path::new("bash"))
+    }
+}
+
+/// Read the settings, which has to happen before [`get`] for them to apply.
+pub fn init() -> anyhow::Result<()> {
+    let settings = Settings::load()?;
+    let _ = SETTINGS.set(settings);
+    Ok(())
+}
+
+/// Read the settings unless they already have been, so they're read once and then kept for the rest of the process.
+#[cfg(feature = "ffi")]
+pub fn init_once() -> anyhow::Result<()> {
+    if SETTINGS.get().is_some() {
+        return Ok(());
+    }
+
+    init()
+}
+
+/// The settings read by [`init`], or the built-in defaults if it hasn't been called, such as in tests.
+pub fn get() -> &'static Settings {
+    SETTINGS.get_or_init(Settings::default)
+}
+
+/// Base directory of the config files of the current user, following the XDG base directory specification.
+pub fn config_home() -> Option<PathBuf> {
+    env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| Path::new(&home).join(".config")))
+}
+
+/// Path of the config file of the current user, if their config directory is known.
+fn user_config_path() -> Option<PathBuf> {
+    config_home().map(|dir| dir.join(env!("CARGO_PKG_NAME")).join("config.toml"))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use super::*;
+
+    fn from_env(vars: &[(&str, &str)]) -> anyhow::Result<Settings> {
+        let vars = vars.iter().copied().collect::<HashMap<_, _>>();
+        Settings::from_env(|name| vars.get(name).map(|value| value.to_string()))
+    }
+
+    #[test]
+    fn later_sources_take_precedence() {
+        let mut settings: Settings = toml::from_str(indoc::indoc! {r#"
+            hook_dir = "/etc/pacman.d/wrapperize-hooks"
+            store_dir = "/usr/lib/wrapperize"
+            strict = false
+        "#})
+        .unwrap();
+
+        settings.merge(Settings {
+            store_dir: Some("/opt/unwrapped".into()),
+            backend: Some("apt".to_owned()),
+            ..Default::default()
+        });
+
+        settings
+            .merge(from_env(&[("WRAPPERIZE_STRICT", "true"), ("WRAPPERIZE_BACKEND", "")]).unwrap());
+        settings.merge(from_env(&[("WRAPPERIZE_ESCALATE", "doas")]).unwrap());
+
+        assert_eq!(
+            settings,
+            Settings {
+                hook_dir: Some("/etc/pacman.d/wrapperize-hooks".into()),
+                backend: Some("apt".to_owned()),
+                shell: None,
+                store_dir: Some("/opt/unwrapped".into()),
+                strict: Some(true),
+                escalate: Some(escalate::Tool::Doas),
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_settings() {
+        assert!(from_env(&[("WRAPPERIZE_STRICT", "maybe")]).is_err());
+        assert!(from_env(&[("WRAPPERIZE_ESCALATE", "su")]).is_err());
+        assert!(from_env(&[("WRAPPERIZE_STORE_DIR", "relative")]).is_err());
+        assert!(from_env(&[("WRAPPERIZE_BACKEND", "../apt")]).is_err());
+        assert!(toml::from_str::<Settings>("hooks_dir = \"/etc\"").is_err());
+    }
+
+    #[test]
+    fn built_in_defaults() {
+        let settings = Settings::default();
+
+        assert_eq!(settings.hook_dir(), Path::new("/etc/pacman.d/hooks"));
+        assert_eq!(settings.shebang(), "/usr/bin/env bash");
+        assert_eq!(settings.shell_program(), Path::new("bash"));
+    }
+}
@@ -0,0 +1,59 @@
+use std::borrow::Cow;
+
+/// Quote a string so bash treats it as a single literal word.
+///
+/// Strings made up entirely of characters that have no special meaning to bash are returned as-is.
+pub fn quote(value: &str) -> Cow<'_, str> {
+    if !value.is_empty() && value.chars().all(is_safe_char) {
+        return value.into();
+    }
+
+    // single quotes can't be escaped inside single quotes, so close the quote, add an escaped quote, and reopen it
+    format!("'{}'", value.replace('\'', r"'\''")).into()
+}
+
+/// Quote a string so fish treats it as a single literal word.
+pub fn quote_fish(value: &str) -> Cow<'_, str> {
+    if !value.is_empty() && value.chars().all(is_safe_char) {
+        return value.into();
+    }
+
+    // unlike bash, fish allows escaping quotes and backslashes inside single quotes
+    format!("'{}'", value.replace('\\', r"\\").replace('\'', r"\'")).into()
+}
+
+fn is_safe_char(ch: char) -> bool {
+    ch.is_ascii_alphanumeric()
+        || matches!(
+            ch,
+            '-' | '_' | '=' | '.' | '/' | ',' | ':' | '+' | '@' | '%'
+        )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn safe_strings_are_unquoted() {
+        assert_eq!(quote("--flag=value"), "--flag=value");
+        assert_eq!(quote("/usr/bin/test"), "/usr/bin/test");
+    }
+
+    #[test]
+    fn special_strings_are_quoted() {
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("has space"), "'has space'");
+        assert_eq!(quote("*.glob"), "'*.glob'");
+        assert_eq!(quote("$HOME"), "'$HOME'");
+        assert_eq!(quote(r#"it's "quoted""#), r#"'it'\''s "quoted"'"#);
+    }
+
+    #[test]
+    fn special_strings_are_quoted_for_fish() {
+        assert_eq!(quote_fish("--flag=value"), "--flag=value");
+        assert_eq!(quote_fish("has space"), "'has space'");
+        assert_eq!(quote_fish("$HOME"), "'$HOME'");
+        assert_eq!(quote_fish(r"it's a \"), r"'it\'s a \\'");
+    }
+}
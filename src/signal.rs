@@ -0,0 +1,139 @@
+use std::fmt;
+
+use indoc::writedoc;
+
+/// Signals sent to the wrapper that are passed on to the executable when it runs as a child of the wrapper.
+///
+/// Signals from the terminal, such as an interrupt from Ctrl+C, reach both anyway, but ones sent to the wrapper alone
+/// by `kill` or a service manager would otherwise leave the executable running.
+const FORWARDED_SIGNALS: [&str; 3] = ["TERM", "INT", "HUP"];
+
+/// Write the lines that run `command` as a child of the wrapper and wait for it to exit, for wrappers that have
+/// something to do after the executable exits, so they can't replace themselves with it.
+///
+/// Signals sent to the wrapper while the child runs are forwarded to it, and its exit status is left in `$status`.
+pub fn write_run_as_child(command: &str, mut writer: impl fmt::Write) -> fmt::Result {
+    writedoc!(
+        writer,
+        r#"
+            child=
+            interrupted=
+            forward_signal() {{
+                interrupted=1
+                if [ -n "$child" ]; then
+                    kill -s "$1" "$child" 2>/dev/null || true
+                fi
+            }}
+        "#
+    )?;
+
+    for signal in FORWARDED_SIGNALS {
+        writeln!(writer, "trap 'forward_signal {signal}' {signal}")?;
+    }
+
+    // without job control, a background command ignores interrupts, which the executable would inherit, and reads
+    // from `/dev/null` instead of the wrapper's stdin
+    writedoc!(
+        writer,
+        r#"
+            ( trap - INT QUIT; exec {command} ) <&0 &
+            child=$!
+            status=0
+            wait "$child" || status=$?
+            while [ -n "$interrupted" ]; do
+                interrupted=
+                next_status=0
+                wait "$child" 2>/dev/null || next_status=$?
+                if [ "$next_status" -ne 127 ]; then
+                    status=$next_status
+                fi
+            done
+        "#
+    )
+}
+
+/// Write the lines that exit the wrapper with the status of a child run by [`write_run_as_child`].
+///
+/// If the child was killed by a signal, the wrapper kills itself with the same one, so whatever started the wrapper
+/// sees the same cause of death as if the executable had been run directly.
+pub fn write_exit(mut writer: impl fmt::Write) -> fmt::Result {
+    writedoc!(
+        writer,
+        r#"
+            if [ "$status" -gt 128 ] && signal="$(kill -l "$status" 2>/dev/null)"; then
+                trap - "$signal"
+                kill -s "$signal" "$$"
+            fi
+            exit "$status"
+        "#
+    )
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::{
+        io::Write,
+        os::unix::process::ExitStatusExt,
+        process::{Child, Command, Stdio},
+        thread,
+        time::Duration,
+    };
+
+    use super::*;
+
+    fn spawn(child_script: &str) -> Child {
+        let mut script = String::from("set -euo pipefail\n");
+        write_run_as_child(r#"bash -c "$1""#, &mut script).unwrap();
+        write_exit(&mut script).unwrap();
+
+        Command::new("bash")
+            .arg("-c")
+            .arg(script)
+            .arg("wrapper")
+            .arg(child_script)
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .spawn()
+            .unwrap()
+    }
+
+    fn send(child: &Child, signal: &str) {
+        // give the wrapper time to set up its traps and start the child
+        thread::sleep(Duration::from_millis(300));
+
+        let status = Command::new("kill")
+            .args(["-s", signal, &child.id().to_string()])
+            .status()
+            .unwrap();
+        assert!(status.success());
+    }
+
+    #[test]
+    fn passes_exit_status_and_stdin() {
+        let mut child = spawn("read -r line; echo \"$line\"; exit 7");
+        child.stdin.take().unwrap().write_all(b"input\n").unwrap();
+
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.status.code(), Some(7));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "input\n");
+    }
+
+    #[test]
+    fn forwards_signals() {
+        let child = spawn("trap 'echo terminated; exit 3' TERM; sleep 5 >/dev/null & wait");
+        send(&child, "TERM");
+
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.status.code(), Some(3));
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "terminated\n");
+    }
+
+    #[test]
+    fn dies_from_the_same_signal() {
+        let child = spawn("sleep 5");
+        send(&child, "HUP");
+
+        let output = child.wait_with_output().unwrap();
+        assert_eq!(output.status.signal(), Some(1));
+    }
+}
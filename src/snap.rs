@@ -0,0 +1,92 @@
+use std::{
+    env, fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{i18n::t, shell};
+
+/// Directory that `snapd` places the commands of installed snaps in, which can't be modified.
+const BIN_DIR: &str = "/snap/bin";
+
+/// Directory the shim for a snap command is placed in by default, which comes before [`BIN_DIR`] in the default
+/// `$PATH`.
+pub const DEFAULT_SHIM_DIR: &str = "/usr/local/bin";
+
+/// Returns the name of the snap app that `path` runs, if it's one of the commands of an installed snap.
+pub fn app_name(path: &Path) -> Option<&str> {
+    let name = path.strip_prefix(BIN_DIR).ok()?.to_str()?;
+    (!name.is_empty() && !name.contains('/')).then_some(name)
+}
+
+/// Path of the command that runs the snap app named `app`.
+pub fn command_path(app: &str) -> PathBuf {
+    Path::new(BIN_DIR).join(app)
+}
+
+/// Returns true if `path` is a symlink to the command of a snap app, which stands in for the original executable of
+/// a shim.
+pub fn is_command_link(path: &Path) -> bool {
+    path.read_link()
+        .is_ok_and(|target| app_name(&target).is_some())
+}
+
+/// Check that `app` is the name of a snap app, such as `firefox` or `lxd.lxc`.
+pub fn validate_app(app: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        !app.is_empty()
+            && app
+                .chars()
+                .all(|ch| ch.is_ascii_alphanumeric() || matches!(ch, '-' | '.' | '_')),
+        t!("snap-invalid-app", app = app)
+    );
+
+    Ok(())
+}
+
+/// Returns true if a command in `shim_dir` is found before the commands of snaps when searching `$PATH`.
+pub fn shim_dir_takes_precedence(shim_dir: &Path) -> bool {
+    let Some(path) = env::var_os("PATH") else {
+        return false;
+    };
+
+    let dirs = env::split_paths(&path).collect::<Vec<_>>();
+    let position = |dir: &Path| dirs.iter().position(|entry| entry == dir);
+
+    match (position(shim_dir), position(Path::new(BIN_DIR))) {
+        (Some(shim), Some(snap)) => shim < snap,
+        (Some(_), None) => true,
+        (None, _) => false,
+    }
+}
+
+/// Write the command that runs the snap app named `app`, which the arguments for the app are appended to.
+///
+/// The app has to be run through `snap run`, since its command decides which app to run from the name it was
+/// invoked with.
+pub fn write_command(app: &str, mut writer: impl fmt::Write) -> fmt::Result {
+    write!(writer, "snap run {}", shell::quote(app))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn snap_commands() {
+        assert_eq!(app_name(Path::new("/snap/bin/lxd.lxc")), Some("lxd.lxc"));
+        assert_eq!(app_name(Path::new("/snap/bin")), None);
+        assert_eq!(
+            app_name(Path::new("/snap/firefox/current/usr/bin/firefox")),
+            None
+        );
+        assert_eq!(app_name(Path::new("/usr/bin/firefox")), None);
+
+        let mut command = String::new();
+        write_command("firefox", &mut command).unwrap();
+        assert_eq!(command, "snap run firefox");
+
+        assert!(validate_app("lxd.lxc").is_ok());
+        assert!(validate_app("").is_err());
+        assert!(validate_app("$(reboot)").is_err());
+    }
+}
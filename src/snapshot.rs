@@ -0,0 +1,88 @@
+use std::{io, process::Command};
+
+use anyhow::Context;
+
+use crate::i18n::t;
+
+/// Programs that can take a snapshot of the system, in the order they're tried.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Tool {
+    Snapper,
+    Timeshift,
+}
+
+impl Tool {
+    const ALL: [Self; 2] = [Self::Snapper, Self::Timeshift];
+
+    fn program(self) -> &'static str {
+        match self {
+            Self::Snapper => "snapper",
+            Self::Timeshift => "timeshift",
+        }
+    }
+
+    fn args(self, description: &str) -> Vec<&str> {
+        match self {
+            // the number cleanup algorithm lets snapper prune these along with the ones taken by pacman hooks
+            Self::Snapper => vec![
+                "create",
+                "--description",
+                description,
+                "--cleanup-algorithm",
+                "number",
+            ],
+            Self::Timeshift => vec!["--create", "--scripted", "--comments", description],
+        }
+    }
+}
+
+/// Take a snapshot of the system with the first available tool out of `snapper` and `timeshift`, so a change made
+/// to system files can be undone as a whole.
+pub fn create(description: &str) -> anyhow::Result<()> {
+    for tool in Tool::ALL {
+        let status = match Command::new(tool.program())
+            .args(tool.args(description))
+            .status()
+        {
+            Ok(status) => status,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => continue,
+            Err(err) => {
+                return Err(err).with_context(|| t!("snapshot-run-failed", tool = tool.program()));
+            }
+        };
+
+        if !status.success() {
+            anyhow::bail!(t!(
+                "snapshot-failed",
+                tool = tool.program(),
+                status = status.to_string(),
+            ));
+        }
+
+        println!(
+            "{}",
+            t!(
+                "snapshot-created",
+                tool = tool.program(),
+                description = description
+            )
+        );
+        return Ok(());
+    }
+
+    anyhow::bail!(t!("snapshot-no-tool"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn description_is_passed_as_single_arg() {
+        let description = "wrapperize: wrap /usr/bin/my app";
+
+        for tool in Tool::ALL {
+            assert!(tool.args(description).contains(&description));
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use std::fmt;
+
+use indoc::writedoc;
+
+use crate::i18n::t;
+
+/// Returns an error if `path` can't be used as the status file of a wrapper.
+///
+/// Wrappers run in whatever directory they're started from, so the path has to be absolute, or start with a variable
+/// such as `$XDG_STATE_HOME`.
+pub fn validate(path: &str) -> anyhow::Result<()> {
+    anyhow::ensure!(
+        path.starts_with('/') || path.starts_with('$'),
+        t!("status-file-relative", path = path)
+    );
+
+    Ok(())
+}
+
+/// Write the line that records when the executable was started, which has to come before it's run.
+pub fn write_start(mut writer: impl fmt::Write) -> fmt::Result {
+    writeln!(writer, "printf -v started '%(%FT%T%z)T' -1")
+}
+
+/// Write the lines that replace the file at `path` with the exit status, process ID and start and finish times of the
+/// executable, once a child run by [`crate::signal::write_run_as_child`] has exited.
+///
+/// Variables in `path` are expanded when the wrapper runs. The file is replaced in one step, so it's never seen half
+/// written, and failing to write it doesn't change the exit status of the wrapper. If `relax_unset` is set, unset
+/// variables in `path` are expanded to nothing instead of failing with `set -u`.
+pub fn write_report(path: &str, relax_unset: bool, mut writer: impl fmt::Write) -> fmt::Result {
+    writeln!(writer, "printf -v finished '%(%FT%T%z)T' -1")?;
+
+    if relax_unset {
+        writeln!(writer, "set +u")?;
+    }
+
+    writeln!(writer, r#"status_file="{}""#, path.replace('"', "\\\""))?;
+
+    if relax_unset {
+        writeln!(writer, "set -u")?;
+    }
+
+    writedoc!(
+        writer,
+        r#"
+            {{
+                mkdir -p "$(dirname "$status_file")" &&
+                    printf 'status=%s\npid=%s\nstarted=%s\nfinished=%s\n' \
+                        "$status" "$child" "$started" "$finished" >"$status_file.$$" &&
+                    mv -f "$status_file.$$" "$status_file"
+            }} 2>/dev/null || rm -f "$status_file.$$"
+        "#
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn validates_paths() {
+        assert!(validate("/var/lib/status/app").is_ok());
+        assert!(validate("$XDG_RUNTIME_DIR/app.status").is_ok());
+        assert!(validate("${HOME}/.local/state/app").is_ok());
+        assert!(validate("app.status").is_err());
+        assert!(validate("~/app.status").is_err());
+    }
+}
@@ -0,0 +1,132 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+};
+
+use anyhow::Context;
+
+use crate::{error::IoError, i18n::t};
+
+/// Directories that are always searched for symlinks, in addition to the ones in `$PATH`.
+const DEFAULT_SEARCH_DIRS: &[&str] = &["/usr/bin", "/usr/local/bin", "/usr/sbin", "/opt"];
+
+/// Returns the directories that commonly hold symlinks to executables, without duplicates.
+pub fn search_dirs() -> Vec<PathBuf> {
+    let path_dirs = env::var_os("PATH")
+        .map(|path| env::split_paths(&path).collect::<Vec<_>>())
+        .unwrap_or_default();
+
+    let mut dirs = Vec::new();
+
+    for dir in DEFAULT_SEARCH_DIRS
+        .iter()
+        .map(PathBuf::from)
+        .chain(path_dirs)
+    {
+        // directories like `/bin` are commonly symlinks to others, so resolve them to avoid searching twice
+        let Ok(dir) = fs::canonicalize(dir) else {
+            continue;
+        };
+
+        if !dirs.contains(&dir) {
+            dirs.push(dir);
+        }
+    }
+
+    dirs
+}
+
+/// Find all symlinks directly inside `dirs` that resolve to `target`.
+pub fn find_pointing_at(target: &Path, dirs: &[PathBuf]) -> Vec<PathBuf> {
+    let Ok(target) = fs::canonicalize(target) else {
+        return Vec::new();
+    };
+
+    let mut links = Vec::new();
+
+    for dir in dirs {
+        let Ok(entries) = fs::read_dir(dir) else {
+            continue;
+        };
+
+        for entry in entries.flatten() {
+            let is_symlink = entry.file_type().is_ok_and(|ty| ty.is_symlink());
+
+            if is_symlink && fs::canonicalize(entry.path()).is_ok_and(|path| path == target) {
+                links.push(entry.path());
+            }
+        }
+    }
+
+    links.sort();
+    links
+}
+
+/// Atomically replace the symlink at `link` with one pointing at `new_target`.
+pub fn repoint(link: &Path, new_target: &Path) -> anyhow::Result<()> {
+    let file_name = link
+        .file_name()
+        .with_context(|| IoError::new(link, t!("symlink-no-file-name")))?;
+
+    let temp_link = link.with_file_name(format!(
+        ".{}-{}-tmp",
+        file_name.to_string_lossy(),
+        env!("CARGO_PKG_NAME")
+    ));
+
+    match fs::remove_file(&temp_link) {
+        Ok(()) => {}
+        Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+        Err(err) => {
+            return Err(IoError::new(&temp_link, anyhow::Error::new(err)).into());
+        }
+    }
+
+    symlink(new_target, &temp_link)
+        .with_context(|| IoError::new(&temp_link, t!("symlink-create-failed")))?;
+
+    fs::rename(&temp_link, link).with_context(|| IoError::new(link, t!("symlink-replace-failed")))
+}
+
+#[cfg(unix)]
+fn symlink(target: &Path, link: &Path) -> io::Result<()> {
+    std::os::unix::fs::symlink(target, link)
+}
+
+#[cfg(not(unix))]
+fn symlink(_target: &Path, _link: &Path) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        t!("symlink-unsupported"),
+    ))
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use std::os::unix;
+
+    use super::*;
+    use crate::test_util;
+
+    #[test]
+    fn finds_and_repoints_links() {
+        let temp_dir = test_util::temp_dir("symlinks");
+        let dirs = [temp_dir.path().to_owned()];
+        let dir = &dirs[0];
+        let target = dir.join("vim");
+        let other = dir.join("other");
+
+        fs::write(&target, "").unwrap();
+        fs::write(&other, "").unwrap();
+        unix::fs::symlink("vim", dir.join("vi")).unwrap();
+        unix::fs::symlink("vi", dir.join("view")).unwrap();
+        unix::fs::symlink(&other, dir.join("unrelated")).unwrap();
+
+        let links = find_pointing_at(&target, &dirs);
+        assert_eq!(links, [dir.join("vi"), dir.join("view")]);
+
+        repoint(&dir.join("vi"), &other).unwrap();
+        assert_eq!(fs::read_link(dir.join("vi")).unwrap(), other);
+        assert_eq!(find_pointing_at(&target, &dirs), Vec::<PathBuf>::new());
+    }
+}
@@ -0,0 +1,191 @@
+use std::{
+    fmt::{self, Write},
+    io::{self, IsTerminal},
+};
+
+/// How rows of values are printed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, strum::EnumString)]
+#[strum(serialize_all = "lowercase")]
+pub enum Format {
+    /// Columns aligned under a header, for reading in a terminal.
+    Table,
+    /// Comma-separated values with a header row, for spreadsheets.
+    Csv,
+    /// An array with an object per row, keyed by the names of the columns.
+    Json,
+}
+
+impl Format {
+    /// The format given on the command line, or a table if it wasn't given and stdout is a terminal.
+    ///
+    /// Returns `None` if neither is the case, so commands keep printing one line per item when their output is read
+    /// by another program.
+    pub fn resolve(format: Option<Self>) -> Option<Self> {
+        format.or_else(|| io::stdout().is_terminal().then_some(Self::Table))
+    }
+}
+
+/// Rows of values under named columns, printed in any `Format`.
+pub struct Table {
+    /// Names of the columns, which are the keys of the values in JSON and are shown in uppercase as the header of a
+    /// table.
+    columns: Vec<&'static str>,
+    rows: Vec<Vec<String>>,
+}
+
+impl Table {
+    pub fn new(columns: Vec<&'static str>) -> Self {
+        Self {
+            columns,
+            rows: Vec::new(),
+        }
+    }
+
+    /// Add a row, which has to have a value for each column.
+    pub fn push(&mut self, row: Vec<String>) {
+        debug_assert_eq!(row.len(), self.columns.len());
+        self.rows.push(row);
+    }
+
+    /// Print the rows to stdout in `format`.
+    pub fn print(&self, format: Format) -> anyhow::Result<()> {
+        let mut output = String::new();
+        self.write(format, &mut output)?;
+        print!("{output}");
+
+        Ok(())
+    }
+
+    fn write(&self, format: Format, writer: impl Write) -> anyhow::Result<()> {
+        match format {
+            Format::Table => self.write_table(writer)?,
+            Format::Csv => self.write_csv(writer)?,
+            Format::Json => self.write_json(writer)?,
+        }
+
+        Ok(())
+    }
+
+    fn write_table(&self, mut writer: impl Write) -> fmt::Result {
+        let header = self
+            .columns
+            .iter()
+            .map(|column| column.to_uppercase())
+            .collect::<Vec<_>>();
+
+        let widths = (0..self.columns.len())
+            .map(|column| {
+                std::iter::once(&header[column])
+                    .chain(self.rows.iter().map(|row| &row[column]))
+                    .map(|value| value.chars().count())
+                    .max()
+                    .unwrap_or(0)
+            })
+            .collect::<Vec<_>>();
+
+        for row in std::iter::once(&header).chain(&self.rows) {
+            let mut line = String::new();
+
+            for (column, value) in row.iter().enumerate() {
+                if column > 0 {
+                    line.push_str("  ");
+                }
+
+                write!(line, "{value:<width$}", width = widths[column])?;
+            }
+
+            // the last column is padded like the others, but trailing spaces would only get in the way of copying it
+            writeln!(writer, "{}", line.trim_end())?;
+        }
+
+        Ok(())
+    }
+
+    fn write_csv(&self, mut writer: impl Write) -> fmt::Result {
+        let columns = self.columns.iter().map(|column| column.to_string());
+
+        for row in std::iter::once(columns.collect::<Vec<_>>()).chain(self.rows.iter().cloned()) {
+            let fields = row.iter().map(|value| csv_field(value)).collect::<Vec<_>>();
+            // spreadsheets expect the line endings of RFC 4180
+            write!(writer, "{}\r\n", fields.join(","))?;
+        }
+
+        Ok(())
+    }
+
+    fn write_json(&self, mut writer: impl Write) -> anyhow::Result<()> {
+        let rows = self
+            .rows
+            .iter()
+            .map(|row| {
+                self.columns
+                    .iter()
+                    .map(|column| column.to_string())
+                    .zip(row.iter().cloned().map(serde_json::Value::String))
+                    .collect::<serde_json::Map<_, _>>()
+            })
+            .collect::<Vec<_>>();
+
+        writeln!(writer, "{}", serde_json::to_string_pretty(&rows)?)?;
+
+        Ok(())
+    }
+}
+
+/// `value` as a CSV field, which is quoted if it contains a separator, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn table() -> Table {
+        let mut table = Table::new(vec!["path", "status"]);
+        table.push(vec!["/usr/bin/vim".to_owned(), "ok".to_owned()]);
+        table.push(vec!["/usr/bin/a, b".to_owned(), "say \"hi\"".to_owned()]);
+        table
+    }
+
+    fn write(format: Format) -> String {
+        let mut output = String::new();
+        table().write(format, &mut output).unwrap();
+        output
+    }
+
+    #[test]
+    fn aligned_table() {
+        assert_eq!(
+            write(Format::Table),
+            "PATH           STATUS\n\
+             /usr/bin/vim   ok\n\
+             /usr/bin/a, b  say \"hi\"\n"
+        );
+    }
+
+    #[test]
+    fn quoted_csv() {
+        assert_eq!(
+            write(Format::Csv),
+            "path,status\r\n/usr/bin/vim,ok\r\n\"/usr/bin/a, b\",\"say \"\"hi\"\"\"\r\n"
+        );
+    }
+
+    #[test]
+    fn json_objects() {
+        let rows: serde_json::Value = serde_json::from_str(&write(Format::Json)).unwrap();
+
+        assert_eq!(
+            rows,
+            serde_json::json!([
+                { "path": "/usr/bin/vim", "status": "ok" },
+                { "path": "/usr/bin/a, b", "status": "say \"hi\"" },
+            ])
+        );
+    }
+}
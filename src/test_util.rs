@@ -0,0 +1,15 @@
+//! Helpers shared by the tests of multiple modules.
+
+use std::path::Path;
+
+use crate::file::{self, REGULAR_MODE, TempDir};
+
+/// A directory for the test `name` that no other test or run uses, removed along with its contents when dropped.
+pub fn temp_dir(name: &str) -> TempDir {
+    TempDir::new(&format!("test-{name}")).expect("temp dir creation should succeed")
+}
+
+/// Write `content` to `path` as an executable.
+pub fn write_with_execute_bit(path: &Path, content: &[u8]) -> anyhow::Result<()> {
+    file::write_atomic(path, content, REGULAR_MODE.with_execute_for_readers().0)
+}
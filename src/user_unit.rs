@@ -0,0 +1,306 @@
+use std::{
+    env, fs, io,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use anyhow::Context;
+use indoc::formatdoc;
+
+use crate::{error::IoError, file, header::Header, i18n::t, settings, wrapper};
+
+/// Start of the names of every unit and install script created by this program.
+const PREFIX: &str = concat!(env!("CARGO_PKG_NAME"), "-");
+
+/// Directory of the systemd units of the current user.
+pub fn dir() -> anyhow::Result<PathBuf> {
+    let config_dir = settings::config_home().with_context(|| t!("unit-no-config-home"))?;
+
+    Ok(config_dir.join("systemd/user"))
+}
+
+/// Name of the unit of the given type for the wrapper with the given hook name, such as `path` or `service`.
+///
+/// Unit names can't contain most punctuation, so anything else is escaped like `systemd-escape` does.
+pub fn unit_name(hook_name: &str, unit_type: &str) -> String {
+    let mut name = String::from(PREFIX);
+
+    for byte in hook_name.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b':') {
+            name.push(char::from(byte));
+        } else {
+            name.push_str(&format!(r"\x{byte:02x}"));
+        }
+    }
+
+    format!("{name}.{unit_type}")
+}
+
+/// Path of the script that reinstalls the wrapper with the given hook name when its path unit is triggered.
+pub fn install_script_path(hook_name: &str) -> anyhow::Result<PathBuf> {
+    Ok(dir()?.join(unit_name(hook_name, "sh")))
+}
+
+/// Paths of the path unit, the service and the install script for the given hook name, in that order.
+pub fn all_paths(hook_name: &str) -> anyhow::Result<[PathBuf; 3]> {
+    let dir = dir()?;
+
+    Ok(["path", "service", "sh"].map(|unit_type| dir.join(unit_name(hook_name, unit_type))))
+}
+
+/// Generate the path unit that starts the service of the wrapper at `paths` whenever the wrapped executable is
+/// replaced, such as by the program updating itself.
+pub fn generate_path_unit(paths: &wrapper::ExecPaths, header: &Header) -> String {
+    formatdoc! {r#"
+        {header}
+
+        [Unit]
+        Description=Watch {wrapped} for its wrapper to be replaced
+
+        [Path]
+        PathChanged={wrapped}
+        Unit={service}
+
+        [Install]
+        WantedBy=default.target
+        "#,
+        wrapped = escape_specifiers(&paths.wrapped.original.to_string_lossy()),
+        service = unit_name(&paths.hook_name, "service"),
+    }
+}
+
+/// Generate the service that runs the install script at `install_script_path`, recreating the wrapper at `paths`.
+pub fn generate_service(
+    paths: &wrapper::ExecPaths,
+    header: &Header,
+    install_script_path: &Path,
+) -> String {
+    formatdoc! {r#"
+        {header}
+
+        [Unit]
+        Description=Recreate the wrapper of {wrapped}
+        ConditionPathExists={wrapped}
+
+        [Service]
+        Type=oneshot
+        ExecStart={exec}
+        "#,
+        wrapped = escape_specifiers(&paths.wrapped.original.to_string_lossy()),
+        exec = quote_exec_arg(install_script_path),
+    }
+}
+
+/// Write both units of the wrapper at `paths` with the permissions in `mode`, which run the install script at
+/// `install_script_path`.
+pub fn write(
+    paths: &wrapper::ExecPaths,
+    header: &Header,
+    install_script_path: &Path,
+    mode: u32,
+) -> anyhow::Result<()> {
+    let dir = dir()?;
+
+    let units = [
+        ("path", generate_path_unit(paths, header)),
+        (
+            "service",
+            generate_service(paths, header, install_script_path),
+        ),
+    ];
+
+    for (unit_type, content) in units {
+        let path = dir.join(unit_name(&paths.hook_name, unit_type));
+
+        file::write_atomic(&path, content.as_bytes(), mode)
+            .with_context(|| IoError::new(&path, t!("unit-write-failed")))?;
+    }
+
+    Ok(())
+}
+
+/// Create the directory of the systemd units of the current user if it doesn't exist.
+pub fn create_dir() -> anyhow::Result<()> {
+    let dir = dir()?;
+
+    fs::create_dir_all(&dir).with_context(|| IoError::new(&dir, t!("unit-create-dir-failed")))
+}
+
+/// Start watching the wrapped executable for the wrapper with the given hook name, now and on every login.
+///
+/// The wrapper itself is already in place, so failing to reach the service manager of the user, such as when it isn't
+/// running, is only reported.
+pub fn enable(hook_name: &str) {
+    let path_unit = unit_name(hook_name, "path");
+
+    let result =
+        systemctl(&["daemon-reload"]).and_then(|()| systemctl(&["enable", "--now", &path_unit]));
+
+    if let Err(err) = result {
+        eprintln!(
+            "{}",
+            t!(
+                "unit-enable-failed",
+                error = format!("{err:#}"),
+                unit = path_unit
+            )
+        );
+    }
+}
+
+/// Stop watching the wrapped executable for the wrapper with the given hook name until it's enabled again, so the
+/// wrapper can be replaced without the old install script running.
+pub fn stop(hook_name: &str) {
+    let Ok([path_unit, ..]) = all_paths(hook_name) else {
+        return;
+    };
+
+    // a unit that isn't loaded isn't watching anything
+    if path_unit.exists() {
+        let _ = systemctl(&["stop", &unit_name(hook_name, "path")]);
+    }
+}
+
+/// Stop watching the wrapped executable of the wrapper at `paths`, and remove its units and install script, if they
+/// exist.
+pub fn remove_all(paths: &wrapper::ExecPaths) -> anyhow::Result<()> {
+    // without a home directory, there can't be any units to remove
+    let Ok(unit_paths) = all_paths(&paths.hook_name) else {
+        return Ok(());
+    };
+
+    if !unit_paths.iter().any(|path| path.exists()) {
+        return Ok(());
+    }
+
+    remove(&unit_paths)
+}
+
+/// Remove the units and install scripts at `unit_paths`, disabling the path units and timers among them first.
+pub fn remove(unit_paths: &[PathBuf]) -> anyhow::Result<()> {
+    for path in unit_paths {
+        // path units watch executables, and timers remove expired wrappers
+        let is_trigger = path
+            .extension()
+            .is_some_and(|ext| ext == "path" || ext == "timer");
+
+        // the service manager may not be running, in which case nothing is being triggered anyway
+        if is_trigger && path.exists() {
+            let name = path.file_name().unwrap_or_default().to_string_lossy();
+            let _ = systemctl(&["disable", "--now", &name]);
+        }
+    }
+
+    for path in unit_paths {
+        file::remove_if_exists(path).map_err(|err| IoError::new(path, err))?;
+    }
+
+    let _ = systemctl(&["daemon-reload"]);
+
+    Ok(())
+}
+
+/// Find every unit and install script in the unit directory of the current user that was created by this program.
+pub fn find_all() -> anyhow::Result<Vec<PathBuf>> {
+    find_in(&dir()?)
+}
+
+/// Find every unit and install script in the unit directory `dir` that was created by this program.
+pub fn find_in(dir: &Path) -> anyhow::Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => return Err(IoError::new(dir, anyhow::Error::new(err)).into()),
+    };
+
+    let mut paths = entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            path.file_name()
+                .is_some_and(|name| name.to_string_lossy().starts_with(PREFIX))
+                && fs::read_to_string(path).is_ok_and(|content| Header::find(&content).is_some())
+        })
+        .collect::<Vec<_>>();
+
+    paths.sort();
+    Ok(paths)
+}
+
+/// Run `systemctl --user` with `args`.
+fn systemctl(args: &[&str]) -> anyhow::Result<()> {
+    let status = Command::new("systemctl")
+        .arg("--user")
+        .args(args)
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .with_context(|| t!("systemctl-run-failed"))?;
+
+    anyhow::ensure!(
+        status.success(),
+        t!(
+            "systemctl-failed",
+            args = format!("--user {}", args.join(" ")),
+            status = status.to_string(),
+        )
+    );
+
+    Ok(())
+}
+
+/// Escape `%`, which systemd would otherwise expand as a specifier.
+pub fn escape_specifiers(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+/// Quote a path for use as the command of an `ExecStart` line.
+pub fn quote_exec_arg(path: &Path) -> String {
+    let path = path
+        .to_string_lossy()
+        .replace('\\', r"\\")
+        .replace('"', "\\\"");
+    format!(r#""{}""#, escape_specifiers(&path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unit_names_are_escaped() {
+        assert_eq!(
+            unit_name("my+app-0123abcd", "path"),
+            r"wrapperize-my\x2bapp-0123abcd.path"
+        );
+        assert_eq!(
+            unit_name("my%20app-0123abcd", "service"),
+            r"wrapperize-my\x2520app-0123abcd.service"
+        );
+    }
+
+    #[test]
+    fn units_run_the_install_script() {
+        let paths =
+            wrapper::ExecPaths::try_from_path(Path::new("/home/user/bin/100% app")).unwrap();
+        let header = Header::new(&paths.wrapped.original, &wrapper::Params::default());
+
+        let path_unit = generate_path_unit(&paths, &header);
+        let service = unit_name(&paths.hook_name, "service");
+
+        assert!(path_unit.starts_with(&header.to_string()));
+        assert!(path_unit.contains("\nPathChanged=/home/user/bin/100%% app\n"));
+        assert!(path_unit.contains(&format!("\nUnit={service}\n")));
+
+        let service = generate_service(
+            &paths,
+            &header,
+            Path::new("/home/user/.config/systemd/user/my \"app\".sh"),
+        );
+
+        assert!(service.contains("\nConditionPathExists=/home/user/bin/100%% app\n"));
+        assert!(
+            service.contains("\nExecStart=\"/home/user/.config/systemd/user/my \\\"app\\\".sh\"\n")
+        );
+    }
+}
@@ -0,0 +1,58 @@
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{file, i18n::t};
+
+/// A program that routes the network traffic of the command it launches through a proxy.
+#[derive(
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    strum::EnumIter,
+    strum::EnumString,
+    strum::Display,
+    Serialize,
+    Deserialize,
+    schemars::JsonSchema,
+)]
+#[strum(serialize_all = "lowercase")]
+#[serde(rename_all = "lowercase")]
+pub enum Via {
+    /// Route through the proxies configured for `proxychains`, such as a SOCKS proxy.
+    Proxychains,
+    /// Route through Tor with `torsocks`.
+    Torsocks,
+}
+
+impl Via {
+    /// Parse a program name, listing the known programs if it isn't one of them.
+    pub fn parse(value: &str) -> Result<Self, String> {
+        value.parse().map_err(|_| {
+            let known = Self::iter().map(|via| via.to_string()).collect::<Vec<_>>();
+            t!("via-unknown", program = value, known = known.join(", "),)
+        })
+    }
+
+    fn program(self) -> &'static str {
+        match self {
+            Self::Proxychains => "proxychains",
+            Self::Torsocks => "torsocks",
+        }
+    }
+
+    /// Arguments to prefix a command with so its traffic is routed through the proxy.
+    pub fn launcher(self) -> Vec<String> {
+        vec![self.program().into()]
+    }
+
+    /// Check that the program is installed, since the wrapper would otherwise fail every time it runs.
+    pub fn ensure_installed(self) -> anyhow::Result<()> {
+        if file::find_program(self.program()).is_none() {
+            anyhow::bail!(t!("via-not-installed", program = self.program()));
+        }
+
+        Ok(())
+    }
+}
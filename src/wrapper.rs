@@ -1,328 +1,4125 @@
 use std::{
+    borrow::Cow,
+    collections::{BTreeMap, HashSet},
     fmt::{self, Display, Write as FmtWrite},
-    io::Write,
-    path::Path,
-    process::{self, Command, Stdio},
+    fs,
+    io::Read,
+    path::{Path, PathBuf},
 };
 
 use anyhow::Context;
-use indoc::{concatdoc, formatdoc};
-use tap::Tap;
+use indoc::formatdoc;
+use serde::{Deserialize, Serialize};
 
-use crate::{env, error::IoError, file, pacman_hook, path};
+use crate::{
+    access::Access,
+    affinity::CpuList,
+    appimage,
+    container::{Container, HostContainer},
+    env,
+    error::IoError,
+    expiry::{self, Expiry},
+    file::{self, FileMode},
+    hash,
+    header::Header,
+    hours::AllowedHours,
+    i18n::t,
+    journal, jvm,
+    limit::Limit,
+    metadata::{DORMANT_LINE, Metadata, is_false},
+    mount, pacman_hook, path, plugin,
+    preset::{self, Preset},
+    settings, shell, signal, snap, status_file, symlink, user_unit,
+    via::Via,
+};
+
+#[cfg(target_os = "linux")]
+use crate::preview;
+
+/// Line included in every generated script, used to recognize wrappers that were already installed.
+///
+/// This is a macro so it can be used with `concat!`.
+macro_rules! marker {
+    () => {
+        concat!(
+            "# Automatically generated by '",
+            ::std::env!("CARGO_PKG_NAME"),
+            "'."
+        )
+    };
+}
+
+const MARKER: &str = marker!();
+
+/// Number of bytes at the start of a file that are enough to hold the shebang and the marker of a wrapper.
+const MARKER_SEARCH_LEN: u64 = 4096;
+
+/// Makes a script stop at the first failing command or unset variable, instead of carrying on with partial state.
+const STRICT_OPTIONS: &str = "set -euo pipefail";
+
+/// Environment variable that makes a wrapper print every command it runs, including the final `exec`, to stderr.
+const TRACE_VAR: &str = "WRAPPERIZE_TRACE";
+
+/// Environment variable that makes a wrapper run the original executable as it was invoked, if it's `1` or a
+/// comma-separated list of names that includes the name of the executable.
+pub const DISABLE_VAR: &str = "WRAPPERIZE_DISABLE";
+
+/// Exit status of a masked wrapper, which is what shells use for a command that was found but can't be run.
+const MASK_EXIT_CODE: u8 = 126;
+
+/// Placeholder for the name of the executable in an unwrapped filename pattern.
+const NAME_PLACEHOLDER: &str = "{name}";
+
+/// Filename pattern of the original executable when it's kept next to its wrapper.
+const DEFAULT_UNWRAPPED_PATTERN: &str = ".{name}-unwrapped";
+
+/// Replace the name placeholder in an unwrapped filename pattern.
+fn expand_unwrapped_pattern(pattern: &str, name: &str) -> String {
+    pattern.replace(NAME_PLACEHOLDER, name)
+}
 
-const SCRIPT_TEMPLATE: &str = concatdoc! {"
-    #!/usr/bin/env bash
-    # Automatically generated by '", env!("CARGO_PKG_NAME"), "'.
-"};
+/// Returns an error if `pattern` can't be used as the filename of an original executable.
+fn validate_unwrapped_pattern(pattern: &str) -> anyhow::Result<()> {
+    if !pattern.contains(NAME_PLACEHOLDER) {
+        anyhow::bail!(t!(
+            "wrapper-pattern-no-name",
+            pattern = pattern,
+            placeholder = NAME_PLACEHOLDER,
+        ));
+    }
+
+    if pattern == NAME_PLACEHOLDER {
+        anyhow::bail!(t!("wrapper-pattern-is-name"));
+    }
+
+    if pattern.contains('/') {
+        anyhow::bail!(t!("wrapper-pattern-slash", pattern = pattern));
+    }
+
+    Ok(())
+}
+
+/// Returns an error if `tag` can't be used to group wrappers, since it has to be easy to type and pass around.
+fn validate_tag(tag: &str) -> anyhow::Result<()> {
+    let is_valid = !tag.is_empty()
+        && tag
+            .chars()
+            .all(|ch| ch.is_alphanumeric() || matches!(ch, '-' | '_' | '.'));
+
+    anyhow::ensure!(is_valid, t!("wrapper-invalid-tag", tag = tag));
+
+    Ok(())
+}
+
+/// First lines of every generated script, with the shell from the settings.
+fn script_template() -> String {
+    format!("#!{}\n{MARKER}\n", settings::get().shebang())
+}
 
 pub struct ExecPaths {
     pub unwrapped: path::Escaped,
     pub wrapped: path::Escaped,
     pub wrapped_filename: String,
+    /// Name that the `pacman` hooks and install script for the wrapper are derived from.
+    pub hook_name: String,
+    /// Sidecar file storing the parameters the wrapper was created with.
+    pub metadata: path::Escaped,
 }
 
 impl ExecPaths {
+    /// Derive every path belonging to the wrapper of `path`.
+    ///
+    /// If the wrapper already exists and was created with a custom location for its original executable, the
+    /// unwrapped path points there.
     pub fn try_from_path(path: &Path) -> anyhow::Result<Self> {
+        let mut paths = Self::with_params(path, &Params::default())?;
+
+        // missing or unreadable metadata is reported by the commands that need it
+        if let Ok(metadata) = Metadata::read(&paths.metadata.original) {
+            paths.unwrapped = path::Escaped::new(paths.expected_unwrapped_path(&metadata.params));
+        }
+
+        Ok(paths)
+    }
+
+    /// Derive every path belonging to a wrapper of `path` generated with `params`, without looking at any existing
+    /// wrapper.
+    fn with_params(path: &Path, params: &Params) -> anyhow::Result<Self> {
         let wrapped = path::Escaped::new(path);
 
         let filename = path
             .file_name()
-            .context("invalid path provided")?
+            .with_context(|| t!("wrapper-invalid-path"))?
             .to_string_lossy()
             .into_owned();
 
-        let unwrapped = path::Escaped::new(path.with_file_name(format!(".{filename}-unwrapped")));
+        let metadata = path::Escaped::new(path.with_file_name(Self::metadata_filename(&filename)));
+        let hook_name = pacman_hook::hook_name(path);
+
+        let unwrapped =
+            path::Escaped::new(Self::unwrapped_path(path, &filename, &hook_name, params));
 
         Ok(Self {
             unwrapped,
             wrapped,
             wrapped_filename: filename,
+            hook_name,
+            metadata,
         })
     }
+
+    /// Path of the original executable of the wrapper at `path`, as placed by a wrapper generated with `params`.
+    ///
+    /// Inside a store directory, the hook name stands in for the filename since it's unique to the wrapped path.
+    fn unwrapped_path(path: &Path, filename: &str, hook_name: &str, params: &Params) -> PathBuf {
+        let pattern = params.unwrapped_pattern.as_deref();
+
+        match &params.store_dir {
+            Some(dir) => dir.join(pattern.map_or_else(
+                || hook_name.to_owned(),
+                |pattern| expand_unwrapped_pattern(pattern, hook_name),
+            )),
+            None => path.with_file_name(match pattern {
+                Some(pattern) => expand_unwrapped_pattern(pattern, filename),
+                None if params.appimage => appimage::unwrapped_filename(filename),
+                None => expand_unwrapped_pattern(DEFAULT_UNWRAPPED_PATTERN, filename),
+            }),
+        }
+    }
+
+    /// The unwrapped path that a wrapper generated with `params` should use.
+    fn expected_unwrapped_path(&self, params: &Params) -> PathBuf {
+        Self::unwrapped_path(
+            &self.wrapped.original,
+            &self.wrapped_filename,
+            &self.hook_name,
+            params,
+        )
+    }
+
+    /// Visible path through which the original executable can be run directly, if the wrapper exposes it.
+    pub fn exposed_original_path(&self) -> PathBuf {
+        self.wrapped
+            .original
+            .with_file_name(format!("{}-unwrapped", self.wrapped_filename))
+    }
+
+    fn metadata_filename(wrapped_filename: &str) -> String {
+        format!(".{wrapped_filename}-{}.toml", env!("CARGO_PKG_NAME"))
+    }
+
+    /// Returns the filename of the wrapped executable if `filename` is the name of a wrapper metadata file.
+    pub fn wrapped_filename_from_metadata(filename: &str) -> Option<&str> {
+        filename
+            .strip_prefix('.')?
+            .strip_suffix(".toml")?
+            .strip_suffix(env!("CARGO_PKG_NAME"))?
+            .strip_suffix('-')
+    }
+
+    /// Returns the filename of the wrapped executable if `filename` is the name of an unwrapped executable.
+    fn wrapped_filename_from_unwrapped(filename: &str) -> Option<&str> {
+        filename.strip_prefix('.')?.strip_suffix("-unwrapped")
+    }
+
+    /// Returns true if the executable has already been wrapped.
+    pub fn is_wrapped(&self) -> anyhow::Result<bool> {
+        self.unwrapped
+            .original
+            .try_exists()
+            .with_context(|| IoError::new(&self.unwrapped.original, t!("wrapper-check-wrapped")))
+    }
 }
 
-pub struct InstallScript {
-    contents: String,
+/// The outcome of a successful [`unwrap`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Unwrapped {
+    /// The original executable was moved back over its wrapper.
+    Restored,
+    /// The shim of a snap command was removed, so the command runs the snap directly again.
+    ShimRemoved,
+    /// Neither the original executable nor its wrapper exist anymore, so only leftover files were removed.
+    Missing,
 }
 
-impl InstallScript {
-    pub fn create(
-        paths: &ExecPaths,
-        wrapper_script: impl Display,
-        save_to_disk: Option<&Path>,
-    ) -> anyhow::Result<Self> {
-        let wrapper_install_script = Self::generate_script(paths, wrapper_script)
-            .context("failed to generate wrapper install script")?;
-
-        if let Some(path) = save_to_disk {
-            file::write_with_execute_bit(path, wrapper_install_script.as_bytes()).with_context(
-                || {
-                    IoError::new(
-                        path,
-                        "failed to write wrapper install script for pacman hook",
-                    )
-                },
-            )?;
+/// Restore the original executable over its wrapper, and remove the metadata and any `pacman` hooks for it.
+///
+/// The original is restored even if the wrapper itself is gone, such as when the package was removed without the
+/// removal hook running.
+///
+/// If `keep_hooks` is set, the metadata, hooks and expiry timer are kept and the wrapper is marked as dormant instead,
+/// so it's recreated on the next upgrade of the executable. Unwrapping a dormant wrapper removes what was kept.
+pub fn unwrap(paths: &ExecPaths, keep_hooks: bool) -> anyhow::Result<Unwrapped> {
+    let dormant_metadata = if keep_hooks {
+        let metadata = Metadata::read(&paths.metadata.original)
+            .with_context(|| t!("wrapper-keep-hooks-no-metadata"))?;
+
+        if !metadata.use_pacman_hooks && metadata.params.backend.is_none() {
+            anyhow::bail!(t!("wrapper-no-hooks-to-keep"));
         }
 
-        Ok(Self {
-            contents: wrapper_install_script,
+        Some(Metadata {
+            dormant: true,
+            ..metadata
         })
+    } else {
+        None
+    };
+
+    let wrapped_exists = paths
+        .wrapped
+        .original
+        .try_exists()
+        .with_context(|| IoError::new(&paths.wrapped.original, t!("io-check-exists")))?;
+
+    mount::ensure_writable(&paths.wrapped.original)?;
+
+    let metadata = Metadata::read(&paths.metadata.original).ok();
+    let is_dormant = metadata.as_ref().is_some_and(|metadata| metadata.dormant);
+    // the metadata is the only record of which backend recreates the wrapper
+    let backend = metadata.and_then(|metadata| metadata.params.backend);
+
+    let is_wrapped = paths.is_wrapped()?;
+
+    // the state is checked before anything is removed, so an unwrap that fails leaves everything in place
+    match (is_wrapped, wrapped_exists) {
+        (false, true) if is_dormant => {}
+        (false, true) if is_wrapper_script(&paths.wrapped.original)? => {
+            return Err(IoError::new(
+                &paths.wrapped.original,
+                t!(
+                    "wrapper-original-missing-reinstall",
+                    path = paths.unwrapped.original.display().to_string(),
+                ),
+            )
+            .into());
+        }
+        (false, true) => {
+            return Err(IoError::new(&paths.wrapped.original, t!("wrapper-not-wrapped")).into());
+        }
+        (false, false) if keep_hooks => {
+            return Err(
+                IoError::new(&paths.wrapped.original, t!("wrapper-executable-gone")).into(),
+            );
+        }
+        _ => {}
     }
 
-    pub fn execute(self) -> anyhow::Result<process::ExitStatus> {
-        let mut cmd = Command::new("/usr/bin/env")
-            .arg("bash")
-            .stdin(Stdio::piped())
-            .spawn()
-            .context("failed to spawn bash to execute wrapper installer")?;
+    remove_exposed_original(paths)?;
 
-        cmd.stdin
-            .take()
-            .context("no stdin configured for bash")?
-            .write_all(self.contents.as_bytes())
-            .context("failed to pipe wrapper install script to bash")?;
+    // the units would recreate the wrapper as soon as the original executable is restored over it
+    user_unit::remove_all(paths)?;
 
-        cmd.wait().map_err(Into::into)
+    // the hooks recreate a dormant wrapper without its expiry, so the timer is kept to still unwrap it in time
+    if !keep_hooks {
+        expiry::remove_all(paths)?;
     }
 
-    fn generate_script(paths: &ExecPaths, wrapper_script: impl Display) -> anyhow::Result<String> {
-        Ok(formatdoc! { r#"
-            {SCRIPT_TEMPLATE}
-            mv "{wrapped_path}" "{unwrapped_path}"
+    let outcome = match (is_wrapped, wrapped_exists) {
+        // a shim has nothing to restore, since the snap command it stands in front of was never moved
+        (true, _) if snap::is_command_link(&paths.unwrapped.original) => {
+            for path in [&paths.wrapped.original, &paths.unwrapped.original] {
+                file::remove_if_exists(path).map_err(|err| IoError::new(path, err))?;
+            }
 
-            cat << _{program_name}_eof > "{wrapped_path}"
-            {wrapper_script}
-            _{program_name}_eof
+            Unwrapped::ShimRemoved
+        }
+        (true, _) => {
+            fs::rename(&paths.unwrapped.original, &paths.wrapped.original).with_context(|| {
+                IoError::new(&paths.unwrapped.original, t!("wrapper-restore-failed"))
+            })?;
 
-            chmod +x "{wrapped_path}"
-            "#,
-            wrapped_path = paths.wrapped.escaped,
-            unwrapped_path = paths.unwrapped.escaped,
-            program_name = env!("CARGO_PKG_NAME"),
-        })
+            Unwrapped::Restored
+        }
+        // a dormant wrapper already has its original executable back in place
+        (false, true) => Unwrapped::Restored,
+        (false, false) => Unwrapped::Missing,
+    };
+
+    if let Some(metadata) = dormant_metadata {
+        metadata.write(&paths.metadata.original)?;
+        return Ok(outcome);
     }
-}
 
-#[derive(Default)]
-pub struct Params<'a> {
-    pub args: &'a [String],
-    pub add_passthrough_args_first: bool,
-    pub env_vars: &'a [env::Variable<'a>],
+    if let Some(backend) = backend {
+        plugin::remove_backend(&backend, paths)?;
+    }
+
+    file::remove_if_exists(&paths.metadata.original)
+        .map_err(|err| IoError::new(&paths.metadata.original, err))?;
+
+    pacman_hook::remove_all(paths)?;
+
+    Ok(outcome)
 }
 
-impl<'a> Params<'a> {
-    #[cfg(test)]
-    fn with_args(args: &'a [String]) -> Self {
-        Self {
-            args,
-            ..Default::default()
+/// Describe everything wrong with the installed wrapper at `paths`; an empty list means the wrapper is intact.
+pub fn problems(paths: &ExecPaths) -> anyhow::Result<Vec<String>> {
+    let mut problems = Vec::new();
+
+    let metadata = match Metadata::read(&paths.metadata.original) {
+        Ok(metadata) => Some(metadata),
+        Err(err) => {
+            problems.push(format!("{err:#}"));
+            None
         }
-    }
+    };
 
-    #[cfg(test)]
-    fn with_env_vars(env_vars: &'a [env::Variable<'a>]) -> Self {
-        Self {
-            env_vars,
-            ..Default::default()
+    let dormant = metadata.as_ref().is_some_and(|metadata| metadata.dormant);
+
+    if metadata
+        .as_ref()
+        .is_some_and(|metadata| metadata.params.user_units)
+    {
+        for path in user_unit::all_paths(&paths.hook_name)? {
+            if !path.exists() {
+                problems.push(t!(
+                    "problem-recreate-file-missing",
+                    path = path.display().to_string(),
+                ));
+            }
         }
     }
-}
 
-pub fn create(
-    paths: &ExecPaths,
-    wrapper_params: &Params,
-    use_pacman_hooks: bool,
-) -> anyhow::Result<InstallScript> {
-    let wrapper_already_exists = paths.unwrapped.original.try_exists().with_context(|| {
-        IoError::new(
-            &paths.unwrapped.original,
-            "failed to check if wrapped path already exists",
-        )
-    })?;
+    if let Some(backend) = metadata
+        .as_ref()
+        .and_then(|metadata| metadata.params.backend.as_deref())
+    {
+        if plugin::find(plugin::Kind::Backend, backend).is_none() {
+            problems.push(t!("problem-backend-missing", backend = backend));
+        }
 
-    if wrapper_already_exists {
-        return Err(IoError::new(
-            &paths.wrapped.original,
-            format!(
-                "wrapper already exists for this file at `{}`",
-                paths.unwrapped.original.display()
-            ),
-        )
-        .into());
+        let install_script = plugin::install_script_path(&paths.hook_name);
+
+        if !install_script.exists() {
+            problems.push(t!(
+                "problem-recreate-file-missing",
+                path = install_script.display().to_string(),
+            ));
+        }
     }
 
-    let wrapper_script_fmt =
-        fmt::from_fn(|writer| write_full_wrapper_script(&paths.unwrapped, wrapper_params, writer));
+    if let Some(expires) = metadata
+        .as_ref()
+        .filter(|metadata| !metadata.dormant)
+        .and_then(|metadata| metadata.params.expires)
+    {
+        let user = metadata
+            .as_ref()
+            .is_some_and(|metadata| metadata.params.user_units);
 
-    // the wrapper install script uses the same path / filename as the pacman install hook but with a different
-    // extension, so we can initialize the pacman install hook now to simply clone and alter its pre-computed path
-    // for the wrapper install script
-    let pacman_install_hook = pacman_hook::Hook::new(
-        &paths.wrapped_filename,
-        pacman_hook::TriggerAction::InstallOrUpdate,
-    );
+        if expires.is_past() {
+            problems.push(t!(
+                "problem-expired",
+                expires = expires.to_string(),
+                program = env!("CARGO_PKG_NAME"),
+                path = paths.wrapped.original.display().to_string(),
+            ));
+        }
 
-    // the wrapper install script only needs a path if it's going to be saved to disk,
-    // and we only need to write it to disk if we're generating pacman hooks
-    let wrapper_install_script_path = use_pacman_hooks.then(|| {
-        pacman_install_hook.path.clone().tap_mut(|p| {
-            p.set_extension("sh");
-        })
-    });
+        let [timer, service] = expiry::all_paths(&paths.hook_name, user)?;
 
-    let wrapper_install_script = InstallScript::create(
-        paths,
-        wrapper_script_fmt,
-        wrapper_install_script_path.as_deref(),
-    )?;
+        for path in [&timer, &service] {
+            if !path.exists() {
+                problems.push(t!(
+                    "problem-expiry-file-missing",
+                    path = path.display().to_string(),
+                ));
+            }
+        }
 
-    // the install script path is only present if we're using pacman hooks, so we
-    // can use it to determine if we should skip the hook setup, and unwrap the
-    // path value at the same time for later use
-    let Some(wrapper_install_script_path) = wrapper_install_script_path else {
-        return Ok(wrapper_install_script);
-    };
+        if let Some(program) = expiry::read_program(&service)
+            && !program.exists()
+        {
+            problems.push(t!(
+                "problem-expiry-program-missing",
+                service = service.display().to_string(),
+                missing = program.display().to_string(),
+                program = env!("CARGO_PKG_NAME"),
+                path = paths.wrapped.original.display().to_string(),
+            ));
+        }
+    }
+
+    let wrapped_exists = paths
+        .wrapped
+        .original
+        .try_exists()
+        .with_context(|| IoError::new(&paths.wrapped.original, t!("io-check-exists")))?;
 
-    // since we are using pacman hooks, generate their contents and write them all to disk now
+    if !wrapped_exists {
+        problems.push(t!(
+            "problem-wrapper-missing",
+            path = paths.wrapped.original.display().to_string(),
+        ));
+    }
 
-    pacman_hook::create_dir()?;
+    // a dormant wrapper has the original executable back in place until the hooks recreate it
+    if dormant {
+        return Ok(problems);
+    }
 
-    pacman_install_hook.generate_and_write_to_disk(paths)?;
+    if !paths.is_wrapped()? {
+        problems.push(t!(
+            "problem-original-missing",
+            path = paths.unwrapped.original.display().to_string(),
+        ));
+    }
 
-    pacman_hook::Hook::new(
-        &paths.wrapped_filename,
-        pacman_hook::TriggerAction::Removal {
-            wrapper_install_script_path,
-        },
-    )
-    .generate_and_write_to_disk(paths)?;
+    if wrapped_exists && !is_wrapper_script(&paths.wrapped.original)? {
+        problems.push(t!(
+            "problem-not-generated",
+            path = paths.wrapped.original.display().to_string(),
+            program = env!("CARGO_PKG_NAME"),
+        ));
+    } else if let Some(metadata) = &metadata {
+        // wrappers generated before headers were added don't have one to compare against
+        let header = fs::read_to_string(&paths.wrapped.original)
+            .ok()
+            .and_then(|content| Header::find(&content));
 
-    Ok(wrapper_install_script)
+        if header.is_some_and(|header| header.params_hash != metadata.params.hash()) {
+            problems.push(t!(
+                "problem-params-differ",
+                path = paths.wrapped.original.display().to_string(),
+                metadata = paths.metadata.original.display().to_string(),
+            ));
+        }
+    }
+
+    Ok(problems)
 }
 
-fn write_wrapper_script_content(
-    unwrapped_exec_path: &path::Escaped,
-    params: &Params,
-    mut writer: impl FmtWrite,
-) -> fmt::Result {
-    // first, add all environment variables to the wrapper
-    for env in params.env_vars {
-        env.write_bash_line(&mut writer)?;
+/// Returns the path of the wrapper that `path` belongs to, if it's a wrapper generated by this program or the original
+/// executable that one moved out of the way.
+pub fn existing_wrapper(path: &Path) -> anyhow::Result<Option<PathBuf>> {
+    if ExecPaths::try_from_path(path)?.is_wrapped()? {
+        return Ok(Some(path.into()));
     }
 
-    // now run the executable with the wrapper arguments
+    let wrapped_filename = path
+        .file_name()
+        .and_then(|filename| filename.to_str())
+        .and_then(ExecPaths::wrapped_filename_from_unwrapped);
 
-    // compile time sanity check: the escaped path should be escaping the same quote
-    // character used in the `write!` call
-    const _: () = assert!(path::Escaped::ESCAPE_CHAR == '"');
-    write!(writer, r#"exec "{}""#, unwrapped_exec_path.escaped)?;
+    if let Some(wrapped_filename) = wrapped_filename {
+        let wrapped_path = path.with_file_name(wrapped_filename);
 
-    fn write_passthrough(mut writer: impl FmtWrite) -> fmt::Result {
-        write!(writer, r#" "\$@""#)
+        if ExecPaths::try_from_path(&wrapped_path)?.is_wrapped()? {
+            return Ok(Some(wrapped_path));
+        }
     }
 
-    fn write_args(params: &Params, mut writer: impl FmtWrite) -> fmt::Result {
-        for arg in params.args {
-            write!(writer, " {}", arg)?;
-        }
+    // a wrapper can also be copied or moved elsewhere, in which case its header still points at the original
+    if path.is_file() && is_wrapper_script(path)? {
+        let header = fs::read_to_string(path)
+            .ok()
+            .and_then(|content| Header::find(&content));
 
-        Ok(())
+        return Ok(Some(
+            header.map_or_else(|| path.into(), |header| header.path),
+        ));
     }
 
-    if params.add_passthrough_args_first {
-        write_passthrough(&mut writer)?;
-        write_args(params, &mut writer)?;
-    } else {
-        write_args(params, &mut writer)?;
-        write_passthrough(&mut writer)?;
+    Ok(None)
+}
+
+/// Returns true if the file at `path` was generated by this program, which puts the marker right after the shebang.
+///
+/// Only the start of the file is read, so executables that merely contain the marker, such as this program itself,
+/// aren't mistaken for wrappers.
+fn is_wrapper_script(path: &Path) -> anyhow::Result<bool> {
+    let file = fs::File::open(path).with_context(|| IoError::new(path, t!("io-read-file")))?;
+
+    // a binary can go on for a long time before its first line break
+    let mut start = Vec::new();
+    file.take(MARKER_SEARCH_LEN)
+        .read_to_end(&mut start)
+        .with_context(|| IoError::new(path, t!("io-read-file")))?;
+
+    Ok(start.split(|&byte| byte == b'\n').nth(1) == Some(MARKER.as_bytes()))
+}
+
+/// Whether a wrapper is being created from scratch, or an existing one is being regenerated.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    Create,
+    Update,
+}
+
+pub struct InstallScript {
+    contents: String,
+}
+
+impl InstallScript {
+    /// Generate an install script that moves the executable at the wrapped path to the unwrapped path, unless it's
+    /// already a wrapper, and then writes `wrapper_script` over the wrapped path.
+    pub fn generate(
+        paths: &ExecPaths,
+        header: &Header,
+        wrapper_script: impl Display,
+        params: &Params,
+    ) -> anyhow::Result<Self> {
+        let contents = Self::generate_script(paths, header, wrapper_script, params)
+            .with_context(|| t!("wrapper-generate-install-script-failed"))?;
+
+        Ok(Self { contents })
     }
 
-    Ok(())
+    pub fn save(&self, path: &Path, mode: u32) -> anyhow::Result<()> {
+        file::write_atomic(path, self.contents.as_bytes(), mode)
+            .with_context(|| IoError::new(path, t!("wrapper-write-install-script-failed")))
+    }
+
+    fn generate_script(
+        paths: &ExecPaths,
+        header: &Header,
+        wrapper_script: impl Display,
+        params: &Params,
+    ) -> anyhow::Result<String> {
+        // the hook can run again without the package having replaced the wrapper (such as when several targets
+        // trigger it in one transaction), in which case moving the wrapper over the original would break it,
+        // and an identical wrapper doesn't need to be rewritten at all
+        let rename_cmd = formatdoc! { r#"
+            {pacnew_cmd}if grep -qxF {header} "{wrapped_path}" && [ -e "{unwrapped_path}" ]; then
+                echo "wrapper for {wrapped_path} is already up to date"
+                exit 0
+            fi
+
+            if ! grep -qxF {marker} "{wrapped_path}"; then
+                {mkdir_cmd}mv "{wrapped_path}" "{unwrapped_path}"
+
+                if [ -f "{metadata_path}" ]; then
+                    sed -i {dormant_pattern} "{metadata_path}"
+                fi
+            fi
+
+            "#,
+            header = shell::quote(&header.to_string()),
+            marker = shell::quote(MARKER),
+            wrapped_path = paths.wrapped.escaped,
+            unwrapped_path = paths.unwrapped.escaped,
+            metadata_path = paths.metadata.escaped,
+            dormant_pattern = shell::quote(&format!("/^{DORMANT_LINE}$/d")),
+            mkdir_cmd = store_dir_cmd(paths),
+            pacnew_cmd = pacnew_cmd(paths, params),
+        };
+
+        let strict_cmd = if params.no_strict {
+            String::new()
+        } else {
+            format!("{STRICT_OPTIONS}\n\n")
+        };
+
+        // the wrapper should be runnable by whoever could run the original executable, unless told otherwise
+        let chmod_args = match params.wrapper_mode {
+            Some(mode) => mode.to_string(),
+            None => format!("--reference=\"{}\"", paths.unwrapped.escaped),
+        };
+
+        let expose_cmd = if params.expose_original {
+            format!(
+                "ln -sfn \"{}\" \"{}\"\n",
+                paths.unwrapped.escaped,
+                path::Escaped::new(paths.exposed_original_path()).escaped
+            )
+        } else {
+            String::new()
+        };
+
+        Ok(formatdoc! { r#"
+            {script_template}{header}
+
+            {strict_cmd}{rename_cmd}temp_path="$(mktemp "{wrapped_path}.XXXXXX")"
+
+            cat << '_{program_name}_eof' > "$temp_path"
+            {wrapper_script}
+            _{program_name}_eof
+
+            chmod {chmod_args} "$temp_path"
+            mv -f "$temp_path" "{wrapped_path}"
+            {expose_cmd}"#,
+            script_template = script_template(),
+            wrapped_path = paths.wrapped.escaped,
+            program_name = env!("CARGO_PKG_NAME"),
+        })
+    }
 }
 
-fn write_full_wrapper_script(
-    unwrapped_exec_path: &path::Escaped,
-    params: &Params,
-    mut writer: impl FmtWrite,
-) -> fmt::Result {
-    writer.write_str(SCRIPT_TEMPLATE)?;
-    write_wrapper_script_content(unwrapped_exec_path, params, writer)
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct Params {
+    /// Arguments to launch the executable with.
+    pub args: Vec<String>,
+    /// Arguments that always precede the passthrough arguments, regardless of `add_passthrough_args_first`.
+    pub args_before: Vec<String>,
+    /// Arguments that always follow the passthrough arguments, regardless of `add_passthrough_args_first`.
+    pub args_after: Vec<String>,
+    /// Place the arguments after the passthrough arguments, so they are seen last by the executable.
+    pub add_passthrough_args_first: bool,
+    /// Ignore the arguments passed to the wrapper, so the executable only ever sees the wrapper arguments.
+    pub no_passthrough: bool,
+    /// Directory to move the original executable into, instead of a hidden file next to the wrapper.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub store_dir: Option<PathBuf>,
+    /// Filename of the original executable, where `{name}` is replaced by the name of the executable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unwrapped_pattern: Option<String>,
+    /// Source `/etc/wrapperize/conf.d/<name>.conf` when the wrapper runs, if it exists, so the parameters can be
+    /// adjusted without regenerating the wrapper.
+    #[serde(skip_serializing_if = "is_false")]
+    pub runtime_config: bool,
+    /// Source `$XDG_CONFIG_HOME/wrapperize/<name>.override` of the user running the wrapper after the runtime
+    /// configuration, if it exists, so users can add their own arguments and environment variables.
+    #[serde(skip_serializing_if = "is_false")]
+    pub user_override: bool,
+    /// Add a visible `<name>-unwrapped` symlink next to the wrapper that runs the original executable directly.
+    #[serde(skip_serializing_if = "is_false")]
+    pub expose_original: bool,
+    /// Leave out the strict bash options (`set -euo pipefail`) from the generated wrapper and install scripts.
+    #[serde(skip_serializing_if = "is_false")]
+    pub no_strict: bool,
+    /// The executable is an AppImage, so it keeps its extension once moved out of the way, and sees the path of the
+    /// wrapper as the name it was run with.
+    #[serde(skip_serializing_if = "is_false")]
+    pub appimage: bool,
+    /// The executable is a symlink to a multi-call binary, such as BusyBox, which decides what to run by the name it
+    /// was run with, so it sees the path of the wrapper as that name.
+    #[serde(skip_serializing_if = "is_false")]
+    pub applet: bool,
+    /// The executable is protected as configuration by `pacman`, through the `backup` array of its package or
+    /// `NoUpgrade`, so an upgrade leaves its new version in a `.pacnew` file that the install script moves into place.
+    #[serde(skip_serializing_if = "is_false")]
+    pub config_protected: bool,
+    /// Wrap the file even if it doesn't look like an executable, such as a shared library or a data file.
+    #[serde(skip_serializing_if = "is_false")]
+    pub allow_non_executable: bool,
+    /// Interpreter from the shebang of the original script, along with its argument, which runs the script with the
+    /// path of the wrapper as the name it was run with.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub interpreter: Vec<String>,
+    /// Permissions of the wrapper, instead of those of the original executable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wrapper_mode: Option<FileMode>,
+    /// Permissions of the `pacman` hooks, with execute permission added for the install script.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hook_mode: Option<FileMode>,
+    /// Built-in sets of parameters, applied when the wrapper runs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub presets: Vec<Preset>,
+    /// DRI device the `gpu-offload` preset renders on, such as `pci-0000_01_00_0`, instead of the default one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gpu_device: Option<String>,
+    /// Flags the `wayland` preset adds instead of the defaults, for Chromium versions that need different ones.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub wayland_flags: Vec<String>,
+    /// Options for every JVM the executable starts, such as `-Xmx4g`, passed through `JAVA_TOOL_OPTIONS`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub jvm_options: Vec<String>,
+    /// URL of the proxy the `proxy` preset routes the executable through, such as `http://proxy.example.com:3128`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<String>,
+    /// Comma-separated hosts the `proxy` preset excludes from the proxy, such as `localhost,.internal.example.com`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+    /// Container that runs in place of the original executable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub container: Option<Container>,
+    /// Distrobox or toolbox container the executable runs in, instead of the original executable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_container: Option<HostContainer>,
+    /// Snap app the wrapper runs with `snap run`, in which case the wrapper is a shim in a directory that comes before
+    /// the commands of snaps in `$PATH`, since those can't be replaced.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snap: Option<String>,
+    /// Program the executable's network traffic is routed through.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub via: Option<Via>,
+    /// Send the output of the executable to the journal with `systemd-cat` when it isn't run in a terminal.
+    #[serde(skip_serializing_if = "is_false")]
+    pub journal: bool,
+    /// File the exit status, process ID and start and finish times of the executable are written to after every run,
+    /// where `{name}` is replaced by the name of the executable and variables such as `$HOME` are expanded at runtime.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_file: Option<String>,
+    /// Run the executable in its own network namespace with `unshare`, so it has no network access.
+    #[serde(skip_serializing_if = "is_false")]
+    pub no_network: bool,
+    /// CPUs the executable is confined to with `taskset`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpus: Option<CpuList>,
+    /// Soft resource limits to launch the executable with, such as `nofile=65536`.
+    #[serde(rename = "ulimits", skip_serializing_if = "Vec::is_empty")]
+    pub limits: Vec<Limit>,
+    /// The `pacman` operations that recreate the wrapper; all of them if empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hook_operations: Vec<pacman_hook::Operation>,
+    /// Recreate the wrapper with systemd user units whenever the executable is replaced, such as when it updates
+    /// itself, instead of with `pacman` hooks.
+    #[serde(skip_serializing_if = "is_false")]
+    pub user_units: bool,
+    /// Plugin that recreates the wrapper whenever its package manager replaces the executable, instead of `pacman`
+    /// hooks, which is the `wrapperize-backend-<name>` executable in `$PATH`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend: Option<String>,
+    /// When the wrapper is removed by a systemd timer, restoring the original executable.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires: Option<Expiry>,
+    /// Why the executable was wrapped, for whoever comes across the wrapper later.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+    /// Names of groups the wrapper belongs to, such as `gaming`, so related wrappers can be managed together.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Refuse to run the executable, printing this message instead, or a generic one if it's empty.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mask: Option<String>,
+    /// Which users can run the wrapper, on top of the permissions of the file.
+    #[serde(skip_serializing_if = "Access::is_empty")]
+    pub access: Access,
+    /// Time of day the wrapper can be run in, in the local time of the system, unless `WRAPPERIZE_IGNORE_HOURS` is
+    /// set to `1`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_hours: Option<AllowedHours>,
+    /// Environment variables to launch the executable with.
+    #[serde(rename = "envs")]
+    pub env_vars: Vec<env::Variable<'static>>,
+    /// Named sets of parameters, one of which can be selected with `WRAPPERIZE_PROFILE` when the wrapper runs.
+    #[serde(skip_serializing_if = "BTreeMap::is_empty")]
+    pub profiles: BTreeMap<String, Profile>,
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Parameters applied on top of the defaults when a profile is selected at runtime.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize, schemars::JsonSchema)]
+#[serde(default)]
+pub struct Profile {
+    /// Arguments added after the default arguments.
+    pub args: Vec<String>,
+    /// Environment variables set in addition to the default ones, replacing any with the same name.
+    #[serde(rename = "envs")]
+    pub env_vars: Vec<env::Variable<'static>>,
+}
 
-    mod generate_wrapper_script {
-        use super::*;
+impl Profile {
+    fn dedup(&mut self) {
+        dedup_env_vars(&mut self.env_vars);
+    }
+}
 
-        fn gen_script_content(path: &path::Escaped, params: &Params) -> anyhow::Result<String> {
-            let mut buffer = String::new();
-            write_wrapper_script_content(path, params, &mut buffer)?;
-            Ok(buffer)
+impl Params {
+    /// A short, stable hash of these parameters, for telling apart wrappers generated with different ones.
+    pub fn hash(&self) -> String {
+        let serialized = serde_json::to_vec(self).expect("parameters should always serialize");
+        format!("{:016x}", hash::fnv1a(&serialized))
+    }
+
+    /// Merge `other` into these parameters.
+    ///
+    /// Arguments from `other` are appended, keeping their order and repeats, unless they're exactly the arguments
+    /// already present, and environment variables from `other` replace any existing variable with the same name.
+    ///
+    /// Returns the names of environment variables whose values were replaced.
+    pub fn merge(&mut self, other: Params) -> Vec<String> {
+        extend_args(&mut self.args, other.args);
+        extend_args(&mut self.args_before, other.args_before);
+        extend_args(&mut self.args_after, other.args_after);
+        self.env_vars.extend(other.env_vars);
+        self.limits.extend(other.limits);
+        self.presets.extend(other.presets);
+        self.jvm_options.extend(other.jvm_options);
+        self.tags.extend(other.tags);
+        self.add_passthrough_args_first |= other.add_passthrough_args_first;
+        self.no_passthrough |= other.no_passthrough;
+        self.no_strict |= other.no_strict;
+        self.expose_original |= other.expose_original;
+        self.runtime_config |= other.runtime_config;
+        self.user_override |= other.user_override;
+        self.no_network |= other.no_network;
+        self.journal |= other.journal;
+        self.appimage |= other.appimage;
+        self.applet |= other.applet;
+        self.config_protected |= other.config_protected;
+        self.allow_non_executable |= other.allow_non_executable;
+        self.user_units |= other.user_units;
+
+        if other.store_dir.is_some() {
+            self.store_dir = other.store_dir;
         }
 
-        #[test]
-        fn no_args() {
-            let path = path::Escaped::new("test_bin");
-            let result = gen_script_content(&path, &Params::default()).unwrap();
-            assert_eq!(result, r#"exec "test_bin" "\$@""#);
+        if other.unwrapped_pattern.is_some() {
+            self.unwrapped_pattern = other.unwrapped_pattern;
         }
 
-        #[test]
-        fn path_with_space() {
-            let path = path::Escaped::new("/usr/bin/test bin");
-            let result = gen_script_content(&path, &Params::default()).unwrap();
-            assert_eq!(result, r#"exec "/usr/bin/test bin" "\$@""#);
+        if !other.interpreter.is_empty() {
+            self.interpreter = other.interpreter;
         }
 
-        #[test]
-        fn path_with_quote() {
-            let path = path::Escaped::new("/usr/bin/\"test\"");
-            let result = gen_script_content(&path, &Params::default()).unwrap();
-            assert_eq!(result, r#"exec "/usr/bin/\"test\"" "\$@""#);
+        if other.wrapper_mode.is_some() {
+            self.wrapper_mode = other.wrapper_mode;
         }
 
-        #[test]
-        fn with_args() {
-            let path = path::Escaped::new("/usr/bin/test_bin");
-            let args = &[String::from("--arg1"), String::from("--arg2")];
-            let result = gen_script_content(&path, &Params::with_args(args)).unwrap();
+        if other.hook_mode.is_some() {
+            self.hook_mode = other.hook_mode;
+        }
 
-            assert_eq!(result, r#"exec "/usr/bin/test_bin" --arg1 --arg2 "\$@""#);
+        if other.gpu_device.is_some() {
+            self.gpu_device = other.gpu_device;
         }
 
-        #[test]
-        fn with_env_vars() {
-            let path = path::Escaped::new("/usr/bin/test_bin");
+        if !other.wayland_flags.is_empty() {
+            self.wayland_flags = other.wayland_flags;
+        }
 
-            let env_vars = &[
-                env::Variable::new("ENV1", "val1"),
-                env::Variable::new("ENV2", "val2"),
-            ];
+        if other.proxy.is_some() {
+            self.proxy = other.proxy;
+        }
 
-            let result = gen_script_content(&path, &Params::with_env_vars(env_vars)).unwrap();
+        if other.no_proxy.is_some() {
+            self.no_proxy = other.no_proxy;
+        }
 
-            assert_eq!(
-                result,
-                formatdoc! { r#"
-                    export ENV1="val1"
-                    export ENV2="val2"
-                    exec "/usr/bin/test_bin" "\$@""#
-                }
-            );
+        if other.container.is_some() {
+            self.container = other.container;
         }
 
-        #[test]
-        fn passthrough_args_first() {
-            let path = path::Escaped::new("/usr/bin/test_bin");
+        if other.host_container.is_some() {
+            self.host_container = other.host_container;
+        }
 
-            let result = gen_script_content(
-                &path,
-                &Params {
-                    args: &[String::from("--arg1"), String::from("--arg2")],
+        if other.snap.is_some() {
+            self.snap = other.snap;
+        }
+
+        if other.via.is_some() {
+            self.via = other.via;
+        }
+
+        if other.status_file.is_some() {
+            self.status_file = other.status_file;
+        }
+
+        if other.cpus.is_some() {
+            self.cpus = other.cpus;
+        }
+
+        if !other.hook_operations.is_empty() {
+            self.hook_operations = other.hook_operations;
+        }
+
+        if other.backend.is_some() {
+            self.backend = other.backend;
+        }
+
+        if other.expires.is_some() {
+            self.expires = other.expires;
+        }
+
+        if other.reason.is_some() {
+            self.reason = other.reason;
+        }
+
+        if other.mask.is_some() {
+            self.mask = other.mask;
+        }
+
+        self.access.merge(other.access);
+
+        if other.allowed_hours.is_some() {
+            self.allowed_hours = other.allowed_hours;
+        }
+
+        for (name, other) in other.profiles {
+            let profile = self.profiles.entry(name).or_default();
+            extend_args(&mut profile.args, other.args);
+            profile.env_vars.extend(other.env_vars);
+        }
+
+        self.dedup()
+    }
+
+    /// Whether these parameters change how the executable is launched, so a wrapper with them does anything at all.
+    pub fn changes_launch(&self) -> bool {
+        !self.args.is_empty()
+            || !self.args_before.is_empty()
+            || !self.args_after.is_empty()
+            || !self.env_vars.is_empty()
+            || !self.profiles.is_empty()
+            || self.runtime_config
+            || self.user_override
+            || !self.limits.is_empty()
+            || !self.presets.is_empty()
+            || !self.jvm_options.is_empty()
+            || self.no_network
+            || self.journal
+            || self.status_file.is_some()
+            || self.cpus.is_some()
+            || self.via.is_some()
+            || self.container.is_some()
+            || self.host_container.is_some()
+            || self.mask.is_some()
+            || !self.access.is_empty()
+            || self.allowed_hours.is_some()
+    }
+
+    /// Arguments of `other` that these parameters already have, which [`merge`](Self::merge) would add again.
+    pub fn repeated_args(&self, other: &Params) -> Vec<String> {
+        let mut repeated = Vec::new();
+
+        for (args, new) in [
+            (&self.args, &other.args),
+            (&self.args_before, &other.args_before),
+            (&self.args_after, &other.args_after),
+        ] {
+            // the same arguments aren't added again at all
+            if args != new {
+                repeated.extend(new.iter().filter(|arg| args.contains(arg)).cloned());
+            }
+        }
+
+        dedup_values(&mut repeated);
+        repeated
+    }
+
+    /// Remove repeated JVM options and tags, keeping the first occurrence, and resolve environment variables with the
+    /// same name by keeping the last one defined, at the position of the first one. Arguments are left alone, since
+    /// repeating one, such as `-v -v`, can change what it does.
+    ///
+    /// Returns the names of environment variables whose values were replaced.
+    pub fn dedup(&mut self) -> Vec<String> {
+        for values in [&mut self.jvm_options, &mut self.tags] {
+            dedup_values(values);
+        }
+
+        for profile in self.profiles.values_mut() {
+            profile.dedup();
+        }
+
+        dedup_limits(&mut self.limits);
+
+        let mut seen_presets = HashSet::new();
+        self.presets.retain(|preset| seen_presets.insert(*preset));
+
+        dedup_env_vars(&mut self.env_vars)
+    }
+
+    #[cfg(test)]
+    pub fn with_args(args: Vec<String>) -> Self {
+        Self {
+            args,
+            ..Default::default()
+        }
+    }
+
+    #[cfg(test)]
+    fn with_env_vars(env_vars: Vec<env::Variable<'static>>) -> Self {
+        Self {
+            env_vars,
+            ..Default::default()
+        }
+    }
+}
+
+/// Append `new` to `args`, unless they're the same arguments, so updating a wrapper with the arguments it already
+/// has doesn't repeat them.
+fn extend_args(args: &mut Vec<String>, new: Vec<String>) {
+    if *args != new {
+        args.extend(new);
+    }
+}
+
+/// Remove repeated values, keeping the first occurrence.
+fn dedup_values(values: &mut Vec<String>) {
+    let mut seen_values = HashSet::new();
+    values.retain(|value| seen_values.insert(value.clone()));
+}
+
+/// Resolve limits for the same resource by keeping the last one defined, at the position of the first one.
+fn dedup_limits(limits: &mut Vec<Limit>) {
+    let mut deduped: Vec<Limit> = Vec::with_capacity(limits.len());
+
+    for limit in limits.drain(..) {
+        match deduped
+            .iter_mut()
+            .find(|existing| existing.resource == limit.resource)
+        {
+            Some(existing) => *existing = limit,
+            None => deduped.push(limit),
+        }
+    }
+
+    *limits = deduped;
+}
+
+/// Resolve environment variables with the same name by keeping the last one defined, at the position of the first
+/// one, while keeping every distinct value appended or prepended to one. Returns the names of environment variables
+/// whose values were replaced.
+fn dedup_env_vars(vars: &mut Vec<env::Variable<'static>>) -> Vec<String> {
+    let mut replaced = Vec::new();
+    let mut env_vars: Vec<env::Variable<'static>> = Vec::with_capacity(vars.len());
+
+    for var in vars.drain(..) {
+        // every addition to a list is kept, unless it adds the same value again
+        if var.op != env::Operation::Set {
+            if !env_vars.contains(&var) {
+                env_vars.push(var);
+            }
+
+            continue;
+        }
+
+        match env_vars
+            .iter_mut()
+            .find(|existing| existing.name == var.name && existing.op == env::Operation::Set)
+        {
+            Some(existing) => {
+                if existing.value != var.value && !replaced.iter().any(|name| *name == var.name) {
+                    replaced.push(var.name.to_string());
+                }
+
+                *existing = var;
+            }
+            None => env_vars.push(var),
+        }
+    }
+
+    *vars = env_vars;
+    replaced
+}
+
+/// A wrapper whose install script has been generated in memory, but that hasn't been written to disk yet.
+///
+/// Staging has no side effects, so multiple wrappers can be staged in parallel before installing them one by one.
+pub struct Staged<'a> {
+    pub paths: ExecPaths,
+    pub mode: Mode,
+    params: &'a Params,
+    use_pacman_hooks: bool,
+    /// Contents of the wrapper script.
+    wrapper_script: String,
+    /// Script that reinstalls the wrapper after `pacman` has replaced the wrapped executable.
+    hook_install_script: InstallScript,
+    /// Whether everything on disk already matches what would be installed.
+    up_to_date: bool,
+}
+
+pub fn stage(
+    mut paths: ExecPaths,
+    wrapper_params: &Params,
+    use_pacman_hooks: bool,
+    mode: Mode,
+) -> anyhow::Result<Staged<'_>> {
+    validate_params(wrapper_params, use_pacman_hooks)?;
+
+    let expected_unwrapped = paths.expected_unwrapped_path(wrapper_params);
+
+    if paths.unwrapped.original != expected_unwrapped {
+        // the original executable would have to be moved along with the wrapper, which is what unwrapping is for
+        if paths.is_wrapped()? {
+            anyhow::bail!(t!(
+                "wrapper-original-elsewhere",
+                path = paths.unwrapped.original.display().to_string(),
+                expected = expected_unwrapped.display().to_string(),
+            ));
+        }
+
+        paths.unwrapped = path::Escaped::new(expected_unwrapped);
+    }
+
+    let wrapper_already_exists = paths.is_wrapped()?;
+
+    let (wrapper_script, hook_install_script) = generate_scripts(&paths, wrapper_params)?;
+
+    let up_to_date = wrapper_already_exists
+        && is_up_to_date(
+            &paths,
+            wrapper_params,
+            use_pacman_hooks,
+            &wrapper_script,
+            &hook_install_script,
+        )?;
+
+    if mode == Mode::Update && !wrapper_already_exists {
+        return Err(IoError::new(&paths.wrapped.original, t!("wrapper-none")).into());
+    }
+
+    // recreating an identical wrapper is allowed, so repeated invocations can succeed without changes
+    if mode == Mode::Create && wrapper_already_exists && !up_to_date {
+        let path = paths.unwrapped.original.display().to_string();
+
+        // wrappers created before metadata was stored won't have any, so this is best-effort
+        let msg = match Metadata::read(&paths.metadata.original) {
+            Ok(metadata) => t!(
+                "wrapper-exists-version",
+                path = path,
+                program = env!("CARGO_PKG_NAME"),
+                version = metadata.version.to_string(),
+            ),
+            Err(_) => t!("wrapper-exists", path = path),
+        };
+
+        return Err(IoError::new(&paths.wrapped.original, msg).into());
+    }
+
+    Ok(Staged {
+        paths,
+        params: wrapper_params,
+        use_pacman_hooks,
+        mode,
+        wrapper_script,
+        hook_install_script,
+        up_to_date,
+    })
+}
+
+/// Check the wrapper of `path` with `wrapper_params` and generate every file it would be installed with, without
+/// reading or changing anything on the system, as if the executable had never been wrapped.
+pub fn render<'a>(
+    path: &Path,
+    wrapper_params: &'a Params,
+    use_pacman_hooks: bool,
+) -> anyhow::Result<Staged<'a>> {
+    validate_params(wrapper_params, use_pacman_hooks)?;
+
+    let paths = ExecPaths::with_params(path, wrapper_params)?;
+    let (wrapper_script, hook_install_script) = generate_scripts(&paths, wrapper_params)?;
+
+    Ok(Staged {
+        paths,
+        params: wrapper_params,
+        use_pacman_hooks,
+        mode: Mode::Create,
+        wrapper_script,
+        hook_install_script,
+        up_to_date: false,
+    })
+}
+
+/// Generate the wrapper script of the executable at `path` with `wrapper_params`, launching the executable where it is
+/// rather than an original moved out of the way, so the parameters can be tried out without installing anything.
+pub fn render_in_place(path: &Path, wrapper_params: &Params) -> anyhow::Result<String> {
+    validate_params(wrapper_params, false)?;
+
+    let header = Header::new(path, wrapper_params);
+
+    let mut wrapper_script = String::new();
+    write_full_wrapper_script(
+        &path::Escaped::new(path),
+        &header,
+        wrapper_params,
+        &mut wrapper_script,
+    )
+    .with_context(|| t!("wrapper-generate-failed"))?;
+
+    Ok(wrapper_script)
+}
+
+/// Returns an error if the wrapper can't be generated with `wrapper_params`, such as because some of them can't be
+/// used together.
+fn validate_params(wrapper_params: &Params, use_pacman_hooks: bool) -> anyhow::Result<()> {
+    if let Some(dir) = wrapper_params
+        .store_dir
+        .as_ref()
+        .filter(|dir| !dir.is_absolute())
+    {
+        return Err(IoError::new(dir, t!("wrapper-store-dir-relative")).into());
+    }
+
+    if let Some(pattern) = &wrapper_params.unwrapped_pattern {
+        validate_unwrapped_pattern(pattern)?;
+    }
+
+    if wrapper_params
+        .reason
+        .as_ref()
+        .is_some_and(|reason| reason.trim().is_empty())
+    {
+        anyhow::bail!(t!("wrapper-empty-reason"));
+    }
+
+    for tag in &wrapper_params.tags {
+        validate_tag(tag)?;
+    }
+
+    // an empty name would be selected whenever no profile is
+    if wrapper_params.profiles.contains_key("") {
+        anyhow::bail!(t!("wrapper-empty-profile-name"));
+    }
+
+    preset::validate_options(wrapper_params)?;
+
+    if let Some(container) = &wrapper_params.container {
+        container.validate()?;
+    }
+
+    if let Some(container) = &wrapper_params.host_container {
+        anyhow::ensure!(
+            wrapper_params.container.is_none(),
+            t!(
+                "wrapper-container-and-host-container",
+                tool = container.tool.to_string(),
+            )
+        );
+
+        container.validate()?;
+    }
+
+    // the image is what the container or snap would replace
+    anyhow::ensure!(
+        !wrapper_params.appimage
+            || (wrapper_params.container.is_none()
+                && wrapper_params.host_container.is_none()
+                && wrapper_params.snap.is_none()),
+        t!("wrapper-appimage-container")
+    );
+
+    if !wrapper_params.interpreter.is_empty() {
+        anyhow::ensure!(
+            !wrapper_params.appimage
+                && wrapper_params.container.is_none()
+                && wrapper_params.host_container.is_none()
+                && wrapper_params.snap.is_none(),
+            t!("wrapper-interpreter-not-script")
+        );
+
+        // the interpreter and its argument are the first two words of a shebang
+        anyhow::ensure!(
+            wrapper_params.interpreter.len() <= 2,
+            t!("wrapper-interpreter-args")
+        );
+    }
+
+    // the name the applet is run with is lost when something else runs it
+    if wrapper_params.applet {
+        anyhow::ensure!(
+            !wrapper_params.appimage
+                && wrapper_params.interpreter.is_empty()
+                && wrapper_params.container.is_none()
+                && wrapper_params.host_container.is_none()
+                && wrapper_params.snap.is_none(),
+            t!("wrapper-applet-container")
+        );
+
+        anyhow::ensure!(
+            launcher(wrapper_params).is_empty()
+                && wrapper_params.presets.is_empty()
+                && !wrapper_params.journal,
+            t!("wrapper-applet-launcher")
+        );
+    }
+
+    // nothing the executable would be launched with matters once it's refused
+    if wrapper_params.mask.is_some() {
+        let others = Params {
+            mask: None,
+            ..wrapper_params.clone()
+        };
+
+        anyhow::ensure!(
+            !others.changes_launch() && others.snap.is_none(),
+            t!("wrapper-mask-with-params")
+        );
+    }
+
+    if let Some(app) = &wrapper_params.snap {
+        anyhow::ensure!(
+            wrapper_params.container.is_none() && wrapper_params.host_container.is_none(),
+            t!("wrapper-snap-container")
+        );
+
+        // snaps are updated by `snapd` rather than `pacman`, and the shim isn't touched by either
+        anyhow::ensure!(!use_pacman_hooks, t!("wrapper-snap-hooks"));
+
+        snap::validate_app(app)?;
+    }
+
+    for option in &wrapper_params.jvm_options {
+        jvm::validate_option(option)?;
+    }
+
+    wrapper_params.access.validate()?;
+
+    if let Some(path) = &wrapper_params.status_file {
+        status_file::validate(path)?;
+    }
+
+    if let Some(expires) = wrapper_params.expires {
+        anyhow::ensure!(
+            !expires.is_past(),
+            t!("wrapper-expired", expires = expires.to_string())
+        );
+    }
+
+    if wrapper_params.user_units {
+        anyhow::ensure!(!use_pacman_hooks, t!("wrapper-hooks-and-user-units"));
+
+        // the shim isn't replaced when the snap is updated
+        anyhow::ensure!(wrapper_params.snap.is_none(), t!("wrapper-snap-user-units"));
+    }
+
+    if let Some(backend) = &wrapper_params.backend {
+        plugin::validate_name(backend)?;
+
+        anyhow::ensure!(
+            !use_pacman_hooks && !wrapper_params.user_units,
+            t!("wrapper-backend-and-hooks", backend = backend.as_str())
+        );
+
+        anyhow::ensure!(wrapper_params.snap.is_none(), t!("wrapper-snap-backend"));
+    }
+
+    Ok(())
+}
+
+/// Generate the wrapper script and the install script of its `pacman` hooks.
+fn generate_scripts(
+    paths: &ExecPaths,
+    wrapper_params: &Params,
+) -> anyhow::Result<(String, InstallScript)> {
+    let header = Header::new(&paths.wrapped.original, wrapper_params);
+
+    let mut wrapper_script = String::new();
+    write_full_wrapper_script(
+        &paths.unwrapped,
+        &header,
+        wrapper_params,
+        &mut wrapper_script,
+    )
+    .with_context(|| t!("wrapper-generate-failed"))?;
+
+    let hook_install_script =
+        InstallScript::generate(paths, &header, &wrapper_script, wrapper_params)?;
+
+    Ok((wrapper_script, hook_install_script))
+}
+
+/// Returns true if the installed wrapper, its metadata, and its hooks all match what would be generated.
+fn is_up_to_date(
+    paths: &ExecPaths,
+    wrapper_params: &Params,
+    use_pacman_hooks: bool,
+    wrapper_script: &str,
+    hook_install_script: &InstallScript,
+) -> anyhow::Result<bool> {
+    // anything unreadable is treated as out of date, since reinstalling will replace it
+    let Ok(metadata) = Metadata::read(&paths.metadata.original) else {
+        return Ok(false);
+    };
+
+    // wrappers from other versions may have been generated differently
+    let metadata_matches = metadata.version == env!("CARGO_PKG_VERSION")
+        && metadata.params == *wrapper_params
+        && metadata.use_pacman_hooks == use_pacman_hooks
+        && !metadata.dormant;
+
+    if !metadata_matches {
+        return Ok(false);
+    }
+
+    let read = |path: &Path| fs::read_to_string(path).ok();
+
+    let installed_script = read(&paths.wrapped.original);
+
+    if installed_script.as_deref().map(str::trim_end) != Some(wrapper_script.trim_end()) {
+        return Ok(false);
+    }
+
+    if !user_units_up_to_date(paths, wrapper_params, hook_install_script) {
+        return Ok(false);
+    }
+
+    if !expiry_up_to_date(paths, wrapper_params) {
+        return Ok(false);
+    }
+
+    let backend_script = read(&plugin::install_script_path(&paths.hook_name));
+
+    if backend_script.as_deref()
+        != wrapper_params
+            .backend
+            .as_ref()
+            .map(|_| hook_install_script.contents.as_str())
+    {
+        return Ok(false);
+    }
+
+    let install_script_path = pacman_hook::install_script_path(&paths.hook_name);
+    let hook_paths = pacman_hook::all_paths(&paths.hook_name);
+
+    if !use_pacman_hooks {
+        return Ok(!hook_paths.iter().any(|path| path.exists()));
+    }
+
+    Ok(hook_paths.iter().all(|path| path.exists())
+        && read(&install_script_path).as_deref() == Some(&hook_install_script.contents))
+}
+
+/// Returns true if the systemd user units of the wrapper match what would be generated, or don't exist if it has none.
+fn user_units_up_to_date(
+    paths: &ExecPaths,
+    wrapper_params: &Params,
+    hook_install_script: &InstallScript,
+) -> bool {
+    let Ok(unit_paths) = user_unit::all_paths(&paths.hook_name) else {
+        // without a home directory, there can't be any units
+        return !wrapper_params.user_units;
+    };
+
+    let installed = unit_paths
+        .each_ref()
+        .map(|path| fs::read_to_string(path).ok());
+
+    if !wrapper_params.user_units {
+        return installed.iter().all(Option::is_none);
+    }
+
+    let header = Header::new(&paths.wrapped.original, wrapper_params);
+    let [_, _, install_script_path] = &unit_paths;
+
+    installed
+        == [
+            Some(user_unit::generate_path_unit(paths, &header)),
+            Some(user_unit::generate_service(
+                paths,
+                &header,
+                install_script_path,
+            )),
+            Some(hook_install_script.contents.clone()),
+        ]
+}
+
+/// Returns true if the timer and service removing the wrapper match what would be generated, or don't exist if it
+/// doesn't expire.
+fn expiry_up_to_date(paths: &ExecPaths, wrapper_params: &Params) -> bool {
+    let Ok(expected) = expiry_files(paths, wrapper_params) else {
+        return false;
+    };
+
+    expected
+        .iter()
+        .all(|(path, content)| fs::read_to_string(path).ok() == *content)
+}
+
+/// The timer and service removing the wrapper at `paths` once it expires, along with the contents they would be
+/// installed with, or `None` if they'd be removed.
+fn expiry_files(
+    paths: &ExecPaths,
+    wrapper_params: &Params,
+) -> anyhow::Result<Vec<(PathBuf, Option<String>)>> {
+    let user = wrapper_params.user_units;
+
+    let Some(expires) = wrapper_params.expires else {
+        // without a home directory, there can't be any units of the user
+        return Ok(expiry::all_paths(&paths.hook_name, user)
+            .map(|unit_paths| unit_paths.into_iter().map(|path| (path, None)).collect())
+            .unwrap_or_default());
+    };
+
+    let header = Header::new(&paths.wrapped.original, wrapper_params);
+    let [timer, service] = expiry::all_paths(&paths.hook_name, user)?;
+
+    Ok(vec![
+        (timer, Some(expiry::generate_timer(paths, &header, expires))),
+        (
+            service,
+            Some(expiry::generate_service(
+                paths,
+                &header,
+                &expiry::program_path()?,
+            )),
+        ),
+    ])
+}
+
+impl Staged<'_> {
+    /// Whether everything on disk already matches what would be installed, so installing would change nothing.
+    pub fn is_up_to_date(&self) -> bool {
+        self.up_to_date
+    }
+
+    /// Whether the wrapper gets `pacman` hooks.
+    pub fn uses_pacman_hooks(&self) -> bool {
+        self.use_pacman_hooks
+    }
+
+    /// Install the wrapper, and write its metadata, and the install script and `pacman` hooks if enabled, to disk.
+    pub fn install(self) -> anyhow::Result<()> {
+        let paths = &self.paths;
+        let header = Header::new(&paths.wrapped.original, self.params);
+
+        if let Some(via) = self.params.via {
+            via.ensure_installed()?;
+        }
+
+        if let Some(container) = &self.params.container {
+            container.ensure_engine_installed()?;
+        }
+
+        if let Some(container) = &self.params.host_container {
+            container.ensure_tool_installed()?;
+        }
+
+        mount::ensure_writable(&paths.wrapped.original)?;
+        mount::ensure_writable(&paths.unwrapped.original)?;
+
+        // only the stored parameters record which backend recreated the previous wrapper
+        let previous_backend = match self.mode {
+            Mode::Update => Metadata::read(&paths.metadata.original)
+                .ok()
+                .and_then(|metadata| metadata.params.backend),
+            Mode::Create => None,
+        };
+
+        // the install script of the previous wrapper would otherwise put it back as soon as this one is written
+        if self.mode == Mode::Update {
+            user_unit::stop(&paths.hook_name);
+        }
+
+        self.install_wrapper()?;
+
+        // persist the parameters next to the wrapper so it can be faithfully regenerated later
+        Metadata::new(
+            &paths.wrapped.original,
+            self.params.clone(),
+            self.use_pacman_hooks,
+        )
+        .write(&paths.metadata.original)?;
+
+        if self.params.expose_original {
+            symlink::repoint(&paths.exposed_original_path(), &paths.unwrapped.original)?;
+        } else {
+            // the original may have been exposed the last time the wrapper was generated
+            remove_exposed_original(paths)?;
+        }
+
+        // units may have been enabled the last time the wrapper was generated, as may hooks below
+        if self.mode == Mode::Update && !self.params.user_units {
+            user_unit::remove_all(paths)?;
+        }
+
+        // the timer may have been started for an earlier expiry, or for the other service manager
+        if self.mode == Mode::Update {
+            expiry::remove_all(paths)?;
+        }
+
+        if let Some(expires) = self.params.expires {
+            let unit_mode = self.params.hook_mode.unwrap_or(file::REGULAR_MODE);
+            expiry::install(paths, &header, expires, self.params.user_units, unit_mode.0)?;
+        }
+
+        if let Some(previous) =
+            previous_backend.filter(|previous| self.params.backend.as_ref() != Some(previous))
+        {
+            plugin::remove_backend(&previous, paths)?;
+        }
+
+        if let Some(backend) = &self.params.backend {
+            self.install_backend(backend)?;
+        }
+
+        if !self.use_pacman_hooks {
+            if self.mode == Mode::Update {
+                pacman_hook::remove_all(paths)?;
+            }
+
+            if self.params.user_units {
+                self.install_user_units(&header)?;
+            }
+
+            return Ok(());
+        }
+
+        let wrapper_install_script_path = pacman_hook::install_script_path(&paths.hook_name);
+
+        // since we are using pacman hooks, generate their contents and write them all to disk now
+
+        pacman_hook::create_dir()?;
+
+        // hooks named by an older version would otherwise run alongside the new ones
+        pacman_hook::remove_legacy(paths)?;
+
+        let hook_mode = self.params.hook_mode.unwrap_or(file::REGULAR_MODE);
+
+        self.hook_install_script.save(
+            &wrapper_install_script_path,
+            hook_mode.with_execute_for_readers().0,
+        )?;
+
+        for hook in self.hooks() {
+            hook.generate_and_write_to_disk(paths, &header, hook_mode.0)?;
+        }
+
+        Ok(())
+    }
+
+    /// Write the install script and systemd user units of the wrapper, and start watching the wrapped executable.
+    fn install_user_units(&self, header: &Header) -> anyhow::Result<()> {
+        let paths = &self.paths;
+        let install_script_path = user_unit::install_script_path(&paths.hook_name)?;
+        let unit_mode = self.params.hook_mode.unwrap_or(file::REGULAR_MODE);
+
+        user_unit::create_dir()?;
+
+        self.hook_install_script
+            .save(&install_script_path, unit_mode.with_execute_for_readers().0)?;
+
+        user_unit::write(paths, header, &install_script_path, unit_mode.0)?;
+        user_unit::enable(&paths.hook_name);
+
+        Ok(())
+    }
+
+    /// Write the install script of the wrapper, and have the backend named `backend` run it whenever its package manager
+    /// replaces the wrapped executable.
+    fn install_backend(&self, backend: &str) -> anyhow::Result<()> {
+        let paths = &self.paths;
+        let install_script_path = plugin::install_script_path(&paths.hook_name);
+        let script_mode = self.params.hook_mode.unwrap_or(file::REGULAR_MODE);
+
+        fs::create_dir_all(plugin::BACKEND_SCRIPT_DIR).with_context(|| {
+            IoError::new(
+                plugin::BACKEND_SCRIPT_DIR,
+                t!("wrapper-create-backend-dir-failed"),
+            )
+        })?;
+
+        self.hook_install_script.save(
+            &install_script_path,
+            script_mode.with_execute_for_readers().0,
+        )?;
+
+        plugin::install_backend(backend, paths)
+    }
+
+    /// The `pacman` hooks of the wrapper, whether or not they're enabled.
+    fn hooks(&self) -> [pacman_hook::Hook; 2] {
+        let paths = &self.paths;
+
+        [
+            pacman_hook::Hook::new(
+                &paths.hook_name,
+                pacman_hook::TriggerAction::InstallOrUpdate {
+                    operations: self.params.hook_operations.clone(),
+                },
+            ),
+            pacman_hook::Hook::new(
+                &paths.hook_name,
+                pacman_hook::TriggerAction::Removal {
+                    wrapper_install_script_path: pacman_hook::install_script_path(&paths.hook_name),
+                    exposed_original_path: self
+                        .params
+                        .expose_original
+                        .then(|| paths.exposed_original_path()),
+                },
+            ),
+        ]
+    }
+
+    /// Run the install script in a sandbox where the directories of the wrapper and its original are throwaway
+    /// overlays, returning what it would change in them.
+    #[cfg(target_os = "linux")]
+    pub fn preview_install_script(&self) -> anyhow::Result<Vec<preview::Change>> {
+        // the shim of a snap command is written directly, since there's nothing to move out of the way
+        anyhow::ensure!(self.params.snap.is_none(), t!("wrapper-snap-preview"));
+
+        let paths = &self.paths;
+        let dirs = [&paths.wrapped.original, &paths.unwrapped.original]
+            .into_iter()
+            .filter_map(|path| path.parent())
+            .map(Path::to_path_buf)
+            .collect::<Vec<_>>();
+
+        preview::run(&self.hook_install_script.contents, &dirs)
+    }
+
+    /// Every generated file of the wrapper along with the contents it would be installed with, or `None` if
+    /// installing would remove it.
+    pub fn files(&self) -> anyhow::Result<Vec<(PathBuf, Option<String>)>> {
+        let paths = &self.paths;
+
+        let mut files = vec![(
+            paths.wrapped.original.clone(),
+            Some(self.wrapper_script.clone()),
+        )];
+
+        let header = Header::new(&paths.wrapped.original, self.params);
+
+        if self.use_pacman_hooks {
+            files.push((
+                pacman_hook::install_script_path(&paths.hook_name),
+                Some(self.hook_install_script.contents.clone()),
+            ));
+
+            for hook in self.hooks() {
+                let content = hook.generate(paths, &header)?;
+                files.push((hook.path, Some(content)));
+            }
+        } else {
+            files.extend(
+                pacman_hook::all_paths(&paths.hook_name)
+                    .into_iter()
+                    .map(|path| (path, None)),
+            );
+        }
+
+        if self.params.user_units {
+            let [path_unit, service, install_script] = user_unit::all_paths(&paths.hook_name)?;
+            let service_content = user_unit::generate_service(paths, &header, &install_script);
+
+            files.extend([
+                (
+                    path_unit,
+                    Some(user_unit::generate_path_unit(paths, &header)),
+                ),
+                (service, Some(service_content)),
+                (
+                    install_script,
+                    Some(self.hook_install_script.contents.clone()),
+                ),
+            ]);
+        } else if let Ok(unit_paths) = user_unit::all_paths(&paths.hook_name) {
+            // without a home directory, there can't be any units to remove
+            files.extend(unit_paths.into_iter().map(|path| (path, None)));
+        }
+
+        files.push((
+            plugin::install_script_path(&paths.hook_name),
+            self.params
+                .backend
+                .as_ref()
+                .map(|_| self.hook_install_script.contents.clone()),
+        ));
+
+        files.extend(expiry_files(paths, self.params)?);
+
+        Ok(files)
+    }
+
+    /// Move the original executable out of the way if needed, and write the wrapper in its place.
+    ///
+    /// If the wrapper can't be written, the original executable is moved back.
+    fn install_wrapper(&self) -> anyhow::Result<()> {
+        let paths = &self.paths;
+        let wrapped = &paths.wrapped.original;
+        let unwrapped = &paths.unwrapped.original;
+
+        if let Some(app) = &self.params.snap {
+            return self.install_shim(app);
+        }
+
+        // an existing wrapper has already moved the original executable out of the way,
+        // so only the wrapper script itself needs to be rewritten
+        let move_original = self.mode == Mode::Create && !is_wrapper_script(wrapped)?;
+
+        if move_original {
+            if let Some(dir) = unwrapped.parent() {
+                fs::create_dir_all(dir)
+                    .with_context(|| IoError::new(dir, t!("wrapper-create-store-dir-failed")))?;
+            }
+
+            fs::rename(wrapped, unwrapped)
+                .with_context(|| IoError::new(wrapped, t!("wrapper-move-original-failed")))?;
+        }
+
+        let write_wrapper = || {
+            // the wrapper should be runnable by whoever could run the original executable, unless told otherwise
+            let mode = match self.params.wrapper_mode {
+                Some(mode) => mode.0,
+                None => file::mode(unwrapped)
+                    .with_context(|| IoError::new(unwrapped, t!("wrapper-original-mode-failed")))?,
+            };
+
+            file::write_atomic(wrapped, self.wrapper_script.as_bytes(), mode)
+                .with_context(|| IoError::new(wrapped, t!("wrapper-write-failed")))
+        };
+
+        let Err(err) = write_wrapper() else {
+            return Ok(());
+        };
+
+        if move_original {
+            // the wrapper is only ever moved into place once fully written, so the original executable can go back
+            if let Err(rollback_err) = fs::rename(unwrapped, wrapped) {
+                return Err(err.context(t!(
+                    "wrapper-rollback-failed",
+                    path = unwrapped.display().to_string(),
+                    error = rollback_err.to_string(),
+                )));
+            }
+        }
+
+        Err(err)
+    }
+
+    /// Write the wrapper as a shim for the snap app named `app`, with a symlink to the snap's command in place of the
+    /// original executable.
+    fn install_shim(&self, app: &str) -> anyhow::Result<()> {
+        let paths = &self.paths;
+        let wrapped = &paths.wrapped.original;
+
+        // unlike an executable that gets wrapped, anything already at the path of the shim would be lost
+        if wrapped.exists() && !is_wrapper_script(wrapped)? {
+            return Err(IoError::new(wrapped, t!("wrapper-shim-in-the-way")).into());
+        }
+
+        symlink::repoint(&paths.unwrapped.original, &snap::command_path(app))?;
+
+        let mode = self
+            .params
+            .wrapper_mode
+            .unwrap_or(file::REGULAR_MODE.with_execute_for_readers());
+
+        file::write_atomic(wrapped, self.wrapper_script.as_bytes(), mode.0)
+            .with_context(|| IoError::new(wrapped, t!("wrapper-write-shim-failed")))
+    }
+}
+
+/// Remove the symlink exposing the original executable, if there is one.
+fn remove_exposed_original(paths: &ExecPaths) -> anyhow::Result<()> {
+    let exposed = paths.exposed_original_path();
+
+    // only remove what we created, rather than an unrelated file that happens to have the same name
+    let is_ours = fs::read_link(&exposed).is_ok_and(|target| target == paths.unwrapped.original);
+
+    if is_ours {
+        fs::remove_file(&exposed)
+            .with_context(|| IoError::new(&exposed, t!("wrapper-remove-exposed-failed")))?;
+    }
+
+    Ok(())
+}
+
+/// Command creating the directory of the unwrapped executable, if it isn't the directory of the wrapper itself.
+fn store_dir_cmd(paths: &ExecPaths) -> String {
+    match paths.unwrapped.original.parent() {
+        Some(dir) if Some(dir) != paths.wrapped.original.parent() => {
+            format!("mkdir -p \"{}\"\n    ", path::Escaped::new(dir).escaped)
+        }
+        _ => String::new(),
+    }
+}
+
+/// Command moving the new version of a configuration-protected executable from its `.pacnew` file over the unwrapped
+/// executable, since `pacman` leaves the wrapper in place instead of replacing it.
+fn pacnew_cmd(paths: &ExecPaths, params: &Params) -> String {
+    if !params.config_protected {
+        return String::new();
+    }
+
+    formatdoc! { r#"
+        if [ -e "{wrapped_path}.pacnew" ]; then
+            {mkdir_cmd}mv -f "{wrapped_path}.pacnew" "{unwrapped_path}"
+        fi
+
+        "#,
+        wrapped_path = paths.wrapped.escaped,
+        unwrapped_path = paths.unwrapped.escaped,
+        mkdir_cmd = store_dir_cmd(paths),
+    }
+}
+
+/// Environment variable naming the profile a wrapper applies on top of its default parameters.
+pub const PROFILE_VAR: &str = "WRAPPERIZE_PROFILE";
+
+/// Names of the arrays in the wrapper script holding the arguments that precede, replace, and follow the passthrough
+/// arguments.
+pub const ARGS_BEFORE_VAR: &str = "args_before";
+const ARGS_VAR: &str = "args";
+const ARGS_AFTER_VAR: &str = "args_after";
+
+/// Directory of the files that wrappers generated with `runtime_config` source when they run.
+const RUNTIME_CONFIG_DIR: &str = concat!("/etc/", env!("CARGO_PKG_NAME"), "/conf.d");
+
+/// Path of the file sourced at runtime by the wrapper of the executable named `wrapped_filename`.
+pub fn runtime_config_path(wrapped_filename: &str) -> PathBuf {
+    Path::new(RUNTIME_CONFIG_DIR).join(format!("{wrapped_filename}.conf"))
+}
+
+/// Path of the file sourced at runtime by the wrapper of the executable named `wrapped_filename` for the user running
+/// it, as shown to users.
+pub fn user_override_path(wrapped_filename: &str) -> String {
+    format!(
+        "$XDG_CONFIG_HOME/{}/{wrapped_filename}.override",
+        env!("CARGO_PKG_NAME")
+    )
+}
+
+/// Write the lines that source the override file of the user running the wrapper of the executable named
+/// `wrapped_filename`, if it exists.
+fn write_user_override_lines(
+    wrapped_filename: &str,
+    params: &Params,
+    mut writer: impl FmtWrite,
+) -> fmt::Result {
+    let filename = path::Escaped::new(format!("{wrapped_filename}.override"));
+
+    writeln!(
+        writer,
+        r#"user_override="${{XDG_CONFIG_HOME:-${{HOME:-}}/.config}}/{}/{}""#,
+        env!("CARGO_PKG_NAME"),
+        filename.escaped
+    )?;
+
+    // a wrapper run through `sudo` can keep the `HOME` of the user, whose file root shouldn't run
+    writeln!(
+        writer,
+        r#"if [ -f "$user_override" ] && [ -O "$user_override" ]; then"#
+    )?;
+
+    if !params.no_strict {
+        writeln!(writer, "    set +u")?;
+    }
+
+    writeln!(writer, r#"    . "$user_override""#)?;
+
+    if !params.no_strict {
+        writeln!(writer, "    set -u")?;
+    }
+
+    writeln!(writer, "fi")
+}
+
+/// Write the body of the wrapper script for the executable named `wrapped_filename`. If `runtime_config` is set, that
+/// file is sourced right before the executable is launched, so it can add to or override the parameters, followed by
+/// the override file of the user if `user_override` is set.
+fn write_wrapper_script_content(
+    unwrapped_exec_path: &path::Escaped,
+    wrapped_filename: &str,
+    params: &Params,
+    runtime_config: Option<&Path>,
+    mut writer: impl FmtWrite,
+) -> fmt::Result {
+    // values can intentionally reference other variables that may not be set, so they're exempt from `set -u`
+    let relax_unset = !params.no_strict && !params.env_vars.is_empty();
+
+    if relax_unset {
+        writeln!(writer, "set +u")?;
+    }
+
+    // first, add all environment variables to the wrapper
+    for env in &params.env_vars {
+        env.write_bash_line(&mut writer)?;
+    }
+
+    if relax_unset {
+        writeln!(writer, "set -u")?;
+    }
+
+    for limit in &params.limits {
+        limit.write_bash_line(&mut writer)?;
+    }
+
+    // store the wrapper arguments in arrays so every argument is passed through as a single word
+    // no matter what characters it contains
+
+    // profiles, runtime configs and presets can add to the arrays, so they all have to exist
+    let all_arrays = runtime_config.is_some()
+        || params.user_override
+        || !params.profiles.is_empty()
+        || params.presets.iter().any(|preset| preset.adds_args());
+
+    fn write_words(args: &[String], writer: &mut dyn FmtWrite) -> fmt::Result {
+        for (i, arg) in args.iter().enumerate() {
+            if i > 0 {
+                writer.write_char(' ')?;
+            }
+
+            writer.write_str(&shell::quote(arg))?;
+        }
+
+        Ok(())
+    }
+
+    let write_array = |name: &str, args: &[String], writer: &mut dyn FmtWrite| {
+        if args.is_empty() && !all_arrays {
+            return Ok(());
+        }
+
+        write!(writer, "{name}=(")?;
+        write_words(args, writer)?;
+        writeln!(writer, ")")
+    };
+
+    write_array(ARGS_BEFORE_VAR, &params.args_before, &mut writer)?;
+    write_array(ARGS_VAR, &params.args, &mut writer)?;
+    write_array(ARGS_AFTER_VAR, &params.args_after, &mut writer)?;
+
+    if !params.profiles.is_empty() {
+        // profile variables get the same leniency as the default ones
+        let relax_unset = !params.no_strict
+            && params
+                .profiles
+                .values()
+                .any(|profile| !profile.env_vars.is_empty());
+
+        if relax_unset {
+            writeln!(writer, "set +u")?;
+        }
+
+        writeln!(writer, r#"case "${{{PROFILE_VAR}:-}}" in"#)?;
+
+        for (name, profile) in &params.profiles {
+            writeln!(writer, "    {})", shell::quote(name))?;
+
+            for env in &profile.env_vars {
+                writer.write_str("        ")?;
+                env.write_bash_line(&mut writer)?;
+            }
+
+            if !profile.args.is_empty() {
+                write!(writer, "        {ARGS_VAR}+=(")?;
+                write_words(&profile.args, &mut writer)?;
+                writeln!(writer, ")")?;
+            }
+
+            writeln!(writer, "        ;;")?;
+        }
+
+        writeln!(writer, "esac")?;
+
+        if relax_unset {
+            writeln!(writer, "set -u")?;
+        }
+    }
+
+    if let Some(config) = runtime_config {
+        let config = path::Escaped::new(config);
+
+        writeln!(writer, r#"if [ -f "{}" ]; then"#, config.escaped)?;
+
+        // the config is written by hand, so it gets the same leniency as environment variable values
+        if !params.no_strict {
+            writeln!(writer, "    set +u")?;
+        }
+
+        writeln!(writer, r#"    . "{}""#, config.escaped)?;
+
+        if !params.no_strict {
+            writeln!(writer, "    set -u")?;
+        }
+
+        writeln!(writer, "fi")?;
+    }
+
+    if params.user_override {
+        write_user_override_lines(wrapped_filename, params, &mut writer)?;
+    }
+
+    if !params.jvm_options.is_empty() {
+        jvm::write_options_line(&params.jvm_options, &mut writer)?;
+    }
+
+    // the commands to launch through are only known once the wrapper runs
+    let runtime_launcher = !params.presets.is_empty() || params.journal;
+
+    if runtime_launcher {
+        writeln!(writer, "{}=()", preset::LAUNCHER_VAR)?;
+    }
+
+    if params.journal {
+        journal::write_bash(wrapped_filename, &mut writer)?;
+    }
+
+    // presets come last, so they can tell which variables were already set
+    for preset in &params.presets {
+        preset.write_bash(params, &mut writer)?;
+    }
+
+    if let Some(container) = &params.container {
+        // the wrapper's environment variables don't reach into the container unless they're passed to it
+        let env_names = params
+            .env_vars
+            .iter()
+            .chain(
+                params
+                    .profiles
+                    .values()
+                    .flat_map(|profile| &profile.env_vars),
+            )
+            .map(|env| env.name.as_ref());
+
+        container.write_args(env_names, &mut writer)?;
+    }
+
+    // now run the executable with the wrapper arguments
+
+    // compile time sanity check: the escaped path should be escaping the same quote
+    // character used in the `write!` call
+    const _: () = assert!(path::Escaped::ESCAPE_CHAR == '"');
+
+    // the command is run in place of the wrapper, unless the wrapper has something left to do once it exits
+    let mut command = String::new();
+
+    let launcher = launcher(params);
+
+    if keeps_wrapper_name(params, &launcher) {
+        command.write_str(r#"-a "$0" "#)?;
+    }
+
+    if !launcher.is_empty() {
+        write_words(&launcher, &mut command)?;
+        command.write_char(' ')?;
+    }
+
+    if runtime_launcher {
+        write!(command, r#""${{{}[@]}}" "#, preset::LAUNCHER_VAR)?;
+    }
+
+    if let Some(container) = &params.container {
+        container.write_command(&mut command)?;
+    } else if let Some(container) = &params.host_container {
+        container.write_command(wrapped_filename, &mut command)?;
+    } else if let Some(app) = &params.snap {
+        snap::write_command(app, &mut command)?;
+    } else {
+        if !params.interpreter.is_empty() {
+            write_words(&params.interpreter, &mut command)?;
+            command.write_char(' ')?;
+        }
+
+        write!(command, r#""{}""#, unwrapped_exec_path.escaped)?;
+    }
+
+    fn write_passthrough(params: &Params, mut writer: impl FmtWrite) -> fmt::Result {
+        if params.no_passthrough {
+            return Ok(());
+        }
+
+        write!(writer, r#" "$@""#)
+    }
+
+    let write_args = |name: &str, args: &[String], writer: &mut dyn FmtWrite| {
+        if args.is_empty() && !all_arrays {
+            return Ok(());
+        }
+
+        write!(writer, r#" "${{{name}[@]}}""#)
+    };
+
+    write_args(ARGS_BEFORE_VAR, &params.args_before, &mut command)?;
+
+    if params.add_passthrough_args_first {
+        write_passthrough(params, &mut command)?;
+        write_args(ARGS_VAR, &params.args, &mut command)?;
+    } else {
+        write_args(ARGS_VAR, &params.args, &mut command)?;
+        write_passthrough(params, &mut command)?;
+    }
+
+    write_args(ARGS_AFTER_VAR, &params.args_after, &mut command)?;
+
+    let Some(path) = &params.status_file else {
+        return write!(writer, "exec {command}");
+    };
+
+    status_file::write_start(&mut writer)?;
+    signal::write_run_as_child(&command, &mut writer)?;
+
+    // like environment variable values, the path can reference variables that may not be set
+    status_file::write_report(
+        &path.replace(NAME_PLACEHOLDER, wrapped_filename),
+        !params.no_strict,
+        &mut writer,
+    )?;
+
+    signal::write_exit(writer)
+}
+
+/// Describe the command line that the wrapper with `paths` generated with `params` runs, with the arguments of
+/// `profile` if it's selected, and `<arguments>` in place of the arguments passed to the wrapper.
+///
+/// Anything decided when the wrapper runs, such as by presets or a runtime configuration, is left out.
+pub fn describe_command(paths: &ExecPaths, params: &Params, profile: Option<&Profile>) -> String {
+    let mut words = launcher(params)
+        .iter()
+        .map(|word| shell::quote(word).into_owned())
+        .collect::<Vec<_>>();
+
+    let target = if let Some(container) = &params.container {
+        let command = container.command.as_deref().map(shell::quote);
+
+        [
+            format!("{} run <options>", container.engine),
+            shell::quote(&container.image).into_owned(),
+        ]
+        .into_iter()
+        .chain(command.map(Cow::into_owned))
+        .collect::<Vec<_>>()
+        .join(" ")
+    } else if let Some(container) = &params.host_container {
+        let mut target = String::new();
+        container
+            .write_command(&paths.wrapped_filename, &mut target)
+            .expect("writing to a string can't fail");
+        target
+    } else if let Some(app) = &params.snap {
+        let mut target = String::new();
+        snap::write_command(app, &mut target).expect("writing to a string can't fail");
+        target
+    } else {
+        let unwrapped = paths.unwrapped.original.to_string_lossy();
+
+        params
+            .interpreter
+            .iter()
+            .map(String::as_str)
+            .chain([unwrapped.as_ref()])
+            .map(shell::quote)
+            .collect::<Vec<_>>()
+            .join(" ")
+    };
+
+    words.push(target);
+
+    words.extend(
+        launch_args(params, profile)
+            .into_iter()
+            .map(|arg| match arg {
+                LaunchArg::Fixed(arg) => shell::quote(arg).into_owned(),
+                LaunchArg::Passthrough => "<arguments>".to_owned(),
+            }),
+    );
+
+    words.join(" ")
+}
+
+/// Whether the wrapper generated with `params` launches the executable with the name the wrapper was run with, given
+/// the commands it's launched through.
+fn keeps_wrapper_name(params: &Params, launcher: &[String]) -> bool {
+    // the runtime of an AppImage sets `ARGV0` to the name it was run with, which some use to decide what to run, an
+    // interpreter exposes it as well, and a multi-call binary picks its applet by it, but a launcher would be run with
+    // that name instead
+    let keeps_name = params.appimage || params.applet || !params.interpreter.is_empty();
+    let runtime_launcher = !params.presets.is_empty() || params.journal;
+
+    keeps_name && launcher.is_empty() && !runtime_launcher
+}
+
+/// The arguments, starting with the name it's run with, that the wrapper with `paths` generated with `params` launches
+/// its executable with when run as `run_as` with `args`, with the arguments of `profile` if it's selected.
+///
+/// Returns `None` for a container or a snap, whose command lines are only put together when the wrapper runs. Anything
+/// else decided when the wrapper runs, such as by presets or a runtime configuration, is left out.
+pub fn simulate_argv(
+    paths: &ExecPaths,
+    params: &Params,
+    profile: Option<&Profile>,
+    run_as: &str,
+    args: &[String],
+) -> Option<Vec<String>> {
+    if params.container.is_some() || params.host_container.is_some() || params.snap.is_some() {
+        return None;
+    }
+
+    let launcher = launcher(params);
+
+    let mut argv = launcher
+        .iter()
+        .chain(&params.interpreter)
+        .cloned()
+        .chain([paths.unwrapped.original.to_string_lossy().into_owned()])
+        .collect::<Vec<_>>();
+
+    if keeps_wrapper_name(params, &launcher) {
+        argv[0] = run_as.to_owned();
+    }
+
+    for arg in launch_args(params, profile) {
+        match arg {
+            LaunchArg::Fixed(arg) => argv.push(arg.to_owned()),
+            LaunchArg::Passthrough => argv.extend(args.iter().cloned()),
+        }
+    }
+
+    Some(argv)
+}
+
+/// The environment variables that a wrapper generated with `params` sets before launching its executable, with those
+/// of `profile` if it's selected, along with the values they end up with when `current` gives the values they had.
+///
+/// Values that reference other variables or run commands are left as they are, since only the wrapper expands them.
+pub fn simulate_env(
+    params: &Params,
+    profile: Option<&Profile>,
+    current: impl Fn(&str) -> Option<String>,
+) -> Vec<(String, String)> {
+    let mut env: Vec<(String, String)> = Vec::new();
+
+    let mut set = |name: &str, value: &dyn Fn(Option<String>) -> String| {
+        let existing = env.iter().position(|(other, _)| other == name);
+
+        // like in the wrapper, an empty value counts as unset when adding to it
+        let previous = match existing {
+            Some(index) => Some(env[index].1.clone()),
+            None => current(name),
+        }
+        .filter(|previous| !previous.is_empty());
+
+        let value = value(previous);
+
+        match existing {
+            Some(index) => env[index].1 = value,
+            None => env.push((name.to_owned(), value)),
+        }
+    };
+
+    let vars = params
+        .env_vars
+        .iter()
+        .chain(profile.into_iter().flat_map(|profile| &profile.env_vars));
+
+    for var in vars {
+        let value = var.value.as_ref();
+
+        set(&var.name, &|previous| match (var.op, previous) {
+            (env::Operation::Set, _) | (_, None) => value.to_owned(),
+            (env::Operation::Append, Some(previous)) => format!("{previous}:{value}"),
+            (env::Operation::Prepend, Some(previous)) => format!("{value}:{previous}"),
+        });
+    }
+
+    if !params.jvm_options.is_empty() {
+        let options = params.jvm_options.join(" ");
+
+        set(jvm::TOOL_OPTIONS_VAR, &|previous| match previous {
+            Some(previous) => format!("{options} {previous}"),
+            None => options.clone(),
+        });
+    }
+
+    env
+}
+
+/// An argument that a wrapper launches its executable with.
+#[derive(Debug, PartialEq)]
+pub enum LaunchArg<'a> {
+    /// An argument from the parameters of the wrapper.
+    Fixed(&'a str),
+    /// Where the arguments passed to the wrapper go.
+    Passthrough,
+}
+
+/// The arguments that a wrapper generated with `params` launches its executable with, in order, including those of
+/// `profile` if it's selected.
+pub fn launch_args<'a>(params: &'a Params, profile: Option<&'a Profile>) -> Vec<LaunchArg<'a>> {
+    let fixed = |args: &'a [String]| args.iter().map(|arg| LaunchArg::Fixed(arg));
+
+    let args =
+        fixed(&params.args).chain(profile.into_iter().flat_map(|profile| fixed(&profile.args)));
+    let passthrough = (!params.no_passthrough).then_some(LaunchArg::Passthrough);
+
+    let mut launch_args = fixed(&params.args_before).collect::<Vec<_>>();
+
+    if params.add_passthrough_args_first {
+        launch_args.extend(passthrough);
+        launch_args.extend(args);
+    } else {
+        launch_args.extend(args);
+        launch_args.extend(passthrough);
+    }
+
+    launch_args.extend(fixed(&params.args_after));
+    launch_args
+}
+
+/// Commands that change how the executable runs, which are launched with the executable and its arguments appended.
+fn launcher(params: &Params) -> Vec<String> {
+    let mut launcher = Vec::new();
+
+    if params.no_network {
+        // mapping the current user keeps the executable from seeing itself as root inside the namespace
+        launcher
+            .extend(["unshare", "--user", "--map-current-user", "--net", "--"].map(String::from));
+    }
+
+    if let Some(cpus) = &params.cpus {
+        launcher.extend(cpus.launcher());
+    }
+
+    if let Some(via) = params.via {
+        launcher.extend(via.launcher());
+    }
+
+    launcher
+}
+
+/// Message a masked wrapper of the executable named `wrapped_filename` prints, or `None` if it isn't masked.
+pub fn mask_message(params: &Params, wrapped_filename: &str) -> Option<String> {
+    let message = params.mask.as_ref()?;
+
+    Some(if message.is_empty() {
+        format!("{wrapped_filename} has been masked and can't be run")
+    } else {
+        message.clone()
+    })
+}
+
+/// Message a wrapper of the executable named `wrapped_filename` prints when its access rules refuse to run it, quoted
+/// for bash and naming the user from `$access_user`.
+fn access_denied_message(wrapped_filename: &str) -> String {
+    // the user is only known when the wrapper runs
+    format!(
+        r#""$access_user"{}"#,
+        shell::quote(&format!(" isn't allowed to run {wrapped_filename}"))
+    )
+}
+
+/// Whether `disabled`, the value of `DISABLE_VAR`, disables the wrapper of the executable named `wrapped_filename`.
+pub fn is_disabled(disabled: &str, wrapped_filename: &str) -> bool {
+    disabled
+        .split(',')
+        .any(|name| name == "1" || name == wrapped_filename)
+}
+
+/// Write the lines that run the original executable with the arguments and name the wrapper was run with instead of
+/// anything else, if `DISABLE_VAR` disables the wrapper.
+fn write_bypass_lines(
+    unwrapped_exec_path: &path::Escaped,
+    wrapped_filename: &str,
+    params: &Params,
+    mut writer: impl FmtWrite,
+) -> fmt::Result {
+    let mut command = String::new();
+
+    // the shim of a snap is all there is in place of the executable
+    match &params.snap {
+        Some(app) => snap::write_command(app, &mut command)?,
+        None => write!(command, r#"-a "$0" "{}""#, unwrapped_exec_path.escaped)?,
+    }
+
+    writeln!(
+        writer,
+        r#"case ",${{{DISABLE_VAR}:-}}," in *,1,*|*,{},*) exec {command} "$@" ;; esac"#,
+        shell::quote(wrapped_filename)
+    )
+}
+
+fn write_full_wrapper_script(
+    unwrapped_exec_path: &path::Escaped,
+    header: &Header,
+    params: &Params,
+    mut writer: impl FmtWrite,
+) -> fmt::Result {
+    writer.write_str(&script_template())?;
+    writeln!(writer, "{header}")?;
+
+    // the reason is kept next to what it explains, for whoever opens the wrapper
+    if let Some(reason) = &params.reason {
+        for (index, line) in reason.lines().enumerate() {
+            let prefix = if index == 0 { "reason:" } else { "       " };
+            writeln!(writer, "# {prefix} {line}")?;
+        }
+    }
+
+    if !params.no_strict {
+        writeln!(writer, "{STRICT_OPTIONS}")?;
+    }
+
+    writeln!(
+        writer,
+        r#"if [ "${{{TRACE_VAR}:-}}" = 1 ]; then set -x; fi"#
+    )?;
+
+    let wrapped_filename = header
+        .path
+        .file_name()
+        .unwrap_or_default()
+        .to_string_lossy();
+
+    params.access.write_bash_lines(
+        &header.path.to_string_lossy(),
+        &access_denied_message(&wrapped_filename),
+        &mut writer,
+    )?;
+
+    if let Some(hours) = &params.allowed_hours {
+        let message = format!(
+            "{wrapped_filename} can only be run during {hours}; set WRAPPERIZE_IGNORE_HOURS=1 to run it anyway in an \
+            emergency"
+        );
+
+        hours.write_bash_lines(&shell::quote(&message), &mut writer)?;
+    }
+
+    // a masked executable is never run, so nothing else applies
+    if let Some(message) = mask_message(params, &wrapped_filename) {
+        writeln!(writer, "printf '%s\\n' {} >&2", shell::quote(&message))?;
+        return writeln!(writer, "exit {MASK_EXIT_CODE}");
+    }
+
+    write_bypass_lines(unwrapped_exec_path, &wrapped_filename, params, &mut writer)?;
+
+    let runtime_config = params
+        .runtime_config
+        .then(|| runtime_config_path(&wrapped_filename));
+
+    write_wrapper_script_content(
+        unwrapped_exec_path,
+        &wrapped_filename,
+        params,
+        runtime_config.as_deref(),
+        writer,
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use std::process::Command;
+
+    use super::*;
+    use crate::{file::TempDir, i18n, test_util};
+
+    mod install_script {
+        use super::*;
+
+        fn test_paths() -> ExecPaths {
+            ExecPaths::try_from_path(Path::new("/usr/bin/test_bin")).unwrap()
+        }
+
+        #[test]
+        fn rename_is_skipped_when_already_wrapped() {
+            let paths = test_paths();
+            let header = Header::new(&paths.wrapped.original, &Params::default());
+            let script =
+                InstallScript::generate(&paths, &header, "exec true", &Params::default()).unwrap();
+
+            assert_eq!(
+                script.contents,
+                formatdoc! { r#"
+                    #!/usr/bin/env bash
+                    # Automatically generated by 'wrapperize'.
+                    {header}
+
+                    set -euo pipefail
+
+                    if grep -qxF {quoted_header} "/usr/bin/test_bin" && [ -e "/usr/bin/.test_bin-unwrapped" ]; then
+                        echo "wrapper for /usr/bin/test_bin is already up to date"
+                        exit 0
+                    fi
+
+                    if ! grep -qxF '# Automatically generated by '\''wrapperize'\''.' "/usr/bin/test_bin"; then
+                        mv "/usr/bin/test_bin" "/usr/bin/.test_bin-unwrapped"
+
+                        if [ -f "/usr/bin/.test_bin-wrapperize.toml" ]; then
+                            sed -i '/^dormant = true$/d' "/usr/bin/.test_bin-wrapperize.toml"
+                        fi
+                    fi
+
+                    temp_path="$(mktemp "/usr/bin/test_bin.XXXXXX")"
+
+                    cat << '_wrapperize_eof' > "$temp_path"
+                    exec true
+                    _wrapperize_eof
+
+                    chmod --reference="/usr/bin/.test_bin-unwrapped" "$temp_path"
+                    mv -f "$temp_path" "/usr/bin/test_bin"
+                    "#,
+                    quoted_header = shell::quote(&header.to_string()),
+                }
+            );
+        }
+
+        #[test]
+        fn no_strict() {
+            let paths = test_paths();
+            let header = Header::new(&paths.wrapped.original, &Params::default());
+            let params = Params {
+                no_strict: true,
+                ..Params::default()
+            };
+
+            let script = InstallScript::generate(&paths, &header, "exec true", &params).unwrap();
+            assert!(!script.contents.contains(STRICT_OPTIONS));
+        }
+
+        #[test]
+        fn wrapper_mode() {
+            let paths = test_paths();
+            let params = Params {
+                wrapper_mode: Some(FileMode(0o750)),
+                ..Params::default()
+            };
+            let header = Header::new(&paths.wrapped.original, &params);
+
+            let script = InstallScript::generate(&paths, &header, "exec true", &params).unwrap();
+            assert!(script.contents.contains(r#"chmod 0750 "$temp_path""#));
+        }
+
+        #[test]
+        fn config_protected() {
+            let paths = test_paths();
+            let params = Params {
+                config_protected: true,
+                ..Params::default()
+            };
+            let header = Header::new(&paths.wrapped.original, &params);
+
+            let script = InstallScript::generate(&paths, &header, "exec true", &params).unwrap();
+
+            // the new version has to be in place before the wrapper is found to be up to date
+            assert!(script.contents.contains(indoc::indoc! {r#"
+                set -euo pipefail
+
+                if [ -e "/usr/bin/test_bin.pacnew" ]; then
+                    mv -f "/usr/bin/test_bin.pacnew" "/usr/bin/.test_bin-unwrapped"
+                fi
+
+                if grep -qxF"#}));
+        }
+    }
+
+    #[test]
+    fn existing_wrapper_of_original_and_wrapper() {
+        let (_dir, paths) = temp_paths("existing");
+        fs::write(&paths.wrapped.original, "original").unwrap();
+        assert_eq!(existing_wrapper(&paths.wrapped.original).unwrap(), None);
+
+        fs::rename(&paths.wrapped.original, &paths.unwrapped.original).unwrap();
+        fs::write(&paths.wrapped.original, script_template()).unwrap();
+
+        for path in [&paths.wrapped.original, &paths.unwrapped.original] {
+            assert_eq!(
+                existing_wrapper(path).unwrap().as_ref(),
+                Some(&paths.wrapped.original)
+            );
+        }
+
+        let copy = paths.wrapped.original.with_file_name("copy");
+        fs::copy(&paths.wrapped.original, &copy).unwrap();
+        assert_eq!(existing_wrapper(&copy).unwrap(), Some(copy));
+
+        // a binary that merely contains the marker, like this program, isn't a wrapper
+        fs::write(
+            &paths.unwrapped.original,
+            format!("\x7fELF\0\n{MARKER}x\n{MARKER}\n"),
+        )
+        .unwrap();
+        assert!(!is_wrapper_script(&paths.unwrapped.original).unwrap());
+    }
+
+    #[test]
+    fn wrapped_filename_from_metadata() {
+        let paths = ExecPaths::try_from_path(Path::new("/usr/bin/test-bin")).unwrap();
+        let metadata_filename = paths
+            .metadata
+            .original
+            .file_name()
+            .unwrap()
+            .to_str()
+            .unwrap();
+
+        assert_eq!(
+            ExecPaths::wrapped_filename_from_metadata(metadata_filename),
+            Some("test-bin")
+        );
+        assert_eq!(ExecPaths::wrapped_filename_from_metadata("test-bin"), None);
+    }
+
+    /// Paths for wrapping `test-bin` in a directory of its own, which is removed when the returned one is dropped.
+    fn temp_paths(name: &str) -> (TempDir, ExecPaths) {
+        let dir = test_util::temp_dir(&format!("wrapper-{name}"));
+        let paths = ExecPaths::try_from_path(&dir.path().join("test-bin")).unwrap();
+        (dir, paths)
+    }
+
+    #[test]
+    fn identical_wrapper_is_up_to_date() {
+        let (_dir, paths) = temp_paths("up-to-date");
+        fs::write(&paths.wrapped.original, "original").unwrap();
+
+        let params = Params::with_args(vec!["--arg".into()]);
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(!staged.is_up_to_date());
+
+        let paths = ExecPaths::try_from_path(&staged.paths.wrapped.original).unwrap();
+        assert!(staged.install().is_ok());
+
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.is_up_to_date());
+
+        let changed = Params::with_args(vec!["--other".into()]);
+        let paths = ExecPaths::try_from_path(&staged.paths.wrapped.original).unwrap();
+        assert!(stage(paths, &changed, false, Mode::Create).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn wrapper_keeps_original_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_dir, paths) = temp_paths("permissions");
+        fs::write(&paths.wrapped.original, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&paths.wrapped.original, fs::Permissions::from_mode(0o750)).unwrap();
+
+        let params = Params::with_args(vec!["--arg".into()]);
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        let wrapped = staged.paths.wrapped.original.clone();
+        let unwrapped = staged.paths.unwrapped.original.clone();
+        assert!(staged.install().is_ok());
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&wrapped), 0o750);
+        assert_eq!(mode(&unwrapped), 0o750);
+
+        let params = Params {
+            wrapper_mode: Some(FileMode(0o700)),
+            ..params
+        };
+        let paths = ExecPaths::try_from_path(&wrapped).unwrap();
+        let staged = stage(paths, &params, false, Mode::Update).unwrap();
+        assert!(staged.install().is_ok());
+
+        assert_eq!(mode(&wrapped), 0o700);
+        assert_eq!(mode(&unwrapped), 0o750);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn install_script_keeps_original_permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let (_dir, paths) = temp_paths("script-permissions");
+        fs::write(&paths.wrapped.original, "#!/bin/sh\n").unwrap();
+        fs::set_permissions(&paths.wrapped.original, fs::Permissions::from_mode(0o750)).unwrap();
+
+        let params = Params::with_args(vec!["--arg".into()]);
+        let header = Header::new(&paths.wrapped.original, &params);
+        let script = InstallScript::generate(&paths, &header, "exec true", &params).unwrap();
+
+        let status = Command::new("bash")
+            .arg("-c")
+            .arg(&script.contents)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        assert_eq!(mode(&paths.wrapped.original), 0o750);
+        assert_eq!(
+            fs::read_to_string(&paths.wrapped.original).unwrap(),
+            "exec true\n"
+        );
+
+        // the temporary file was moved into place
+        let dir = paths.wrapped.original.parent().unwrap();
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 2);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn renders_in_place() {
+        let (_dir, paths) = temp_paths("in-place");
+        test_util::write_with_execute_bit(
+            &paths.wrapped.original,
+            b"#!/bin/sh\necho \"$VAR\" \"$@\"\n",
+        )
+        .unwrap();
+
+        let params = Params {
+            args: vec!["--arg".into()],
+            env_vars: vec!["VAR=value".parse().unwrap()],
+            ..Default::default()
+        };
+        let script = render_in_place(&paths.wrapped.original, &params).unwrap();
+
+        let output = Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .arg(&paths.wrapped.original)
+            .arg("passed")
+            .output()
+            .unwrap();
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            "value --arg passed\n"
+        );
+
+        // nothing was installed
+        let dir = paths.wrapped.original.parent().unwrap();
+        assert_eq!(fs::read_dir(dir).unwrap().count(), 1);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn masked_wrapper_refuses_to_run() {
+        let (_dir, paths) = temp_paths("mask");
+        test_util::write_with_execute_bit(&paths.wrapped.original, b"#!/bin/sh\necho ran\n")
+            .unwrap();
+
+        let params = Params {
+            mask: Some("use bar instead".into()),
+            ..Default::default()
+        };
+        let script = render_in_place(&paths.wrapped.original, &params).unwrap();
+
+        let output = Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .arg(&paths.wrapped.original)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.status.code(), Some(MASK_EXIT_CODE.into()));
+        assert!(output.stdout.is_empty());
+        assert_eq!(
+            String::from_utf8(output.stderr).unwrap(),
+            "use bar instead\n"
+        );
+
+        // a mask replaces how the executable runs, so nothing else can change that
+        let params = Params {
+            args: vec!["--arg".into()],
+            ..params
+        };
+        assert!(render_in_place(&paths.wrapped.original, &params).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn denied_wrapper_names_user() {
+        let (_dir, paths) = temp_paths("access");
+        test_util::write_with_execute_bit(&paths.wrapped.original, b"#!/bin/sh\necho ran\n")
+            .unwrap();
+
+        let user = Command::new("id").arg("-un").output().unwrap().stdout;
+        let user = String::from_utf8(user).unwrap().trim().to_owned();
+
+        let params = Params {
+            access: Access {
+                deny_users: vec![user.clone()],
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let script = render_in_place(&paths.wrapped.original, &params).unwrap();
+
+        let output = Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .arg(&paths.wrapped.original)
+            .output()
+            .unwrap();
+
+        assert!(!output.status.success());
+        assert!(output.stdout.is_empty());
+
+        // the message is in the language of whoever runs the tests
+        let stderr = String::from_utf8(output.stderr).unwrap();
+        assert!(stderr.contains(&user) && stderr.contains("test-bin"));
+    }
+
+    #[test]
+    fn reason_is_commented() {
+        let (_dir, paths) = temp_paths("reason");
+        test_util::write_with_execute_bit(&paths.wrapped.original, b"#!/bin/sh\necho \"$@\"\n")
+            .unwrap();
+
+        let params = Params {
+            reason: Some("work VPN requires this proxy\nsee ticket 42".into()),
+            ..Params::with_args(vec!["--arg".into()])
+        };
+        let script = render_in_place(&paths.wrapped.original, &params).unwrap();
+
+        assert!(
+            script.contains("\n# reason: work VPN requires this proxy\n#         see ticket 42\n")
+        );
+
+        let output = Command::new("bash")
+            .arg("-c")
+            .arg(&script)
+            .arg(&paths.wrapped.original)
+            .output()
+            .unwrap();
+
+        assert_eq!(output.stdout, b"--arg\n");
+    }
+
+    #[test]
+    fn store_dir_keeps_original_elsewhere() {
+        let (_dir, paths) = temp_paths("store");
+        test_util::write_with_execute_bit(&paths.wrapped.original, b"#!/bin/sh\necho \"$@\"\n")
+            .unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let store_dir = wrapped.parent().unwrap().join("store");
+        let stored = store_dir.join(&paths.hook_name);
+
+        let params = Params {
+            store_dir: Some(store_dir),
+            ..Params::with_args(vec!["--arg".into()])
+        };
+
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+        assert!(stored.is_file());
+
+        let output = Command::new(&wrapped).output().unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "--arg\n");
+
+        // the stored location is found again through the metadata
+        let paths = ExecPaths::try_from_path(&wrapped).unwrap();
+        assert_eq!(paths.unwrapped.original, stored);
+        assert!(stage(paths, &Params::default(), false, Mode::Update).is_err());
+
+        let paths = ExecPaths::try_from_path(&wrapped).unwrap();
+        assert_eq!(unwrap(&paths, false).unwrap(), Unwrapped::Restored);
+        assert!(!stored.exists());
+    }
+
+    #[test]
+    fn unwrapped_pattern() {
+        let (_dir, paths) = temp_paths("pattern");
+        fs::write(&paths.wrapped.original, "original").unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let params = Params {
+            unwrapped_pattern: Some("{name}.orig".into()),
+            ..Params::with_args(vec!["--arg".into()])
+        };
+
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        let paths = ExecPaths::try_from_path(&wrapped).unwrap();
+        assert_eq!(
+            paths.unwrapped.original,
+            wrapped.with_file_name("test-bin.orig")
+        );
+        assert!(paths.is_wrapped().unwrap());
+    }
+
+    #[test]
+    fn invalid_unwrapped_patterns() {
+        for pattern in ["orig", "{name}", "orig/{name}"] {
+            assert!(validate_unwrapped_pattern(pattern).is_err(), "{pattern}");
+        }
+
+        assert!(validate_unwrapped_pattern(DEFAULT_UNWRAPPED_PATTERN).is_ok());
+    }
+
+    #[test]
+    fn invalid_tags() {
+        for tag in ["", "two words", "a,b", "../x"] {
+            assert!(validate_tag(tag).is_err(), "{tag}");
+        }
+
+        for tag in ["gaming", "laptop-only", "v1.2_x"] {
+            assert!(validate_tag(tag).is_ok(), "{tag}");
+        }
+    }
+
+    #[test]
+    fn expose_original() {
+        let (_dir, paths) = temp_paths("expose");
+        fs::write(&paths.wrapped.original, "original").unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let params = Params {
+            expose_original: true,
+            ..Params::with_args(vec!["--arg".into()])
+        };
+
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        let paths = ExecPaths::try_from_path(&wrapped).unwrap();
+        let exposed = paths.exposed_original_path();
+        assert_eq!(exposed, wrapped.with_file_name("test-bin-unwrapped"));
+        assert_eq!(fs::read_link(&exposed).unwrap(), paths.unwrapped.original);
+
+        unwrap(&paths, false).unwrap();
+        assert!(fs::symlink_metadata(&exposed).is_err());
+    }
+
+    #[test]
+    fn disabled_wrapper_runs_original() {
+        let (_dir, paths) = temp_paths("disable");
+        test_util::write_with_execute_bit(
+            &paths.wrapped.original,
+            b"#!/bin/sh\necho \"${MODE:-none}\" \"$@\"\n",
+        )
+        .unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let params = Params {
+            env_vars: vec![env::Variable::new("MODE", "wrapped")],
+            ..Params::with_args(vec!["--arg".into()])
+        };
+
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        let run = |disabled: &str| {
+            let output = Command::new(&wrapped)
+                .env(DISABLE_VAR, disabled)
+                .arg("file")
+                .output()
+                .unwrap();
+
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        assert_eq!(run(""), "wrapped --arg file\n");
+        assert_eq!(run("other"), "wrapped --arg file\n");
+        assert_eq!(run("1"), "none file\n");
+        assert_eq!(run("other,test-bin"), "none file\n");
+    }
+
+    #[test]
+    fn profiles_are_selected_at_runtime() {
+        let (_dir, paths) = temp_paths("profiles");
+        test_util::write_with_execute_bit(
+            &paths.wrapped.original,
+            b"#!/bin/sh\necho \"${MODE:-none}\" \"$@\"\n",
+        )
+        .unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let params = Params {
+            profiles: BTreeMap::from([(
+                "debug".into(),
+                Profile {
+                    args: vec!["--verbose".into()],
+                    env_vars: vec![env::Variable::new("MODE", "debug")],
+                },
+            )]),
+            ..Params::with_args(vec!["--arg".into()])
+        };
+
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        let run = |profile: &str| {
+            let output = Command::new(&wrapped)
+                .env(PROFILE_VAR, profile)
+                .arg("file")
+                .output()
+                .unwrap();
+
+            String::from_utf8(output.stdout).unwrap()
+        };
+
+        assert_eq!(run(""), "none --arg file\n");
+        assert_eq!(run("unknown"), "none --arg file\n");
+        assert_eq!(run("debug"), "debug --arg --verbose file\n");
+
+        // the profiles survive a round trip through the metadata
+        let paths = ExecPaths::try_from_path(&wrapped).unwrap();
+        assert_eq!(
+            Metadata::read(&paths.metadata.original).unwrap().params,
+            params
+        );
+    }
+
+    #[test]
+    fn presets_without_their_tools_installed() {
+        let (_dir, paths) = temp_paths("presets");
+        test_util::write_with_execute_bit(
+            &paths.wrapped.original,
+            b"#!/bin/sh\necho \"${MANGOHUD:-none}\" \"$@\"\n",
+        )
+        .unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let params = Params {
+            presets: vec![Preset::Gaming],
+            ..Default::default()
+        };
+
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        // an empty launcher array is fine even with `set -u`, and bash is run directly since nothing can be found
+        // through `PATH`
+        let output = Command::new("/bin/bash")
+            .env("PATH", "/nonexistent")
+            .arg(&wrapped)
+            .arg("file")
+            .output()
+            .unwrap();
+        assert_eq!(String::from_utf8(output.stdout).unwrap(), "none file\n");
+    }
+
+    #[test]
+    fn relative_store_dir_is_rejected() {
+        let paths = ExecPaths::try_from_path(Path::new("/usr/bin/test-bin")).unwrap();
+        let params = Params {
+            store_dir: Some("store".into()),
+            ..Params::default()
+        };
+
+        assert!(stage(paths, &params, false, Mode::Create).is_err());
+    }
+
+    #[test]
+    fn staged_files() {
+        let params = Params::with_args(vec!["--arg".into()]);
+        let path = Path::new("/usr/bin/test-bin");
+        let paths = ExecPaths::try_from_path(path).unwrap();
+        let hook_paths = pacman_hook::all_paths(&paths.hook_name);
+        let unit_paths = user_unit::all_paths(&paths.hook_name)
+            .unwrap()
+            .into_iter()
+            .chain([plugin::install_script_path(&paths.hook_name)])
+            .chain(expiry::all_paths(&paths.hook_name, false).unwrap())
+            .collect::<Vec<_>>();
+
+        let staged = stage(
+            ExecPaths::try_from_path(path).unwrap(),
+            &params,
+            true,
+            Mode::Create,
+        )
+        .unwrap();
+        let files = staged.files().unwrap();
+        assert_eq!(
+            files[0],
+            (
+                paths.wrapped.original.clone(),
+                Some(staged.wrapper_script.clone())
+            )
+        );
+        assert_eq!(files.len(), 1 + hook_paths.len() + unit_paths.len());
+        assert!(
+            files[..=hook_paths.len()]
+                .iter()
+                .all(|(path, content)| content.is_some()
+                    && (path == &paths.wrapped.original || hook_paths.contains(path)))
+        );
+
+        // units and backend install scripts that aren't enabled would be removed too
+        assert_eq!(
+            files[hook_paths.len() + 1..],
+            unit_paths
+                .iter()
+                .map(|path| (path.clone(), None))
+                .collect::<Vec<_>>()
+        );
+
+        // disabled hooks would be removed
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        let removed = staged
+            .files()
+            .unwrap()
+            .into_iter()
+            .skip(1)
+            .collect::<Vec<_>>();
+        assert_eq!(
+            removed,
+            hook_paths
+                .into_iter()
+                .chain(unit_paths)
+                .map(|path| (path, None))
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn staging_is_reproducible() {
+        // the messages a wrapper prints when it refuses to run are part of what's generated
+        let limited = Params {
+            allowed_hours: Some("08:00-18:00".parse().unwrap()),
+            access: Access {
+                deny_users: vec!["nobody".into()],
+                ..Default::default()
+            },
+            ..Params::with_args(vec!["--arg".into()])
+        };
+
+        let masked = Params {
+            mask: Some(String::new()),
+            ..Default::default()
+        };
+
+        let stage_scripts = |params| {
+            let paths = ExecPaths::try_from_path(Path::new("/usr/bin/test-bin")).unwrap();
+            let staged = stage(paths, params, true, Mode::Create).unwrap();
+            (staged.wrapper_script, staged.hook_install_script.contents)
+        };
+
+        for params in [&limited, &masked] {
+            let scripts = stage_scripts(params);
+            assert_eq!(stage_scripts(params), scripts);
+
+            i18n::set_language("de");
+            let translated = stage_scripts(params);
+            let aborted = t!("aborted");
+            i18n::set_language("en");
+
+            assert_ne!(aborted, t!("aborted"));
+
+            assert_eq!(translated, scripts);
+        }
+    }
+
+    #[test]
+    fn trace_prints_exec_line() {
+        let (_dir, paths) = temp_paths("trace");
+        test_util::write_with_execute_bit(&paths.wrapped.original, b"#!/bin/sh\n").unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let unwrapped = paths.unwrapped.original.clone();
+        let params = Params::with_args(vec!["--arg".into()]);
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        let run = |trace: &str| {
+            let output = Command::new(&wrapped)
+                .env(TRACE_VAR, trace)
+                .output()
+                .unwrap();
+            assert!(output.status.success());
+            String::from_utf8(output.stderr).unwrap()
+        };
+
+        let exec_line = format!("exec {} --arg", unwrapped.display());
+        assert!(run("1").contains(&exec_line));
+        assert!(run("0").is_empty());
+    }
+
+    #[test]
+    fn status_file_records_exit_status() {
+        let (_dir, paths) = temp_paths("status-file");
+        test_util::write_with_execute_bit(&paths.wrapped.original, b"#!/bin/sh\nexit 3\n").unwrap();
+
+        let wrapped = paths.wrapped.original.clone();
+        let dir = wrapped.parent().unwrap().to_owned();
+        let params = Params {
+            status_file: Some("$STATUS_DIR/{name}.status".into()),
+            ..Default::default()
+        };
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        let status = Command::new(&wrapped)
+            .env("STATUS_DIR", dir.join("status"))
+            .status()
+            .unwrap();
+        assert_eq!(status.code(), Some(3));
+
+        let report = fs::read_to_string(dir.join("status/test-bin.status")).unwrap();
+        let fields = report
+            .lines()
+            .filter_map(|line| line.split_once('='))
+            .collect::<BTreeMap<_, _>>();
+
+        assert_eq!(fields["status"], "3");
+        assert!(fields["pid"].parse::<u32>().is_ok());
+        assert!(fields["started"] <= fields["finished"]);
+    }
+
+    #[test]
+    fn path_with_spaces_and_unicode() {
+        let root = test_util::temp_dir("wrapper-My App ünï \"$x\" `y` \\z");
+        let bin_dir = root.path().join("bin");
+        fs::create_dir(&bin_dir).unwrap();
+
+        let tool = bin_dir.join("tööl");
+        test_util::write_with_execute_bit(&tool, b"#!/bin/sh\nprintf '%s\\n' \"$0\" \"$@\"\n")
+            .unwrap();
+
+        let paths = ExecPaths::try_from_path(&tool).unwrap();
+        let params = Params::with_args(vec!["a b".into()]);
+        let staged = stage(paths, &params, false, Mode::Create).unwrap();
+        assert!(staged.install().is_ok());
+
+        let paths = ExecPaths::try_from_path(&tool).unwrap();
+        assert!(paths.is_wrapped().unwrap());
+
+        let output = Command::new(&tool).arg("c").output().unwrap();
+        assert!(output.status.success());
+        assert_eq!(
+            String::from_utf8(output.stdout).unwrap(),
+            format!("{}\na b\nc\n", paths.unwrapped.original.display())
+        );
+    }
+
+    mod unwrap {
+        use super::*;
+
+        #[test]
+        fn restores_original_without_wrapper() {
+            let (_dir, paths) = temp_paths("restore");
+            fs::write(&paths.unwrapped.original, "original").unwrap();
+            fs::write(&paths.metadata.original, "").unwrap();
+
+            assert_eq!(unwrap(&paths, false).unwrap(), Unwrapped::Restored);
+            assert_eq!(
+                fs::read_to_string(&paths.wrapped.original).unwrap(),
+                "original"
+            );
+            assert!(!paths.unwrapped.original.exists());
+            assert!(!paths.metadata.original.exists());
+        }
+
+        #[test]
+        fn reports_missing_executable() {
+            let (_dir, paths) = temp_paths("missing");
+            fs::write(&paths.metadata.original, "").unwrap();
+
+            assert_eq!(unwrap(&paths, false).unwrap(), Unwrapped::Missing);
+            assert!(!paths.metadata.original.exists());
+        }
+
+        #[test]
+        fn keep_hooks_marks_dormant() {
+            let (_dir, paths) = temp_paths("dormant");
+            fs::write(&paths.unwrapped.original, "original").unwrap();
+
+            let metadata = Metadata::new(&paths.wrapped.original, Params::default(), false);
+            metadata.write(&paths.metadata.original).unwrap();
+            assert!(unwrap(&paths, true).is_err());
+            assert!(paths.unwrapped.original.exists());
+
+            Metadata {
+                use_pacman_hooks: true,
+                ..metadata
+            }
+            .write(&paths.metadata.original)
+            .unwrap();
+
+            assert_eq!(unwrap(&paths, true).unwrap(), Unwrapped::Restored);
+            assert!(!paths.unwrapped.original.exists());
+            assert!(Metadata::read(&paths.metadata.original).unwrap().dormant);
+
+            // like when its expiry timer fires before the hooks have recreated it
+            assert_eq!(unwrap(&paths, false).unwrap(), Unwrapped::Restored);
+            assert!(paths.wrapped.original.exists());
+            assert!(!paths.metadata.original.exists());
+        }
+
+        #[test]
+        fn problems_of_intact_and_broken_wrappers() {
+            let (_dir, paths) = temp_paths("problems");
+            assert_eq!(problems(&paths).unwrap().len(), 3);
+
+            fs::write(&paths.wrapped.original, script_template()).unwrap();
+            fs::write(&paths.unwrapped.original, "original").unwrap();
+            Metadata::new(&paths.wrapped.original, Params::default(), false)
+                .write(&paths.metadata.original)
+                .unwrap();
+            assert_eq!(problems(&paths).unwrap(), Vec::<String>::new());
+
+            fs::write(&paths.wrapped.original, "replaced").unwrap();
+            assert_eq!(problems(&paths).unwrap().len(), 1);
+        }
+
+        #[test]
+        fn fails_when_only_wrapper_exists() {
+            let (_dir, paths) = temp_paths("orphaned");
+            fs::write(&paths.wrapped.original, script_template()).unwrap();
+
+            assert!(unwrap(&paths, false).is_err());
+            assert!(paths.wrapped.original.exists());
+
+            fs::write(&paths.wrapped.original, "unrelated").unwrap();
+            assert!(unwrap(&paths, false).is_err());
+        }
+    }
+
+    mod params {
+        use super::*;
+
+        #[test]
+        fn merge_keeps_repeated_args() {
+            let mut params = Params::with_args(vec!["--a".into(), "--b".into()]);
+            params.merge(Params::with_args(vec!["--c".into(), "--a".into()]));
+
+            assert_eq!(params.args, ["--a", "--b", "--c", "--a"]);
+
+            let mut params = Params::default();
+            params.merge(Params::with_args(vec![
+                "-I".into(),
+                "a".into(),
+                "-I".into(),
+                "b".into(),
+            ]));
+
+            assert_eq!(params.args, ["-I", "a", "-I", "b"]);
+        }
+
+        #[test]
+        fn finds_repeated_args() {
+            let params = Params::with_args(vec!["--flag".into(), "--other".into()]);
+
+            assert_eq!(
+                params.repeated_args(&Params::with_args(vec!["--flag".into(), "--new".into()])),
+                ["--flag"]
+            );
+            assert!(
+                params
+                    .repeated_args(&Params::with_args(vec!["--flag".into(), "--other".into()]))
+                    .is_empty()
+            );
+        }
+
+        #[test]
+        fn merge_skips_same_args() {
+            let args = vec!["-v".to_owned(), "-v".to_owned()];
+            let mut params = Params::with_args(args.clone());
+            params.merge(Params::with_args(args));
+
+            assert_eq!(params.args, ["-v", "-v"]);
+        }
+
+        #[test]
+        fn dedup_keeps_args() {
+            let mut params = Params {
+                tags: vec!["a".into(), "a".into()],
+                ..Params::with_args(vec!["-a".into(), "-a".into()])
+            };
+            params.dedup();
+
+            assert_eq!(params.args, ["-a", "-a"]);
+            assert_eq!(params.tags, ["a"]);
+        }
+
+        #[test]
+        fn merge_extends_profiles() {
+            let profile = |args: &[&str]| Profile {
+                args: args.iter().map(|arg| arg.to_string()).collect(),
+                ..Profile::default()
+            };
+
+            let mut params = Params {
+                profiles: BTreeMap::from([("a".into(), profile(&["--x"]))]),
+                ..Params::default()
+            };
+
+            params.merge(Params {
+                profiles: BTreeMap::from([
+                    ("a".into(), profile(&["--y", "--x"])),
+                    ("b".into(), profile(&["--z"])),
+                ]),
+                ..Params::default()
+            });
+
+            assert_eq!(
+                params.profiles,
+                BTreeMap::from([
+                    ("a".into(), profile(&["--x", "--y", "--x"])),
+                    ("b".into(), profile(&["--z"])),
+                ])
+            );
+        }
+
+        #[test]
+        fn merge_last_env_wins() {
+            let mut params = Params::with_env_vars(vec![
+                env::Variable::new("ENV1", "old"),
+                env::Variable::new("ENV2", "val2"),
+            ]);
+
+            let replaced = params.merge(Params::with_env_vars(vec![
+                env::Variable::new("ENV1", "new"),
+                env::Variable::new("ENV2", "val2"),
+            ]));
+
+            assert_eq!(
+                params.env_vars,
+                [
+                    env::Variable::new("ENV1", "new"),
+                    env::Variable::new("ENV2", "val2"),
+                ]
+            );
+            assert_eq!(replaced, ["ENV1"]);
+        }
+
+        #[test]
+        fn merge_keeps_every_addition_to_a_list() {
+            let env = |value: &str| value.parse::<env::Variable>().unwrap();
+
+            let mut params = Params::with_env_vars(vec![env("PATH+=/opt/a/bin"), env("EDITOR=vi")]);
+
+            let replaced = params.merge(Params::with_env_vars(vec![
+                env("PATH+=/opt/b/bin"),
+                env("PATH+=/opt/a/bin"),
+                env("PATH=+/opt/c/bin"),
+                env("EDITOR=vim"),
+            ]));
+
+            assert_eq!(
+                params.env_vars,
+                [
+                    env("PATH+=/opt/a/bin"),
+                    env("EDITOR=vim"),
+                    env("PATH+=/opt/b/bin"),
+                    env("PATH=+/opt/c/bin"),
+                ]
+            );
+            assert_eq!(replaced, ["EDITOR"]);
+        }
+
+        #[test]
+        fn merge_last_limit_wins() {
+            let limits = |limits: &[&str]| {
+                limits
+                    .iter()
+                    .map(|limit| limit.parse().unwrap())
+                    .collect::<Vec<Limit>>()
+            };
+
+            let mut params = Params {
+                limits: limits(&["nofile=1024", "core=0"]),
+                ..Default::default()
+            };
+
+            params.merge(Params {
+                limits: limits(&["stack=8192", "nofile=65536"]),
+                ..Default::default()
+            });
+
+            assert_eq!(
+                params.limits,
+                limits(&["nofile=65536", "core=0", "stack=8192"])
+            );
+        }
+    }
+
+    mod generate_wrapper_script {
+        use super::*;
+
+        fn gen_script_content(path: &path::Escaped, params: &Params) -> anyhow::Result<String> {
+            let mut buffer = String::new();
+            write_wrapper_script_content(path, "test_bin", params, None, &mut buffer)?;
+            Ok(buffer)
+        }
+
+        #[test]
+        fn no_args() {
+            let path = path::Escaped::new("test_bin");
+            let result = gen_script_content(&path, &Params::default()).unwrap();
+            assert_eq!(result, r#"exec "test_bin" "$@""#);
+        }
+
+        #[test]
+        fn path_with_space() {
+            let path = path::Escaped::new("/usr/bin/test bin");
+            let result = gen_script_content(&path, &Params::default()).unwrap();
+            assert_eq!(result, r#"exec "/usr/bin/test bin" "$@""#);
+        }
+
+        #[test]
+        fn path_with_quote() {
+            let path = path::Escaped::new("/usr/bin/\"test\"");
+            let result = gen_script_content(&path, &Params::default()).unwrap();
+            assert_eq!(result, r#"exec "/usr/bin/\"test\"" "$@""#);
+        }
+
+        #[test]
+        fn with_args() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let args = vec![String::from("--arg1"), String::from("--arg2")];
+            let result = gen_script_content(&path, &Params::with_args(args)).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args=(--arg1 --arg2)
+                    exec "/usr/bin/test_bin" "${{args[@]}}" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_special_args() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let args = vec![
+                String::from("--arg with spaces"),
+                String::from("*"),
+                String::from(r#"it's "quoted""#),
+            ];
+            let result = gen_script_content(&path, &Params::with_args(args)).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args=('--arg with spaces' '*' 'it'\''s "quoted"')
+                    exec "/usr/bin/test_bin" "${{args[@]}}" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_env_vars() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+
+            let env_vars = vec![
+                env::Variable::new("ENV1", "val1"),
+                env::Variable::new("ENV2", "val2"),
+            ];
+
+            let result = gen_script_content(&path, &Params::with_env_vars(env_vars)).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    set +u
+                    export ENV1="val1"
+                    export ENV2="val2"
+                    set -u
+                    exec "/usr/bin/test_bin" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_presets() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                presets: vec![Preset::Gaming],
+                cpus: Some("1".parse().unwrap()),
+                ..Params::with_env_vars(vec![env::Variable::new("MANGOHUD", "0")])
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    set +u
+                    export MANGOHUD="0"
+                    set -u
+                    launcher=()
+                    if command -v gamemoderun >/dev/null; then
+                        launcher+=(gamemoderun)
+                    fi
+                    if command -v mangohud >/dev/null; then
+                        export MANGOHUD="${{MANGOHUD:-1}}"
+                    fi
+                    exec taskset -c 1 "${{launcher[@]}}" "/usr/bin/test_bin" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_preset_adding_args() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                presets: vec![Preset::Wayland],
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args_before=()
+                    args=()
+                    args_after=()
+                    launcher=()
+                    if [ -n "${{WAYLAND_DISPLAY:-}}" ]; then
+                        args_before+=(--ozone-platform-hint=auto --enable-features=WaylandWindowDecorations)
+                    fi
+                    exec "${{launcher[@]}}" "/usr/bin/test_bin" "${{args_before[@]}}" "${{args[@]}}" "$@" "${{args_after[@]}}""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_jvm_options() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                jvm_options: vec!["-Xmx4g".into(), "-XX:+UseZGC".into()],
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    export JAVA_TOOL_OPTIONS='-Xmx4g -XX:+UseZGC'"${{JAVA_TOOL_OPTIONS:+ $JAVA_TOOL_OPTIONS}}"
+                    exec "/usr/bin/test_bin" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_container() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                container: Some(Container {
+                    image: "docker.io/library/alpine".into(),
+                    engine: Default::default(),
+                    command: Some("test_bin".into()),
+                    volumes: Vec::new(),
+                    envs: Vec::new(),
+                    mount_cwd: false,
+                }),
+                ..Params::with_env_vars(vec![env::Variable::new("ENV", "value")])
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    set +u
+                    export ENV="value"
+                    set -u
+                    container_args=(--rm --interactive --env=ENV)
+                    if [ -t 0 ] && [ -t 1 ]; then
+                        container_args+=(--tty)
+                    fi
+                    exec podman run "${{container_args[@]}}" docker.io/library/alpine test_bin "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_host_container() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                host_container: Some(HostContainer {
+                    tool: crate::container::HostContainerTool::Distrobox,
+                    name: "arch".into(),
+                    command: None,
+                }),
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(result, r#"exec distrobox enter -n arch -- test_bin "$@""#);
+        }
+
+        #[test]
+        fn with_snap() {
+            let path = path::Escaped::new("/usr/local/bin/.test_bin-unwrapped");
+            let params = Params {
+                snap: Some("test_bin".into()),
+                ..Params::with_args(vec!["--arg".into()])
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                indoc::indoc! {r#"
+                    args=(--arg)
+                    exec snap run test_bin "${args[@]}" "$@""#}
+            );
+        }
+
+        #[test]
+        fn with_appimage() {
+            let path = path::Escaped::new("/opt/apps/.test_bin-unwrapped.AppImage");
+            let params = Params {
+                appimage: true,
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                r#"exec -a "$0" "/opt/apps/.test_bin-unwrapped.AppImage" "$@""#
+            );
+        }
+
+        #[test]
+        fn with_applet() {
+            let path = path::Escaped::new("/usr/bin/.ls-unwrapped");
+            let params = Params {
+                applet: true,
+                ..Params::with_args(vec!["--color=auto".into()])
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                indoc::indoc! {r#"
+                    args=(--color=auto)
+                    exec -a "$0" "/usr/bin/.ls-unwrapped" "${args[@]}" "$@""#}
+            );
+        }
+
+        #[test]
+        fn with_interpreter() {
+            let path = path::Escaped::new("/usr/bin/.test_bin-unwrapped");
+            let params = Params {
+                interpreter: vec!["/usr/bin/env".into(), "python3 -u".into()],
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                r#"exec -a "$0" /usr/bin/env 'python3 -u' "/usr/bin/.test_bin-unwrapped" "$@""#
+            );
+        }
+
+        #[test]
+        fn with_journal() {
+            let path = path::Escaped::new("/usr/bin/.test_bin-unwrapped");
+            let params = Params {
+                journal: true,
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                indoc::indoc! {r#"
+                    launcher=()
+                    if [ ! -t 1 ] && command -v systemd-cat >/dev/null; then
+                        launcher+=(systemd-cat -t test_bin --stderr-priority=warning)
+                    fi
+                    exec "${launcher[@]}" "/usr/bin/.test_bin-unwrapped" "$@""#}
+            );
+        }
+
+        #[test]
+        fn with_status_file() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                status_file: Some("$XDG_RUNTIME_DIR/{name}.status".into()),
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                indoc::indoc! {r#"
+                    printf -v started '%(%FT%T%z)T' -1
+                    child=
+                    interrupted=
+                    forward_signal() {
+                        interrupted=1
+                        if [ -n "$child" ]; then
+                            kill -s "$1" "$child" 2>/dev/null || true
+                        fi
+                    }
+                    trap 'forward_signal TERM' TERM
+                    trap 'forward_signal INT' INT
+                    trap 'forward_signal HUP' HUP
+                    ( trap - INT QUIT; exec "/usr/bin/test_bin" "$@" ) <&0 &
+                    child=$!
+                    status=0
+                    wait "$child" || status=$?
+                    while [ -n "$interrupted" ]; do
+                        interrupted=
+                        next_status=0
+                        wait "$child" 2>/dev/null || next_status=$?
+                        if [ "$next_status" -ne 127 ]; then
+                            status=$next_status
+                        fi
+                    done
+                    printf -v finished '%(%FT%T%z)T' -1
+                    set +u
+                    status_file="$XDG_RUNTIME_DIR/test_bin.status"
+                    set -u
+                    {
+                        mkdir -p "$(dirname "$status_file")" &&
+                            printf 'status=%s\npid=%s\nstarted=%s\nfinished=%s\n' \
+                                "$status" "$child" "$started" "$finished" >"$status_file.$$" &&
+                            mv -f "$status_file.$$" "$status_file"
+                    } 2>/dev/null || rm -f "$status_file.$$"
+                    if [ "$status" -gt 128 ] && signal="$(kill -l "$status" 2>/dev/null)"; then
+                        trap - "$signal"
+                        kill -s "$signal" "$$"
+                    fi
+                    exit "$status"
+                "#}
+            );
+        }
+
+        #[test]
+        fn with_launchers() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                no_network: true,
+                cpus: Some("1".parse().unwrap()),
+                via: Some(Via::Torsocks),
+                ..Default::default()
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                r#"exec unshare --user --map-current-user --net -- taskset -c 1 torsocks "/usr/bin/test_bin" "$@""#
+            );
+        }
+
+        #[test]
+        fn with_limits() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                limits: vec!["nofile=65536".parse().unwrap(), "core=0".parse().unwrap()],
+                ..Params::with_env_vars(vec![env::Variable::new("ENV", "value")])
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    set +u
+                    export ENV="value"
+                    set -u
+                    ulimit -S -n 65536
+                    ulimit -S -c 0
+                    exec "/usr/bin/test_bin" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_cpus() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                cpus: Some("0-3,8".parse().unwrap()),
+                ..Params::with_args(vec!["--arg".into()])
+            };
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args=(--arg)
+                    exec taskset -c 0-3,8 "/usr/bin/test_bin" "${{args[@]}}" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_runtime_config() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params::with_args(vec!["--arg".into()]);
+            let config = runtime_config_path("test_bin");
+
+            let mut result = String::new();
+            write_wrapper_script_content(&path, "test_bin", &params, Some(&config), &mut result)
+                .unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args_before=()
+                    args=(--arg)
+                    args_after=()
+                    if [ -f "/etc/wrapperize/conf.d/test_bin.conf" ]; then
+                        set +u
+                        . "/etc/wrapperize/conf.d/test_bin.conf"
+                        set -u
+                    fi
+                    exec "/usr/bin/test_bin" "${{args_before[@]}}" "${{args[@]}}" "$@" "${{args_after[@]}}""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_user_override() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+            let params = Params {
+                user_override: true,
+                ..Params::with_args(vec!["--arg".into()])
+            };
+
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args_before=()
+                    args=(--arg)
+                    args_after=()
+                    user_override="${{XDG_CONFIG_HOME:-${{HOME:-}}/.config}}/wrapperize/test_bin.override"
+                    if [ -f "$user_override" ] && [ -O "$user_override" ]; then
+                        set +u
+                        . "$user_override"
+                        set -u
+                    fi
+                    exec "/usr/bin/test_bin" "${{args_before[@]}}" "${{args[@]}}" "$@" "${{args_after[@]}}""#
+                }
+            );
+        }
+
+        #[test]
+        fn with_env_vars_no_strict() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+
+            let params = Params {
+                no_strict: true,
+                ..Params::with_env_vars(vec![env::Variable::new("ENV1", "val1")])
+            };
+
+            let result = gen_script_content(&path, &params).unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    export ENV1="val1"
+                    exec "/usr/bin/test_bin" "$@""#
+                }
+            );
+        }
+
+        #[test]
+        fn args_before_and_after() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+
+            let params = Params {
+                args: vec![String::from("--arg")],
+                args_before: vec![String::from("--global"), String::from("subcmd")],
+                args_after: vec![String::from("--sub-flag")],
+                ..Default::default()
+            };
+
+            let result = gen_script_content(&path, &params).unwrap();
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args_before=(--global subcmd)
+                    args=(--arg)
+                    args_after=(--sub-flag)
+                    exec "/usr/bin/test_bin" "${{args_before[@]}}" "${{args[@]}}" "$@" "${{args_after[@]}}""#
+                }
+            );
+
+            let result = gen_script_content(
+                &path,
+                &Params {
+                    add_passthrough_args_first: true,
+                    ..params
+                },
+            )
+            .unwrap();
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args_before=(--global subcmd)
+                    args=(--arg)
+                    args_after=(--sub-flag)
+                    exec "/usr/bin/test_bin" "${{args_before[@]}}" "$@" "${{args[@]}}" "${{args_after[@]}}""#
+                }
+            );
+        }
+
+        #[test]
+        fn no_passthrough() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+
+            let result = gen_script_content(
+                &path,
+                &Params {
+                    args: vec![String::from("--arg1")],
+                    no_passthrough: true,
+                    ..Default::default()
+                },
+            )
+            .unwrap();
+
+            assert_eq!(
+                result,
+                formatdoc! { r#"
+                    args=(--arg1)
+                    exec "/usr/bin/test_bin" "${{args[@]}}""#
+                }
+            );
+        }
+
+        #[test]
+        fn passthrough_args_first() {
+            let path = path::Escaped::new("/usr/bin/test_bin");
+
+            let result = gen_script_content(
+                &path,
+                &Params {
+                    args: vec![String::from("--arg1"), String::from("--arg2")],
                     add_passthrough_args_first: true,
                     ..Default::default()
                 },
@@ -332,9 +4129,181 @@ mod tests {
             assert_eq!(
                 result,
                 formatdoc! { r#"
-                    exec "/usr/bin/test_bin" "\$@" --arg1 --arg2"#
+                    args=(--arg1 --arg2)
+                    exec "/usr/bin/test_bin" "$@" "${{args[@]}}""#
                 }
             );
         }
+
+        #[test]
+        fn describes_command() {
+            let paths = ExecPaths::try_from_path(Path::new("/usr/bin/test_bin")).unwrap();
+            let params = Params {
+                args: vec![String::from("--arg 1")],
+                args_after: vec![String::from("--last")],
+                interpreter: vec![String::from("python3")],
+                ..Default::default()
+            };
+            let profile = Profile {
+                args: vec![String::from("--debug")],
+                ..Default::default()
+            };
+
+            assert_eq!(
+                describe_command(&paths, &params, None),
+                "python3 /usr/bin/.test_bin-unwrapped '--arg 1' <arguments> --last"
+            );
+
+            assert_eq!(
+                describe_command(
+                    &paths,
+                    &Params {
+                        no_passthrough: true,
+                        ..params.clone()
+                    },
+                    Some(&profile)
+                ),
+                "python3 /usr/bin/.test_bin-unwrapped '--arg 1' --debug --last"
+            );
+        }
+    }
+
+    #[test]
+    fn simulates_launch() {
+        let paths = ExecPaths::try_from_path(Path::new("/usr/bin/test_bin")).unwrap();
+        let params = Params {
+            args: vec!["--arg".into()],
+            args_after: vec!["--last".into()],
+            interpreter: vec!["python3".into()],
+            env_vars: vec![
+                env::Variable::parse("PATH=+/opt/bin").unwrap().into_owned(),
+                env::Variable::new("MODE", "fast"),
+            ],
+            jvm_options: vec!["-Xmx1g".into()],
+            ..Default::default()
+        };
+        let args = ["a b".to_owned()];
+
+        // the interpreter sees the name the wrapper was run with
+        assert_eq!(
+            simulate_argv(&paths, &params, None, "test_bin", &args).unwrap(),
+            [
+                "test_bin",
+                "/usr/bin/.test_bin-unwrapped",
+                "--arg",
+                "a b",
+                "--last"
+            ]
+        );
+
+        let profile = Profile {
+            env_vars: vec![env::Variable::parse("MODE+=debug").unwrap().into_owned()],
+            ..Default::default()
+        };
+        let current = |name: &str| (name == "PATH").then(|| "/usr/bin".to_owned());
+
+        assert_eq!(
+            simulate_env(&params, Some(&profile), current),
+            [
+                ("PATH".to_owned(), "/opt/bin:/usr/bin".to_owned()),
+                ("MODE".to_owned(), "fast:debug".to_owned()),
+                ("JAVA_TOOL_OPTIONS".to_owned(), "-Xmx1g".to_owned()),
+            ]
+        );
+
+        let params = Params {
+            snap: Some("app".into()),
+            ..params
+        };
+        assert!(simulate_argv(&paths, &params, None, "test_bin", &args).is_none());
+    }
+
+    /// Renders wrappers for arbitrary paths, arguments, and environment variables, and checks that the wrapped
+    /// executable receives exactly what it was given. Enabled with the `fuzz` feature since every case runs bash.
+    #[cfg(feature = "fuzz")]
+    mod fuzz {
+        use proptest::prelude::*;
+
+        use super::*;
+
+        /// Prints every argument, followed by the value of every `FUZZ_*` variable, separated by NUL bytes.
+        const PRINT_SCRIPT: &str = indoc::indoc! { r#"
+            #!/bin/sh
+            for arg in "$@"; do
+                printf '%s\0' "$arg"
+            done
+
+            i=0
+            while [ "$i" -lt "$FUZZ_COUNT" ]; do
+                eval "printf '%s\0' \"\$FUZZ_$i\""
+                i=$((i + 1))
+            done
+        "# };
+
+        /// A single path component; newlines are excluded since paths are recorded on single-line headers.
+        fn path_component() -> impl Strategy<Value = String> {
+            "[^\0/\n.][^\0/\n]{0,15}"
+        }
+
+        /// An argument, which can contain anything but NUL since it's passed through `argv`.
+        fn arg() -> impl Strategy<Value = String> {
+            "[^\0]{0,16}"
+        }
+
+        /// An environment variable value; `$`, backticks, and backslashes are left out since values are
+        /// intentionally expanded by bash when the wrapper runs.
+        fn env_value() -> impl Strategy<Value = String> {
+            "[^\0$`\\\\]{0,16}"
+        }
+
+        proptest! {
+            #![proptest_config(ProptestConfig::with_cases(64))]
+
+            #[test]
+            fn wrapper_passes_through_exact_argv_and_env(
+                dir in path_component(),
+                filename in path_component(),
+                args in prop::collection::vec(arg(), 0..4),
+                passthrough in prop::collection::vec(arg(), 0..4),
+                env_values in prop::collection::vec(env_value(), 0..4),
+            ) {
+                let root = test_util::temp_dir("fuzz");
+                let bin_dir = root.path().join(&dir);
+                fs::create_dir_all(&bin_dir).unwrap();
+
+                let exec = bin_dir.join(&filename);
+                test_util::write_with_execute_bit(&exec, PRINT_SCRIPT.as_bytes()).unwrap();
+
+                let mut env_vars = vec![env::Variable::new("FUZZ_COUNT", env_values.len().to_string())];
+                env_vars.extend(
+                    env_values
+                        .iter()
+                        .enumerate()
+                        .map(|(i, value)| env::Variable::new(format!("FUZZ_{i}"), value.clone())),
+                );
+
+                let params = Params {
+                    args: args.clone(),
+                    env_vars,
+                    ..Params::default()
+                };
+
+                let paths = ExecPaths::try_from_path(&exec).unwrap();
+                let staged = stage(paths, &params, false, Mode::Create).unwrap();
+                prop_assert!(staged.install().is_ok());
+
+                let output = Command::new(&exec).args(&passthrough).output().unwrap();
+                prop_assert!(output.status.success());
+
+                let expected = args
+                    .iter()
+                    .chain(&passthrough)
+                    .chain(&env_values)
+                    .flat_map(|value| value.bytes().chain([0]))
+                    .collect::<Vec<_>>();
+
+                prop_assert_eq!(output.stdout, expected);
+            }
+        }
     }
 }